@@ -0,0 +1,184 @@
+use std::{
+	io::Write,
+	path::{Path, PathBuf},
+	process::ExitCode,
+};
+
+use bumpalo::Bump;
+use clap::{error::ErrorKind, Args, CommandFactory, ValueEnum};
+use hdx_ast::css::StyleSheet;
+use hdx_transform::Pipeline;
+use hdx_writer::{BaseCssWriter, FormatOptions, OutputOption, SourceMap, SourceMapWriter, WriteCss};
+use miette::Severity;
+use rayon::prelude::*;
+
+use crate::{
+	config::Config,
+	inputs::{common_base, relative_path, resolve_inputs, write_output, Input},
+	report::{exit_code, print_summary, render_diagnostics, FileResult},
+	Cli,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SourceMapMode {
+	/// Write the map next to each output file, as `<output>.map`
+	File,
+	/// Embed the map in the output, as a data URL
+	Inline,
+}
+
+#[derive(Debug, Args)]
+pub struct CompileArgs {
+	/// Files or glob patterns (e.g. `src/**/*.css`) to process. Use `-`, or pipe input, to read from stdin
+	#[clap(value_parser)]
+	pub input: Vec<String>,
+	#[clap(short, long, value_parser)]
+	pub minify: bool,
+	#[clap(short, long, group = "output_file", value_parser)]
+	pub output: Option<String>,
+	/// Write each result into this directory, mirroring the layout of the input files
+	#[clap(long, group = "output_file", value_parser)]
+	pub out_dir: Option<PathBuf>,
+	/// Generate a source map, linking the output back to the input
+	#[clap(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "file")]
+	pub source_map: Option<SourceMapMode>,
+}
+
+fn compile(input: &Input, config: &Config, minify: bool, source_map: bool) -> FileResult {
+	match input.read() {
+		Ok(source_text) => compile_source(input.path(), &source_text, config, minify, source_map),
+		Err(err) => FileResult::io_failure(input.path(), err),
+	}
+}
+
+pub fn compile_source(path: &Path, source_text: &str, config: &Config, minify: bool, source_map: bool) -> FileResult {
+	let mut file = FileResult::new(path);
+	let allocator = Bump::default();
+	let result = hdx_parser::Parser::new(&allocator, source_text, config.parser.features()).parse_with::<StyleSheet>();
+	let (opts, format) = if minify {
+		(OutputOption::none(), FormatOptions::default())
+	} else {
+		(config.format.output_options(), config.format.format_options())
+	};
+	if let Some(mut stylesheet) = result.output {
+		let mut pipeline = Pipeline::new();
+		for pass in config.transform.passes.iter().flatten() {
+			let added = pipeline.add_named(pass);
+			debug_assert!(added, "transform passes are checked when loading the config");
+		}
+		if let Some(targets) = config.browser_targets() {
+			pipeline.extend(Pipeline::downlevel(targets));
+		}
+		if minify {
+			pipeline.extend(Pipeline::minify());
+		}
+		pipeline
+			.run(&mut stylesheet.node, &allocator)
+			.expect("the passes hdx has don't run after each other in a cycle");
+		let mut str = String::new();
+		if source_map {
+			let mut writer = SourceMapWriter::with_format_options(&mut str, opts, format);
+			stylesheet.write_css(&mut writer).unwrap();
+			file.source_map = Some(writer.source_map(&path.display().to_string(), source_text));
+		} else {
+			let mut writer = BaseCssWriter::with_format_options(&mut str, opts, format);
+			stylesheet.write_css(&mut writer).unwrap();
+		}
+		file.output = Some(str);
+	}
+	let (errors, warnings) = config.lint.apply(result.errors, result.warnings);
+	file.errors = errors.len();
+	file.warnings = warnings.len();
+	let file_name = path.display().to_string();
+	file.reports = render_diagnostics(&file_name, source_text, errors, Severity::Error);
+	file.reports.extend(render_diagnostics(&file_name, source_text, warnings, Severity::Warning));
+	file
+}
+
+// Links the CSS being written to `out` (or stdout) to its source map, with a comment at the end. Unless the map is
+// inline, it is written next to the output as `<out>.map`.
+pub fn link_source_map(
+	css: &str,
+	source_map: &SourceMap,
+	source: &Path,
+	out: Option<&Path>,
+	mode: SourceMapMode,
+) -> std::io::Result<String> {
+	let mut source_map = source_map.clone();
+	// Stdin has no path to point to, but the map still carries its content.
+	if let Some(out) = out.filter(|_| source.is_file()) {
+		let dir = out.parent().unwrap_or(Path::new(""));
+		source_map.sources = vec![relative_path(source, dir).to_string_lossy().replace('\\', "/")];
+	}
+	let comment = match (mode, out) {
+		(SourceMapMode::File, Some(out)) => {
+			let mut map_path = out.as_os_str().to_owned();
+			map_path.push(".map");
+			let map_path = PathBuf::from(map_path);
+			source_map.file = out.file_name().map(|name| name.to_string_lossy().into_owned());
+			write_output(&map_path, &source_map.to_json())?;
+			SourceMap::url_comment(&map_path.file_name().unwrap_or_default().to_string_lossy())
+		}
+		_ => source_map.to_inline_comment(),
+	};
+	Ok(format!("{css}\n{comment}"))
+}
+
+pub fn run(args: CompileArgs, verbose: bool) -> ExitCode {
+	let (inputs, configs) = resolve_inputs(&args.input);
+	if inputs.len() > 1 && args.out_dir.is_none() {
+		Cli::command()
+			.error(ErrorKind::MissingRequiredArgument, "--out-dir is required when processing more than one file")
+			.exit();
+	}
+	if matches!(inputs.as_slice(), [Input::Stdin]) && args.out_dir.is_some() {
+		Cli::command().error(ErrorKind::ArgumentConflict, "--out-dir cannot be used when reading from stdin").exit();
+	}
+	if args.source_map == Some(SourceMapMode::File) && args.out_dir.is_none() && args.output.is_none() {
+		Cli::command()
+			.error(
+				ErrorKind::MissingRequiredArgument,
+				"--source-map=file needs --output or --out-dir, use --source-map=inline to write to stdout",
+			)
+			.exit();
+	}
+
+	let start = std::time::Instant::now();
+	let source_map = args.source_map.is_some();
+	let mut results: Vec<FileResult> = inputs
+		.par_iter()
+		.zip(&configs)
+		.map(|(input, config)| compile(input, config, args.minify, source_map))
+		.collect();
+
+	let base = common_base(&inputs);
+	let mut stdout = std::io::stdout().lock();
+	for result in &mut results {
+		if let Some(css) = &result.output {
+			let out = if let Some(out_dir) = &args.out_dir {
+				Some(out_dir.join(result.path.strip_prefix(&base).unwrap_or(&result.path)))
+			} else {
+				args.output.as_ref().map(PathBuf::from)
+			};
+			let css = match (&result.source_map, args.source_map) {
+				(Some(source_map), Some(mode)) => link_source_map(css, source_map, &result.path, out.as_deref(), mode),
+				_ => Ok(css.clone()),
+			};
+			let written = css.and_then(|css| match &out {
+				Some(out) => write_output(out, &css),
+				None => stdout.write_all(css.as_bytes()).and_then(|_| stdout.write_all(b"\n")),
+			});
+			if let Err(err) = written {
+				result.fail_io(err);
+			}
+		}
+		for report in &result.reports {
+			eprintln!("{}", report);
+		}
+	}
+
+	if verbose {
+		print_summary(&results, start);
+	}
+	exit_code(&results)
+}
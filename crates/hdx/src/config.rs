@@ -0,0 +1,549 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt::Display,
+	io::Write,
+	path::{Component, Path, PathBuf},
+	process::ExitCode,
+};
+
+use clap::{Args, Subcommand};
+use hdx_parser::Features;
+use hdx_transform::Targets;
+use hdx_writer::{FormatOptions, HexCase, IndentStyle, OutputOption, QuotePreference, SelectorLayout};
+use miette::{Error, Severity};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	inputs::Input,
+	report::{EXIT_CONFIG, EXIT_IO_FAILURE},
+};
+
+// Config files, in order of preference should a directory contain more than one.
+pub const CONFIG_FILES: [&str; 2] = ["hdx.toml", ".hdxrc.json"];
+
+// Transform passes which can be enabled with `transform.passes`.
+pub use hdx_transform::PASSES as TRANSFORM_PASSES;
+
+/// A project config, read from a `hdx.toml` or `.hdxrc.json` file. Each input uses the config file nearest to it,
+/// merged over any config files in the directories above (until one sets `root = true`), so a subdirectory can
+/// override the settings of the project it's in.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+	/// Don't look in any parent directories for more config files.
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	pub root: bool,
+	/// Browsers to support, e.g. `["chrome >= 100", "safari >= 15"]`, which compiling rewrites what they don't support
+	/// for. See [Targets].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub targets: Option<Vec<String>>,
+	/// Globs of files to process when none are given on the command line.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub include: Option<Vec<String>>,
+	/// Globs of files to skip when expanding glob inputs.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub exclude: Option<Vec<String>>,
+	pub format: FormatConfig,
+	pub parser: ParserConfig,
+	pub lint: LintConfig,
+	pub transform: TransformConfig,
+	/// The config files this was read from, furthest first.
+	#[serde(skip)]
+	pub sources: Vec<PathBuf>,
+}
+
+macro_rules! define_format_config {
+	(
+		toggles { $( $name: ident: $opt: ident, )+ }
+		options { $( $option: ident: $ty: ty, )+ }
+	) => {
+		/// Toggles for each of the [OutputOption]s used when formatting (any left unset are enabled), and the
+		/// [FormatOptions] which lay out the output (any left unset use their default).
+		#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+		#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+		pub struct FormatConfig {
+			$(
+				#[serde(skip_serializing_if = "Option::is_none")]
+				pub $name: Option<bool>,
+			)+
+			$(
+				#[serde(skip_serializing_if = "Option::is_none")]
+				pub $option: Option<$ty>,
+			)+
+		}
+
+		impl FormatConfig {
+			fn merge(&mut self, other: Self) {
+				$(
+					if other.$name.is_some() {
+						self.$name = other.$name;
+					}
+				)+
+				$(
+					if other.$option.is_some() {
+						self.$option = other.$option;
+					}
+				)+
+			}
+
+			fn with_defaults(self) -> Self {
+				let defaults = FormatOptions::default();
+				Self {
+					$( $name: Some(self.$name.unwrap_or(true)), )+
+					$( $option: self.$option.or(defaults.$option.into()), )+
+				}
+			}
+
+			pub fn output_options(&self) -> OutputOption {
+				let mut opts = OutputOption::none();
+				$(
+					if self.$name.unwrap_or(true) {
+						opts |= OutputOption::$opt;
+					}
+				)+
+				opts
+			}
+
+			pub fn format_options(&self) -> FormatOptions {
+				let mut format = FormatOptions::default();
+				$(
+					if let Some(value) = self.$option {
+						format.$option = value.into();
+					}
+				)+
+				format
+			}
+		}
+	};
+}
+
+define_format_config! {
+	toggles {
+		nesting: Nesting,
+		whitespace: Whitespace,
+		comments: Comments,
+		trailing: Trailing,
+		inconsistent_quotes: InconsistentQuotes,
+		quoted_ident_like_strings: QuotedIdentLikeStrings,
+		redundant_rules: RedundantRules,
+		redundant_declarations: RedundantDeclarations,
+		redundant_shorthand_values: RedundantShorthandValues,
+		redundant_default_values: RedundantDefaultValues,
+		redundant_boolean_media_features: RedundantBooleanMediaFeatures,
+		expanded_numbers: ExpandedNumbers,
+	}
+	options {
+		indent_style: IndentStyle,
+		indent_width: u8,
+		max_line_width: u32,
+		quotes: QuotePreference,
+		blank_line_between_rules: bool,
+		selector_layout: SelectorLayout,
+		hex_case: HexCase,
+	}
+}
+
+macro_rules! define_parser_config {
+	( $( $name: ident: $feature: ident, )+ ) => {
+		/// Toggles for the parser [Features] (any left unset are disabled), which every command parses with.
+		#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+		#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+		pub struct ParserConfig {
+			$(
+				#[serde(skip_serializing_if = "Option::is_none")]
+				pub $name: Option<bool>,
+			)+
+		}
+
+		impl ParserConfig {
+			fn merge(&mut self, other: Self) {
+				$(
+					if other.$name.is_some() {
+						self.$name = other.$name;
+					}
+				)+
+			}
+
+			fn with_defaults(self) -> Self {
+				Self { $( $name: Some(self.$name.unwrap_or(false)), )+ }
+			}
+
+			pub fn features(&self) -> Features {
+				let mut features = Features::none();
+				$(
+					if self.$name.unwrap_or(false) {
+						features |= Features::$feature;
+					}
+				)+
+				features
+			}
+		}
+	};
+}
+
+define_parser_config! {
+	strict: Strict,
+	allow_ie_hacks: AllowIeHacks,
+	allow_custom_media: AllowCustomMedia,
+	scss: Scss,
+	no_vendor_prefixes: NoVendorPrefixes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleSeverity {
+	Off,
+	Warn,
+	Error,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+	/// Severity overrides, keyed by diagnostic code. Codes can be written in full (`hdx_parser::UnknownRule`) or
+	/// without the crate prefix (`UnknownRule`).
+	pub rules: BTreeMap<String, RuleSeverity>,
+}
+
+impl LintConfig {
+	fn merge(&mut self, other: Self) {
+		self.rules.extend(other.rules);
+	}
+
+	/// The severity to report a diagnostic at, or `None` if its rule has been turned off.
+	pub fn severity(&self, code: Option<&str>, default: Severity) -> Option<Severity> {
+		let rule = code.and_then(|code| {
+			self.rules.get(code).or_else(|| code.rsplit_once("::").and_then(|(_, name)| self.rules.get(name)))
+		});
+		match rule {
+			None => Some(default),
+			Some(RuleSeverity::Off) => None,
+			Some(RuleSeverity::Warn) => Some(Severity::Warning),
+			Some(RuleSeverity::Error) => Some(Severity::Error),
+		}
+	}
+
+	/// Sorts errors and warnings by their configured severity, dropping any which are turned off.
+	pub fn apply(&self, errors: Vec<Error>, warnings: Vec<Error>) -> (Vec<Error>, Vec<Error>) {
+		let mut out = (vec![], vec![]);
+		let diagnostics = errors.into_iter().map(|e| (e, Severity::Error));
+		for (diagnostic, default) in diagnostics.chain(warnings.into_iter().map(|w| (w, Severity::Warning))) {
+			match self.severity(diagnostic.code().map(|c| c.to_string()).as_deref(), default) {
+				Some(Severity::Error) => out.0.push(diagnostic),
+				Some(_) => out.1.push(diagnostic),
+				None => {}
+			}
+		}
+		out
+	}
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransformConfig {
+	/// Transform passes to run when compiling, in order unless one has to run after another. See [TRANSFORM_PASSES].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub passes: Option<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct ConfigError {
+	path: PathBuf,
+	message: String,
+}
+
+impl Display for ConfigError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.path.display(), self.message)
+	}
+}
+
+impl Config {
+	fn load(path: &Path) -> Result<Self, ConfigError> {
+		let error = |message: String| ConfigError { path: path.to_path_buf(), message };
+		let text = std::fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+		let mut config: Config = if path.extension().is_some_and(|ext| ext == "json") {
+			serde_json::from_str(&text).map_err(|err| error(err.to_string()))?
+		} else {
+			toml::from_str(&text).map_err(|err| error(err.to_string()))?
+		};
+		for target in config.targets.iter().flatten() {
+			parse_target(target).map_err(error)?;
+		}
+		for pass in config.transform.passes.iter().flatten() {
+			if !TRANSFORM_PASSES.contains(&pass.as_str()) {
+				return Err(error(format!(
+					"unknown transform pass `{pass}`, expected one of: {}",
+					TRANSFORM_PASSES.join(", ")
+				)));
+			}
+		}
+		// Globs are relative to the config file they're written in.
+		let dir = path.parent().unwrap_or(Path::new(""));
+		for globs in [&mut config.include, &mut config.exclude].into_iter().flatten() {
+			for glob in globs.iter_mut() {
+				if let Err(err) = glob::Pattern::new(glob) {
+					return Err(error(format!("{glob}: {err}")));
+				}
+				*glob = dir.join(&*glob).to_string_lossy().into_owned();
+			}
+		}
+		config.sources.push(path.to_path_buf());
+		Ok(config)
+	}
+
+	fn merge(&mut self, other: Self) {
+		self.root = other.root;
+		if other.targets.is_some() {
+			self.targets = other.targets;
+		}
+		if other.include.is_some() {
+			self.include = other.include;
+		}
+		if other.exclude.is_some() {
+			self.exclude = other.exclude;
+		}
+		if other.transform.passes.is_some() {
+			self.transform.passes = other.transform.passes;
+		}
+		self.format.merge(other.format);
+		self.parser.merge(other.parser);
+		self.lint.merge(other.lint);
+		self.sources.extend(other.sources);
+	}
+
+	/// Fills in the defaults for anything left unset, to show the config as it will be used.
+	fn with_defaults(self) -> Self {
+		Self {
+			targets: Some(self.targets.unwrap_or_default()),
+			include: Some(self.include.unwrap_or_default()),
+			exclude: Some(self.exclude.unwrap_or_default()),
+			format: self.format.with_defaults(),
+			parser: self.parser.with_defaults(),
+			transform: TransformConfig { passes: Some(self.transform.passes.unwrap_or_default()) },
+			..self
+		}
+	}
+
+	/// The oldest version of each browser in `targets`, or `None` if there aren't any targets.
+	pub fn browser_targets(&self) -> Option<Targets> {
+		let mut targets = Targets::default();
+		for (browser, version) in self.targets.as_ref()?.iter().flat_map(|target| parse_target(target)) {
+			let oldest = match browser {
+				"chrome" => &mut targets.chrome,
+				"edge" => &mut targets.edge,
+				"firefox" => &mut targets.firefox,
+				"ie" => &mut targets.ie,
+				_ => &mut targets.safari,
+			};
+			*oldest = Some(oldest.map_or(version, |oldest| oldest.min(version)));
+		}
+		Some(targets)
+	}
+
+	pub fn is_excluded(&self, path: &Path) -> bool {
+		let path = absolute(path);
+		self.exclude.iter().flatten().any(|glob| glob::Pattern::new(glob).is_ok_and(|glob| glob.matches_path(&path)))
+	}
+}
+
+// Browsers which can be targeted, which are those in [Targets].
+const BROWSERS: [&str; 5] = ["chrome", "edge", "firefox", "ie", "safari"];
+
+// Reads a target such as `chrome >= 100`, or `chrome 100`, into the browser and its oldest version to support.
+fn parse_target(target: &str) -> Result<(&'static str, u32), String> {
+	let (name, version) = match target.split_whitespace().collect::<Vec<_>>()[..] {
+		[name, ">=", version] | [name, version] => (name, version),
+		_ => return Err(format!("target `{target}` should be a browser and version, such as `chrome >= 100`")),
+	};
+	let Some(browser) = BROWSERS.into_iter().find(|browser| browser.eq_ignore_ascii_case(name)) else {
+		return Err(format!("unknown browser `{name}` in target `{target}`, expected one of: {}", BROWSERS.join(", ")));
+	};
+	match version.parse() {
+		Ok(version) => Ok((browser, version)),
+		Err(_) => Err(format!("target `{target}` should have a whole version number, such as `chrome >= 100`")),
+	}
+}
+
+/// Finds and caches the config for each directory, so inputs which share a directory only read its config once.
+#[derive(Default)]
+pub struct ConfigResolver {
+	dirs: HashMap<PathBuf, Config>,
+}
+
+impl ConfigResolver {
+	pub fn resolve(&mut self, input: &Input) -> Result<Config, ConfigError> {
+		let path = match input {
+			Input::Stdin => absolute(Path::new("")),
+			Input::File(path) => absolute(path),
+		};
+		if path.is_dir() {
+			self.resolve_dir(&path)
+		} else {
+			self.resolve_dir(path.parent().unwrap_or(&path))
+		}
+	}
+
+	fn resolve_dir(&mut self, dir: &Path) -> Result<Config, ConfigError> {
+		if let Some(config) = self.dirs.get(dir) {
+			return Ok(config.clone());
+		}
+		let own = CONFIG_FILES.iter().map(|name| dir.join(name)).find(|file| file.is_file());
+		let config = match own.map(|file| Config::load(&file)).transpose()? {
+			Some(own) if own.root => own,
+			own => {
+				let mut config = match dir.parent() {
+					Some(parent) => self.resolve_dir(parent)?,
+					None => Config::default(),
+				};
+				if let Some(own) = own {
+					config.merge(own);
+				}
+				config
+			}
+		};
+		self.dirs.insert(dir.to_path_buf(), config.clone());
+		Ok(config)
+	}
+}
+
+// Makes a path absolute against the current directory, without touching the file system to resolve symlinks.
+pub fn absolute(path: &Path) -> PathBuf {
+	let path =
+		if path.is_absolute() { path.to_path_buf() } else { std::env::current_dir().unwrap_or_default().join(path) };
+	let mut normalized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => {
+				normalized.pop();
+			}
+			component => normalized.push(component),
+		}
+	}
+	normalized
+}
+
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+	#[clap(subcommand)]
+	command: ConfigCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+	/// Print the config which applies to a file or directory, merged from every config file above it
+	Print {
+		#[clap(value_parser, default_value = ".")]
+		path: PathBuf,
+	},
+}
+
+pub fn run(args: ConfigArgs) -> ExitCode {
+	match args.command {
+		ConfigCommand::Print { path } => {
+			let config = match ConfigResolver::default().resolve(&Input::File(path)) {
+				Ok(config) => config,
+				Err(err) => {
+					eprintln!("{}", err);
+					return ExitCode::from(EXIT_CONFIG);
+				}
+			};
+			let mut out = String::new();
+			if config.sources.is_empty() {
+				out.push_str("# No config files found, using the defaults\n");
+			}
+			for source in &config.sources {
+				out.push_str(&format!("# {}\n", source.display()));
+			}
+			out.push_str(&toml::to_string_pretty(&config.with_defaults()).unwrap());
+			if std::io::stdout().lock().write_all(out.as_bytes()).is_err() {
+				return ExitCode::from(EXIT_IO_FAILURE);
+			}
+			ExitCode::SUCCESS
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_merge() {
+		let mut config: Config = toml::from_str(
+			r#"
+			targets = ["chrome >= 100"]
+			[format]
+			comments = false
+			trailing = false
+			[lint.rules]
+			UnknownRule = "off"
+			"#,
+		)
+		.unwrap();
+		config.merge(
+			toml::from_str(
+				r#"
+				[format]
+				trailing = true
+				[lint.rules]
+				UnknownRule = "error"
+				"#,
+			)
+			.unwrap(),
+		);
+		assert_eq!(config.targets, Some(vec!["chrome >= 100".to_string()]));
+		let opts = config.format.output_options();
+		assert!(!opts.contains(OutputOption::Comments));
+		assert!(opts.contains(OutputOption::Trailing | OutputOption::Whitespace));
+		assert_eq!(config.lint.rules.get("UnknownRule"), Some(&RuleSeverity::Error));
+	}
+
+	#[test]
+	fn test_format_options() {
+		let config: FormatConfig =
+			toml::from_str("indent-style = \"spaces\"\nindent-width = 4\nmax-line-width = 80\nquotes = \"single\"")
+				.unwrap();
+		let format = config.format_options();
+		assert_eq!(format.indent_style, IndentStyle::Spaces);
+		assert_eq!(format.indent_width, 4);
+		assert_eq!(format.max_line_width, Some(80));
+		assert_eq!(format.quotes, QuotePreference::Single);
+		assert_eq!(format.selector_layout, SelectorLayout::Wrapped);
+	}
+
+	#[test]
+	fn test_parser_features() {
+		let mut config: Config = toml::from_str("[parser]\nstrict = true\nscss = true").unwrap();
+		config.merge(toml::from_str("[parser]\nscss = false\nallow-ie-hacks = true").unwrap());
+		assert_eq!(config.parser.features(), Features::Strict | Features::AllowIeHacks);
+		assert_eq!(ParserConfig::default().features(), Features::default());
+	}
+
+	#[test]
+	fn test_lint_severity() {
+		let config: LintConfig =
+			toml::from_str("[rules]\nUnknownRule = \"off\"\n\"hdx_parser::Unexpected\" = \"warn\"").unwrap();
+		assert_eq!(config.severity(Some("hdx_parser::UnknownRule"), Severity::Error), None);
+		assert_eq!(config.severity(Some("hdx_parser::Unexpected"), Severity::Error), Some(Severity::Warning));
+		assert_eq!(config.severity(Some("hdx_parser::ExpectedEnd"), Severity::Error), Some(Severity::Error));
+		assert_eq!(config.severity(None, Severity::Warning), Some(Severity::Warning));
+	}
+
+	#[test]
+	fn test_targets() {
+		let config: Config = toml::from_str("targets = [\"ie >= 11\", \"Firefox 60\", \"firefox >= 52\"]").unwrap();
+		let targets = config.browser_targets().unwrap();
+		assert_eq!(targets, Targets { ie: Some(11), firefox: Some(52), ..Targets::default() });
+		assert_eq!(Config::default().browser_targets(), None);
+		assert!(parse_target("netscape >= 4").is_err());
+		assert!(parse_target("chrome > 100").is_err());
+		assert!(parse_target("chrome >= latest").is_err());
+	}
+
+	#[test]
+	fn test_rejects_unknown_keys() {
+		assert!(toml::from_str::<Config>("[format]\ncolours = true").is_err());
+	}
+}
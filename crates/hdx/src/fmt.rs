@@ -0,0 +1,121 @@
+use std::{io::Write, process::ExitCode};
+
+use bumpalo::Bump;
+use clap::Args;
+use hdx_ast::css::StyleSheet;
+use hdx_writer::{BaseCssWriter, WriteCss};
+use miette::Severity;
+use rayon::prelude::*;
+use similar::TextDiff;
+
+use crate::{
+	config::Config,
+	inputs::{resolve_inputs, write_output, Input},
+	report::{print_summary, render_diagnostics, FileResult, EXIT_IO_FAILURE, EXIT_PARSE_ERRORS},
+};
+
+#[derive(Debug, Args)]
+#[clap(
+	after_help = "Exit codes: 0 success, 1 parse errors or unformatted files (with --check), 74 I/O failure, 78 invalid config"
+)]
+pub struct FmtArgs {
+	/// Files or glob patterns to format. Use `-`, or pipe input, to format stdin to stdout
+	#[clap(value_parser)]
+	pub input: Vec<String>,
+	/// Don't write any files, instead print a diff of each file which isn't formatted and exit non-zero
+	#[clap(long, value_parser)]
+	pub check: bool,
+}
+
+struct Formatted {
+	file: FileResult,
+	source_text: String,
+}
+
+impl Formatted {
+	fn changed(&self) -> bool {
+		self.file.output.as_ref().is_some_and(|css| css != &self.source_text)
+	}
+
+	fn diff(&self) -> String {
+		let name = self.file.path.display().to_string();
+		let formatted = self.file.output.as_deref().unwrap_or_default();
+		TextDiff::from_lines(self.source_text.as_str(), formatted).unified_diff().header(&name, &name).to_string()
+	}
+}
+
+fn format(input: &Input, config: &Config) -> Formatted {
+	let source_text = match input.read() {
+		Ok(source_text) => source_text,
+		Err(err) => return Formatted { file: FileResult::io_failure(input.path(), err), source_text: String::new() },
+	};
+	let mut file = FileResult::new(input.path());
+	{
+		let allocator = Bump::default();
+		let result = hdx_parser::Parser::new(&allocator, source_text.as_str(), config.parser.features())
+			.parse_with::<StyleSheet>();
+		// Files which don't parse are left as they are, even if the lint config turns their errors down.
+		if result.errors.is_empty() {
+			file.output = result.output.map(|stylesheet| {
+				let mut str = String::new();
+				let mut writer = BaseCssWriter::with_format_options(
+					&mut str,
+					config.format.output_options(),
+					config.format.format_options(),
+				);
+				stylesheet.write_css(&mut writer).unwrap();
+				str
+			});
+		}
+		let (errors, warnings) = config.lint.apply(result.errors, result.warnings);
+		file.errors = errors.len();
+		file.warnings = warnings.len();
+		let file_name = input.path().display().to_string();
+		file.reports = render_diagnostics(&file_name, &source_text, errors, Severity::Error);
+		file.reports.extend(render_diagnostics(&file_name, &source_text, warnings, Severity::Warning));
+	}
+	Formatted { file, source_text }
+}
+
+pub fn run(args: FmtArgs, verbose: bool) -> ExitCode {
+	let (inputs, configs) = resolve_inputs(&args.input);
+	let start = std::time::Instant::now();
+	let mut formatted: Vec<Formatted> =
+		inputs.par_iter().zip(&configs).map(|(input, config)| format(input, config)).collect();
+
+	let mut stdout = std::io::stdout().lock();
+	let mut unformatted = 0;
+	for (input, result) in inputs.iter().zip(formatted.iter_mut()) {
+		if result.changed() {
+			unformatted += 1;
+		}
+		if let Some(css) = &result.file.output {
+			let written = match input {
+				_ if args.check && result.changed() => stdout.write_all(result.diff().as_bytes()),
+				_ if args.check => Ok(()),
+				Input::Stdin => stdout.write_all(css.as_bytes()),
+				Input::File(path) if result.changed() => write_output(path, css),
+				Input::File(_) => Ok(()),
+			};
+			if let Err(err) = written {
+				result.file.fail_io(err);
+			}
+		}
+		for report in &result.file.reports {
+			eprintln!("{}", report);
+		}
+	}
+
+	let results: Vec<FileResult> = formatted.into_iter().map(|f| f.file).collect();
+	if verbose {
+		print_summary(&results, start);
+		eprintln!("{} file(s) {}", unformatted, if args.check { "need formatting" } else { "formatted" });
+	}
+	if results.iter().any(|r| r.io_failed) {
+		ExitCode::from(EXIT_IO_FAILURE)
+	} else if results.iter().any(|r| r.errors > 0) || (args.check && unformatted > 0) {
+		ExitCode::from(EXIT_PARSE_ERRORS)
+	} else {
+		ExitCode::SUCCESS
+	}
+}
@@ -0,0 +1,130 @@
+use std::{
+	collections::HashSet,
+	io::{IsTerminal, Read},
+	path::{Component, Path, PathBuf},
+};
+
+use clap::{error::ErrorKind, CommandFactory};
+
+use crate::{
+	config::{absolute, Config, ConfigResolver},
+	report::EXIT_CONFIG,
+	Cli,
+};
+
+pub enum Input {
+	Stdin,
+	File(PathBuf),
+}
+
+impl Input {
+	pub fn path(&self) -> &Path {
+		match self {
+			Self::Stdin => Path::new("<stdin>"),
+			Self::File(path) => path,
+		}
+	}
+
+	pub fn read(&self) -> std::io::Result<String> {
+		match self {
+			Self::Stdin => {
+				let mut source_text = String::new();
+				std::io::stdin().read_to_string(&mut source_text)?;
+				Ok(source_text)
+			}
+			Self::File(path) => std::fs::read_to_string(path),
+		}
+	}
+}
+
+/// Resolves the positional inputs into a list of files, expanding any glob patterns, along with the config for each.
+/// With no inputs this uses the `include` globs of the config, or if there are none, reads from stdin if input is being
+/// piped in. A lone `-` always reads from stdin. Files matched by a glob are skipped if their config excludes them.
+pub fn resolve_inputs(inputs: &[String]) -> (Vec<Input>, Vec<Config>) {
+	let mut resolver = ConfigResolver::default();
+	let mut config_for = |input: &Input| match resolver.resolve(input) {
+		Ok(config) => config,
+		Err(err) => {
+			eprintln!("{}", err);
+			std::process::exit(EXIT_CONFIG.into())
+		}
+	};
+	let patterns = match inputs {
+		[] => match config_for(&Input::Stdin).include {
+			Some(include) if !include.is_empty() => include,
+			// CI, git hooks and editors run without a terminal, so stdin is only read when there's nothing to include.
+			_ if !std::io::stdin().is_terminal() => return (vec![Input::Stdin], vec![config_for(&Input::Stdin)]),
+			_ => Cli::command()
+				.error(
+					ErrorKind::MissingRequiredArgument,
+					"Need an input file, input on stdin, or `include` in a config",
+				)
+				.exit(),
+		},
+		[input] if input == "-" => return (vec![Input::Stdin], vec![config_for(&Input::Stdin)]),
+		inputs if inputs.iter().any(|input| input == "-") => {
+			Cli::command().error(ErrorKind::ArgumentConflict, "stdin (`-`) cannot be combined with other inputs").exit()
+		}
+		inputs => inputs.to_vec(),
+	};
+	let mut files = vec![];
+	for input in &patterns {
+		if input.contains(['*', '?', '[']) {
+			match glob::glob(input) {
+				Ok(paths) => files.extend(paths.flatten().filter(|p| p.is_file()).map(|p| (p, true))),
+				Err(err) => Cli::command().error(ErrorKind::ValueValidation, format!("{input}: {err}")).exit(),
+			}
+		} else {
+			files.push((PathBuf::from(input), false));
+		}
+	}
+	let cwd = absolute(Path::new(""));
+	let mut seen = HashSet::new();
+	let mut resolved = (vec![], vec![]);
+	for (file, globbed) in files {
+		// Globs from a config are absolute, so show them relative to the current directory where possible.
+		let file = file.strip_prefix(&cwd).map(Path::to_path_buf).unwrap_or(file);
+		if !seen.insert(absolute(&file)) {
+			continue;
+		}
+		let input = Input::File(file);
+		let config = config_for(&input);
+		if globbed && config.is_excluded(input.path()) {
+			continue;
+		}
+		resolved.0.push(input);
+		resolved.1.push(config);
+	}
+	resolved
+}
+
+// The deepest directory containing every file, used to mirror the input tree into `--out-dir`.
+pub fn common_base(inputs: &[Input]) -> PathBuf {
+	common_dir(inputs.iter().map(|input| input.path().parent().unwrap_or(Path::new(""))))
+}
+
+// The deepest directory which all of the given directories are within.
+pub fn common_dir<'p>(dirs: impl IntoIterator<Item = &'p Path>) -> PathBuf {
+	let mut base: Option<PathBuf> = None;
+	for dir in dirs {
+		base = Some(match base {
+			None => dir.to_path_buf(),
+			Some(base) => base.components().zip(dir.components()).take_while(|(a, b)| a == b).map(|(a, _)| a).collect(),
+		});
+	}
+	base.unwrap_or_default()
+}
+
+// The path to get to `path` from `dir`, e.g. for a source map to refer to its source.
+pub fn relative_path(path: &Path, dir: &Path) -> PathBuf {
+	let (path, dir) = (absolute(path), absolute(dir));
+	let common = path.components().zip(dir.components()).take_while(|(a, b)| a == b).count();
+	dir.components().skip(common).map(|_| Component::ParentDir).chain(path.components().skip(common)).collect()
+}
+
+pub fn write_output(path: &Path, css: &str) -> std::io::Result<()> {
+	if let Some(parent) = path.parent() {
+		std::fs::create_dir_all(parent)?;
+	}
+	std::fs::write(path, css.as_bytes())
+}
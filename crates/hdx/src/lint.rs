@@ -0,0 +1,229 @@
+use std::{io::Write, process::ExitCode};
+
+use bumpalo::Bump;
+use clap::{Args, ValueEnum};
+use hdx_ast::css::StyleSheet;
+use hdx_parser::LineIndex;
+use miette::{Error, Severity};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{
+	config::Config,
+	inputs::{resolve_inputs, Input},
+	report::{exit_code, print_summary, render_diagnostics, FileResult},
+};
+
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub enum LintFormat {
+	/// Human readable reports, with the offending source highlighted
+	#[default]
+	Pretty,
+	/// A JSON array of diagnostics
+	Json,
+	/// GitHub Actions workflow commands, which annotate the lines in a pull request
+	Github,
+	/// Checkstyle XML, as understood by most CI servers
+	Checkstyle,
+}
+
+#[derive(Debug, Args)]
+#[clap(after_help = "Exit codes: 0 success, 1 errors, 3 warnings only, 74 I/O failure, 78 invalid config")]
+pub struct LintArgs {
+	/// Files or glob patterns to lint. Use `-`, or pipe input, to lint stdin
+	#[clap(value_parser)]
+	pub input: Vec<String>,
+	/// How to report the diagnostics
+	#[clap(long, value_enum, default_value_t)]
+	pub format: LintFormat,
+}
+
+#[derive(Debug, Serialize)]
+struct LintDiagnostic {
+	file: String,
+	// The diagnostic code, e.g. `hdx_parser::UnknownRule`
+	rule: String,
+	severity: &'static str,
+	message: String,
+	help: Option<String>,
+	offset: usize,
+	length: usize,
+	line: usize,
+	column: usize,
+	end_line: usize,
+	end_column: usize,
+}
+
+impl LintDiagnostic {
+	fn new(file: &str, line_index: &LineIndex, error: &Error, severity: Severity) -> Self {
+		let (offset, length) =
+			error.labels().and_then(|mut labels| labels.next()).map_or((0, 0), |label| (label.offset(), label.len()));
+		let (line, column) = line_col(line_index, offset);
+		let (end_line, end_column) = line_col(line_index, offset + length);
+		Self {
+			file: file.to_string(),
+			rule: error.code().map(|code| code.to_string()).unwrap_or_default(),
+			severity: if severity == Severity::Error { "error" } else { "warning" },
+			message: error.to_string(),
+			help: error.help().map(|help| help.to_string()),
+			offset,
+			length,
+			line,
+			column,
+			end_line,
+			end_column,
+		}
+	}
+}
+
+// 1-indexed line & column (in UTF-16 code units, as editors count them) of a byte offset
+fn line_col(line_index: &LineIndex, offset: usize) -> (usize, usize) {
+	let line_col = line_index.line_col_utf16(offset as u32);
+	(line_col.line as usize + 1, line_col.col as usize + 1)
+}
+
+struct Linted {
+	file: FileResult,
+	diagnostics: Vec<LintDiagnostic>,
+}
+
+fn lint(input: &Input, config: &Config, format: LintFormat) -> Linted {
+	let source_text = match input.read() {
+		Ok(source_text) => source_text,
+		Err(err) => return Linted { file: FileResult::io_failure(input.path(), err), diagnostics: vec![] },
+	};
+	let file_name = input.path().display().to_string();
+	let mut file = FileResult::new(input.path());
+	let allocator = Bump::default();
+	let result =
+		hdx_parser::Parser::new(&allocator, source_text.as_str(), config.parser.features()).parse_with::<StyleSheet>();
+	let (errors, warnings) = config.lint.apply(result.errors, result.warnings);
+	file.errors = errors.len();
+	file.warnings = warnings.len();
+	let line_index = LineIndex::new(&source_text);
+	let diagnostics = errors
+		.iter()
+		.map(|err| LintDiagnostic::new(&file_name, &line_index, err, Severity::Error))
+		.chain(warnings.iter().map(|warn| LintDiagnostic::new(&file_name, &line_index, warn, Severity::Warning)))
+		.collect();
+	if matches!(format, LintFormat::Pretty) {
+		file.reports = render_diagnostics(&file_name, &source_text, errors, Severity::Error);
+		file.reports.extend(render_diagnostics(&file_name, &source_text, warnings, Severity::Warning));
+	}
+	Linted { file, diagnostics }
+}
+
+// https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions#setting-an-error-message
+fn github_escape(str: &str, property: bool) -> String {
+	let str = str.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A");
+	if property {
+		str.replace(':', "%3A").replace(',', "%2C")
+	} else {
+		str
+	}
+}
+
+fn xml_escape(str: &str) -> String {
+	str.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+fn write_report<W: Write>(out: &mut W, format: LintFormat, linted: &[Linted]) -> std::io::Result<()> {
+	match format {
+		LintFormat::Pretty => {
+			for linted in linted {
+				for report in &linted.file.reports {
+					writeln!(out, "{}", report)?;
+				}
+			}
+		}
+		LintFormat::Json => {
+			let diagnostics: Vec<&LintDiagnostic> = linted.iter().flat_map(|l| &l.diagnostics).collect();
+			serde_json::to_writer_pretty(&mut *out, &diagnostics)?;
+			writeln!(out)?;
+		}
+		LintFormat::Github => {
+			for d in linted.iter().flat_map(|l| &l.diagnostics) {
+				writeln!(
+					out,
+					"::{} file={},line={},col={},endLine={},endColumn={},title={}::{}",
+					d.severity,
+					github_escape(&d.file, true),
+					d.line,
+					d.column,
+					d.end_line,
+					d.end_column,
+					github_escape(&d.rule, true),
+					github_escape(&d.message, false),
+				)?;
+			}
+		}
+		LintFormat::Checkstyle => {
+			writeln!(out, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+			writeln!(out, r#"<checkstyle version="4.3">"#)?;
+			for linted in linted {
+				writeln!(out, r#"<file name="{}">"#, xml_escape(&linted.file.path.display().to_string()))?;
+				for d in &linted.diagnostics {
+					writeln!(
+						out,
+						r#"<error line="{}" column="{}" severity="{}" message="{}" source="{}"/>"#,
+						d.line,
+						d.column,
+						d.severity,
+						xml_escape(&d.message),
+						xml_escape(&d.rule),
+					)?;
+				}
+				writeln!(out, "</file>")?;
+			}
+			writeln!(out, "</checkstyle>")?;
+		}
+	}
+	Ok(())
+}
+
+pub fn run(args: LintArgs, verbose: bool) -> ExitCode {
+	let (inputs, configs) = resolve_inputs(&args.input);
+	let start = std::time::Instant::now();
+	let mut linted: Vec<Linted> =
+		inputs.par_iter().zip(&configs).map(|(input, config)| lint(input, config, args.format)).collect();
+
+	// I/O failures have no diagnostic to report, so always go to stderr.
+	for linted in &mut linted {
+		if linted.file.io_failed {
+			for report in linted.file.reports.drain(..) {
+				eprintln!("{}", report);
+			}
+		}
+	}
+	let mut stdout = std::io::stdout().lock();
+	if let Err(err) = write_report(&mut stdout, args.format, &linted) {
+		eprintln!("{}", err);
+		return ExitCode::from(crate::report::EXIT_IO_FAILURE);
+	}
+
+	let results: Vec<FileResult> = linted.into_iter().map(|l| l.file).collect();
+	if verbose {
+		print_summary(&results, start);
+	}
+	exit_code(&results)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_line_col() {
+		let line_index = LineIndex::new("a{}");
+		assert_eq!(line_col(&line_index, 0), (1, 1));
+		assert_eq!(line_col(&line_index, 2), (1, 3));
+		assert_eq!(line_col(&line_index, 99), (1, 4));
+		assert_eq!(line_col(&LineIndex::new("a{\n\tb:c\n}"), 4), (2, 2));
+	}
+
+	#[test]
+	fn test_github_escape() {
+		assert_eq!(github_escape("a:b,c%", true), "a%3Ab%2Cc%25");
+		assert_eq!(github_escape("a:b\nc", false), "a:b%0Ac");
+	}
+}
@@ -1,58 +1,48 @@
-use bumpalo::Bump;
-use clap::Parser;
-use hdx_ast::css::StyleSheet;
-use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
-use miette::{GraphicalReportHandler, GraphicalTheme, NamedSource};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+mod compile;
+mod config;
+mod fmt;
+mod inputs;
+mod lint;
+mod report;
+mod watch;
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about, long_about = None)]
-struct Cli {
-	#[clap(value_parser)]
-	input: Vec<String>,
-	#[clap(short, long, value_parser)]
-	minify: bool,
-	#[clap(short, long, group = "output_file", value_parser)]
-	output: Option<String>,
+#[clap(args_conflicts_with_subcommands = true)]
+#[clap(after_help = "Exit codes: 0 success, 1 parse errors, 3 warnings only, 74 I/O failure, 78 invalid config")]
+pub struct Cli {
+	#[clap(subcommand)]
+	command: Option<Command>,
+	#[clap(flatten)]
+	compile: compile::CompileArgs,
+	/// Print a summary of the processed files, and how long it took, to stderr
+	#[clap(short, long, global = true, value_parser)]
+	verbose: bool,
 }
 
-fn main() {
-	let args = Cli::parse();
-
-	if args.input.is_empty() {
-		panic!("Need input file");
-	}
-
-	if args.input.len() > 1 {
-		todo!("Can't handle multiple files yet")
-	}
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Format files in place
+	Fmt(fmt::FmtArgs),
+	/// Report errors and warnings found in files
+	Lint(lint::LintArgs),
+	/// Inspect the `hdx.toml` or `.hdxrc.json` config files which apply to a path
+	Config(config::ConfigArgs),
+	/// Compile files into an output directory, then keep rebuilding them as they change
+	Watch(watch::WatchArgs),
+}
 
-	let file_name = args.input.first().unwrap();
-	let source_text = std::fs::read_to_string(file_name).unwrap();
-	let allocator = Bump::default();
-	let result = hdx_parser::Parser::new(&allocator, source_text.as_str(), hdx_parser::Features::default())
-		.parse_with::<StyleSheet>();
-	{
-		let start = std::time::Instant::now();
-		let mut str = String::new();
-		let opts = if args.minify { OutputOption::none() } else { OutputOption::all() };
-		let mut writer = BaseCssWriter::new(&mut str, opts);
-		if let Some(stylesheet) = &result.output {
-			stylesheet.write_css(&mut writer).unwrap();
-			if let Some(file) = args.output {
-				std::fs::write(file, str.as_bytes()).unwrap();
-			} else {
-				println!("{}", str);
-				eprintln!("Slurped up CSS in {:?}! Neat!", start.elapsed());
-			}
-		} else {
-			let handler = GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor());
-			for err in result.errors {
-				let mut report = String::new();
-				let named = NamedSource::new(file_name, source_text.clone());
-				let err = err.with_source_code(named);
-				handler.render_report(&mut report, err.as_ref()).unwrap();
-				println!("{}", report);
-			}
-		}
+fn main() -> ExitCode {
+	let args = Cli::parse();
+	match args.command {
+		Some(Command::Fmt(fmt)) => fmt::run(fmt, args.verbose),
+		Some(Command::Lint(lint)) => lint::run(lint, args.verbose),
+		Some(Command::Config(config)) => config::run(config),
+		Some(Command::Watch(watch)) => watch::run(watch),
+		None => compile::run(args.compile, args.verbose),
 	}
 }
@@ -0,0 +1,89 @@
+use std::{
+	path::{Path, PathBuf},
+	process::ExitCode,
+};
+
+use hdx_parser::Diagnostic;
+use hdx_writer::SourceMap;
+use miette::{Error, GraphicalReportHandler, GraphicalTheme, NamedSource, Report, Severity};
+
+// Exit codes, from most to least severe. Usage errors exit with 2, courtesy of clap.
+pub const EXIT_IO_FAILURE: u8 = 74;
+pub const EXIT_PARSE_ERRORS: u8 = 1;
+pub const EXIT_WARNINGS: u8 = 3;
+pub const EXIT_CONFIG: u8 = 78;
+
+/// The outcome of running a command over a single input.
+#[derive(Default)]
+pub struct FileResult {
+	pub path: PathBuf,
+	pub output: Option<String>,
+	pub source_map: Option<SourceMap>,
+	pub reports: Vec<String>,
+	pub errors: usize,
+	pub warnings: usize,
+	pub io_failed: bool,
+}
+
+impl FileResult {
+	pub fn new(path: &Path) -> Self {
+		Self { path: path.to_path_buf(), ..Default::default() }
+	}
+
+	pub fn io_failure(path: &Path, err: std::io::Error) -> Self {
+		let mut result = Self::new(path);
+		result.fail_io(err);
+		result
+	}
+
+	pub fn fail_io(&mut self, err: std::io::Error) {
+		self.reports.push(format!("{}: {err}", self.path.display()));
+		self.io_failed = true;
+	}
+}
+
+pub fn render_diagnostics(
+	file_name: &str,
+	source_text: &str,
+	diagnostics: Vec<Error>,
+	severity: Severity,
+) -> Vec<String> {
+	let handler = GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor());
+	diagnostics
+		.into_iter()
+		.map(|err| {
+			let mut report = String::new();
+			// Parser diagnostics don't carry a severity; which list they end up in (errors or warnings) decides it.
+			let diagnostic = Report::new(Diagnostic::new(err, severity.into()))
+				.with_source_code(NamedSource::new(file_name, source_text.to_string()));
+			handler.render_report(&mut report, diagnostic.as_ref()).unwrap();
+			report
+		})
+		.collect()
+}
+
+pub fn print_summary(results: &[FileResult], start: std::time::Instant) {
+	let failed = results.iter().filter(|r| r.output.is_none()).count();
+	let errors: usize = results.iter().map(|r| r.errors).sum();
+	let warnings: usize = results.iter().map(|r| r.warnings).sum();
+	eprintln!(
+		"Slurped up {} file(s) ({} failed, {} error(s), {} warning(s)) in {:?}! Neat!",
+		results.len(),
+		failed,
+		errors,
+		warnings,
+		start.elapsed()
+	);
+}
+
+pub fn exit_code(results: &[FileResult]) -> ExitCode {
+	if results.iter().any(|r| r.io_failed) {
+		ExitCode::from(EXIT_IO_FAILURE)
+	} else if results.iter().any(|r| r.errors > 0) {
+		ExitCode::from(EXIT_PARSE_ERRORS)
+	} else if results.iter().any(|r| r.warnings > 0) {
+		ExitCode::from(EXIT_WARNINGS)
+	} else {
+		ExitCode::SUCCESS
+	}
+}
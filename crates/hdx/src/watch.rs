@@ -0,0 +1,232 @@
+use std::{
+	collections::{hash_map::DefaultHasher, HashMap},
+	hash::{Hash, Hasher},
+	path::{Path, PathBuf},
+	process::ExitCode,
+	sync::mpsc::{channel, Receiver},
+	time::{Duration, Instant},
+};
+
+use clap::Args;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+	compile::{compile_source, link_source_map, SourceMapMode},
+	config::{absolute, ConfigResolver, CONFIG_FILES},
+	inputs::{common_dir, write_output, Input},
+	report::{FileResult, EXIT_IO_FAILURE},
+};
+
+// How long to wait for more changes to arrive before rebuilding, so that saving many files at once (or an editor
+// writing a file in several steps) results in a single rebuild.
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+	/// Files or directories to watch. Directories are searched for `.css` files
+	#[clap(value_parser, required = true)]
+	pub input: Vec<PathBuf>,
+	#[clap(short, long, value_parser)]
+	pub minify: bool,
+	/// Write each result into this directory, mirroring the layout of the watched files
+	#[clap(long, value_parser)]
+	pub out_dir: PathBuf,
+	/// Generate a source map, linking the output back to the input
+	#[clap(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "file")]
+	pub source_map: Option<SourceMapMode>,
+}
+
+struct Build {
+	roots: Vec<PathBuf>,
+	base: PathBuf,
+	out_dir: PathBuf,
+	minify: bool,
+	source_map: Option<SourceMapMode>,
+	configs: ConfigResolver,
+	// Content hashes of the last build of each file, to skip any which are saved without changing.
+	hashes: HashMap<PathBuf, u64>,
+}
+
+impl Build {
+	fn new(args: &WatchArgs) -> Self {
+		let roots: Vec<PathBuf> = args.input.iter().map(|path| absolute(path)).collect();
+		let dirs = roots.iter().map(|root| if root.is_dir() { root.as_path() } else { root.parent().unwrap_or(root) });
+		let base = common_dir(dirs);
+		Self {
+			roots,
+			base,
+			out_dir: absolute(&args.out_dir),
+			minify: args.minify,
+			source_map: args.source_map,
+			configs: ConfigResolver::default(),
+			hashes: HashMap::new(),
+		}
+	}
+
+	fn is_watched(&self, path: &Path) -> bool {
+		path.extension().is_some_and(|ext| ext == "css")
+			&& !path.starts_with(&self.out_dir)
+			&& self.roots.iter().any(|root| path.starts_with(root))
+	}
+
+	// Every watched file, with directories searched recursively.
+	fn files(&self) -> Vec<PathBuf> {
+		let mut files = vec![];
+		for root in &self.roots {
+			if root.is_dir() {
+				files.extend(self.files_within(root));
+			} else if self.is_watched(root) {
+				files.push(root.clone());
+			}
+		}
+		files
+	}
+
+	fn files_within(&self, dir: &Path) -> Vec<PathBuf> {
+		let pattern = dir.join("**").join("*.css");
+		let files = glob::glob(&pattern.to_string_lossy()).into_iter().flatten().flatten();
+		files.filter(|file| self.is_watched(file)).collect()
+	}
+
+	fn out_path(&self, path: &Path) -> PathBuf {
+		self.out_dir.join(path.strip_prefix(&self.base).unwrap_or(path))
+	}
+
+	// Rebuilds a file, returning `None` if its content hasn't changed since the last build.
+	fn build(&mut self, path: &Path) -> Option<FileResult> {
+		let display = display_path(path);
+		if !path.exists() {
+			self.hashes.remove(path)?;
+			let mut result = FileResult::new(&display);
+			let out = self.out_path(path);
+			if let Err(err) = std::fs::remove_file(&out) {
+				result.fail_io(err);
+			}
+			let mut map = out.into_os_string();
+			map.push(".map");
+			// The map may not have been written, so there's nothing to report if it's missing.
+			std::fs::remove_file(map).ok();
+			return Some(result);
+		}
+		let source_text = match std::fs::read_to_string(path) {
+			Ok(source_text) => source_text,
+			Err(err) => return Some(FileResult::io_failure(&display, err)),
+		};
+		let mut hasher = DefaultHasher::new();
+		source_text.hash(&mut hasher);
+		let hash = hasher.finish();
+		if self.hashes.insert(path.to_path_buf(), hash) == Some(hash) {
+			return None;
+		}
+		let config = match self.configs.resolve(&Input::File(path.to_path_buf())) {
+			// Files named directly are always built, otherwise the config can exclude them.
+			Ok(config) if config.is_excluded(path) && !self.roots.iter().any(|root| root == path) => return None,
+			Ok(config) => config,
+			Err(err) => {
+				self.hashes.remove(path);
+				let mut result = FileResult::new(&display);
+				result.reports.push(err.to_string());
+				result.errors += 1;
+				return Some(result);
+			}
+		};
+		let mut result = compile_source(&display, &source_text, &config, self.minify, self.source_map.is_some());
+		if let Some(css) = &result.output {
+			let out = self.out_path(path);
+			let css = match (&result.source_map, self.source_map) {
+				(Some(source_map), Some(mode)) => link_source_map(css, source_map, path, Some(&out), mode),
+				_ => Ok(css.clone()),
+			};
+			if let Err(err) = css.and_then(|css| write_output(&out, &css)) {
+				result.fail_io(err);
+			}
+		}
+		if result.errors > 0 || result.io_failed {
+			// Try again next time, even if the file is saved unchanged.
+			self.hashes.remove(path);
+		}
+		Some(result)
+	}
+
+	fn build_all(&mut self, paths: Vec<PathBuf>) {
+		let start = Instant::now();
+		let results: Vec<FileResult> = paths.iter().filter_map(|path| self.build(path)).collect();
+		if results.is_empty() {
+			return;
+		}
+		for result in &results {
+			for report in &result.reports {
+				eprintln!("{}", report);
+			}
+		}
+		let failed = results.iter().filter(|r| r.errors > 0 || r.io_failed).count();
+		eprintln!(
+			"Built {} file(s){} in {:?}",
+			results.len() - failed,
+			if failed > 0 { format!(" ({failed} failed)") } else { String::new() },
+			start.elapsed()
+		);
+	}
+}
+
+// Watched paths are absolute, but are shown relative to the current directory where possible.
+fn display_path(path: &Path) -> PathBuf {
+	path.strip_prefix(absolute(Path::new(""))).unwrap_or(path).to_path_buf()
+}
+
+// Blocks until something changes, then collects every change which arrives shortly after it.
+fn next_changes(rx: &Receiver<notify::Result<Event>>) -> Option<Vec<Event>> {
+	let mut events = vec![rx.recv().ok()?];
+	while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+		events.push(event);
+	}
+	Some(events.into_iter().filter_map(|event| event.map_err(|err| eprintln!("Watch error: {err}")).ok()).collect())
+}
+
+pub fn run(args: WatchArgs) -> ExitCode {
+	let mut build = Build::new(&args);
+	let (tx, rx) = channel();
+	let mut watcher = match RecommendedWatcher::new(tx, notify::Config::default()) {
+		Ok(watcher) => watcher,
+		Err(err) => {
+			eprintln!("Could not start watching: {err}");
+			return ExitCode::from(EXIT_IO_FAILURE);
+		}
+	};
+	for root in &build.roots {
+		if let Err(err) = watcher.watch(root, RecursiveMode::Recursive) {
+			eprintln!("{}: {err}", display_path(root).display());
+			return ExitCode::from(EXIT_IO_FAILURE);
+		}
+	}
+
+	let files = build.files();
+	build.build_all(files);
+	eprintln!("Watching for changes...");
+	while let Some(events) = next_changes(&rx) {
+		let mut paths: Vec<PathBuf> = vec![];
+		let mut config_changed = false;
+		for path in events.into_iter().filter(|e| !matches!(e.kind, EventKind::Access(_))).flat_map(|e| e.paths) {
+			if path.file_name().is_some_and(|name| CONFIG_FILES.iter().any(|file| name == *file)) {
+				config_changed = true;
+			} else if build.is_watched(&path) && !paths.contains(&path) {
+				paths.push(path);
+			} else if path.is_dir() && build.roots.iter().any(|root| path.starts_with(root)) {
+				// Files can be written to a new directory before it's watched, so their changes may never arrive.
+				for file in build.files_within(&path) {
+					if !paths.contains(&file) {
+						paths.push(file);
+					}
+				}
+			}
+		}
+		if config_changed {
+			// Any file could be affected by a config change, so start over.
+			build.configs = ConfigResolver::default();
+			build.hashes.clear();
+			paths = build.files();
+		}
+		build.build_all(paths);
+	}
+	ExitCode::SUCCESS
+}
@@ -0,0 +1,100 @@
+mod helpers;
+
+use helpers::{hdx, read, write};
+
+#[test]
+fn compile_globs_into_out_dir() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "src/a.css", "a { color: red }");
+	write(dir.path(), "src/nested/b.css", "b { color: blue }");
+	write(dir.path(), "src/notes.txt", "not css");
+	let output = hdx(dir.path()).args(["--minify", "--out-dir", "dist", "src/**/*.css"]).output().unwrap();
+	assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+	assert!(output.stdout.is_empty());
+	assert_eq!(read(dir.path(), "dist/a.css"), "a{color:red}");
+	assert_eq!(read(dir.path(), "dist/nested/b.css"), "b{color:blue}");
+	assert!(!dir.path().join("dist/notes.txt").exists());
+}
+
+#[test]
+fn compile_many_files() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "a.css", "a { color: red }");
+	write(dir.path(), "b.css", "b { colr: blue; : ; }");
+	let output = hdx(dir.path()).args(["-m", "--verbose", "--out-dir", "dist", "a.css", "b.css"]).output().unwrap();
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert_eq!(read(dir.path(), "dist/a.css"), "a{color:red}");
+	// The diagnostics of every file are printed, followed by a summary of them all.
+	assert!(stderr.contains("[b.css:1:18]"), "{stderr}");
+	assert!(stderr.contains("Slurped up 2 file(s) (0 failed, 1 error(s),"), "{stderr}");
+	// More than one file can't all be written to stdout.
+	let output = hdx(dir.path()).args(["a.css", "b.css"]).output().unwrap();
+	assert_eq!(output.status.code(), Some(2));
+	assert!(String::from_utf8_lossy(&output.stderr).contains("--out-dir is required"));
+}
+
+#[test]
+fn compile_stdin() {
+	let dir = tempfile::tempdir().unwrap();
+	hdx(dir.path())
+		.args(["-m", "-"])
+		.write_stdin("a { color: red }")
+		.assert()
+		.success()
+		.stdout("a{color:red}\n")
+		.stderr("");
+	// Input piped in without any paths is read too.
+	hdx(dir.path()).arg("-m").write_stdin("b { color: blue }").assert().success().stdout("b{color:blue}\n");
+	let output = hdx(dir.path()).args(["-m", "--verbose"]).write_stdin("a{}").output().unwrap();
+	assert!(String::from_utf8_lossy(&output.stderr).starts_with("Slurped up 1 file(s)"));
+	// Without a terminal, the `include` of a config is still compiled, rather than stdin, unless `-` is given.
+	write(dir.path(), "hdx.toml", "include = [\"*.css\"]");
+	write(dir.path(), "a.css", "a { color: red }");
+	hdx(dir.path()).arg("-m").write_stdin("").assert().success().stdout("a{color:red}\n");
+	hdx(dir.path()).args(["-m", "-"]).write_stdin("b { color: blue }").assert().success().stdout("b{color:blue}\n");
+}
+
+#[test]
+fn compile_targets() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "a.css", "a { width: initial }");
+	hdx(dir.path()).arg("a.css").assert().success().stdout("a {\n\twidth: initial;\n}\n\n");
+	// IE doesn't support `initial`.
+	write(dir.path(), "hdx.toml", "targets = [\"ie >= 11\"]");
+	hdx(dir.path()).arg("a.css").assert().success().stdout("a {\n\twidth: auto;\n}\n\n");
+	write(dir.path(), "hdx.toml", "targets = [\"chrome >= 100\"]");
+	hdx(dir.path()).arg("a.css").assert().success().stdout("a {\n\twidth: initial;\n}\n\n");
+	write(dir.path(), "hdx.toml", "targets = [\"netscape >= 4\"]");
+	hdx(dir.path()).arg("a.css").assert().code(78);
+}
+
+#[test]
+fn compile_exit_codes() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "errors.css", "a { : ; }");
+	write(dir.path(), "warnings.css", "a { backgroud-color: red }");
+	hdx(dir.path()).arg("errors.css").assert().code(1);
+	hdx(dir.path()).arg("warnings.css").assert().code(3);
+	hdx(dir.path()).arg("missing.css").assert().code(74);
+	// The most severe outcome of any of the files is the one exited with.
+	hdx(dir.path()).args(["--out-dir", "dist", "warnings.css", "errors.css", "missing.css"]).assert().code(74);
+	write(dir.path(), "config/hdx.toml", "[format");
+	write(dir.path(), "config/a.css", "a {}");
+	hdx(dir.path()).arg("config/a.css").assert().code(78);
+}
+
+#[test]
+fn compile_warnings() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "a.css", "a{border:1px sollid red}");
+	let output = hdx(dir.path()).args(["-m", "a.css"]).output().unwrap();
+	assert_eq!(output.status.code(), Some(3));
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains("hdx_parser::InvalidValue"), "{stderr}");
+	assert!(stderr.contains("Did you mean 'solid'?"), "{stderr}");
+	// The severity of each warning is configured just as it is when linting.
+	write(dir.path(), "hdx.toml", "[lint.rules]\nInvalidValue = \"error\"");
+	hdx(dir.path()).args(["-m", "a.css"]).assert().code(1);
+	write(dir.path(), "hdx.toml", "[lint.rules]\nInvalidValue = \"off\"");
+	hdx(dir.path()).args(["-m", "a.css"]).assert().success().stderr("");
+}
@@ -0,0 +1,56 @@
+mod helpers;
+
+use helpers::{hdx, read, write};
+
+#[test]
+fn fmt_in_place() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "src/a.css", "a{color:red}");
+	write(dir.path(), "src/b.css", "b {\n\tcolor: blue;\n}\n");
+	hdx(dir.path()).args(["fmt", "src/*.css"]).assert().success().stdout("");
+	assert_eq!(read(dir.path(), "src/a.css"), "a {\n\tcolor: red;\n}\n");
+	assert_eq!(read(dir.path(), "src/b.css"), "b {\n\tcolor: blue;\n}\n");
+	hdx(dir.path()).arg("fmt").write_stdin("c{color:green}").assert().success().stdout("c {\n\tcolor: green;\n}\n");
+}
+
+#[test]
+fn fmt_check() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "a.css", "a{color:red}\n");
+	write(dir.path(), "b.css", "b {\n\tcolor: blue;\n}\n");
+	// Only the files which aren't formatted are shown, as a diff, and none are written.
+	hdx(dir.path())
+		.args(["fmt", "--check", "a.css", "b.css"])
+		.assert()
+		.code(1)
+		.stdout("--- a.css\n+++ a.css\n@@ -1 +1,3 @@\n-a{color:red}\n+a {\n+\tcolor: red;\n+}\n");
+	assert_eq!(read(dir.path(), "a.css"), "a{color:red}\n");
+	hdx(dir.path()).args(["fmt", "--check", "b.css"]).assert().success().stdout("");
+	write(dir.path(), "c.css", "c { : ; }");
+	hdx(dir.path()).args(["fmt", "--check", "c.css"]).assert().code(1).stdout("");
+}
+
+#[test]
+fn fmt_warnings() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "a.css", "a{border:1px sollid red}");
+	let output = hdx(dir.path()).args(["fmt", "--check", "a.css"]).output().unwrap();
+	assert_eq!(output.status.code(), Some(1));
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains("hdx_parser::InvalidValue"), "{stderr}");
+	// The severity of each warning is configured just as it is when linting.
+	write(dir.path(), "hdx.toml", "[lint.rules]\nInvalidValue = \"off\"");
+	hdx(dir.path()).args(["fmt", "a.css"]).assert().success().stderr("");
+	assert_eq!(read(dir.path(), "a.css"), "a {\n\tborder: 1px sollid red;\n}\n");
+}
+
+#[test]
+fn fmt_parser_features() {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path(), "a.css", "a{*zoom:1}");
+	hdx(dir.path()).args(["fmt", "a.css"]).assert().code(1);
+	assert_eq!(read(dir.path(), "a.css"), "a{*zoom:1}");
+	write(dir.path(), "hdx.toml", "[parser]\nallow-ie-hacks = true");
+	hdx(dir.path()).args(["fmt", "a.css"]).assert().success();
+	assert_eq!(read(dir.path(), "a.css"), "a {\n\t*zoom: 1;\n}\n");
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use assert_cmd::Command;
+
+/// The `hdx` binary, run from within `dir` so that relative paths and config files are found there.
+pub fn hdx(dir: &Path) -> Command {
+	let mut cmd = Command::cargo_bin("hdx").unwrap();
+	cmd.current_dir(dir);
+	cmd
+}
+
+/// Writes `text` to `path` within `dir`, creating any directories leading to it.
+pub fn write(dir: &Path, path: &str, text: &str) {
+	let path = dir.join(path);
+	std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+	std::fs::write(path, text).unwrap();
+}
+
+/// Reads `path` within `dir`.
+pub fn read(dir: &Path, path: &str) -> String {
+	std::fs::read_to_string(dir.join(path)).unwrap()
+}
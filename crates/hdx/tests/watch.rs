@@ -0,0 +1,69 @@
+mod helpers;
+
+use std::{
+	path::Path,
+	process::{Child, Command, Stdio},
+	time::{Duration, Instant},
+};
+
+use helpers::{hdx, read, write};
+
+// Stops `hdx watch` once the test is done with it, even if it fails.
+struct Watching(Child);
+
+impl Drop for Watching {
+	fn drop(&mut self) {
+		self.0.kill().ok();
+		self.0.wait().ok();
+	}
+}
+
+// Waits for `done` to be true, failing with `waiting_for` if it takes too long.
+fn wait_for(waiting_for: &str, done: impl Fn() -> bool) {
+	let start = Instant::now();
+	while !done() {
+		assert!(start.elapsed() < Duration::from_secs(10), "timed out waiting for {waiting_for}");
+		std::thread::sleep(Duration::from_millis(20));
+	}
+}
+
+fn read_or_default(dir: &Path, path: &str) -> String {
+	std::fs::read_to_string(dir.join(path)).unwrap_or_default()
+}
+
+#[test]
+fn watch_rebuilds_changed_files() {
+	let dir = tempfile::tempdir().unwrap();
+	let dir = dir.path();
+	write(dir, "src/a.css", "a { color: red }");
+	// There's nowhere to write to without an out dir.
+	hdx(dir).args(["watch", "src"]).assert().code(2);
+	let stderr = std::fs::File::create(dir.join("stderr.txt")).unwrap();
+	let mut watching = Watching(
+		Command::new(assert_cmd::cargo::cargo_bin("hdx"))
+			.args(["watch", "-m", "src", "--out-dir", "dist"])
+			.current_dir(dir)
+			.stdout(Stdio::null())
+			.stderr(stderr)
+			.spawn()
+			.unwrap(),
+	);
+	wait_for("the first build", || read_or_default(dir, "stderr.txt").contains("Watching for changes..."));
+	assert_eq!(read(dir, "dist/a.css"), "a{color:red}");
+
+	write(dir, "src/a.css", "a { color: blue }");
+	wait_for("a.css to be rebuilt", || read_or_default(dir, "dist/a.css") == "a{color:blue}");
+	write(dir, "src/nested/b.css", "b { color: green }");
+	wait_for("b.css to be built", || read_or_default(dir, "dist/nested/b.css") == "b{color:green}");
+
+	// Errors are printed, and watching carries on.
+	write(dir, "src/a.css", "a { : ; }");
+	wait_for("the error", || read_or_default(dir, "stderr.txt").contains("(1 failed)"));
+	assert!(read(dir, "stderr.txt").contains("[src/a.css:1:6]"), "{}", read(dir, "stderr.txt"));
+	assert!(watching.0.try_wait().unwrap().is_none());
+	write(dir, "src/a.css", "a { color: black }");
+	wait_for("a.css to be fixed", || read_or_default(dir, "dist/a.css") == "a{color:black}");
+
+	std::fs::remove_file(dir.join("src/nested/b.css")).unwrap();
+	wait_for("b.css to be removed", || !dir.join("dist/nested/b.css").exists());
+}
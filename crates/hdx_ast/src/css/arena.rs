@@ -0,0 +1,40 @@
+//! The functions each arena allocated field is deserialized with, from `#[serde(deserialize_with = "...")]`.
+//!
+//! These are only used by the nodes of this crate, for fields with the lifetime `'a` of the node they're within. The
+//! nodes are [DeserializeIn<'a>](hdx_parser::DeserializeIn), so are only deserialized into an arena by an
+//! [ArenaSeed](hdx_parser::ArenaSeed) which borrows it for `'a`, as [hdx_parser::arena] requires.
+
+use hdx_lexer::Token;
+use hdx_parser::{arena, Box, ComplexSelector, Vec};
+use serde::{Deserialize, Deserializer};
+
+pub(crate) fn vec<'de, 'a, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D) -> Result<Vec<'a, T>, D::Error> {
+	// SAFETY: see the module docs.
+	unsafe { arena::vec(deserializer) }
+}
+
+pub(crate) fn boxed<'de, 'a, D: Deserializer<'de>, T: Deserialize<'de>>(
+	deserializer: D,
+) -> Result<Box<'a, T>, D::Error> {
+	// SAFETY: see the module docs.
+	unsafe { arena::boxed(deserializer) }
+}
+
+pub(crate) fn option_boxed<'de, 'a, D: Deserializer<'de>, T: Deserialize<'de>>(
+	deserializer: D,
+) -> Result<Option<Box<'a, T>>, D::Error> {
+	// SAFETY: see the module docs.
+	unsafe { arena::option_boxed(deserializer) }
+}
+
+pub(crate) fn complex_selectors<'de, 'a, D: Deserializer<'de>, T: Deserialize<'de>>(
+	deserializer: D,
+) -> Result<Vec<'a, ComplexSelector<'a, T>>, D::Error> {
+	// SAFETY: see the module docs.
+	unsafe { arena::complex_selectors(deserializer) }
+}
+
+pub(crate) fn token<'de, 'a, D: Deserializer<'de>>(deserializer: D) -> Result<Token<'a>, D::Error> {
+	// SAFETY: see the module docs.
+	unsafe { arena::token(deserializer) }
+}
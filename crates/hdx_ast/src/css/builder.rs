@@ -0,0 +1,173 @@
+//! Building nodes in the arena a style sheet was parsed into, for transforms which add to it. Nodes can be built
+//! directly, or parsed from a piece of CSS such as `color: red` when that's easier.
+//!
+//! A [VisitMut] is given an [AstBuilder] by [VisitContext::builder] when visiting with one, as the passes of an
+//! `hdx_transform::Pipeline` are. The children of a node are best edited in its `exit_` hook, once they've been
+//! visited, or in its `enter_` hook so that the visitor visits them as they have become.
+//!
+//! [VisitMut]: crate::css::visit::VisitMut
+//! [VisitContext::builder]: crate::css::visit::VisitContext::builder
+
+use bumpalo::Bump;
+use hdx_atom::ToAtom;
+use hdx_parser::{Box, Commented, Features, Parser, ParserReturn, Result, Spanned, Vec};
+
+use crate::css::{
+	fragment,
+	properties::{Property, StyleValue},
+	selector::SelectorList,
+	stylerule::{StyleDeclaration, StyleRule},
+	stylesheet::Rule,
+	StyleSheet,
+};
+
+/// Builds nodes in the arena `'a`, which is that of the [Parser] a style sheet was parsed with.
+#[derive(Debug, Clone, Copy)]
+pub struct AstBuilder<'a> {
+	allocator: &'a Bump,
+}
+
+impl<'a> AstBuilder<'a> {
+	pub fn new(allocator: &'a Bump) -> Self {
+		Self { allocator }
+	}
+
+	#[inline]
+	pub fn allocator(&self) -> &'a Bump {
+		self.allocator
+	}
+
+	#[inline]
+	pub fn vec<T>(&self) -> Vec<'a, T> {
+		Vec::new_in(self.allocator)
+	}
+
+	#[inline]
+	pub fn vec_from_iter<T>(&self, iter: impl IntoIterator<Item = T>) -> Vec<'a, T> {
+		Vec::from_iter_in(iter, self.allocator)
+	}
+
+	#[inline]
+	pub fn boxed<T>(&self, value: T) -> Box<'a, T> {
+		Box::new_in(value, self.allocator)
+	}
+
+	#[inline]
+	pub fn str(&self, str: &str) -> &'a str {
+		self.allocator.alloc_str(str)
+	}
+
+	/// Wraps `node` as it would be in a list of rules or declarations. It has no comments, and a dummy span as it
+	/// isn't in the source text.
+	#[inline]
+	pub fn node<T>(&self, node: T) -> Spanned<Commented<'a, T>> {
+		Spanned::dummy(Commented { node, trivia: None })
+	}
+
+	pub fn style_sheet(&self, rules: impl IntoIterator<Item = Rule<'a>>) -> StyleSheet<'a> {
+		StyleSheet { rules: self.vec_from_iter(rules.into_iter().map(|rule| self.node(rule))), trivia: None }
+	}
+
+	pub fn style_rule(
+		&self,
+		selectors: SelectorList<'a>,
+		declarations: impl IntoIterator<Item = Property<'a>>,
+	) -> Rule<'a> {
+		let declarations = self.vec_from_iter(declarations.into_iter().map(|declaration| self.node(declaration)));
+		Rule::Style(StyleRule {
+			selectors: Spanned::dummy(selectors),
+			style: Spanned::dummy(StyleDeclaration { declarations, rules: self.vec(), trivia: None }),
+		})
+	}
+
+	pub fn declaration(&self, name: &str, value: StyleValue<'a>) -> Property<'a> {
+		Property { name: name.to_ascii_lowercase_atom(), value, important: false }
+	}
+
+	/// Parses a list of selectors, such as `a:hover, .nav > li`.
+	pub fn selector_list(&self, source_text: &str) -> Result<SelectorList<'a>> {
+		self.output(fragment::parse_selector_list(self.allocator, self.str(source_text), Features::default()))
+	}
+
+	/// Parses the value of the property `name`, such as `calc(100% - 1em)` for `width`.
+	pub fn value(&self, name: &str, source_text: &str) -> Result<StyleValue<'a>> {
+		self.output(fragment::parse_property_value(self.allocator, name, self.str(source_text), Features::default()))
+	}
+
+	/// Parses a declaration, such as `color: red !important`.
+	pub fn parse_declaration(&self, source_text: &str) -> Result<Property<'a>> {
+		self.output(Parser::new(self.allocator, self.str(source_text), Features::default()).parse_entirely_with())
+	}
+
+	/// Parses a rule, such as `a { color: red }` or `@media print { a { color: black } }`.
+	pub fn parse_rule(&self, source_text: &str) -> Result<Rule<'a>> {
+		self.output(Parser::new(self.allocator, self.str(source_text), Features::default()).parse_entirely_with())
+	}
+
+	// The spans of parsed nodes are within the text they were parsed from, rather than the style sheet they're added
+	// to, so they're only kept for the sake of the nodes within them.
+	fn output<T>(&self, mut result: ParserReturn<Spanned<T>>) -> Result<T> {
+		if result.errors.is_empty() {
+			if let Some(output) = result.output {
+				return Ok(output.node);
+			}
+		}
+		Err(result.errors.remove(0))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::css::{
+		values,
+		visit::{VisitContext, VisitMut, VisitableMut},
+	};
+	use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
+
+	fn write(stylesheet: &StyleSheet) -> String {
+		let mut string = String::new();
+		stylesheet.write_css(&mut BaseCssWriter::new(&mut string, OutputOption::none())).unwrap();
+		string
+	}
+
+	#[test]
+	fn test_build() {
+		let allocator = Bump::default();
+		let builder = AstBuilder::new(&allocator);
+		let width = builder.declaration("Width", StyleValue::Width(values::Width::Auto));
+		let color = builder.declaration("color", builder.value("color", "red").unwrap());
+		let rule = builder.style_rule(builder.selector_list("a:hover, b").unwrap(), [width, color]);
+		let media = builder.parse_rule("@media print { c { color: black } }").unwrap();
+		let stylesheet = builder.style_sheet([rule, media]);
+		assert_eq!(write(&stylesheet), "a:hover,b{width:auto;color:red}@media print{c{color:black}}");
+		assert!(builder.selector_list("a {").is_err());
+		assert!(builder.parse_declaration("color: red !important").unwrap().important);
+	}
+
+	// Adds a prefixed fallback ahead of each `width: stretch`, for browsers which don't support it yet.
+	#[derive(Default)]
+	struct Fallback();
+
+	impl<'a> VisitMut<'a> for Fallback {
+		fn exit_style_rule(&mut self, rule: &mut StyleRule<'a>, ctx: &VisitContext<'a>) {
+			let builder = ctx.builder().unwrap();
+			let declarations = &mut rule.style.node.declarations;
+			for i in (0..declarations.len()).rev() {
+				if declarations[i].node.node.value == StyleValue::Width(values::Width::Stretch) {
+					let fallback = builder.parse_declaration("width: -webkit-fill-available").unwrap();
+					declarations.insert(i, builder.node(fallback));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_visit_with_builder() {
+		let allocator = Bump::default();
+		let builder = AstBuilder::new(&allocator);
+		let mut stylesheet = builder.style_sheet([builder.parse_rule("a { width: stretch; height: 1px }").unwrap()]);
+		stylesheet.accept_mut_with(&mut Fallback::default(), &mut VisitContext::new(builder));
+		assert_eq!(write(&stylesheet), "a{width:-webkit-fill-available;width:stretch;height:1px}");
+	}
+}
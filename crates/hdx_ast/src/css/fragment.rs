@@ -0,0 +1,117 @@
+//! Parsing pieces of CSS which aren't a whole style sheet, such as the contents of an HTML `style` attribute, or a
+//! selector passed to `querySelector()`. Each of these parses the whole of `source_text`, so anything left over is
+//! reported as an error.
+
+use bumpalo::Bump;
+use hdx_atom::ToAtom;
+use hdx_parser::{Block, DeclarationValue, Features, Parser, ParserReturn, Spanned};
+
+use crate::css::{
+	properties::StyleValue, rules::media::MediaQueryList, selector::SelectorList, stylerule::StyleDeclaration,
+};
+
+/// Parses the declarations of an HTML `style` attribute, which are like those of a style rule but without the curly
+/// braces around them.
+///
+/// https://drafts.csswg.org/css-style-attr/#syntax
+pub fn parse_style_attribute<'a>(
+	allocator: &'a Bump,
+	source_text: &'a str,
+	features: Features,
+) -> ParserReturn<Spanned<StyleDeclaration<'a>>> {
+	Parser::new(allocator, source_text, features).parse_entirely_by(|parser| {
+		let span = parser.span();
+		let (declarations, rules) = StyleDeclaration::parse_block_contents(parser)?;
+		let span = span.end(parser.pos());
+		let mut comments = parser.comments_within(span);
+		comments.extend(parser.leading_comments());
+		let trivia = parser.new_trivia(parser.new_vec(), comments);
+		Ok(Spanned { node: StyleDeclaration { declarations, rules, trivia }, span })
+	})
+}
+
+/// Parses the value of the property `name`, as it would be if it were declared in a style rule, such as
+/// `parse_property_value(&allocator, "width", "calc(100% - 1em)", Features::default())`.
+pub fn parse_property_value<'a>(
+	allocator: &'a Bump,
+	name: &str,
+	source_text: &'a str,
+	features: Features,
+) -> ParserReturn<Spanned<StyleValue<'a>>> {
+	let name = name.to_ascii_lowercase_atom();
+	Parser::new(allocator, source_text, features).parse_entirely_by(|parser| {
+		let span = parser.span();
+		let node = StyleValue::parse_declaration_value(&name, parser)?;
+		Ok(Spanned { node, span: span.end(parser.pos()) })
+	})
+}
+
+/// Parses a list of selectors, such as `a:hover, .nav > li`, on its own rather than ahead of a style rule's block.
+pub fn parse_selector_list<'a>(
+	allocator: &'a Bump,
+	source_text: &'a str,
+	features: Features,
+) -> ParserReturn<Spanned<SelectorList<'a>>> {
+	Parser::new(allocator, source_text, features).parse_entirely_with::<SelectorList>()
+}
+
+/// Parses a list of media queries, such as those passed to `matchMedia()` or in a `<link>`'s `media` attribute.
+pub fn parse_media_query_list<'a>(
+	allocator: &'a Bump,
+	source_text: &'a str,
+	features: Features,
+) -> ParserReturn<Spanned<MediaQueryList>> {
+	Parser::new(allocator, source_text, features).parse_entirely_with::<MediaQueryList>()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
+
+	fn write<'a, T: WriteCss<'a> + std::hash::Hash>(result: ParserReturn<Spanned<T>>) -> String {
+		assert!(result.errors.is_empty(), "{:?}", result.errors);
+		let mut string = String::new();
+		let mut writer = BaseCssWriter::new(&mut string, OutputOption::none());
+		result.output.unwrap().write_css(&mut writer).unwrap();
+		string
+	}
+
+	#[test]
+	fn test_style_attribute() {
+		let allocator = Bump::default();
+		let css = "color: red; width: 1px !important /* wide */";
+		let result = parse_style_attribute(&allocator, css, Features::default());
+		assert_eq!(result.output.as_ref().unwrap().node.declarations.len(), 2);
+		assert_eq!(write(result), "color:red;width:1px!important");
+		assert!(!parse_style_attribute(&allocator, "color: red }", Features::default()).errors.is_empty());
+	}
+
+	#[test]
+	fn test_property_value() {
+		let allocator = Bump::default();
+		let result = parse_property_value(&allocator, "Width", "calc(100% - 1em)", Features::default());
+		assert!(matches!(result.output.as_ref().unwrap().node, StyleValue::Computed(_)));
+		let result = parse_property_value(&allocator, "float", "left", Features::default());
+		assert!(matches!(result.output.as_ref().unwrap().node, StyleValue::Float(_)));
+		assert_eq!(write(result), "left");
+		assert!(!parse_property_value(&allocator, "float", "left;", Features::default()).errors.is_empty());
+	}
+
+	#[test]
+	fn test_selector_list() {
+		let allocator = Bump::default();
+		assert_eq!(
+			write(parse_selector_list(&allocator, "a:hover, .nav > li", Features::default())),
+			"a:hover,.nav>li"
+		);
+		assert!(!parse_selector_list(&allocator, "a { color: red }", Features::default()).errors.is_empty());
+	}
+
+	#[test]
+	fn test_media_query_list() {
+		let allocator = Bump::default();
+		let result = parse_media_query_list(&allocator, "screen, print and (min-width: 600px)", Features::default());
+		assert_eq!(result.output.as_ref().unwrap().node.len(), 2);
+	}
+}
@@ -1,7 +1,7 @@
-use hdx_derive::{Atomizable, Parsable, Writable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Visitable, Writable};
 
-#[derive(Atomizable, Parsable, Writable, Default, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Parsable, Writable, Visitable, ShiftSpans, Default, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum LineStyle {
 	#[default]
 	None, // atom!("none")
@@ -1,3 +1,7 @@
+#[cfg(feature = "serde")]
+pub(crate) mod arena;
+pub mod builder;
+pub mod fragment;
 pub mod keywords;
 pub mod properties;
 pub mod rules;
@@ -9,4 +13,27 @@ pub mod units;
 pub mod values;
 pub mod visit;
 
+pub use fragment::*;
 pub use stylesheet::StyleSheet;
+
+// Each of the nodes which can be parsed on their own can be read back in, such as from the JSON of `hdx_wasm::parse()`.
+#[cfg(feature = "serde")]
+macro_rules! deserialize_in {
+	($($node: ty),+) => {
+		$(
+			// SAFETY: `'a` is the lifetime of every arena allocated field within the node.
+			unsafe impl<'a> hdx_parser::DeserializeIn<'a> for $node {}
+		)+
+	};
+}
+
+#[cfg(feature = "serde")]
+deserialize_in!(
+	StyleSheet<'a>,
+	stylesheet::Rule<'a>,
+	stylerule::StyleDeclaration<'a>,
+	properties::Property<'a>,
+	properties::StyleValue<'a>,
+	selector::SelectorList<'a>,
+	rules::media::MediaQueryList
+);
@@ -0,0 +1,276 @@
+use hdx_atom::{atom, Atom, ToAtom};
+
+// The value definition syntax of properties, as written in their specifications, to show alongside values which don't
+// match it. Each line matches the property_list, but only those which are likely to be written by hand are here.
+// https://drafts.csswg.org/css-values-4/#value-defs
+pub(crate) fn grammar(name: &Atom) -> Option<&'static str> {
+	Some(match name {
+		// https://drafts.csswg.org/css-align-3/#property-index
+		&atom!("align-content") => {
+			"normal | <baseline-position> | <content-distribution> | <overflow-position>? <content-position>"
+		}
+		&atom!("align-items") => "normal | stretch | <baseline-position> | <overflow-position>? <self-position>",
+		&atom!("align-self") => "auto | normal | stretch | <baseline-position> | <overflow-position>? <self-position>",
+		&atom!("column-gap") => "normal | <length-percentage [0,∞]>",
+		&atom!("gap") => "<'row-gap'> <'column-gap'>?",
+		&atom!("justify-content") => {
+			"normal | <content-distribution> | <overflow-position>? [ <content-position> | left | right ]"
+		}
+		&atom!("justify-items") => {
+			"normal | stretch | <baseline-position> | <overflow-position>? [ <self-position> | left | right ] | legacy | legacy && [ left | right | center ]"
+		}
+		&atom!("justify-self") => {
+			"auto | normal | stretch | <baseline-position> | <overflow-position>? [ <self-position> | left | right ]"
+		}
+		&atom!("row-gap") => "normal | <length-percentage [0,∞]>",
+
+		// https://drafts.csswg.org/css-animations-1/#property-index
+		&atom!("animation") => "<single-animation>#",
+		&atom!("animation-delay") => "<time>#",
+		&atom!("animation-direction") => "<single-animation-direction>#",
+		&atom!("animation-duration") => "[ auto | <time [0s,∞]> ]#",
+		&atom!("animation-fill-mode") => "<single-animation-fill-mode>#",
+		&atom!("animation-iteration-count") => "<single-animation-iteration-count>#",
+		&atom!("animation-name") => "[ none | <keyframes-name> ]#",
+		&atom!("animation-play-state") => "<single-animation-play-state>#",
+		&atom!("animation-timing-function") => "<easing-function>#",
+
+		// https://drafts.csswg.org/css-backgrounds-3/#property-index
+		&atom!("background") => "<bg-layer>#? , <final-bg-layer>",
+		&atom!("background-attachment") => "<attachment>#",
+		&atom!("background-clip") => "<visual-box>#",
+		&atom!("background-color") => "<color>",
+		&atom!("background-image") => "<bg-image>#",
+		&atom!("background-origin") => "<visual-box>#",
+		&atom!("background-position") => "<bg-position>#",
+		&atom!("background-repeat") => "<repeat-style>#",
+		&atom!("background-size") => "<bg-size>#",
+		&atom!("border")
+		| &atom!("border-top")
+		| &atom!("border-right")
+		| &atom!("border-bottom")
+		| &atom!("border-left") => "<line-width> || <line-style> || <color>",
+		&atom!("border-color") => "<color>{1,4}",
+		&atom!("border-radius") => "<length-percentage [0,∞]>{1,4} [ / <length-percentage [0,∞]>{1,4} ]?",
+		&atom!("border-style") => "<line-style>{1,4}",
+		&atom!("border-width") => "<line-width>{1,4}",
+		&atom!("border-top-color")
+		| &atom!("border-right-color")
+		| &atom!("border-bottom-color")
+		| &atom!("border-left-color") => "<color>",
+		&atom!("border-top-style")
+		| &atom!("border-right-style")
+		| &atom!("border-bottom-style")
+		| &atom!("border-left-style") => "<line-style>",
+		&atom!("border-top-width")
+		| &atom!("border-right-width")
+		| &atom!("border-bottom-width")
+		| &atom!("border-left-width") => "<line-width>",
+		&atom!("box-shadow") => "<spread-shadow>#",
+
+		// https://drafts.csswg.org/css-box-4/#property-index
+		&atom!("margin") => "<'margin-top'>{1,4}",
+		&atom!("margin-top") | &atom!("margin-right") | &atom!("margin-bottom") | &atom!("margin-left") => {
+			"<length-percentage> | auto"
+		}
+		&atom!("padding") => "<'padding-top'>{1,4}",
+		&atom!("padding-top") | &atom!("padding-right") | &atom!("padding-bottom") | &atom!("padding-left") => {
+			"<length-percentage [0,∞]>"
+		}
+
+		// https://drafts.csswg.org/css-color-5/#property-index
+		&atom!("color") => "<color>",
+		&atom!("opacity") => "<opacity-value>",
+
+		// https://drafts.csswg.org/css-display-4/#property-index
+		&atom!("display") => {
+			"[ <display-outside> || <display-inside> ] | <display-listitem> | <display-internal> | <display-box> | <display-legacy>"
+		}
+		&atom!("visibility") => "visible | hidden | collapse",
+
+		// https://drafts.csswg.org/css-flexbox-1/#property-index
+		&atom!("flex") => "none | [ <'flex-grow'> <'flex-shrink'>? || <'flex-basis'> ]",
+		&atom!("flex-basis") => "content | <'width'>",
+		&atom!("flex-direction") => "row | row-reverse | column | column-reverse",
+		&atom!("flex-flow") => "<'flex-direction'> || <'flex-wrap'>",
+		&atom!("flex-grow") | &atom!("flex-shrink") => "<number [0,∞]>",
+		&atom!("flex-wrap") => "nowrap | wrap | wrap-reverse",
+		&atom!("order") => "<integer>",
+
+		// https://drafts.csswg.org/css-fonts-4/#property-index
+		&atom!("font") => {
+			"[ [ <'font-style'> || <font-variant-css2> || <'font-weight'> || <font-width-css3> ]? <'font-size'> [ / <'line-height'> ]? <'font-family'># ] | <system-family-name>"
+		}
+		&atom!("font-family") => "[ <family-name> | <generic-family> ]#",
+		&atom!("font-size") => "<absolute-size> | <relative-size> | <length-percentage [0,∞]> | math",
+		&atom!("font-style") => "normal | italic | oblique <angle [-90deg,90deg]>?",
+		&atom!("font-weight") => "<font-weight-absolute> | bolder | lighter",
+
+		// https://drafts.csswg.org/css-inline-3/#property-index
+		&atom!("line-height") => "normal | <number [0,∞]> | <length-percentage [0,∞]>",
+		&atom!("vertical-align") => "[ first | last ] || <'alignment-baseline'> || <'baseline-shift'>",
+
+		// https://drafts.csswg.org/css-lists-3/#property-index
+		&atom!("list-style") => "<'list-style-position'> || <'list-style-image'> || <'list-style-type'>",
+		&atom!("list-style-position") => "inside | outside",
+		&atom!("list-style-type") => "<counter-style> | <string> | none",
+
+		// https://drafts.csswg.org/css-overflow-3/#property-index
+		&atom!("overflow") => "<'overflow-block'>{1,2}",
+		&atom!("overflow-x") | &atom!("overflow-y") | &atom!("overflow-block") | &atom!("overflow-inline") => {
+			"visible | hidden | clip | scroll | auto"
+		}
+		&atom!("text-overflow") => "clip | ellipsis",
+
+		// https://drafts.csswg.org/css-position-3/#property-index
+		&atom!("position") => "static | relative | absolute | sticky | fixed",
+		&atom!("top") | &atom!("right") | &atom!("bottom") | &atom!("left") => "auto | <length-percentage>",
+
+		// https://drafts.csswg.org/css-sizing-3/#property-index
+		&atom!("box-sizing") => "content-box | border-box",
+		&atom!("width") | &atom!("height") | &atom!("min-width") | &atom!("min-height") => {
+			"auto | <length-percentage [0,∞]> | min-content | max-content | fit-content(<length-percentage [0,∞]>)"
+		}
+		&atom!("max-width") | &atom!("max-height") => {
+			"none | <length-percentage [0,∞]> | min-content | max-content | fit-content(<length-percentage [0,∞]>)"
+		}
+
+		// https://drafts.csswg.org/css-text-4/#property-index
+		&atom!("text-align") => "start | end | left | right | center | justify | match-parent | justify-all",
+		&atom!("text-transform") => "none | [ capitalize | uppercase | lowercase ] || full-width || full-size-kana",
+		&atom!("white-space") => "normal | pre | nowrap | pre-wrap | break-spaces | pre-line",
+		&atom!("word-break") => "normal | keep-all | break-all | break-word",
+
+		// https://drafts.csswg.org/css-text-decor-4/#property-index
+		&atom!("text-decoration-line") => "none | [ underline || overline || line-through || blink ]",
+		&atom!("text-decoration-style") => "solid | double | dotted | dashed | wavy",
+
+		// https://drafts.csswg.org/css-transitions-1/#property-index
+		&atom!("transition") => "<single-transition>#",
+		&atom!("transition-delay") | &atom!("transition-duration") => "<time>#",
+		&atom!("transition-property") => "none | <single-transition-property>#",
+		&atom!("transition-timing-function") => "<easing-function>#",
+
+		// https://drafts.csswg.org/css-ui-4/#property-index
+		&atom!("cursor") => "[ [ <url> | <url-set> ] [ <x> <y> ]? ]#? <cursor-predefined>",
+		&atom!("outline") => "<'outline-width'> || <'outline-style'> || <'outline-color'>",
+		&atom!("outline-style") => "auto | <outline-line-style>",
+		&atom!("pointer-events") => "auto | none",
+		&atom!("user-select") => "auto | text | none | contain | all",
+
+		// https://drafts.csswg.org/css2/#propidx
+		&atom!("clear") => "inline-start | inline-end | block-start | block-end | left | right | top | bottom | both-inline | both-block | both | none",
+		&atom!("float") => "block-start | block-end | inline-start | inline-end | snap-block | <snap-block()> | snap-inline | <snap-inline()> | left | right | top | bottom | none",
+		&atom!("z-index") => "auto | <integer>",
+		_ => return None,
+	})
+}
+
+// The keywords which can be written in place of the data type `name`, so they can be suggested for misspelled ones.
+fn data_type_keywords(name: &str) -> &'static [&'static str] {
+	match name {
+		"absolute-size" => &["xx-small", "x-small", "small", "medium", "large", "x-large", "xx-large", "xxx-large"],
+		"attachment" => &["scroll", "fixed", "local"],
+		"baseline-position" => &["first", "last", "baseline"],
+		"color" => &["currentcolor", "transparent"],
+		"content-distribution" => &["space-between", "space-around", "space-evenly", "stretch"],
+		"content-position" => &["center", "start", "end", "flex-start", "flex-end"],
+		"cursor-predefined" => &[
+			"auto",
+			"default",
+			"none",
+			"context-menu",
+			"help",
+			"pointer",
+			"progress",
+			"wait",
+			"cell",
+			"crosshair",
+			"text",
+			"vertical-text",
+			"alias",
+			"copy",
+			"move",
+			"no-drop",
+			"not-allowed",
+			"grab",
+			"grabbing",
+			"col-resize",
+			"row-resize",
+			"all-scroll",
+			"zoom-in",
+			"zoom-out",
+		],
+		"display-box" => &["contents", "none"],
+		"display-inside" => &["flow", "flow-root", "table", "flex", "grid", "ruby"],
+		"display-internal" => &[
+			"table-row-group",
+			"table-header-group",
+			"table-footer-group",
+			"table-row",
+			"table-cell",
+			"table-column-group",
+			"table-column",
+			"table-caption",
+			"ruby-base",
+			"ruby-text",
+			"ruby-base-container",
+			"ruby-text-container",
+		],
+		"display-legacy" => &["inline-block", "inline-table", "inline-flex", "inline-grid"],
+		"display-listitem" => &["list-item"],
+		"display-outside" => &["block", "inline", "run-in"],
+		"easing-function" => &["linear", "ease", "ease-in", "ease-out", "ease-in-out", "step-start", "step-end"],
+		"font-weight-absolute" => &["normal", "bold"],
+		"generic-family" => &[
+			"serif",
+			"sans-serif",
+			"monospace",
+			"cursive",
+			"fantasy",
+			"system-ui",
+			"math",
+			"ui-serif",
+			"ui-sans-serif",
+			"ui-monospace",
+			"ui-rounded",
+		],
+		"line-style" | "outline-line-style" => {
+			&["none", "hidden", "dotted", "dashed", "solid", "double", "groove", "ridge", "inset", "outset"]
+		}
+		"line-width" => &["thin", "medium", "thick"],
+		"overflow-position" => &["unsafe", "safe"],
+		"relative-size" => &["larger", "smaller"],
+		"repeat-style" => &["repeat-x", "repeat-y", "repeat", "space", "round", "no-repeat"],
+		"self-position" => &["center", "start", "end", "self-start", "self-end", "flex-start", "flex-end"],
+		"single-animation-direction" => &["normal", "reverse", "alternate", "alternate-reverse"],
+		"single-animation-fill-mode" => &["none", "forwards", "backwards", "both"],
+		"single-animation-iteration-count" => &["infinite"],
+		"single-animation-play-state" => &["running", "paused"],
+		"visual-box" => &["content-box", "padding-box", "border-box"],
+		_ => &[],
+	}
+}
+
+/// The keywords which a value of the given `grammar` could have, such as `solid` for `<line-style>`.
+pub(crate) fn keywords(grammar: &'static str) -> Vec<&'static str> {
+	let mut found = vec![];
+	let mut start = None;
+	for (i, char) in grammar.char_indices().chain([(grammar.len(), ' ')]) {
+		if char.is_ascii_alphanumeric() || char == '-' {
+			start.get_or_insert(i);
+			continue;
+		}
+		let Some(start) = start.take() else { continue };
+		let word = &grammar[start..i];
+		match grammar[..start].chars().next_back() {
+			Some('<') => found.extend(data_type_keywords(word)),
+			// Other properties, such as `<'flex-grow'>` within `flex`.
+			Some('\'') => found.extend(self::grammar(&word.to_atom()).map(keywords).unwrap_or_default()),
+			// Functions, and numbers such as those in `[0,∞]`.
+			_ if char == '(' || word.trim_start_matches('-').starts_with(|c: char| c.is_ascii_digit()) => {}
+			_ => found.push(word),
+		}
+	}
+	found
+}
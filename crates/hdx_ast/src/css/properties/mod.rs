@@ -1,18 +1,25 @@
-use std::{default::Default, fmt::Debug, hash::Hash};
+use std::{cell::RefCell, collections::HashMap, default::Default, fmt::Debug, hash::Hash};
 
-use hdx_atom::{atom, Atom};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::Token;
-use hdx_derive::Visitable;
-use hdx_parser::{peek, Declaration, DeclarationValue, Parse, Parser, Result as ParserResult, State};
-use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
+use hdx_parser::{
+	closest_match, diagnostics, peek, Declaration, DeclarationValue, Features, Parse, Parser, Remnants,
+	Result as ParserResult, Span, Spanned, State,
+};
+use hdx_writer::{write_comma_list, write_space_list, CssWriter, Result as WriterResult, WriteCss};
 
-use crate::{css::values, syntax::ComponentValues};
+use crate::{
+	css::values,
+	syntax::{ComponentValue, ComponentValues},
+};
 
+mod grammar;
 mod property_list;
-use property_list::apply_properties;
+pub(crate) use property_list::apply_properties;
 
-#[derive(PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Custom<'a>(pub ComponentValues<'a>);
 
 impl<'a> WriteCss<'a> for Custom<'a> {
@@ -27,8 +34,8 @@ impl<'a> Parse<'a> for Custom<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Computed<'a>(pub ComponentValues<'a>);
 
 impl<'a> WriteCss<'a> for Computed<'a> {
@@ -43,8 +50,8 @@ impl<'a> Parse<'a> for Computed<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Unknown<'a>(pub ComponentValues<'a>);
 
 impl<'a> Parse<'a> for Unknown<'a> {
@@ -53,37 +60,89 @@ impl<'a> Parse<'a> for Unknown<'a> {
 	}
 }
 
+impl<'a> Unknown<'a> {
+	// Splits the value into the items of a top level comma separated list (e.g. `transition`), or failing that a list
+	// of strings (e.g. `grid-template-areas`), so that it can be wrapped.
+	fn list_items(&self) -> (std::vec::Vec<&[Spanned<ComponentValue<'a>>]>, bool) {
+		fn is_whitespace(value: &Spanned<ComponentValue>) -> bool {
+			matches!(value.node, ComponentValue::Token(Token::Whitespace))
+		}
+		fn trim<'b, 'a>(mut values: &'b [Spanned<ComponentValue<'a>>]) -> &'b [Spanned<ComponentValue<'a>>] {
+			while values.first().is_some_and(is_whitespace) {
+				values = &values[1..];
+			}
+			while values.last().is_some_and(is_whitespace) {
+				values = &values[..values.len() - 1];
+			}
+			values
+		}
+		let values = &self.0 .0[..];
+		let is_comma = |value: &Spanned<ComponentValue>| matches!(value.node, ComponentValue::Token(Token::Comma));
+		if values.iter().any(is_comma) {
+			return (values.split(is_comma).map(trim).collect(), true);
+		}
+		let mut strings = values.iter().filter(|value| !is_whitespace(value)).peekable();
+		if strings.peek().is_some()
+			&& strings.all(|value| matches!(value.node, ComponentValue::Token(Token::String(..))))
+		{
+			return (values.chunks(1).filter(|value| !is_whitespace(&value[0])).collect(), false);
+		}
+		(vec![], false)
+	}
+}
+
 impl<'a> WriteCss<'a> for Unknown<'a> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
+		// Values are written as they were, unless they're too long to fit on the line and can be wrapped.
+		if sink.remaining_width().is_some_and(|remaining| sink.measure(&self.0) > remaining) {
+			match self.list_items() {
+				(items, true) if items.len() > 1 => return write_comma_list(sink, &items),
+				(items, false) if items.len() > 1 => return write_space_list(sink, &items),
+				_ => {}
+			}
+		}
 		self.0.write_css(sink)
 	}
 }
 
-#[derive(Visitable, PartialEq, Debug, Hash)]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
 #[visitable(call)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename = "property"))]
 pub struct Property<'a> {
-	#[visitable(skip)]
 	pub name: Atom,
-	#[visitable(skip)]
 	pub value: StyleValue<'a>,
-	#[visitable(skip)]
 	pub important: bool,
 }
 
 impl<'a> Parse<'a> for Property<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
+		let name_span = parser.peek_span();
 		let (name, value, important) = Self::parse_declaration(parser)?;
+		// Vendor prefixed properties and hacks are likely to be unknown on purpose.
+		if matches!(value, StyleValue::Unknown(_)) && !name.starts_with(['-', '*', '_']) {
+			if let Some(suggestion) = suggest_property(&name) {
+				parser.warn(diagnostics::UnknownPropertySuggest(name.clone(), suggestion.to_atom(), name_span).into());
+			}
+		}
 		Ok(Self { name, value, important })
 	}
 }
 
+impl<'a> From<Remnants> for Property<'a> {
+	fn from(remnants: Remnants) -> Self {
+		Self { name: atom!(""), value: StyleValue::Remnants(remnants), important: false }
+	}
+}
+
 impl<'a> Declaration<'a> for Property<'a> {
 	type DeclarationValue = StyleValue<'a>;
 }
 
 impl<'a> WriteCss<'a> for Property<'a> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
+		if let StyleValue::Remnants(remnants) = &self.value {
+			return remnants.write_css(sink);
+		}
 		sink.write_str(self.name.as_ref())?;
 		sink.write_char(':')?;
 		sink.write_whitespace()?;
@@ -97,10 +156,58 @@ impl<'a> WriteCss<'a> for Property<'a> {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Property<'a> {
+	// Values of different properties often look alike once serialized, so the value is read as one of `name`'s.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		#[derive(serde::Deserialize)]
+		struct Fields {
+			name: Atom,
+			value: serde_json::Value,
+			#[serde(default)]
+			important: bool,
+		}
+		let Fields { name, value, important } = Fields::deserialize(deserializer)?;
+		let value = StyleValue::deserialize_for(&name, value).map_err(serde::de::Error::custom)?;
+		Ok(Self { name, value, important })
+	}
+}
+
+thread_local! {
+	// Unknown properties tend to be used over and over, such as those which are yet to be added to the property list,
+	// and looking through every known property each time is slow.
+	static SUGGESTIONS: RefCell<HashMap<Atom, Option<&'static str>>> = RefCell::default();
+}
+
+// The known property which `name` was most likely meant to be, if it isn't known itself.
+fn suggest_property(name: &Atom) -> Option<&'static str> {
+	macro_rules! property_names {
+		( $(
+			$name: ident$(<$a: lifetime>)?: atom!($atom: tt),
+		)+ ) => {
+			[$($atom),+]
+		}
+	}
+	SUGGESTIONS.with(|suggestions| {
+		*suggestions.borrow_mut().entry(name.clone()).or_insert_with(|| {
+			let names = apply_properties!(property_names);
+			if names.contains(&name.as_ref()) {
+				None
+			} else {
+				// A standard property which only its prefixed forms are known for is one hdx doesn't have yet,
+				// rather than a misspelling of them.
+				closest_match(name, names).filter(|suggestion| {
+					suggestion.strip_prefix('-').and_then(|s| s.split_once('-')).map(|(_, s)| s) != Some(name.as_ref())
+				})
+			}
+		})
+	})
+}
+
 #[inline]
 fn is_computed_token(token: &Token) -> bool {
 	matches!(token, Token::Function(atom) if matches!(
-		atom.to_ascii_lowercase(),
+		atom.to_ascii_lowercase_atom(),
 		atom!("var")
 			| atom!("calc") | atom!("min")
 			| atom!("max") | atom!("clamp")
@@ -115,25 +222,58 @@ fn is_computed_token(token: &Token) -> bool {
 	))
 }
 
+// Values which depend on something hdx can't see, such as a var() nested in a function, or a vendor prefixed keyword,
+// can't be said to be invalid.
+fn is_unverifiable(values: &[Spanned<ComponentValue>]) -> bool {
+	values.iter().any(|value| match &value.node {
+		ComponentValue::Function(function) => {
+			matches!(function.name.to_ascii_lowercase_atom(), atom!("var") | atom!("env") | atom!("attr"))
+				|| is_vendor_prefixed(&function.name)
+				|| is_unverifiable(&function.values)
+		}
+		ComponentValue::SimpleBlock(block) => is_unverifiable(&block.values),
+		ComponentValue::Token(Token::Ident(name)) => is_vendor_prefixed(name),
+		_ => false,
+	})
+}
+
+// Properties which hdx only models some of the grammar of, such as the lists, images and counters of `content`, so
+// values it can't parse aren't known to be invalid.
+fn is_partially_modelled(name: &Atom) -> bool {
+	matches!(name.to_ascii_lowercase_atom(), atom!("content"))
+}
+
+fn is_vendor_prefixed(name: &str) -> bool {
+	name.starts_with('-') && !name.starts_with("--")
+}
+
 macro_rules! style_value {
     ( $(
         $name: ident$(<$a: lifetime>)?: $atom: pat,
     )+ ) => {
-		#[derive(PartialEq, Debug, Clone, Hash)]
-		#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+		#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+		#[visitable(call)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", rename_all = "kebab-case"))]
 		pub enum StyleValue<'a> {
 			Initial,
 			Inherit,
 			Unset,
 			Revert,
 			RevertLayer,
+			// What's left of a declaration which couldn't be parsed, kept in place of its property.
+			#[cfg_attr(feature = "serde", serde(untagged))]
+			Remnants(Remnants),
+			#[visitable(call)]
 			#[cfg_attr(feature = "serde", serde(untagged))]
 			Custom(Custom<'a>),
+			#[visitable(call)]
 			#[cfg_attr(feature = "serde", serde(untagged))]
 			Computed(Computed<'a>),
+			#[visitable(call)]
 			#[cfg_attr(feature = "serde", serde(untagged))]
 			Unknown(Unknown<'a>),
 			$(
+				#[visitable(call)]
 				#[cfg_attr(feature = "serde", serde(untagged))]
 				$name(values::$name$(<$a>)?),
 			)+
@@ -159,6 +299,71 @@ impl<'a> StyleValue<'a> {
 		}
 		apply_properties!(default_value)
 	}
+
+	/// Deserializes the value of the property `name`, falling back to any which `value` fits, such as `Unknown` for
+	/// one which didn't match the property's grammar.
+	#[cfg(feature = "serde")]
+	pub fn deserialize_for(name: &Atom, value: serde_json::Value) -> serde_json::Result<Self> {
+		use serde::Deserialize;
+		macro_rules! deserialize_value {
+			( $(
+				$name: ident$(<$a: lifetime>)?: $atom: pat,
+			)+ ) => {
+				match name {
+					$(
+						&$atom => values::$name::deserialize(&value).ok().map(Self::$name),
+					)+
+					_ => None,
+				}
+			}
+		}
+		match apply_properties!(deserialize_value) {
+			Some(value) => Ok(value),
+			None => Self::deserialize(value),
+		}
+	}
+}
+
+impl<'a> StyleValue<'a> {
+	// Values which didn't match the grammar of the property `name` are kept as they are, with a warning pointing to
+	// where they `failed_at`, unless parsing with Features::Strict. Values of `known` properties which hdx doesn't
+	// model yet have nowhere they failed at, so are kept without one.
+	fn parse_unknown(name: &Atom, known: bool, failed_at: Option<Span>, parser: &mut Parser<'a>) -> ParserResult<Self> {
+		let span = parser.span();
+		let value = Unknown::parse(parser)?;
+		let span = span.end(parser.pos());
+		// `width: 100px\9` hides a declaration from everything but IE 10 and older.
+		let ie_hack = span.source_text(parser.source_text()).ends_with("\\9") || name.starts_with(['*', '_']);
+		if ie_hack && parser.enabled(Features::AllowIeHacks) {
+			return Ok(Self::Unknown(value));
+		}
+		if parser.enabled(Features::Strict) && ie_hack {
+			Err(diagnostics::DisallowedIeHack(name.clone(), span))?
+		}
+		let Some(failed_at) = failed_at.filter(|_| !is_partially_modelled(name) && !is_unverifiable(&value.0 .0)) else {
+			if parser.enabled(Features::Strict) && !known {
+				Err(diagnostics::UnknownProperty(name.clone(), span))?
+			}
+			return Ok(Self::Unknown(value));
+		};
+		let error = Self::invalid_value(name, failed_at, parser.source_text());
+		if parser.enabled(Features::Strict) {
+			return Err(error.into());
+		}
+		parser.warn(error.into());
+		Ok(Self::Unknown(value))
+	}
+
+	fn invalid_value(name: &Atom, failed_at: Span, source_text: &str) -> diagnostics::InvalidValue {
+		let help = grammar::grammar(name).map(|grammar| {
+			let mut help = format!("The syntax of '{}' is `{}`.", name, grammar);
+			if let Some(keyword) = closest_match(failed_at.source_text(source_text), grammar::keywords(grammar)) {
+				help.push_str(&format!(" Did you mean '{}'?", keyword));
+			}
+			help
+		});
+		diagnostics::InvalidValue(name.clone(), help, failed_at)
+	}
 }
 
 impl<'a> WriteCss<'a> for StyleValue<'a> {
@@ -173,6 +378,7 @@ impl<'a> WriteCss<'a> for StyleValue<'a> {
 					Self::Unset => atom!("unset").write_css(sink),
 					Self::Revert => atom!("revert").write_css(sink),
 					Self::RevertLayer => atom!("revert-layer").write_css(sink),
+					Self::Remnants(v) => v.write_css(sink),
 					Self::Custom(v) => v.write_css(sink),
 					Self::Unknown(v) => v.write_css(sink),
 					Self::Computed(v) => v.write_css(sink),
@@ -188,11 +394,12 @@ impl<'a> WriteCss<'a> for StyleValue<'a> {
 
 impl<'a> DeclarationValue<'a> for StyleValue<'a> {
 	fn parse_declaration_value(name: &Atom, parser: &mut Parser<'a>) -> ParserResult<Self> {
-		if name.starts_with("--") {
+		// SCSS variables can hold any value, just as custom properties can.
+		if name.starts_with("--") || name.starts_with('$') {
 			return Ok(Self::Custom(Custom::parse(parser)?));
 		}
 		if let Token::Ident(atom) = parser.peek() {
-			match atom.to_ascii_lowercase() {
+			match atom.to_ascii_lowercase_atom() {
 				atom!("initial") => {
 					parser.advance();
 					return Ok(Self::Initial);
@@ -227,11 +434,21 @@ impl<'a> DeclarationValue<'a> for StyleValue<'a> {
 					$(
 						&$atom => {
 							let checkpoint = parser.checkpoint();
-							if let Ok(val) = values::$name::parse(parser) {
-								if peek!(parser, Token::Semicolon | Token::RightCurly | Token::Eof | Token::Delim('!')) {
-									return Ok(Self::$name(val))
+							let start = parser.pos();
+							// The first token which didn't match, which is the one after the value if it was parsed
+							// but there's more to it, or the last one parsed before it failed.
+							let failed_at = match values::$name::parse(parser) {
+								Ok(val) => {
+									if peek!(parser, Token::Semicolon | Token::RightCurly | Token::Eof | Token::Delim('!')) {
+										return Ok(Self::$name(val))
+									}
+									Some(parser.peek_span())
 								}
-							}
+								// Values hdx doesn't model yet can't be told apart from invalid ones.
+								Err(err) if err.downcast_ref::<diagnostics::Unimplemented>().is_some() => None,
+								Err(_) if parser.pos() == start => Some(parser.peek_span()),
+								Err(_) => Some(parser.cur_span()),
+							};
 							if is_computed_token(parser.peek()) {
 								parser.rewind(checkpoint);
 								Self::Computed(Computed::parse(parser)?)
@@ -240,12 +457,12 @@ impl<'a> DeclarationValue<'a> for StyleValue<'a> {
 								if is_computed_token(parser.peek()) {
 									Self::Computed(Computed::parse(parser)?)
 								} else {
-									Self::Unknown(Unknown::parse(parser)?)
+									Self::parse_unknown(name, true, failed_at, parser)?
 								}
 							}
 						},
 					)+
-					_ => Self::Unknown(Unknown::parse(parser)?)
+					_ => Self::parse_unknown(name, false, None, parser)?
 				}
 			}
 		}
@@ -256,12 +473,12 @@ impl<'a> DeclarationValue<'a> for StyleValue<'a> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::test_helpers::*;
+	use crate::{css::StyleSheet, test_helpers::*};
 
 	#[test]
 	fn size_test() {
-		assert_size!(Property, 160);
-		assert_size!(StyleValue, 144);
+		assert_size!(Property, 192);
+		assert_size!(StyleValue, 176);
 	}
 
 	#[test]
@@ -278,4 +495,83 @@ mod tests {
 		assert_minify!(Property, "width: 1px", "width:1px");
 		assert_minify!(Property, "width: min(1px, 2px)", "width:min(1px, 2px)");
 	}
+
+	#[test]
+	fn test_invalid_value() {
+		let allocator = bumpalo::Bump::default();
+		let source_text = "border: 1px sollid red";
+		let result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<Property>();
+		assert!(result.errors.is_empty());
+		assert!(matches!(result.output.unwrap().node.value, StyleValue::Unknown(_)));
+		let warning = result.warnings[0].downcast_ref::<diagnostics::InvalidValue>().unwrap();
+		assert_eq!(warning.2.source_text(source_text), "sollid");
+		assert_eq!(
+			warning.1.as_deref(),
+			Some("The syntax of 'border' is `<line-width> || <line-style> || <color>`. Did you mean 'solid'?")
+		);
+		let result = Parser::new(&allocator, "float: lefty", Features::default()).parse_entirely_with::<Property>();
+		let warning = result.warnings[0].downcast_ref::<diagnostics::InvalidValue>().unwrap();
+		assert!(warning.1.as_ref().unwrap().ends_with("Did you mean 'left'?"));
+		let result = Parser::new(&allocator, "qwertyuiop: red", Features::default()).parse_entirely_with::<Property>();
+		assert!(result.warnings.is_empty());
+		let result =
+			Parser::new(&allocator, "border: 1px sollid red", Features::Strict).parse_entirely_with::<Property>();
+		assert!(result.errors[0].downcast_ref::<diagnostics::InvalidValue>().is_some());
+	}
+
+	#[test]
+	fn test_valid_values_do_not_warn() {
+		let allocator = bumpalo::Bump::default();
+		for source_text in [
+			"flex-grow: 1",
+			"flex: 1 1 auto",
+			"order: 2",
+			"gap: 1rem",
+			"transition: opacity .3s",
+			"display: block",
+			"display: inline flex !important",
+			"flex-wrap: nowrap",
+			"position: -webkit-sticky",
+			"border-color: rgba(var(--rgb), var(--opacity))",
+			"content: open-quote",
+			"content: counters(section, '.') '. '",
+			"content: counter(x)",
+			"color: canvastext",
+			"font-family: SF Mono, monospace",
+			"pointer-events: all",
+			"-ms-flex-pack: distribute",
+			"-webkit-flex: 1 1 auto",
+			"-webkit-font-smoothing: subpixel-antialiased",
+			"content: url(a.png)",
+			"content: \"a\" \"b\"",
+		] {
+			let result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<Property>();
+			assert!(result.warnings.is_empty(), "{}: {:?}", source_text, result.warnings);
+		}
+		let source_text = "a{display:block;margin-top:0;flex-grow:1;cursor:pointer}";
+		let result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<StyleSheet>();
+		assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+	}
+
+	#[test]
+	fn test_unknown_property() {
+		let allocator = bumpalo::Bump::default();
+		let source_text = "backgroud-color: red";
+		let result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<Property>();
+		let warning = result.warnings[0].downcast_ref::<diagnostics::UnknownPropertySuggest>().unwrap();
+		assert_eq!((warning.1.as_ref(), warning.2.source_text(source_text)), ("background-color", "backgroud-color"));
+		for source_text in
+			["-webkit-backgroud-color: red", "qwertyuiop: red", "--backgroud-color: red", "transform-origin: 0 0"]
+		{
+			let result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<Property>();
+			assert!(result.warnings.is_empty(), "{}", source_text);
+		}
+	}
+
+	#[test]
+	fn test_grammar_keywords() {
+		assert_eq!(grammar::keywords("<line-width>"), ["thin", "medium", "thick"]);
+		assert_eq!(grammar::keywords("auto | <length-percentage [0,∞]> | fit-content(<length-percentage>)"), ["auto"]);
+		assert_eq!(grammar::keywords("<'flex-direction'> || <'flex-wrap'>").len(), 7);
+	}
 }
@@ -1,5 +1,5 @@
-use hdx_atom::{atom, Atomizable};
-use hdx_derive::Atomizable;
+use hdx_atom::{atom, Atomizable, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 use hdx_lexer::{Include, QuoteStyle, Token};
 use hdx_parser::{
 	diagnostics::{self},
@@ -8,8 +8,8 @@ use hdx_parser::{
 use hdx_writer::{write_css, CssWriter, OutputOption, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-syntax-3/#charset-rule
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub enum Charset {
 	#[atomizable("utf-8")]
 	Utf8,
@@ -65,15 +65,15 @@ pub enum Charset {
 
 impl<'a> Parse<'a> for Charset {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect!(parser.next(), Token::AtKeyword(atom!("charset")));
-		expect!(parser.next_with(Include::Whitespace), Token::Whitespace);
-		match parser.next_with(Include::Whitespace) {
+		expect!(parser.next_token(), Token::AtKeyword("charset"));
+		expect!(parser.next_token_with(Include::Whitespace), Token::Whitespace);
+		match parser.next_token_with(Include::Whitespace) {
 			Token::String(atom, QuoteStyle::Double) => {
-				if let Some(rule) = Self::from_atom(atom) {
-					expect!(parser.next_with(Include::Whitespace), Token::Semicolon);
+				if let Some(rule) = Self::from_atom(&atom.to_atom()) {
+					expect!(parser.next_token_with(Include::Whitespace), Token::Semicolon);
 					Ok(rule)
 				} else {
-					Err(diagnostics::UnexpectedCharset(atom.clone(), parser.span()))?
+					Err(diagnostics::UnexpectedCharset(atom.to_atom(), parser.span()))?
 				}
 			}
 			token => unexpected!(parser, token),
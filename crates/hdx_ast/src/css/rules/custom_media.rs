@@ -0,0 +1,75 @@
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
+use hdx_lexer::Token;
+use hdx_parser::{
+	diagnostics, expect_ignore_case, unexpected, Features, Parse, Parser, Result as ParserResult, Spanned,
+};
+use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
+
+use super::MediaQueryList;
+
+// https://drafts.csswg.org/mediaqueries-5/#custom-mq
+// These are only parsed with Features::AllowCustomMedia, as they're yet to be supported by browsers.
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
+pub struct CustomMedia {
+	pub name: Atom,
+	pub query: Spanned<MediaQueryList>,
+}
+
+impl<'a> Parse<'a> for CustomMedia {
+	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
+		let span = parser.span();
+		expect_ignore_case!(parser.next_token(), Token::AtKeyword(atom!("custom-media")));
+		if !parser.enabled(Features::AllowCustomMedia) {
+			Err(diagnostics::UnexpectedAtRule(atom!("custom-media"), span.end(parser.pos())))?
+		}
+		let name = match parser.next_token().clone() {
+			Token::Ident(name) if name.starts_with("--") => name.to_atom(),
+			token => unexpected!(parser, token),
+		};
+		let query = MediaQueryList::parse_spanned(parser)?;
+		match parser.peek().clone() {
+			Token::Semicolon => parser.advance(),
+			Token::Eof => {}
+			token => unexpected!(parser, token),
+		}
+		Ok(Self { name, query })
+	}
+}
+
+impl<'a> WriteCss<'a> for CustomMedia {
+	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
+		write_css!(sink, '@', atom!("custom-media"), ' ', self.name, ' ', self.query, ';');
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{css::StyleSheet, test_helpers::*};
+	use bumpalo::Bump;
+
+	#[test]
+	fn size_test() {
+		assert_size!(CustomMedia, 136);
+	}
+
+	#[test]
+	fn test_features() {
+		let allocator = Bump::default();
+		let css = "@custom-media --narrow (max-width: 30em);";
+		let parser = Parser::new(&allocator, css, Features::AllowCustomMedia);
+		let result = parser.parse_entirely_with::<CustomMedia>();
+		assert!(result.errors.is_empty());
+		assert_eq!(result.output.unwrap().node.name, "--narrow".to_atom());
+		// Without the feature they're unknown at-rules.
+		let result = Parser::new(&allocator, css, Features::default()).parse_entirely_with::<StyleSheet>();
+		assert!(result.errors.is_empty());
+		assert!(!result.warnings.is_empty());
+		let css = "@media (--narrow) { a { color: red } }";
+		let result = Parser::new(&allocator, css, Features::AllowCustomMedia).parse_entirely_with::<StyleSheet>();
+		assert!(result.errors.is_empty() && result.warnings.is_empty());
+	}
+}
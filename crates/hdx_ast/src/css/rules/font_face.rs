@@ -1,17 +1,21 @@
 use hdx_atom::{atom, Atom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_parser::{expect_ignore_case, Declaration, Parse, Parser, Result as ParserResult, RuleList, Spanned, Vec};
 use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
 
 use crate::css::properties::StyleValue;
 
 // https://drafts.csswg.org/css-fonts/#font-face-rule
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
-pub struct FontFace<'a>(Vec<'a, Spanned<FontProperty<'a>>>);
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
+pub struct FontFace<'a>(
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
+	Vec<'a, Spanned<FontProperty<'a>>>,
+);
 
 impl<'a> Parse<'a> for FontFace<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case!(parser.next(), Token::AtKeyword(atom!("font-face")));
+		expect_ignore_case!(parser.next_token(), Token::AtKeyword(atom!("font-face")));
 		Ok(Self(Self::parse_rule_list(parser)?))
 	}
 }
@@ -40,8 +44,8 @@ impl<'a> WriteCss<'a> for FontFace<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename = "property"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", rename = "property"))]
 pub struct FontProperty<'a> {
 	name: Atom,
 	value: StyleValue<'a>,
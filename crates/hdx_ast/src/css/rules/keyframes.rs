@@ -1,4 +1,5 @@
-use hdx_atom::{atom, Atom};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::{QuoteStyle, Token};
 use hdx_parser::{
 	diagnostics, discard, expect, expect_ignore_case, unexpected, unexpected_ident, AtRule, Parse, Parser,
@@ -10,8 +11,8 @@ use smallvec::{smallvec, SmallVec};
 use crate::css::{properties::Property, units::Percent};
 
 // https://drafts.csswg.org/css-animations/#at-ruledef-keyframes
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct Keyframes<'a> {
 	name: Spanned<KeyframeName>,
 	rules: Spanned<KeyframeList<'a>>,
@@ -19,7 +20,7 @@ pub struct Keyframes<'a> {
 
 impl<'a> Parse<'a> for Keyframes<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case!(parser.next(), Token::AtKeyword(atom!("keyframes")));
+		expect_ignore_case!(parser.next_token(), Token::AtKeyword(atom!("keyframes")));
 		let span = parser.span();
 		match Self::parse_at_rule(parser)? {
 			(Some(name), Some(rules)) => Ok(Self { name, rules }),
@@ -44,8 +45,8 @@ impl<'a> WriteCss<'a> for Keyframes<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct KeyframeName(pub Atom, pub QuoteStyle);
 
 impl KeyframeName {
@@ -59,15 +60,15 @@ impl KeyframeName {
 
 impl<'a> Parse<'a> for KeyframeName {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
+		match parser.next_token() {
 			Token::Ident(atom) => {
-				if Self::valid_ident(atom) {
-					Ok(Self(atom.clone(), QuoteStyle::None))
+				if Self::valid_ident(&atom.to_atom()) {
+					Ok(Self(atom.to_atom(), QuoteStyle::None))
 				} else {
 					unexpected_ident!(parser, atom)
 				}
 			}
-			Token::String(atom, quote_style) => Ok(Self(atom.clone(), *quote_style)),
+			Token::String(atom, quote_style) => Ok(Self(atom.to_atom(), *quote_style)),
 			token => unexpected!(parser, token),
 		}
 	}
@@ -79,13 +80,15 @@ impl<'a> WriteCss<'a> for KeyframeName {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
-pub struct KeyframeList<'a>(Vec<'a, Spanned<Keyframe<'a>>>);
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
+pub struct KeyframeList<'a>(
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))] Vec<'a, Spanned<Keyframe<'a>>>,
+);
 
 impl<'a> Parse<'a> for KeyframeList<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect!(parser.next(), Token::LeftCurly);
+		expect!(parser.next_token(), Token::LeftCurly);
 		let mut rules = parser.new_vec();
 		loop {
 			if discard!(parser, Token::RightCurly) {
@@ -108,10 +111,12 @@ impl<'a> WriteCss<'a> for KeyframeList<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Keyframe<'a> {
 	selector: SmallVec<[KeyframeSelector; 1]>,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	properties: Vec<'a, Spanned<Property<'a>>>,
 }
 
@@ -162,8 +167,8 @@ impl<'a> WriteCss<'a> for Keyframe<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum KeyframeSelector {
 	From,
 	To,
@@ -172,13 +177,13 @@ pub enum KeyframeSelector {
 
 impl<'a> Parse<'a> for KeyframeSelector {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+		match parser.next_token() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("from") => Ok(KeyframeSelector::From),
 				atom!("to") => Ok(KeyframeSelector::To),
 				_ => unexpected_ident!(parser, atom),
 			},
-			Token::Dimension(n, atom!("%"), _) if *n >= 0.0 && *n <= 100.0 => Ok(Self::Percent(n.into())),
+			Token::Dimension(n, "%", _, _) if *n >= 0.0 && *n <= 100.0 => Ok(Self::Percent(n.into())),
 			token => unexpected!(parser, token),
 		}
 	}
@@ -9,7 +9,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(DeviceHeightMediaFeature, 20);
+		assert_size!(DeviceHeightMediaFeature, 40);
 	}
 
 	#[test]
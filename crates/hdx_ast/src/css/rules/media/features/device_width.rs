@@ -9,7 +9,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(DeviceWidthMediaFeature, 20);
+		assert_size!(DeviceWidthMediaFeature, 40);
 	}
 
 	#[test]
@@ -1,19 +1,20 @@
 use hdx_atom::atom;
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{expect, expect_ignore_case, peek, unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", content = "value"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value"))]
 pub enum HackMediaFeature {
 	IEBackslashZero,
 }
 
 impl<'a> Parse<'a> for HackMediaFeature {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case! { parser.next(), Token::Ident(_):
+		expect_ignore_case! { parser.next_token(), Token::Ident(_):
 			atom!("min-width") => {
-				expect!(parser.next(), Token::Colon);
+				expect!(parser.next_token(), Token::Colon);
 				let (a, b, c, d, e) = (
 					parser.legacy_peek_next_char(0),
 					parser.legacy_peek_next_char(1),
@@ -21,7 +22,7 @@ impl<'a> Parse<'a> for HackMediaFeature {
 					parser.legacy_peek_next_char(3),
 					parser.legacy_peek_next_char(4)
 				);
-				if peek!(parser, Token::Dimension(_, _, _)) &&
+				if peek!(parser, Token::Dimension(_, _, _, _)) &&
 				(matches!((a, b, c, d), (Some('0'), Some('\\'), Some('0'), Some(' ') | Some(')') | None))) ||
 				(matches!((a, b, c, d, e), (Some(' '), Some('0'), Some('\\'), Some('0'), Some(' ') | Some(')') | None)))
 				{
@@ -9,7 +9,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(HeightMediaFeature, 20);
+		assert_size!(HeightMediaFeature, 40);
 	}
 
 	#[test]
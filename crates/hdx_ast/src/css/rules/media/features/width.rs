@@ -9,7 +9,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(WidthMediaFeature, 20);
+		assert_size!(WidthMediaFeature, 40);
 	}
 
 	#[test]
@@ -1,13 +1,16 @@
-use hdx_derive::{Atomizable, Parsable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Visitable};
 use smallvec::{smallvec, SmallVec};
 
-use hdx_atom::{atom, Atom, Atomizable};
+use hdx_atom::{atom, Atom, Atomizable, ToAtom};
 use hdx_lexer::Token;
 use hdx_parser::{
-	diagnostics, discard, expect, expect_ignore_case, match_ignore_case, peek, todo, unexpected_ident, AtRule, Parse,
-	Parser, Result as ParserResult, RuleList, Spanned, Vec,
+	diagnostics, discard, expect, expect_ignore_case, match_ignore_case, peek, todo, unexpected_ident, AtRule, Box,
+	Commented, Features, Parse, Parser, Result as ParserResult, RuleList, Spanned, Trivia, Vec,
+};
+use hdx_writer::{
+	write_comment_lines, write_css, write_list, write_trailing_comments, CssWriter, OutputOption,
+	Result as WriterResult, WriteCss,
 };
-use hdx_writer::{write_css, write_list, CssWriter, OutputOption, Result as WriterResult, WriteCss};
 
 use crate::css::stylesheet::Rule;
 
@@ -15,20 +18,29 @@ mod features;
 use features::*;
 
 // https://drafts.csswg.org/mediaqueries-4/
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct Media<'a> {
 	pub query: Spanned<MediaQueryList>,
 	pub rules: Spanned<MediaRules<'a>>,
+	// Comments after the last rule in the block
+	#[cfg_attr(
+		feature = "serde",
+		serde(skip_serializing_if = "Option::is_none", default, deserialize_with = "crate::css::arena::option_boxed")
+	)]
+	pub trivia: Option<Box<'a, Trivia<'a>>>,
 }
 
 // https://drafts.csswg.org/css-conditional-3/#at-ruledef-media
 impl<'a> Parse<'a> for Media<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case!(parser.next(), Token::AtKeyword(atom!("media")));
+		expect_ignore_case!(parser.next_token(), Token::AtKeyword(atom!("media")));
 		let span = parser.span();
 		match Self::parse_at_rule(parser)? {
-			(Some(query), Some(rules)) => Ok(Self { query, rules }),
+			(Some(query), Some(rules)) => {
+				let comments = parser.comments_within(rules.span);
+				Ok(Self { query, rules, trivia: parser.new_trivia(parser.new_vec(), comments) })
+			}
 			(Some(_), None) => Err(diagnostics::MissingAtRuleBlock(span.end(parser.pos())))?,
 			(None, Some(_)) => Err(diagnostics::MissingAtRulePrelude(span.end(parser.pos())))?,
 			(None, None) => Err(diagnostics::MissingAtRulePrelude(span.end(parser.pos())))?,
@@ -55,14 +67,24 @@ impl<'a> WriteCss<'a> for Media<'a> {
 		sink.indent();
 		self.rules.write_css(sink)?;
 		sink.write_newline()?;
+		if let Some(trivia) = &self.trivia {
+			write_comment_lines(sink, &trivia.trailing)?;
+		}
 		sink.dedent();
 		sink.write_char('}')
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
-pub struct MediaRules<'a>(pub Vec<'a, Spanned<Rule<'a>>>);
+pub struct MediaRules<'a>(pub Vec<'a, Spanned<Commented<'a, Rule<'a>>>>);
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for MediaRules<'a> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		crate::css::arena::vec(deserializer).map(Self)
+	}
+}
 
 impl<'a> Parse<'a> for MediaRules<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
@@ -71,24 +93,29 @@ impl<'a> Parse<'a> for MediaRules<'a> {
 }
 
 impl<'a> RuleList<'a> for MediaRules<'a> {
-	type Rule = Rule<'a>;
+	type Rule = Commented<'a, Rule<'a>>;
 }
 
 impl<'a> WriteCss<'a> for MediaRules<'a> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
 		let mut rules = self.0.iter().peekable();
 		while let Some(rule) = rules.next() {
+			write_comment_lines(sink, rule.node.leading())?;
 			rule.write_css(sink)?;
+			write_trailing_comments(sink, rule.node.trailing())?;
 			if rules.peek().is_some() {
 				sink.write_newline()?;
+				if sink.format_options().blank_line_between_rules {
+					sink.write_newline()?;
+				}
 			}
 		}
 		Ok(())
 	}
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MediaQueryList(pub SmallVec<[Spanned<MediaQuery>; 1]>);
 
 impl MediaQueryList {
@@ -116,10 +143,10 @@ impl<'a> WriteCss<'a> for MediaQueryList {
 	}
 }
 
-#[derive(Parsable, Atomizable, Debug, PartialEq, Hash)]
+#[derive(Parsable, Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum MediaPreCondition {
@@ -127,8 +154,9 @@ pub enum MediaPreCondition {
 	Only,
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MediaQuery {
 	precondition: Option<MediaPreCondition>,
 	media_type: Option<MediaType>,
@@ -144,7 +172,7 @@ impl<'a> Parse<'a> for MediaQuery {
 			condition = Some(MediaCondition::parse(parser)?);
 			return Ok(Self { precondition, media_type, condition });
 		}
-		expect_ignore_case! { parser.next(), Token::Ident(_):
+		expect_ignore_case! { parser.next_token(), Token::Ident(_):
 			atom => {
 				if let Some(cond) = MediaPreCondition::from_atom(&atom) {
 					precondition = Some(cond);
@@ -157,7 +185,7 @@ impl<'a> Parse<'a> for MediaQuery {
 		}
 		match parser.peek() {
 			Token::Ident(ident) if precondition.is_some() => {
-				media_type = MediaType::from_atom(ident);
+				media_type = MediaType::from_atom(&ident.to_atom());
 				if media_type.is_some() {
 					parser.advance();
 				} else {
@@ -197,8 +225,8 @@ impl<'a> WriteCss<'a> for MediaQuery {
 	}
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value"))]
 pub enum MediaType {
 	All,
 	Print,
@@ -240,8 +268,8 @@ impl<'a> WriteCss<'a> for MediaType {
 	}
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", content = "value"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value"))]
 pub enum MediaCondition {
 	Is(MediaFeature),
 	Not(MediaFeature),
@@ -270,7 +298,7 @@ impl<'a> Parse<'a> for MediaCondition {
 		expect_ignore_case! { parser.peek(), Token::Ident(_):
 			atom!("and") => {
 				loop {
-					expect_ignore_case!(parser.next(), Token::Ident(atom!("and")));
+					expect_ignore_case!(parser.next_token(), Token::Ident(atom!("and")));
 					features.push(MediaFeature::parse(parser)?);
 					if !match_ignore_case!(parser.peek(), Token::Ident(atom!("and"))) {
 						return Ok(Self::And(features));
@@ -279,7 +307,7 @@ impl<'a> Parse<'a> for MediaCondition {
 			},
 			atom!("or") => {
 				loop {
-					expect_ignore_case!(parser.next(), Token::Ident(atom!("or")));
+					expect_ignore_case!(parser.next_token(), Token::Ident(atom!("or")));
 					features.push(MediaFeature::parse(parser)?);
 					if !match_ignore_case!(parser.peek(), Token::Ident(atom!("or"))) {
 						return Ok(Self::Or(features));
@@ -334,11 +362,17 @@ impl<'a> WriteCss<'a> for MediaCondition {
 macro_rules! media_feature {
 	( $($name: ident($typ: ident): atom!($atom: tt)$(| $alts:pat)*,)+) => {
 		// https://drafts.csswg.org/mediaqueries-5/#media-descriptor-table
-		#[derive(Debug, PartialEq, Hash)]
-		#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+		#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde::Serialize, serde::Deserialize),
+			serde(tag = "type", content = "value")
+		)]
 		pub enum MediaFeature {
 			$($name($typ),)+
 			Hack(HackMediaFeature),
+			// A reference to an `@custom-media` rule, only parsed with Features::AllowCustomMedia.
+			Custom { name: Atom },
 		}
 	}
 }
@@ -347,7 +381,16 @@ apply_medias!(media_feature);
 
 impl<'a> Parse<'a> for MediaFeature {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect!(parser.next(), Token::LeftParen);
+		expect!(parser.next_token(), Token::LeftParen);
+		if parser.enabled(Features::AllowCustomMedia) {
+			if let Token::Ident(name) = parser.peek().clone() {
+				if name.starts_with("--") {
+					parser.advance();
+					expect!(parser.next_token(), Token::RightParen);
+					return Ok(Self::Custom { name: name.to_atom() });
+				}
+			}
+		}
 		macro_rules! match_media {
 			( $($name: ident($typ: ident): atom!($atom: tt)$(| $alts:pat)*,)+) => {
 				expect_ignore_case!{ parser.peek(), Token::Ident(_):
@@ -357,12 +400,17 @@ impl<'a> Parse<'a> for MediaFeature {
 		}
 		let mut value = apply_medias!(match_media);
 		if value.is_err() {
+			let span = parser.span();
+			let name = if let Token::Ident(name) = parser.peek() { name.to_atom() } else { atom!("") };
 			if let Ok(hack) = HackMediaFeature::parse(parser) {
+				if parser.enabled(Features::Strict) && !parser.enabled(Features::AllowIeHacks) {
+					Err(diagnostics::DisallowedIeHack(name, span.end(parser.pos())))?
+				}
 				value = Ok(Self::Hack(hack));
 			}
 		}
 		if value.is_ok() {
-			expect!(parser.next(), Token::RightParen);
+			expect!(parser.next_token(), Token::RightParen);
 		}
 		value
 	}
@@ -376,6 +424,7 @@ impl<'a> WriteCss<'a> for MediaFeature {
 				match self {
 					$(Self::$name(f) => f.write_css(sink)?,)+
 					Self::Hack(f) => f.write_css(sink)?,
+					Self::Custom { name } => name.write_css(sink)?,
 				}
 			}
 		}
@@ -464,10 +513,10 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Media, 144);
-		assert_size!(MediaQueryList, 96);
-		assert_size!(MediaQuery, 72);
-		assert_size!(MediaCondition, 48);
+		assert_size!(Media, 176);
+		assert_size!(MediaQueryList, 120);
+		assert_size!(MediaQuery, 96);
+		assert_size!(MediaCondition, 72);
 		assert_size!(MediaType, 16);
 	}
 
@@ -513,6 +562,14 @@ mod tests {
 		assert_minify!(Media, "@media print {}", "");
 	}
 
+	#[test]
+	fn test_ie_hack() {
+		let allocator = bumpalo::Bump::default();
+		let result =
+			Parser::new(&allocator, "(MIN-WIDTH: 0\\0)", Features::Strict).parse_entirely_with::<MediaFeature>();
+		assert_eq!(result.errors[0].to_string(), "'MIN-WIDTH' is a hack for old versions of Internet Explorer");
+	}
+
 	#[test]
 	fn test_errors() {
 		assert_parse_error!(MediaQuery, "(hover) and or (pointer)");
@@ -1,3 +1,4 @@
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult};
 
@@ -5,6 +6,7 @@ pub mod charset;
 pub mod color_profile;
 pub mod container;
 pub mod counter_style;
+pub mod custom_media;
 pub mod document;
 pub mod font_face;
 pub mod font_feature_values;
@@ -26,6 +28,7 @@ pub use charset::*;
 pub use color_profile::*;
 pub use container::*;
 pub use counter_style::*;
+pub use custom_media::*;
 pub use document::*;
 pub use font_face::*;
 pub use font_feature_values::*;
@@ -43,6 +46,7 @@ pub use starting_style::*;
 pub use supports::*;
 pub use webkit::*;
 
+#[derive(Visitable, ShiftSpans, Hash)]
 pub struct NoPreludeAllowed;
 impl<'a> Parse<'a> for NoPreludeAllowed {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
@@ -53,6 +57,7 @@ impl<'a> Parse<'a> for NoPreludeAllowed {
 	}
 }
 
+#[derive(Visitable, ShiftSpans, Hash)]
 pub struct NoBlockAllowed;
 impl<'a> Parse<'a> for NoBlockAllowed {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
@@ -8,13 +8,13 @@ use smallvec::{smallvec, SmallVec};
 
 use super::NoPreludeAllowed;
 use crate::{css::properties::Property, Specificity, ToSpecificity};
-use hdx_atom::{atom, Atom, Atomizable};
-use hdx_derive::Atomizable;
+use hdx_atom::{atom, Atom, Atomizable, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 
 // https://drafts.csswg.org/cssom-1/#csspagerule
 // https://drafts.csswg.org/css-page-3/#at-page-rule
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct Page<'a> {
 	pub selectors: Option<Spanned<PageSelectorList>>,
 	pub style: Spanned<PageDeclaration<'a>>,
@@ -23,7 +23,7 @@ pub struct Page<'a> {
 // https://drafts.csswg.org/css-page-3/#syntax-page-selector
 impl<'a> Parse<'a> for Page<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case!(parser.next(), Token::AtKeyword(atom!("page")));
+		expect_ignore_case!(parser.next_token(), Token::AtKeyword(atom!("page")));
 		let span = parser.span();
 		let (selectors, style) = Self::parse_at_rule(parser)?;
 		if let Some(style) = style {
@@ -56,8 +56,8 @@ impl<'a> WriteCss<'a> for Page<'a> {
 	}
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct PageSelectorList(pub SmallVec<[Spanned<PageSelector>; 1]>);
 
 impl<'a> Parse<'a> for PageSelectorList {
@@ -82,8 +82,8 @@ impl<'a> WriteCss<'a> for PageSelectorList {
 	}
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct PageSelector {
 	pub page_type: Option<Atom>,
 	pub pseudos: SmallVec<[Spanned<PagePseudoClass>; 1]>,
@@ -94,7 +94,7 @@ impl<'a> Parse<'a> for PageSelector {
 		let mut page_type = None;
 		let mut pseudos = smallvec![];
 		if let Token::Ident(atom) = parser.peek() {
-			page_type = Some(atom.clone());
+			page_type = Some(atom.to_atom());
 			parser.advance();
 		}
 		while let Token::Colon = parser.peek() {
@@ -133,8 +133,8 @@ impl PageSelector {
 	}
 }
 
-#[derive(Atomizable, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum PagePseudoClass {
 	Left,
 	Right,
@@ -144,11 +144,11 @@ pub enum PagePseudoClass {
 
 impl<'a> Parse<'a> for PagePseudoClass {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect!(parser.next(), Token::Colon);
-		match parser.next_with(Include::Whitespace) {
-			Token::Ident(name) => match Self::from_atom(name) {
+		expect!(parser.next_token(), Token::Colon);
+		match parser.next_token_with(Include::Whitespace) {
+			Token::Ident(name) => match Self::from_atom(&name.to_atom()) {
 				Some(v) => Ok(v),
-				_ => Err(diagnostics::UnexpectedPseudoClass(name.clone(), parser.span()).into()),
+				_ => Err(diagnostics::UnexpectedPseudoClass(name.to_atom(), parser.span()).into()),
 			},
 			token => unexpected!(parser, token),
 		}
@@ -166,12 +166,12 @@ impl ToSpecificity for PagePseudoClass {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct PageDeclaration<'a> {
-	#[cfg_attr(feature = "serde", serde(borrow))]
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	pub properties: Vec<'a, Spanned<Property<'a>>>,
-	#[cfg_attr(feature = "serde", serde(borrow))]
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	pub rules: Vec<'a, Spanned<MarginRule<'a>>>,
 }
 
@@ -221,20 +221,19 @@ impl<'a> WriteCss<'a> for PageDeclaration<'a> {
 }
 
 // https://drafts.csswg.org/cssom-1/#cssmarginrule
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct MarginRule<'a> {
 	pub name: PageMarginBox,
-	#[cfg_attr(feature = "serde", serde(borrow))]
 	pub style: Spanned<MarginDeclaration<'a>>,
 }
 
 impl<'a> Parse<'a> for MarginRule<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		let span = parser.span();
-		match parser.next() {
+		match parser.next_token() {
 			Token::AtKeyword(atom) => {
-				if let Some(name) = PageMarginBox::from_atom(atom) {
+				if let Some(name) = PageMarginBox::from_atom(&atom.to_atom()) {
 					let (_, style) = Self::parse_at_rule(parser)?;
 					if let Some(style) = style {
 						Ok(Self { name, style })
@@ -242,7 +241,7 @@ impl<'a> Parse<'a> for MarginRule<'a> {
 						Err(diagnostics::MissingAtRuleBlock(span.end(parser.pos())))?
 					}
 				} else {
-					Err(diagnostics::UnexpectedAtRule(atom.clone(), parser.span()))?
+					Err(diagnostics::UnexpectedAtRule(atom.to_atom(), parser.span()))?
 				}
 			}
 			token => unexpected!(parser, token),
@@ -265,8 +264,8 @@ impl<'a> WriteCss<'a> for MarginRule<'a> {
 	}
 }
 
-#[derive(Atomizable, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum PageMarginBox {
 	TopLeftCorner,     // atom!("top-left-corner")
 	TopLeft,           // atom!("top-left")
@@ -286,12 +285,12 @@ pub enum PageMarginBox {
 	LeftTop,           // atom!("left-top")
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct MarginDeclaration<'a> {
-	#[cfg_attr(feature = "serde", serde(borrow))]
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	pub properties: Vec<'a, Spanned<Property<'a>>>,
-	#[cfg_attr(feature = "serde", serde(borrow))]
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	pub rules: Vec<'a, Spanned<MarginRule<'a>>>,
 }
 
@@ -1,27 +1,39 @@
 use crate::{css::stylesheet::Rule, syntax::SimpleBlock};
-use hdx_atom::atom;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{
-	diagnostics, expect, expect_ignore_case, match_ignore_case, peek, unexpected, unexpected_ident, AtRule, Parse,
-	Parser, Result as ParserResult, RuleList, Spanned, Vec,
+	diagnostics, expect, expect_ignore_case, match_ignore_case, peek, unexpected, unexpected_ident, AtRule, Box,
+	Commented, Parse, Parser, Result as ParserResult, RuleList, Spanned, Trivia, Vec,
+};
+use hdx_writer::{
+	write_comment_lines, write_trailing_comments, CssWriter, OutputOption, Result as WriterResult, WriteCss,
 };
-use hdx_writer::{CssWriter, OutputOption, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-conditional-3/#at-supports
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct Supports<'a> {
 	pub condition: Spanned<SupportsCondition<'a>>,
 	pub rules: Spanned<SupportsRules<'a>>,
+	// Comments after the last rule in the block
+	#[cfg_attr(
+		feature = "serde",
+		serde(skip_serializing_if = "Option::is_none", default, deserialize_with = "crate::css::arena::option_boxed")
+	)]
+	pub trivia: Option<Box<'a, Trivia<'a>>>,
 }
 
 // https://drafts.csswg.org/css-conditional-3/#at-ruledef-supports
 impl<'a> Parse<'a> for Supports<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case!(parser.next(), Token::AtKeyword(atom!("supports")));
+		expect_ignore_case!(parser.next_token(), Token::AtKeyword(atom!("supports")));
 		let span = parser.span();
 		match Self::parse_at_rule(parser)? {
-			(Some(condition), Some(rules)) => Ok(Self { condition, rules }),
+			(Some(condition), Some(rules)) => {
+				let comments = parser.comments_within(rules.span);
+				Ok(Self { condition, rules, trivia: parser.new_trivia(parser.new_vec(), comments) })
+			}
 			(Some(_), None) => Err(diagnostics::MissingAtRuleBlock(span.end(parser.pos())))?,
 			(None, Some(_)) => Err(diagnostics::MissingAtRulePrelude(span.end(parser.pos())))?,
 			(None, None) => Err(diagnostics::MissingAtRulePrelude(span.end(parser.pos())))?,
@@ -53,15 +65,25 @@ impl<'a> WriteCss<'a> for Supports<'a> {
 		sink.indent();
 		self.rules.write_css(sink)?;
 		sink.write_newline()?;
+		if let Some(trivia) = &self.trivia {
+			write_comment_lines(sink, &trivia.trailing)?;
+		}
 		sink.dedent();
 		sink.write_char('}')?;
 		Ok(())
 	}
 }
 
-#[derive(Debug, PartialEq, Hash)]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
-pub struct SupportsRules<'a>(pub Vec<'a, Spanned<Rule<'a>>>);
+pub struct SupportsRules<'a>(pub Vec<'a, Spanned<Commented<'a, Rule<'a>>>>);
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for SupportsRules<'a> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		crate::css::arena::vec(deserializer).map(Self)
+	}
+}
 
 impl<'a> Parse<'a> for SupportsRules<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
@@ -70,28 +92,35 @@ impl<'a> Parse<'a> for SupportsRules<'a> {
 }
 
 impl<'a> RuleList<'a> for SupportsRules<'a> {
-	type Rule = Rule<'a>;
+	type Rule = Commented<'a, Rule<'a>>;
 }
 
 impl<'a> WriteCss<'a> for SupportsRules<'a> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
 		let mut rules = self.0.iter().peekable();
 		while let Some(rule) = rules.next() {
+			write_comment_lines(sink, rule.node.leading())?;
 			rule.write_css(sink)?;
+			write_trailing_comments(sink, rule.node.trailing())?;
 			if rules.peek().is_some() {
 				sink.write_newline()?;
+				if sink.format_options().blank_line_between_rules {
+					sink.write_newline()?;
+				}
 			}
 		}
 		Ok(())
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", content = "value"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value"))]
 pub enum SupportsCondition<'a> {
 	Is(SupportsFeature<'a>),
 	Not(SupportsFeature<'a>),
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	And(Vec<'a, SupportsFeature<'a>>),
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	Or(Vec<'a, SupportsFeature<'a>>),
 }
 
@@ -101,20 +130,20 @@ impl<'a> Parse<'a> for SupportsCondition<'a> {
 			Token::LeftParen => {
 				let wrapped = peek!(parser, 2, Token::LeftParen);
 				if wrapped {
-					parser.next();
+					parser.next_token();
 				}
 				let feature = SupportsFeature::parse(parser)?;
 				match parser.peek() {
-					Token::Ident(ident) => match ident.to_ascii_lowercase() {
+					Token::Ident(ident) => match ident.to_ascii_lowercase_atom() {
 						atom!("and") => {
 							let mut features = parser.new_vec();
 							features.push(feature);
 							loop {
-								expect_ignore_case!(parser.next(), Token::Ident(atom!("and")));
+								expect_ignore_case!(parser.next_token(), Token::Ident(atom!("and")));
 								features.push(SupportsFeature::parse(parser)?);
 								if !match_ignore_case!(parser.peek(), Token::Ident(atom!("and"))) {
 									if wrapped {
-										expect!(parser.next(), Token::RightParen);
+										expect!(parser.next_token(), Token::RightParen);
 									}
 									return Ok(Self::And(features));
 								}
@@ -124,11 +153,11 @@ impl<'a> Parse<'a> for SupportsCondition<'a> {
 							let mut features = parser.new_vec();
 							features.push(feature);
 							loop {
-								expect_ignore_case!(parser.next(), Token::Ident(atom!("or")));
+								expect_ignore_case!(parser.next_token(), Token::Ident(atom!("or")));
 								features.push(SupportsFeature::parse(parser)?);
 								if !match_ignore_case!(parser.peek(), Token::Ident(atom!("or"))) {
 									if wrapped {
-										expect!(parser.next(), Token::RightParen);
+										expect!(parser.next_token(), Token::RightParen);
 									}
 									return Ok(Self::Or(features));
 								}
@@ -136,24 +165,24 @@ impl<'a> Parse<'a> for SupportsCondition<'a> {
 						}
 						_ => {
 							if wrapped {
-								expect!(parser.next(), Token::RightParen);
+								expect!(parser.next_token(), Token::RightParen);
 							}
 							Ok(Self::Is(feature))
 						}
 					},
 					_ => {
 						if wrapped {
-							expect!(parser.next(), Token::RightParen);
+							expect!(parser.next_token(), Token::RightParen);
 						}
 						Ok(Self::Is(feature))
 					}
 				}
 			}
-			Token::Ident(ident) => match ident.to_ascii_lowercase() {
+			Token::Ident(ident) => match ident.to_ascii_lowercase_atom() {
 				atom!("and") => {
 					let mut features = parser.new_vec();
 					loop {
-						expect_ignore_case!(parser.next(), Token::Ident(atom!("and")));
+						expect_ignore_case!(parser.next_token(), Token::Ident(atom!("and")));
 						features.push(SupportsFeature::parse(parser)?);
 						if !match_ignore_case!(parser.peek(), Token::Ident(atom!("and"))) {
 							return Ok(Self::And(features));
@@ -163,7 +192,7 @@ impl<'a> Parse<'a> for SupportsCondition<'a> {
 				atom!("or") => {
 					let mut features = parser.new_vec();
 					loop {
-						expect_ignore_case!(parser.next(), Token::Ident(atom!("or")));
+						expect_ignore_case!(parser.next_token(), Token::Ident(atom!("or")));
 						features.push(SupportsFeature::parse(parser)?);
 						if !match_ignore_case!(parser.peek(), Token::Ident(atom!("or"))) {
 							return Ok(Self::And(features));
@@ -228,8 +257,8 @@ impl<'a> WriteCss<'a> for SupportsCondition<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct SupportsFeature<'a>(pub SimpleBlock<'a>);
 
 impl<'a> Parse<'a> for SupportsFeature<'a> {
@@ -252,7 +281,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Supports, 96);
+		assert_size!(Supports, 104);
 		assert_size!(SupportsCondition, 48);
 		assert_size!(SupportsRules, 32);
 	}
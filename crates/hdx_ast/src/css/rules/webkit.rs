@@ -1,12 +1,13 @@
 use hdx_atom::atom;
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_parser::{diagnostics, expect_ignore_case, AtRule, Parse, Parser, Result as ParserResult, Spanned};
 use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
 
 use super::{KeyframeList, KeyframeName};
 
 // https://drafts.csswg.org/css-animations/#at-ruledef-keyframes
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct WebkitKeyframes<'a> {
 	name: Spanned<KeyframeName>,
 	rules: Spanned<KeyframeList<'a>>,
@@ -14,7 +15,7 @@ pub struct WebkitKeyframes<'a> {
 
 impl<'a> Parse<'a> for WebkitKeyframes<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case!(parser.next(), Token::AtKeyword(atom!("-webkit-keyframes")));
+		expect_ignore_case!(parser.next_token(), Token::AtKeyword(atom!("-webkit-keyframes")));
 		let span = parser.span();
 		match Self::parse_at_rule(parser)? {
 			(Some(name), Some(rules)) => Ok(Self { name, rules }),
@@ -1,12 +1,14 @@
-use hdx_atom::{atom, Atom};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::{Include, QuoteStyle, Token};
 use hdx_parser::{expect, peek, unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
 
 use super::NSPrefix;
 
-#[derive(Default, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, Default, Debug, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct Attribute {
 	pub ns_prefix: NSPrefix,
 	pub name: Atom,
@@ -18,20 +20,20 @@ pub struct Attribute {
 
 impl<'a> Parse<'a> for Attribute {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect!(parser.next(), Token::LeftSquare);
+		expect!(parser.next_token(), Token::LeftSquare);
 		let mut attr = Self::default();
-		match parser.next().clone() {
-			Token::Delim('|') => match parser.next_with(Include::Whitespace) {
+		match parser.next_token().clone() {
+			Token::Delim('|') => match parser.next_token_with(Include::Whitespace) {
 				Token::Ident(name) => {
-					attr.name = name.clone();
+					attr.name = name.to_atom();
 				}
 				token => unexpected!(parser, token),
 			},
-			Token::Delim('*') => match parser.next_with(Include::Whitespace) {
-				Token::Delim('|') => match parser.next_with(Include::Whitespace) {
+			Token::Delim('*') => match parser.next_token_with(Include::Whitespace) {
+				Token::Delim('|') => match parser.next_token_with(Include::Whitespace) {
 					Token::Ident(name) => {
 						attr.ns_prefix = NSPrefix::Wildcard;
-						attr.name = name.clone();
+						attr.name = name.to_atom();
 					}
 					token => unexpected!(parser, token),
 				},
@@ -39,41 +41,41 @@ impl<'a> Parse<'a> for Attribute {
 			},
 			Token::Ident(ns) => match parser.peek_with(Include::Whitespace).clone() {
 				Token::Delim('|') if peek!(parser, 2, Token::Ident(_)) => {
-					expect!(parser.next_with(Include::Whitespace), Token::Delim('|'));
-					match parser.next_with(Include::Whitespace) {
+					expect!(parser.next_token_with(Include::Whitespace), Token::Delim('|'));
+					match parser.next_token_with(Include::Whitespace) {
 						Token::Ident(name) => {
-							attr.ns_prefix = NSPrefix::Named(ns);
-							attr.name = name.clone();
+							attr.ns_prefix = NSPrefix::Named(ns.to_atom());
+							attr.name = name.to_atom();
 						}
 						token => unexpected!(parser, token),
 					}
 				}
 				_ => {
-					attr.name = ns;
+					attr.name = ns.to_atom();
 				}
 			},
 			token => unexpected!(parser, token),
 		};
-		match parser.next() {
+		match parser.next_token() {
 			Token::Delim('=') => attr.matcher = AttributeMatch::Exact,
 			Token::Delim('~') => {
-				expect!(parser.next_with(Include::all()), Token::Delim('='));
+				expect!(parser.next_token_with(Include::all()), Token::Delim('='));
 				attr.matcher = AttributeMatch::SpaceList
 			}
 			Token::Delim('|') => {
-				expect!(parser.next_with(Include::all()), Token::Delim('='));
+				expect!(parser.next_token_with(Include::all()), Token::Delim('='));
 				attr.matcher = AttributeMatch::LangPrefix
 			}
 			Token::Delim('^') => {
-				expect!(parser.next_with(Include::all()), Token::Delim('='));
+				expect!(parser.next_token_with(Include::all()), Token::Delim('='));
 				attr.matcher = AttributeMatch::Prefix
 			}
 			Token::Delim('$') => {
-				expect!(parser.next_with(Include::all()), Token::Delim('='));
+				expect!(parser.next_token_with(Include::all()), Token::Delim('='));
 				attr.matcher = AttributeMatch::Suffix
 			}
 			Token::Delim('*') => {
-				expect!(parser.next_with(Include::all()), Token::Delim('='));
+				expect!(parser.next_token_with(Include::all()), Token::Delim('='));
 				attr.matcher = AttributeMatch::Contains
 			}
 			Token::RightSquare => {
@@ -81,22 +83,22 @@ impl<'a> Parse<'a> for Attribute {
 			}
 			token => unexpected!(parser, token),
 		}
-		match parser.next().clone() {
-			Token::Ident(value) => attr.value = value,
+		match parser.next_token().clone() {
+			Token::Ident(value) => attr.value = value.to_atom(),
 			Token::String(value, quote_style) => {
 				attr.quote = quote_style;
-				attr.value = value;
+				attr.value = value.to_atom();
 			}
 			token => unexpected!(parser, token),
 		};
-		match parser.next() {
+		match parser.next_token() {
 			Token::Ident(ident) => {
-				attr.modifier = match ident.to_ascii_lowercase() {
+				attr.modifier = match ident.to_ascii_lowercase_atom() {
 					atom!("i") => AttributeModifier::Insensitive,
 					atom!("s") => AttributeModifier::Sensitive,
 					atom => unexpected_ident!(parser, atom),
 				};
-				expect!(parser.next(), Token::RightSquare);
+				expect!(parser.next_token(), Token::RightSquare);
 				Ok(attr)
 			}
 			Token::RightSquare => Ok(attr),
@@ -119,8 +121,8 @@ impl<'a> WriteCss<'a> for Attribute {
 	}
 }
 
-#[derive(Default, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", content = "value"))]
+#[derive(Visitable, ShiftSpans, Default, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value"))]
 pub enum AttributeMatch {
 	#[default]
 	Any, // [attr]
@@ -147,8 +149,8 @@ impl<'a> WriteCss<'a> for AttributeMatch {
 	}
 }
 
-#[derive(Default, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, Default, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub enum AttributeModifier {
 	#[default]
 	None,
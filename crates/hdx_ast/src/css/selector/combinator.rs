@@ -1,9 +1,11 @@
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::{Include, Token};
 use hdx_parser::{discard, expect, peek, unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 // https://drafts.csswg.org/selectors/#combinators
 pub enum Combinator {
 	Descendant,        // (Space)
@@ -27,7 +29,7 @@ impl<'a> Parse<'a> for Combinator {
 				'~' => Self::SubsequentSibling,
 				'&' => Self::Nesting,
 				'|' => {
-					expect!(parser.next_with(Include::Whitespace), Token::Delim('|'));
+					expect!(parser.next_token_with(Include::Whitespace), Token::Delim('|'));
 					Self::Column
 				}
 				_ if could_be_descendant_combinator => return Ok(Self::Descendant),
@@ -1,5 +1,5 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::{Atomizable, Parsable, Writable};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{discard, expect, unexpected, unexpected_function, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -7,10 +7,11 @@ use smallvec::{smallvec, SmallVec};
 
 use super::{ForgivingSelector, Nth, RelativeSelector, SelectorList};
 
-#[derive(PartialEq, Debug, Hash)]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[visitable(call)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum FunctionalPseudoClass<'a> {
@@ -33,8 +34,8 @@ pub enum FunctionalPseudoClass<'a> {
 
 impl<'a> Parse<'a> for FunctionalPseudoClass<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		let val = match parser.next() {
-			Token::Function(ident) => match ident.to_ascii_lowercase() {
+		let val = match parser.next_token() {
+			Token::Function(ident) => match ident.to_ascii_lowercase_atom() {
 				atom!("dir") => Self::Dir(DirValue::parse(parser)?),
 				atom!("has") => Self::Has(RelativeSelector::parse(parser)?),
 				atom!("host") => Self::Host(SelectorList::parse(parser)?),
@@ -43,8 +44,8 @@ impl<'a> Parse<'a> for FunctionalPseudoClass<'a> {
 				atom!("lang") => {
 					let mut langs = smallvec![];
 					loop {
-						match parser.next() {
-							Token::Ident(atom) | Token::String(atom, _) => langs.push(atom.clone()),
+						match parser.next_token() {
+							Token::Ident(atom) | Token::String(atom, _) => langs.push(atom.to_atom()),
 							token => unexpected!(parser, token),
 						}
 						if !discard!(parser, Token::Comma) {
@@ -62,8 +63,8 @@ impl<'a> Parse<'a> for FunctionalPseudoClass<'a> {
 				atom!("nth-of-type") => Self::NthOfType(Nth::parse(parser)?),
 				atom!("where") => Self::Where(ForgivingSelector::parse(parser)?),
 				atom!("state") => {
-					if let Token::Ident(atom) = parser.next().clone() {
-						Self::State(atom)
+					if let Token::Ident(atom) = parser.next_token().clone() {
+						Self::State(atom.to_atom())
 					} else {
 						unexpected!(parser)
 					}
@@ -72,7 +73,7 @@ impl<'a> Parse<'a> for FunctionalPseudoClass<'a> {
 			},
 			token => unexpected!(parser, token),
 		};
-		expect!(parser.next(), Token::RightParen);
+		expect!(parser.next_token(), Token::RightParen);
 		Ok(val)
 	}
 }
@@ -160,8 +161,12 @@ impl<'a> WriteCss<'a> for FunctionalPseudoClass<'a> {
 	}
 }
 
-#[derive(Writable, Parsable, Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Writable, Parsable, Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum DirValue {
 	Rtl, // atom!("rtl")
 	Ltr, // atom!("ltr")
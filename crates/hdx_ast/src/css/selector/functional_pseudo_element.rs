@@ -1,4 +1,5 @@
-use hdx_atom::{atom, Atom};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::{Include, Token};
 use hdx_parser::{expect, todo, unexpected, unexpected_function, Parse, Parser, Result as ParserResult, Vec};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -6,35 +7,41 @@ use smallvec::{smallvec, SmallVec};
 
 use super::SelectorComponent;
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FunctionalPseudoElement<'a> {
 	// https://drafts.csswg.org/css-highlight-api/#custom-highlight-pseudo
 	Highlight(Atom),
 	// https://drafts.csswg.org/css-shadow-parts/#part
 	Part(SmallVec<[Atom; 1]>),
 	// https://drafts.csswg.org/css-scoping/#slotted-pseudo
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	Slotted(Vec<'a, SelectorComponent<'a>>),
 }
 
 impl<'a> Parse<'a> for FunctionalPseudoElement<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next().clone() {
-			Token::Function(ident) => match ident.to_ascii_lowercase() {
-				atom!("highlight") => match parser.next().clone() {
+		match parser.next_token().clone() {
+			Token::Function(ident) => match ident.to_ascii_lowercase_atom() {
+				atom!("highlight") => match parser.next_token().clone() {
 					Token::Ident(name) => {
-						expect!(parser.next(), Token::RightParen);
+						expect!(parser.next_token(), Token::RightParen);
 						parser.advance_with(Include::Whitespace);
-						Ok(Self::Highlight(name))
+						Ok(Self::Highlight(name.to_atom()))
 					}
 					token => unexpected!(parser, token),
 				},
 				atom!("part") => {
 					let mut parts = smallvec![];
 					loop {
-						match parser.next() {
+						match parser.next_token() {
 							Token::Ident(name) => {
-								parts.push(name.clone());
+								parts.push(name.to_atom());
 							}
 							Token::RightParen => {
 								parser.advance_with(Include::Whitespace);
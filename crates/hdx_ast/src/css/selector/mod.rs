@@ -1,10 +1,14 @@
-use hdx_atom::{Atom, Atomizable};
+use hdx_atom::{Atom, Atomizable, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{
-	expect, unexpected, Parse, Parser, Result as ParserResult, SelectorComponent as SelectorComponentTrait,
-	SelectorList as SelectorListTrait, Spanned, Vec,
+	closest_match, expect, unexpected, ComplexSelector, Parse, Parser, Result as ParserResult,
+	SelectorComponent as SelectorComponentTrait, SelectorList as SelectorListTrait, Vec,
+};
+use hdx_writer::{
+	write_css, write_inline_comments, write_trailing_comments, CssWriter, Result as WriterResult, SelectorLayout,
+	WriteCss,
 };
-use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
 
 mod attribute;
 mod combinator;
@@ -19,22 +23,30 @@ mod pseudo_element;
 mod tag;
 mod webkit;
 
-use attribute::*;
-use combinator::*;
-use functional_pseudo_class::*;
-use functional_pseudo_element::*;
-use moz::*;
-use ms::*;
-use nth::*;
-use o::*;
-use pseudo_class::*;
-use pseudo_element::*;
-use tag::*;
-use webkit::*;
-
-#[derive(PartialEq, Debug, Hash)]
+pub use attribute::*;
+pub use combinator::*;
+pub use functional_pseudo_class::*;
+pub use functional_pseudo_element::*;
+pub use moz::*;
+pub use ms::*;
+pub use nth::*;
+pub use o::*;
+pub use pseudo_class::*;
+pub use pseudo_element::*;
+pub use tag::*;
+pub use webkit::*;
+
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[visitable(call)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
-pub struct SelectorList<'a>(pub Vec<'a, Spanned<Vec<'a, SelectorComponent<'a>>>>);
+pub struct SelectorList<'a>(pub Vec<'a, ComplexSelector<'a, SelectorComponent<'a>>>);
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for SelectorList<'a> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		crate::css::arena::complex_selectors(deserializer).map(Self)
+	}
+}
 
 impl<'a> Parse<'a> for SelectorList<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
@@ -50,7 +62,9 @@ impl<'a> WriteCss<'a> for SelectorList<'a> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
 		let mut selectors = self.0.iter().peekable();
 		while let Some(selector) = selectors.next() {
+			write_inline_comments(sink, selector.node.leading())?;
 			selector.write_css(sink)?;
+			write_trailing_comments(sink, selector.node.trailing())?;
 			if selectors.peek().is_some() {
 				sink.write_char(',')?;
 				sink.write_whitespace()?;
@@ -60,16 +74,41 @@ impl<'a> WriteCss<'a> for SelectorList<'a> {
 	}
 }
 
+impl<'a> SelectorList<'a> {
+	// Writes the selectors of a style rule, laid out according to the `selector_layout`, unlike selector lists
+	// nested within pseudo classes which are always kept on one line.
+	pub fn write_rule_selectors<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
+		let one_per_line = sink.format_options().selector_layout == SelectorLayout::OnePerLine;
+		let mut selectors = self.0.iter().peekable();
+		while let Some(selector) = selectors.next() {
+			write_inline_comments(sink, selector.node.leading())?;
+			selector.write_css(sink)?;
+			write_trailing_comments(sink, selector.node.trailing())?;
+			if let Some(next) = selectors.peek() {
+				sink.write_char(',')?;
+				if one_per_line || sink.remaining_width().is_some_and(|remaining| sink.measure(*next) + 1 > remaining) {
+					sink.write_newline()?;
+					sink.write_indent()?;
+				} else {
+					sink.write_whitespace()?;
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
 pub type ForgivingSelector<'a> = SelectorList<'a>;
 pub type RelativeSelector<'a> = SelectorList<'a>;
 
 // This encapsulates all `simple-selector` subtypes (e.g. `wq-name`,
 // `id-selector`) into one enum, as it makes parsing and visiting much more
 // practical.
-#[derive(PartialEq, Debug, Hash)]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[visitable(call)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum SelectorComponent<'a> {
@@ -123,6 +162,21 @@ impl<'a> SelectorComponentTrait<'a> for SelectorComponent<'a> {
 		Tag::from_atom(atom).map(Self::Tag)
 	}
 
+	fn suggest_type(atom: &Atom) -> Option<&'static str> {
+		if !matches!(Tag::from_atom(atom), Some(Tag::Unknown(_))) {
+			return None;
+		}
+		closest_match(atom, HtmlTag::atoms().iter().chain(SvgTag::atoms()).chain(MathmlTag::atoms()).copied())
+	}
+
+	fn suggest_pseudo_class(atom: &Atom) -> Option<&'static str> {
+		closest_match(atom, PseudoClass::atoms().iter().chain(LegacyPseudoElement::atoms()).copied())
+	}
+
+	fn suggest_pseudo_element(atom: &Atom) -> Option<&'static str> {
+		closest_match(atom, PseudoElement::atoms().iter().copied())
+	}
+
 	fn pseudo_class_from_atom(atom: &Atom) -> Option<Self> {
 		PseudoClass::from_atom(atom)
 			.map(Self::PseudoClass)
@@ -148,10 +202,10 @@ impl<'a> SelectorComponentTrait<'a> for SelectorComponent<'a> {
 	fn ns_type_from_token(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		let prefix = NSPrefix::parse(parser)?;
 		if !matches!(prefix, NSPrefix::None) {
-			expect!(parser.next(), Token::Delim('|'));
+			expect!(parser.next_token(), Token::Delim('|'));
 		}
-		match parser.next() {
-			Token::Ident(atom) => Ok(Self::NSPrefixedTag((prefix, atom.clone()))),
+		match parser.next_token() {
+			Token::Ident(atom) => Ok(Self::NSPrefixedTag((prefix, atom.to_atom()))),
 			Token::Delim('*') => Ok(Self::NSPrefixedWildcard(prefix)),
 			token => unexpected!(parser, token),
 		}
@@ -210,10 +264,10 @@ impl<'a> WriteCss<'a> for SelectorComponent<'a> {
 	}
 }
 
-#[derive(Default, Debug, PartialEq, Hash)]
+#[derive(Visitable, ShiftSpans, Default, Debug, PartialEq, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum NSPrefix {
@@ -225,9 +279,9 @@ pub enum NSPrefix {
 
 impl<'a> Parse<'a> for NSPrefix {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
+		match parser.next_token() {
 			Token::Delim('*') => Ok(Self::Wildcard),
-			Token::Ident(atom) => Ok(Self::Named(atom.clone())),
+			Token::Ident(atom) => Ok(Self::Named(atom.to_atom())),
 			Token::Delim('|') => Ok(Self::None),
 			token => unexpected!(parser, token),
 		}
@@ -284,7 +338,7 @@ mod tests {
 		assert_parse!(SelectorList, "::before:focus:target:right:playing:popover-open:blank");
 		assert_parse!(SelectorList, ":dir(ltr)");
 		assert_parse!(SelectorList, "tr:nth-child(n-1):state(foo)");
-		assert_parse!(SelectorList, " /**/ .foo", ".foo");
+		assert_parse!(SelectorList, " /**/ .foo", "/**/ .foo");
 		assert_parse!(SelectorList, ":lang(en-gb, en-us)");
 		assert_parse!(SelectorList, "& .foo", "& .foo");
 		assert_parse!(SelectorList, "&:hover", "&:hover");
@@ -309,4 +363,26 @@ mod tests {
 		assert_minify!(SelectorList, "a   b", "a b");
 		assert_minify!(SelectorList, ".foo[attr*='foo'] > * + *", ".foo[attr*=foo]>*+*");
 	}
+
+	#[test]
+	fn test_suggestions() {
+		use hdx_parser::{diagnostics, Features};
+		let allocator = bumpalo::Bump::default();
+		let parse = |source_text| {
+			Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<SelectorList>()
+		};
+		let result = parse("a:hvoer");
+		let error = result.errors[0].downcast_ref::<diagnostics::UnexpectedPseudoClassSuggest>().unwrap();
+		assert_eq!((error.1.as_ref(), error.2.source_text("a:hvoer")), ("hover", "hvoer"));
+		let result = parse("p::frist-line");
+		let error = result.errors[0].downcast_ref::<diagnostics::UnexpectedPseudoElementSuggest>().unwrap();
+		assert_eq!(error.1.as_ref(), "first-line");
+		assert!(result.errors[0].downcast_ref::<diagnostics::UnexpectedPseudoClass>().is_none());
+		assert!(parse("a:zzzzz").errors[0].downcast_ref::<diagnostics::UnexpectedPseudoClass>().is_some());
+		let result = parse("dvi > span");
+		assert!(result.errors.is_empty());
+		let warning = result.warnings[0].downcast_ref::<diagnostics::UnknownTagSuggest>().unwrap();
+		assert_eq!((warning.1.as_ref(), warning.2.source_text("dvi > span")), ("div", "dvi"));
+		assert!(parse("div > my-element").warnings.is_empty());
+	}
 }
@@ -1,5 +1,5 @@
 use hdx_atom::atom;
-use hdx_derive::Atomizable;
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{expect, expect_ignore_case, todo, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -7,8 +7,8 @@ use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 use super::functional_pseudo_class::DirValue;
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/Mozilla_Extensions#pseudo-elements_and_pseudo-classes
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum MozPseudoElement {
 	#[atomizable("-moz-anonymous-block")]
 	AnonymousBlock,
@@ -160,8 +160,8 @@ pub enum MozPseudoElement {
 	ViewportScroll,
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum MozFunctionalPseudoElement {
 	TreeCell(()),
 	TreeCellText(()),
@@ -178,7 +178,7 @@ pub enum MozFunctionalPseudoElement {
 
 impl<'a> Parse<'a> for MozFunctionalPseudoElement {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case! { parser.next(), Token::Function(_):
+		expect_ignore_case! { parser.next_token(), Token::Function(_):
 			atom!("-moz-tree-cell") => todo!(parser),
 			atom!("-moz-tree-cell-text") => todo!(parser),
 			atom!("-moz-tree-checkbox") => todo!(parser),
@@ -201,8 +201,8 @@ impl<'a> WriteCss<'a> for MozFunctionalPseudoElement {
 }
 
 // https://searchfox.org/mozilla-central/source/xpcom/ds/StaticAtoms.py#2502
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum MozPseudoClass {
 	#[atomizable("-moz-any")]
 	Any,
@@ -260,18 +260,18 @@ pub enum MozPseudoClass {
 	WindowInactive,
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum MozFunctionalPseudoClass {
 	LocaleDir(DirValue),
 }
 
 impl<'a> Parse<'a> for MozFunctionalPseudoClass {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case! { parser.next(), Token::Function(_):
+		expect_ignore_case! { parser.next_token(), Token::Function(_):
 			atom!("-moz-locale-dir") => {
 				let dir = DirValue::parse(parser)?;
-				expect!(parser.next(), Token::RightParen);
+				expect!(parser.next_token(), Token::RightParen);
 				Ok(Self::LocaleDir(dir))
 			}
 		}
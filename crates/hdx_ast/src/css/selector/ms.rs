@@ -1,7 +1,7 @@
-use hdx_derive::Atomizable;
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum MsPseudoElement {
 	#[atomizable("-ms-backdrop")]
 	Backdrop,
@@ -41,8 +41,8 @@ pub enum MsPseudoElement {
 	Value,
 }
 
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum MsPseudoClass {
 	#[atomizable("-ms-fullscreen")]
 	Fullscreen,
@@ -1,22 +1,23 @@
-use hdx_atom::atom;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::{Include, Token};
 use hdx_parser::{unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Nth(i32, i32);
 
 impl<'a> Parse<'a> for Nth {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		let mut b_sign = 0;
 		let a = match parser.peek().clone() {
-			Token::Number(b, t) if !t.is_float() => {
+			Token::Number(b, t, _) if !t.is_float() => {
 				parser.advance();
 				return Ok(Self(0, b as i32));
 			}
-			Token::Dimension(a, _, t) if !t.is_float() => a as i32,
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Dimension(a, _, t, _) if !t.is_float() => a as i32,
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("even") => {
 					parser.advance();
 					return Ok(Self(2, 0));
@@ -61,14 +62,11 @@ impl<'a> Parse<'a> for Nth {
 			token => unexpected!(parser, token),
 		};
 		let b = match parser.peek().clone() {
-			Token::Ident(atom!("n"))
-			| Token::Ident(atom!("N"))
-			| Token::Dimension(_, atom!("n"), _)
-			| Token::Dimension(_, atom!("N"), _) => {
+			Token::Ident("n") | Token::Ident("N") | Token::Dimension(_, "n", _, _) | Token::Dimension(_, "N", _, _) => {
 				parser.advance();
 				None
 			}
-			Token::Ident(atom) | Token::Dimension(_, atom, _) => {
+			Token::Ident(atom) | Token::Dimension(_, atom, _, _) => {
 				parser.advance();
 				let mut chars = atom.chars();
 				if atom.starts_with('-') {
@@ -92,7 +90,7 @@ impl<'a> Parse<'a> for Nth {
 			_ => None,
 		};
 		match parser.peek().clone() {
-			Token::Number(i, t) if b.is_none() && !t.is_float() => {
+			Token::Number(i, t, _) if b.is_none() && !t.is_float() => {
 				parser.advance();
 				if t.is_signed() && b_sign != 0 {
 					unexpected!(parser)
@@ -109,8 +107,8 @@ impl<'a> Parse<'a> for Nth {
 				} else {
 					b_sign = 1;
 				}
-				match parser.next().clone() {
-					Token::Number(i, t) if !t.is_float() && !t.is_signed() => Ok(Self(a, (i as i32) * b_sign)),
+				match parser.next_token().clone() {
+					Token::Number(i, t, _) if !t.is_float() && !t.is_signed() => Ok(Self(a, (i as i32) * b_sign)),
 					token => unexpected!(parser, token),
 				}
 			}
@@ -1,7 +1,7 @@
-use hdx_derive::Atomizable;
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum OPseudoElement {
 	#[atomizable("-o-inner-spin-button")]
 	InnerSpinButton,
@@ -21,8 +21,8 @@ pub enum OPseudoElement {
 	Selection,
 }
 
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum OPseudoClass {
 	#[atomizable("-o-prefocus")]
 	Prefocus,
@@ -1,7 +1,8 @@
-use hdx_derive::Atomizable;
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum PseudoClass {
 	Active,           // atom!("active")
 	AnyLink,          // atom!("any-link")
@@ -1,7 +1,8 @@
-use hdx_derive::Atomizable;
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum PseudoElement {
 	After,              // atom!("after")
 	Backdrop,           // atom!("backdrop")
@@ -19,8 +20,8 @@ pub enum PseudoElement {
 	TargetText,         // atom!("target-text")
 }
 
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum LegacyPseudoElement {
 	After,       // atom!("after")
 	Before,      // atom!("before")
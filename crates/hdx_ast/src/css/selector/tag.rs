@@ -1,11 +1,12 @@
-use hdx_atom::{atom, Atom, Atomizable};
-use hdx_derive::Atomizable;
+use hdx_atom::{atom, Atom, Atomizable, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Tag {
 	Html(HtmlTag),
 	Svg(SvgTag),
@@ -97,9 +98,9 @@ impl Atomizable for Tag {
 
 impl<'a> Parse<'a> for Tag {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
+		match parser.next_token() {
 			Token::Ident(atom) => {
-				if let Some(tag) = Tag::from_atom(atom) {
+				if let Some(tag) = Tag::from_atom(&atom.to_atom()) {
 					Ok(tag)
 				} else {
 					unexpected_ident!(parser, atom)
@@ -116,8 +117,8 @@ impl<'a> WriteCss<'a> for Tag {
 	}
 }
 
-#[derive(Atomizable, PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Atomizable, Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum HtmlTag {
 	A,           // atom!("a")
 	Abbr,        // atom!("abbr")
@@ -248,8 +249,8 @@ pub enum HtmlTag {
 	Xmp,         // atom!("xmp")
 }
 
-#[derive(Atomizable, PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Atomizable, Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum SvgTag {
 	A,                   // atom!("a")
 	Animate,             // atom!("animate")
@@ -329,8 +330,8 @@ pub enum SvgTag {
 	Vkern,               // atom!("vkern")
 }
 
-#[derive(Atomizable, PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Atomizable, Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum MathmlTag {
 	Maction,       // atom!("maction")
 	Math,          // atom!("math")
@@ -1,11 +1,11 @@
 use hdx_atom::atom;
-use hdx_derive::Atomizable;
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 use hdx_parser::{expect_ignore_case, todo, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://searchfox.org/wubkat/source/Source/WebCore/css/CSSPseudoSelectors.json
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum WebkitPseudoElement {
 	#[atomizable("-webkit-calendar-picker-indicator")]
 	CalendarDatePickerIndicator,
@@ -135,15 +135,15 @@ pub enum WebkitPseudoElement {
 	ValidationBubbleTextBlock,
 }
 
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum WebkitFunctionalPseudoElement {
 	Distributed(()), // atom!("-webkit-distributed")
 }
 
 impl<'a> Parse<'a> for WebkitFunctionalPseudoElement {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case! { parser.next(), Token::Function(_):
+		expect_ignore_case! { parser.next_token(), Token::Function(_):
 			atom!("-webkit-distributed") => todo!(parser),
 		}
 	}
@@ -157,15 +157,15 @@ impl<'a> WriteCss<'a> for WebkitFunctionalPseudoElement {
 
 // TODO: functional pseudos
 // -webkit-any() alias of :is()
-#[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum WebkitFunctionalPseudoClass {
 	Any(()),
 }
 
 impl<'a> Parse<'a> for WebkitFunctionalPseudoClass {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case! { parser.next(), Token::Function(_):
+		expect_ignore_case! { parser.next_token(), Token::Function(_):
 			atom!("-webkit-any") => todo!(parser),
 		}
 	}
@@ -177,8 +177,8 @@ impl<'a> WriteCss<'a> for WebkitFunctionalPseudoClass {
 	}
 }
 
-#[derive(Atomizable, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum WebkitPseudoClass {
 	#[atomizable("-webkit-animating-full-screen-transition")]
 	AnimatingFullScreenTransition,
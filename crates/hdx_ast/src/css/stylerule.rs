@@ -1,14 +1,16 @@
-use crate::css::{properties::Property, selector::SelectorList};
-use hdx_derive::Visitable;
-use hdx_parser::{Block, Parse, Parser, QualifiedRule, Result as ParserResult, Spanned, Vec};
-use hdx_writer::{CssWriter, OutputOption, Result as WriterResult, WriteCss};
+use crate::css::{properties::Property, selector::SelectorList, stylesheet::Rule};
+use hdx_derive::{ShiftSpans, Visitable};
+use hdx_parser::{
+	Block, Box, Commented, Parse, Parser, QualifiedRule, Result as ParserResult, Span, Spanned, Trivia, Vec,
+};
+use hdx_writer::{
+	write_comment_lines, write_trailing_comments, CssWriter, OutputOption, Result as WriterResult, WriteCss,
+};
 
 // https://drafts.csswg.org/cssom-1/#the-cssstylerule-interface
-#[derive(Visitable, PartialEq, Debug, Hash)]
-#[visitable(call)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename = "stylerule"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", rename = "stylerule"))]
 pub struct StyleRule<'a> {
-	#[visitable(skip)]
 	pub selectors: Spanned<SelectorList<'a>>,
 	#[cfg_attr(feature = "serde", serde(flatten))]
 	pub style: Spanned<StyleDeclaration<'a>>,
@@ -32,7 +34,8 @@ impl<'a> WriteCss<'a> for StyleRule<'a> {
 			return Ok(());
 		}
 		sink.write_indent()?;
-		self.selectors.write_css(sink)?;
+		sink.add_mapping(self.selectors.span);
+		self.selectors.node.write_rule_selectors(sink)?;
 		sink.write_whitespace()?;
 		sink.write_char('{')?;
 		sink.indent();
@@ -46,23 +49,38 @@ impl<'a> WriteCss<'a> for StyleRule<'a> {
 }
 
 // https://drafts.csswg.org/cssom-1/#the-cssstylerule-interface
-#[derive(Visitable, PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename = "style-declaration"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename = "style-declaration")
+)]
 pub struct StyleDeclaration<'a> {
-	pub declarations: Vec<'a, Spanned<Property<'a>>>,
-	pub rules: Vec<'a, Spanned<StyleRule<'a>>>,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
+	pub declarations: Vec<'a, Spanned<Commented<'a, Property<'a>>>>,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
+	pub rules: Vec<'a, Spanned<Commented<'a, Rule<'a>>>>,
+	// Comments after the last declaration or rule
+	#[visitable(skip)]
+	#[cfg_attr(
+		feature = "serde",
+		serde(skip_serializing_if = "Option::is_none", default, deserialize_with = "crate::css::arena::option_boxed")
+	)]
+	pub trivia: Option<Box<'a, Trivia<'a>>>,
 }
 
 impl<'a> Parse<'a> for StyleDeclaration<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
+		let start = parser.pos();
 		let (declarations, rules) = Self::parse_block(parser)?;
-		Ok(Self { declarations, rules })
+		let comments = parser.comments_within(Span::new(start, parser.pos()));
+		Ok(Self { declarations, rules, trivia: parser.new_trivia(parser.new_vec(), comments) })
 	}
 }
 
 impl<'a> Block<'a> for StyleDeclaration<'a> {
-	type Declaration = Property<'a>;
-	type Rule = StyleRule<'a>;
+	type Declaration = Commented<'a, Property<'a>>;
+	type Rule = Commented<'a, Rule<'a>>;
 }
 
 impl<'a> StyleDeclaration<'a> {
@@ -75,21 +93,28 @@ impl<'a> WriteCss<'a> for StyleDeclaration<'a> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
 		let mut iter = self.declarations.iter().peekable();
 		while let Some(decl) = iter.next() {
+			write_comment_lines(sink, decl.node.leading())?;
 			sink.write_indent()?;
 			decl.write_css(sink)?;
-			if iter.peek().is_none() {
+			if iter.peek().is_none() && self.rules.is_empty() {
 				sink.write_trailing_char(';')?;
 			} else {
 				sink.write_char(';')?;
 			}
+			write_trailing_comments(sink, decl.node.trailing())?;
 			sink.write_newline()?;
 		}
 		for rule in self.rules.iter() {
 			sink.write_indent()?;
 			sink.write_newline()?;
+			write_comment_lines(sink, rule.node.leading())?;
 			rule.write_css(sink)?;
+			write_trailing_comments(sink, rule.node.trailing())?;
 			sink.write_newline()?;
 		}
+		if let Some(trivia) = &self.trivia {
+			write_comment_lines(sink, &trivia.trailing)?;
+		}
 		Ok(())
 	}
 }
@@ -101,7 +126,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(StyleRule, 112);
+		assert_size!(StyleRule, 120);
 	}
 
 	#[test]
@@ -1,20 +1,31 @@
-use hdx_atom::atom;
-use hdx_derive::{Atomizable, Visitable};
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 use hdx_lexer::Token;
-use hdx_parser::{diagnostics, Parse, Parser, Result as ParserResult, Spanned, StyleSheet as StyleSheetTrait, Vec};
-use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
+use hdx_parser::{
+	closest_match, diagnostics, Box, Commented, Features, Parse, Parser, Remnants, Result as ParserResult, Span,
+	Spanned, StyleSheet as StyleSheetTrait, Trivia, Vec,
+};
+use hdx_writer::{write_comment_lines, write_trailing_comments, CssWriter, Result as WriterResult, WriteCss};
 
 use crate::{
-	css::{rules, stylerule::StyleRule},
+	css::{properties::Property, rules, stylerule::StyleRule},
 	syntax::{AtRule, QualifiedRule},
 };
 
 // https://drafts.csswg.org/cssom-1/#the-cssstylesheet-interface
-#[derive(Visitable, Debug, PartialEq, Hash)]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
 #[visitable(call)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename = "stylesheet"))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", rename = "stylesheet"))]
 pub struct StyleSheet<'a> {
-	pub rules: Vec<'a, Spanned<Rule<'a>>>,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
+	pub rules: Vec<'a, Spanned<Commented<'a, Rule<'a>>>>,
+	// Comments after the last rule
+	#[visitable(skip)]
+	#[cfg_attr(
+		feature = "serde",
+		serde(skip_serializing_if = "Option::is_none", default, deserialize_with = "crate::css::arena::option_boxed")
+	)]
+	pub trivia: Option<Box<'a, Trivia<'a>>>,
 }
 
 // A StyleSheet represents the root node of a CSS-like language.
@@ -23,19 +34,37 @@ pub struct StyleSheet<'a> {
 // AtRules vs QualifiedRules are differentiated by two different functions.
 impl<'a> Parse<'a> for StyleSheet<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		Ok(Self { rules: Self::parse_stylesheet(parser)? })
+		let start = parser.pos();
+		let rules = Self::parse_stylesheet(parser)?;
+		let mut comments = parser.comments_within(Span::new(start, parser.pos()));
+		// Parsing stops ahead of the end of the file, so there may be more comments after the last rule.
+		comments.extend(parser.leading_comments());
+		Ok(Self { rules, trivia: parser.new_trivia(parser.new_vec(), comments) })
 	}
 }
 
 impl<'a> StyleSheetTrait<'a> for StyleSheet<'a> {
-	type Rule = Rule<'a>;
+	type Rule = Commented<'a, Rule<'a>>;
+
+	fn parts_mut(&mut self) -> (&mut Vec<'a, Spanned<Self::Rule>>, &mut Option<Box<'a, Trivia<'a>>>) {
+		(&mut self.rules, &mut self.trivia)
+	}
 }
 
 impl<'a> WriteCss<'a> for StyleSheet<'a> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
-		for rule in &self.rules {
+		let mut rules = self.rules.iter().peekable();
+		while let Some(rule) = rules.next() {
+			write_comment_lines(sink, rule.node.leading())?;
 			rule.write_css(sink)?;
+			write_trailing_comments(sink, rule.node.trailing())?;
 			sink.write_newline()?;
+			if rules.peek().is_some() && sink.format_options().blank_line_between_rules {
+				sink.write_newline()?;
+			}
+		}
+		if let Some(trivia) = &self.trivia {
+			write_comment_lines(sink, &trivia.trailing)?;
 		}
 		Ok(())
 	}
@@ -48,6 +77,7 @@ macro_rules! apply_rules {
 			ColorProfile: atom!("color-profile"),
 			Container: atom!("container"),
 			CounterStyle: atom!("counter-style"),
+			CustomMedia: atom!("custom-media"),
 			FontFace<'a>: atom!("font-face"),
 			FontFeatureValues: atom!("font-feature-values"),
 			FontPaletteValues: atom!("font-palette-values"),
@@ -74,42 +104,102 @@ macro_rules! apply_rules {
 	};
 }
 
+pub(crate) use apply_rules;
+
 macro_rules! rule {
     ( $(
         $name: ident$(<$a: lifetime>)?: $atom: pat,
     )+ ) => {
 		// https://drafts.csswg.org/cssom-1/#the-cssrule-interface
-		#[derive(Visitable, PartialEq, Debug, Hash)]
+		#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
 		#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
 		pub enum Rule<'a> {
 			$(
-				#[visitable(skip)]
+				#[visitable(call)]
 				$name(rules::$name$(<$a>)?),
 			)+
-			#[visitable(skip, call = visit_unknown_at_rule)]
+			#[visitable(call)]
 			UnknownAt(AtRule<'a>),
+			#[visitable(call)]
 			Style(StyleRule<'a>),
-			#[visitable(skip, call = visit_unknown_rule)]
-			Unknown(QualifiedRule<'a>)
+			#[visitable(call)]
+			Unknown(QualifiedRule<'a>),
+			Remnants(Remnants),
+			// An SCSS `$variable: value;` at the top level, only parsed with Features::Scss.
+			ScssVariable(Box<'a, Property<'a>>),
 		}
 	}
 }
 
 apply_rules!(rule);
 
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Rule<'a> {
+	// Rules are serialized without saying which they are, other than by the `type` some of them have, such as
+	// `WebkitKeyframes`, which would otherwise be read as the first rule of the same shape.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		use serde::de::Error;
+		let value = serde_json::Value::deserialize(deserializer)?;
+		let ty = value.get("type").and_then(|ty| ty.as_str());
+		macro_rules! deserialize_rule {
+			( $(
+				$name: ident$(<$a: lifetime>)?: $atom: pat,
+			)+ ) => {
+				$(
+					if ty == Some(stringify!($name)) {
+						return rules::$name::deserialize(value).map(Self::$name).map_err(D::Error::custom);
+					}
+				)+
+				$(
+					if let Ok(rule) = rules::$name::deserialize(&value) {
+						return Ok(Self::$name(rule));
+					}
+				)+
+			}
+		}
+		apply_rules!(deserialize_rule);
+		if let Ok(rule) = AtRule::deserialize(&value) {
+			return Ok(Self::UnknownAt(rule));
+		}
+		if let Ok(rule) = StyleRule::deserialize(&value) {
+			return Ok(Self::Style(rule));
+		}
+		if let Ok(rule) = QualifiedRule::deserialize(&value) {
+			return Ok(Self::Unknown(rule));
+		}
+		if let Ok(remnants) = Remnants::deserialize(&value) {
+			return Ok(Self::Remnants(remnants));
+		}
+		crate::css::arena::boxed(value).map(Self::ScssVariable).map_err(D::Error::custom)
+	}
+}
+
+impl<'a> From<Remnants> for Rule<'a> {
+	fn from(remnants: Remnants) -> Self {
+		Self::Remnants(remnants)
+	}
+}
+
 impl<'a> Parse<'a> for Rule<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		Ok(match parser.peek() {
+		Ok(match parser.peek().clone() {
 			Token::AtKeyword(atom) => {
+				parser.check_vendor_prefix(atom, parser.span())?;
 				macro_rules! parse_rule {
 					( $(
-						$name: ident$(<$a: lifetime>)?: $atom: pat,
+						$name: ident$(<$a: lifetime>)?: atom!($atom: tt),
 					)+ ) => {
-						match atom.to_ascii_lowercase() {
-							$($atom => rules::$name::try_parse(parser).map(Self::$name),)+
-							_ => {
+						match atom.to_ascii_lowercase_atom() {
+							$(atom!($atom) => rules::$name::try_parse(parser).map(Self::$name),)+
+							name => {
+								let name_span = parser.peek_span();
 								let rule = AtRule::parse_spanned(parser)?;
-								parser.warn(diagnostics::UnknownRule(rule.span).into());
+								match closest_match(&name, [$($atom),+]) {
+									Some(suggestion) => parser.warn(
+										diagnostics::UnknownAtRuleSuggest(name, suggestion.to_atom(), name_span).into()
+									),
+									None => parser.warn(diagnostics::UnknownRule(rule.span).into()),
+								}
 								Ok(Self::UnknownAt(rule.node))
 							}
 						}
@@ -120,8 +210,18 @@ impl<'a> Parse<'a> for Rule<'a> {
 					AtRule::parse(parser).map(Self::UnknownAt)
 				})?
 			}
+			Token::Delim('$') if parser.enabled(Features::Scss) => {
+				let property = Property::parse(parser)?;
+				Self::ScssVariable(parser.boxup(property))
+			}
 			// "Consume a qualified rule from input. If anything is returned, append it to rules."
 			_ => StyleRule::try_parse(parser).map(Self::Style).or_else(|err| {
+				// Selectors which the parser's features disallow are errors, rather than unknown rules.
+				if parser.enabled(Features::Strict)
+					|| err.downcast_ref::<diagnostics::DisallowedVendorPrefix>().is_some()
+				{
+					return Err(err);
+				}
 				parser.warn(err);
 				QualifiedRule::parse(parser).map(Self::Unknown)
 			})?,
@@ -139,6 +239,14 @@ impl<'a> WriteCss<'a> for Rule<'a> {
 					Self::Unknown(v) => v.write_css(sink),
 					Self::UnknownAt(v) => v.write_css(sink),
 					Self::Style(v) => v.write_css(sink),
+					Self::Remnants(v) => {
+						sink.write_indent()?;
+						v.write_css(sink)
+					}
+					Self::ScssVariable(v) => {
+						v.write_css(sink)?;
+						sink.write_char(';')
+					}
 					$(
 						Self::$name(v) => v.write_css(sink),
 					)+
@@ -149,8 +257,8 @@ impl<'a> WriteCss<'a> for Rule<'a> {
 	}
 }
 
-#[derive(Atomizable, PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Atomizable, Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(untagged))]
 pub enum AtRuleId {
 	Charset, // atom!("charset")
 	Page,    // atom!("page")
@@ -160,11 +268,16 @@ pub enum AtRuleId {
 mod tests {
 	use super::*;
 	use crate::test_helpers::*;
+	use hdx_parser::{apply_fixes, Features, Spanned};
+	use hdx_writer::{
+		BaseCssWriter, FormatOptions, HexCase, IdentityWriter, IndentStyle, OutputOption, QuotePreference,
+		SelectorLayout,
+	};
 
 	#[test]
 	fn size_test() {
-		assert_size!(StyleSheet, 32);
-		assert_size!(Rule, 152);
+		assert_size!(StyleSheet, 40);
+		assert_size!(Rule, 176);
 		assert_size!(AtRuleId, 1);
 	}
 
@@ -179,4 +292,182 @@ mod tests {
 	fn test_minify() {
 		assert_minify!(StyleSheet, "body {\n\twidth: 1px;\n}\n", "body{width:1px}");
 	}
+
+	#[test]
+	fn test_comments() {
+		assert_parse!(StyleSheet, "/* a */\nbody {\n\t/* b */\n\twidth: 1px; /* c */\n\t/* d */\n}\n/* e */\n");
+		assert_parse!(StyleSheet, "a, /* b */ c /* d */ {\n}\n");
+		assert_parse!(StyleSheet, "@media screen {\n\t/* a */\n\tbody {\n\t} /* b */\n\t/* c */\n}\n");
+		assert_parse!(StyleSheet, "a{width:1px/* b */}", "a {\n\twidth: 1px; /* b */\n}\n");
+		// Comments within nested nodes are taken by those, including a nested rule which was first tried as a
+		// declaration.
+		assert_parse!(
+			StyleSheet,
+			"@media screen{a{.b/* b */{color:red;/* c */}/* d */}}",
+			"@media screen {\n\ta {\n\t\t\n\t\t.b /* b */ {\n\t\t\tcolor: red; /* c */\n\t\t} /* d */\n\t}\n}\n"
+		);
+		assert_minify!(
+			StyleSheet,
+			"/*! license */\n/* a */\nbody { width: 1px /* b */ }",
+			"/*! license */body{width:1px}"
+		);
+	}
+
+	#[test]
+	fn test_format_options() {
+		let spaces = FormatOptions { indent_style: IndentStyle::Spaces, indent_width: 4, ..Default::default() };
+		assert_format!(StyleSheet, spaces, "a{width:1px}", "a {\n    width: 1px;\n}\n");
+		let blank_lines = FormatOptions { blank_line_between_rules: true, ..Default::default() };
+		assert_format!(StyleSheet, blank_lines, "a{}b{}", "a {\n}\n\nb {\n}\n");
+		let one_per_line = FormatOptions { selector_layout: SelectorLayout::OnePerLine, ..Default::default() };
+		assert_format!(StyleSheet, one_per_line, "a,b{}", "a,\nb {\n}\n");
+		let narrow = FormatOptions { max_line_width: Some(20), ..Default::default() };
+		assert_format!(StyleSheet, narrow, ".one,.two,.three,.four{}", ".one, .two, .three,\n.four {\n}\n");
+		assert_format!(
+			StyleSheet,
+			narrow,
+			"a{transition:opacity 1s,transform 1s}",
+			"a {\n\ttransition: opacity 1s,\n\t\ttransform 1s;\n}\n"
+		);
+		assert_format!(
+			StyleSheet,
+			narrow,
+			"a{grid-template-areas:\"a a\" \"b b\"}",
+			"a {\n\tgrid-template-areas: \"a a\"\n\t\t\"b b\";\n}\n"
+		);
+		let quotes =
+			FormatOptions { quotes: QuotePreference::Single, hex_case: HexCase::Preserve, ..Default::default() };
+		assert_format!(StyleSheet, quotes, "a{content:\"x\";color:#FFF}", "a {\n\tcontent: 'x';\n\tcolor: #FFF;\n}\n");
+		assert_format!(StyleSheet, quotes, "a{color:#aaBBcc80}", "a {\n\tcolor: #aaBBcc80;\n}\n");
+		assert_format!(StyleSheet, quotes, "a{content:\"it's\"}", "a {\n\tcontent: \"it's\";\n}\n");
+	}
+
+	#[test]
+	fn test_identity() {
+		assert_identity!(StyleSheet, "a{color:RED}");
+		assert_identity!(
+			StyleSheet,
+			"\n/* a */\nbody  {\n  width : 1PX ;\n}\n\n\n@media  screen{ b { color : #FFF } }\n"
+		);
+		assert_identity!(StyleSheet, ".a , .b>c{ margin:0 auto!important; }/* x */");
+		assert_identity!(StyleSheet, "a{line-height:1e3;width:1.50px;opacity:.5}");
+	}
+
+	#[test]
+	fn test_identity_edits() {
+		let allocator = bumpalo::Bump::default();
+		let source_text = "a  { color : RED }\n\n/* b */\nb{ width:1PX;height : 2PX }\n@media screen { c{x:y} }\n";
+		let write = |stylesheet: &Spanned<StyleSheet>, fingerprints| {
+			let mut writer = IdentityWriter::new(String::new(), source_text, fingerprints);
+			stylesheet.write_css(&mut writer).unwrap();
+			writer.finish().unwrap()
+		};
+		let result = Parser::new(&allocator, source_text, Features::Lossless).parse_entirely_with::<StyleSheet>();
+		let fingerprints = result.fingerprints.unwrap();
+		let mut stylesheet = result.output.unwrap();
+		let Rule::Style(rule) = &mut stylesheet.node.rules[1].node.node else { panic!("expected a style rule") };
+		rule.style.node.declarations[0].node.node.important = true;
+		assert_eq!(
+			write(&stylesheet, &fingerprints),
+			"a  { color : RED }\n\n/* b */\nb{ width:1PX !important;height : 2PX }\n@media screen { c{x:y} }\n"
+		);
+		stylesheet.node.rules.remove(1);
+		assert_eq!(write(&stylesheet, &fingerprints), "a  { color : RED }\n@media screen { c{x:y} }\n");
+	}
+
+	#[test]
+	fn test_identity_number_edits() {
+		use crate::css::{properties::StyleValue, values::Opacity};
+		let allocator = bumpalo::Bump::default();
+		let source_text = "a { opacity: 0.5 }";
+		let result = Parser::new(&allocator, source_text, Features::Lossless).parse_entirely_with::<StyleSheet>();
+		let fingerprints = result.fingerprints.unwrap();
+		let mut stylesheet = result.output.unwrap();
+		let Rule::Style(rule) = &mut stylesheet.node.rules[0].node.node else { panic!("expected a style rule") };
+		// Within the same integer part, and with the same number of digits, as the value it replaces.
+		rule.style.node.declarations[0].node.node.value = StyleValue::Opacity(Opacity::Number(0.7.into()));
+		let mut writer = IdentityWriter::new(String::new(), source_text, &fingerprints);
+		stylesheet.write_css(&mut writer).unwrap();
+		assert_eq!(writer.finish().unwrap(), "a { opacity: 0.7 }");
+	}
+
+	#[test]
+	fn test_features() {
+		let allocator = bumpalo::Bump::default();
+		let parse = |source_text, features| {
+			let result = Parser::new(&allocator, source_text, features).parse_entirely_with::<StyleSheet>();
+			let mut string = String::new();
+			if let Some(stylesheet) = &result.output {
+				stylesheet.write_css(&mut BaseCssWriter::new(&mut string, OutputOption::none())).unwrap();
+			}
+			(result.errors.len(), string)
+		};
+		assert_eq!(parse("a{colour:red}", Features::default()), (0, "a{colour:red}".into()));
+		assert_eq!(parse("a{colour:red}", Features::Strict).0, 1);
+		assert_eq!(parse("a{width:red}", Features::Strict).0, 1);
+		// Values which hdx doesn't model yet aren't known to be invalid.
+		assert_eq!(parse("a{flex-grow:1;transition:opacity .3s}", Features::Strict).0, 0);
+		let source_text = "a{display:flex;order:2;gap:1rem;flex:1 1 auto;border-color:rgba(var(--rgb),.5)}";
+		assert_eq!(parse(source_text, Features::Strict).0, 0);
+		assert_eq!(parse("a{*zoom:1}", Features::default()).0, 1);
+		assert_eq!(parse("a{*zoom:1}", Features::AllowIeHacks), (0, "a{*zoom:1}".into()));
+		assert_eq!(parse("a{width:1px\\9}", Features::Strict).0, 1);
+		assert_eq!(parse("a{width:1px\\9}", Features::Strict | Features::AllowIeHacks).0, 0);
+		assert_eq!(parse("$gap: 1px;a{margin:$gap}", Features::Scss), (0, "$gap:1px;a{margin:$gap}".into()));
+		assert_eq!(parse("a{$gap:1px}", Features::Scss), (0, "a{$gap:1px}".into()));
+		assert_eq!(parse("a{-webkit-appearance:none}", Features::NoVendorPrefixes).0, 1);
+		assert_eq!(parse("a{--webkit:none}", Features::NoVendorPrefixes).0, 0);
+		assert_eq!(parse("::-moz-selection{color:red}", Features::NoVendorPrefixes).0, 1);
+		assert_eq!(parse("@-webkit-keyframes a{}", Features::NoVendorPrefixes).0, 1);
+	}
+
+	#[test]
+	fn test_suggestions() {
+		let allocator = bumpalo::Bump::default();
+		let source_text = "@meida screen { a { color: red } }";
+		let result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<StyleSheet>();
+		let warning = result.warnings[0].downcast_ref::<diagnostics::UnknownAtRuleSuggest>().unwrap();
+		assert_eq!((warning.1.as_ref(), warning.2.source_text(source_text)), ("media", "@meida"));
+		let result = Parser::new(&allocator, "@foo;", Features::default()).parse_entirely_with::<StyleSheet>();
+		assert!(result.warnings[0].downcast_ref::<diagnostics::UnknownRule>().is_some());
+		let source_text = "button:hover { backgroud-color: red }\n@meida screen {}";
+		let mut result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<StyleSheet>();
+		let diagnostics = result.take_diagnostics();
+		let fixed = apply_fixes(source_text, diagnostics.iter().flat_map(|diagnostic| &diagnostic.fixes));
+		assert_eq!(fixed, "button:hover { background-color: red }\n@media screen {}");
+		let source_text = "a:hovr{color:red}\nb::befor{}\nc{}";
+		let mut result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<StyleSheet>();
+		assert!(result.errors.is_empty());
+		assert_eq!(result.output.as_ref().unwrap().node.rules.len(), 3);
+		let diagnostics = result.take_diagnostics();
+		let fixed = apply_fixes(source_text, diagnostics.iter().flat_map(|diagnostic| &diagnostic.fixes));
+		assert_eq!(fixed, "a:hover{color:red}\nb::before{}\nc{}");
+		// Suggestions are kept even when there's no rule to keep them with.
+		let result = Parser::new(&allocator, "a:hovr", Features::default()).parse_entirely_with::<StyleSheet>();
+		assert_eq!(result.errors.len(), 1);
+		assert!(result.warnings[0].downcast_ref::<diagnostics::UnexpectedPseudoClassSuggest>().is_some());
+	}
+
+	#[test]
+	fn test_recovery() {
+		let allocator = bumpalo::Bump::default();
+		let source_text = "a{color:red}\nb{ width: 1px; : ; color: blue }\n}\nc{color:green}\nd{color:black}";
+		let result = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<StyleSheet>();
+		assert_eq!(result.errors.len(), 2);
+		assert!(!result.panicked);
+		let stylesheet = result.output.unwrap();
+		assert!(matches!(stylesheet.node.rules[2].node.node, Rule::Remnants(_)));
+		let mut string = String::new();
+		stylesheet.write_css(&mut BaseCssWriter::new(&mut string, OutputOption::none())).unwrap();
+		assert_eq!(string, "a{color:red}b{width:1px;:;color:blue}} c{color:green}d{color:black}");
+		let mut string = String::new();
+		stylesheet.write_css(&mut BaseCssWriter::new(&mut string, OutputOption::all())).unwrap();
+		assert_eq!(
+			string,
+			"a {\n\tcolor: red;\n}\nb {\n\twidth: 1px;\n\t:;\n\tcolor: blue;\n}\n}\nc{color:green}\nd {\n\tcolor: black;\n}\n"
+		);
+		let result =
+			Parser::new(&allocator, "@foo x{y}a{color:red}", Features::default()).parse_entirely_with::<StyleSheet>();
+		assert_eq!(result.output.unwrap().node.rules.len(), 2);
+	}
 }
@@ -1,21 +1,24 @@
 mod named;
 mod syntax;
+mod system;
 
 use crate::css::units::{Angle, CSSFloat, Percent};
-use hdx_atom::{atom, Atomizable};
+use hdx_atom::{atom, Atomizable, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{
 	discard, expect, match_ignore_case, todo, unexpected, unexpected_function, unexpected_ident, Parse, Parser,
 	Result as ParserResult,
 };
-use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
+use hdx_writer::{CssWriter, HexCase, Result as WriterResult, WriteCss};
 use std::str::Chars;
 
 pub use named::*;
 pub use syntax::*;
+pub use system::*;
 
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Channel {
 	None,
 	Float(CSSFloat),
@@ -26,19 +29,19 @@ pub enum Channel {
 impl<'a> Parse<'a> for Channel {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		match parser.peek().clone() {
-			Token::Ident(atom) if atom.to_ascii_lowercase() == atom!("none") => {
+			Token::Ident(atom) if atom.to_ascii_lowercase_atom() == atom!("none") => {
 				parser.advance();
 				Ok(Self::None)
 			}
-			Token::Number(n, _) => {
+			Token::Number(n, _, _) => {
 				parser.advance();
 				Ok(Self::Float(n.into()))
 			}
-			Token::Dimension(n, unit, _) if unit.to_ascii_lowercase() == atom!("%") => {
+			Token::Dimension(n, unit, _, _) if unit.to_ascii_lowercase_atom() == atom!("%") => {
 				parser.advance();
 				Ok(Self::Percent(n.into()))
 			}
-			Token::Dimension(_, _, _) => Ok(Self::Hue(Angle::parse(parser)?)),
+			Token::Dimension(_, _, _, _) => Ok(Self::Hue(Angle::parse(parser)?)),
 			token => unexpected!(parser, token),
 		}
 	}
@@ -55,17 +58,17 @@ impl<'a> WriteCss<'a> for Channel {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct AbsoluteColorFunction(pub ColorFunctionSyntax, pub Channel, pub Channel, pub Channel, pub Channel);
 
 impl<'a> Parse<'a> for AbsoluteColorFunction {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		let mut syntax = match parser.next() {
-			Token::Function(atom) => match atom.to_ascii_lowercase() {
-				atom!("color") => match parser.next() {
+		let mut syntax = match parser.next_token() {
+			Token::Function(atom) => match atom.to_ascii_lowercase_atom() {
+				atom!("color") => match parser.next_token() {
 					Token::Ident(atom) => {
-						if let Some(space) = ColorFunctionSyntax::from_color_space(atom.clone()) {
+						if let Some(space) = ColorFunctionSyntax::from_color_space(atom.to_atom()) {
 							space
 						} else {
 							unexpected_ident!(parser, atom)
@@ -110,15 +113,15 @@ impl<'a> Parse<'a> for AbsoluteColorFunction {
 			return Ok(Self(syntax | ColorFunctionSyntax::OmitAlpha, first, second, third, Channel::None));
 		}
 		if syntax.contains(ColorFunctionSyntax::Legacy) {
-			expect!(parser.next(), Token::Comma);
+			expect!(parser.next_token(), Token::Comma);
 		} else {
-			expect!(parser.next(), Token::Delim('/'));
+			expect!(parser.next_token(), Token::Delim('/'));
 		}
 		let fourth = Channel::parse(parser)?;
 		if matches!(fourth, Channel::None) {
 			unexpected!(parser)
 		}
-		expect!(parser.next(), Token::RightParen);
+		expect!(parser.next_token(), Token::RightParen);
 		Ok(Self(syntax, first, second, third, fourth))
 	}
 }
@@ -158,20 +161,38 @@ impl<'a> WriteCss<'a> for AbsoluteColorFunction {
 	}
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Default, Clone, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Color {
 	#[default]
 	CurrentColor,
 	Transparent,
-	Hex(u32),
+	// The parsed RGBA value, followed by how it was written: the number of digits, and a bitmask of which digits
+	// were uppercase.
+	#[cfg_attr(feature = "serde", serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex"))]
+	Hex(u32, u8, u8),
 	Named(NamedColor),
-	Absolute(AbsoluteColorFunction),
+	System(SystemColor),
+	// Boxed, as its four channels would otherwise make every value holding a color several times larger.
+	Absolute(Box<AbsoluteColorFunction>),
 	// TODO: need bumpalo::Box PartialEq, or bumpalo::Box serde
 	// Relative(Box<'a, Color<'a>>, ColorFunction),
 	// Mix(ColorMixSyntax, Box<'a, Color<'a>>, u8, Box<'a, Color<'a>>),
 }
 
+#[cfg(feature = "serde")]
+fn serialize_hex<S: serde::Serializer>(value: &u32, _len: &u8, _upper: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_u32(*value)
+}
+
+// How it was written isn't serialized, so it's read back as all six or eight lowercase digits.
+#[cfg(feature = "serde")]
+fn deserialize_hex<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<(u32, u8, u8), D::Error> {
+	let value = <u32 as serde::Deserialize>::deserialize(deserializer)?;
+	Ok((value, if value & 0xFF == 0xFF { 6 } else { 8 }, 0))
+}
+
 trait HexableChars {
 	fn next_as_hex(&mut self) -> Option<u32>;
 }
@@ -198,23 +219,28 @@ impl<'a> Parse<'a> for Color {
 		match_ignore_case! { parser.peek(), Token::Function(_):
 			atom!("color") => todo!(parser),
 			atom!("color-mix") => todo!(parser),
-			_ => return Ok(Color::Absolute(AbsoluteColorFunction::parse(parser)?))
+			_ => return Ok(Color::Absolute(Box::new(AbsoluteColorFunction::parse(parser)?)))
 		};
-		Ok(match parser.next() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+		Ok(match parser.next_token() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("currentcolor") => Color::CurrentColor,
 				atom!("transparent") => Color::Transparent,
 				name => {
 					if let Some(named) = NamedColor::from_atom(&name) {
 						Color::Named(named)
+					} else if let Some(system) = SystemColor::from_atom(&name) {
+						Color::System(system)
 					} else {
 						unexpected_ident!(parser, atom)
 					}
 				}
 			},
 			Token::Hash(atom) | Token::HashId(atom) => {
+				let upper =
+					atom.bytes().enumerate().fold(0, |mask, (i, b)| mask | ((b.is_ascii_uppercase() as u8) << i));
+				let len = atom.len() as u8;
 				let mut chars = atom.chars();
-				let (r, g, b, a) = match atom.len() {
+				let (r, g, b, a) = match len {
 					// <r><g><b> implied alpha
 					3 => (
 						chars.next_as_hex().unwrap() * 17,
@@ -245,7 +271,7 @@ impl<'a> Parse<'a> for Color {
 					),
 					_ => unexpected!(parser),
 				};
-				Color::Hex(r << 24 | g << 16 | b << 8 | a)
+				Color::Hex(r << 24 | g << 16 | b << 8 | a, len, upper)
 			}
 			token => unexpected!(parser, token),
 		})
@@ -257,7 +283,17 @@ impl<'a> WriteCss<'a> for Color {
 		match self {
 			Self::CurrentColor => atom!("currentcolor").write_css(sink),
 			Self::Transparent => atom!("transparent").write_css(sink),
-			Self::Hex(d) => {
+			Self::Hex(d, len, upper) if sink.format_options().hex_case == HexCase::Preserve => {
+				sink.write_char('#')?;
+				for i in 0..*len as u32 {
+					// Short forms repeat each digit, so the written digit is the high half of each channel.
+					let digit = if *len <= 4 { d >> (28 - i * 8) } else { d >> (28 - i * 4) } & 0xF;
+					let ch = char::from_digit(digit, 16).unwrap();
+					sink.write_char(if upper & (1 << i) != 0 { ch.to_ascii_uppercase() } else { ch })?;
+				}
+				Ok(())
+			}
+			Self::Hex(d, _, _) => {
 				let compacted = ((d & 0x0FF00000) >> 12) | ((d & 0x00000FF0) >> 4);
 				let expanded = ((compacted & 0xF000) << 16)
 					| ((compacted & 0xFF00) << 12)
@@ -276,6 +312,7 @@ impl<'a> WriteCss<'a> for Color {
 				}
 			}
 			Self::Named(name) => name.to_atom().write_css(sink),
+			Self::System(name) => name.to_atom().write_css(sink),
 			Self::Absolute(func) => func.write_css(sink),
 		}
 	}
@@ -288,13 +325,15 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(AbsoluteColorFunction, 36);
-		assert_size!(Color, 36);
+		assert_size!(AbsoluteColorFunction, 72);
+		assert_size!(Color, 16);
 	}
 
 	#[test]
 	fn test_writes() {
 		assert_parse!(Color, "currentcolor");
+		assert_parse!(Color, "canvastext");
+		assert_parse!(Color, "ButtonFace", "buttonface");
 		assert_parse!(Color, "#fff");
 		assert_parse!(Color, "red");
 		assert_parse!(Color, "#ababab");
@@ -1,7 +1,11 @@
-use hdx_derive::Atomizable;
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
 
-#[derive(Atomizable, Clone, Copy, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Clone, Copy, Debug, PartialEq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum NamedColor {
 	Aliceblue,            // atom!("aliceblue")
 	Antiquewhite,         // atom!("antiquewhite")
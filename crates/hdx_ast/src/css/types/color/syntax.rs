@@ -1,18 +1,19 @@
 use bitmask_enum::bitmask;
 use hdx_atom::{atom, Atom};
+use hdx_derive::{ShiftSpans, Visitable};
 
-#[derive(Default, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Whitepoint {
 	D50,
 	#[default]
 	D65,
 }
 
-#[derive(Default)]
+#[derive(Visitable, ShiftSpans, Default)]
 #[bitmask(u8)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum ColorFunctionSyntax {
 	// The anatomy of the u8 for Display values is:
 	//
@@ -201,7 +202,7 @@ impl ColorFunctionSyntax {
 // https://www.w3.org/TR/css-color-4/#color-interpolation-method
 #[bitmask(u8)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum ColorMixSyntax {
 	// The anatomy of the u8 for Display values is:
 	//
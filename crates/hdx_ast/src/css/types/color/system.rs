@@ -0,0 +1,65 @@
+use hdx_derive::{Atomizable, ShiftSpans, Visitable};
+
+// https://drafts.csswg.org/css-color-4/#css-system-colors
+#[derive(Atomizable, Visitable, ShiftSpans, Clone, Copy, Debug, PartialEq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
+pub enum SystemColor {
+	Accentcolor,      // atom!("accentcolor")
+	Accentcolortext,  // atom!("accentcolortext")
+	Activetext,       // atom!("activetext")
+	Buttonborder,     // atom!("buttonborder")
+	Buttonface,       // atom!("buttonface")
+	Buttontext,       // atom!("buttontext")
+	Canvas,           // atom!("canvas")
+	Canvastext,       // atom!("canvastext")
+	Field,            // atom!("field")
+	Fieldtext,        // atom!("fieldtext")
+	Graytext,         // atom!("graytext")
+	Highlight,        // atom!("highlight")
+	Highlighttext,    // atom!("highlighttext")
+	Linktext,         // atom!("linktext")
+	Mark,             // atom!("mark")
+	Marktext,         // atom!("marktext")
+	Selecteditem,     // atom!("selecteditem")
+	Selecteditemtext, // atom!("selecteditemtext")
+	Visitedtext,      // atom!("visitedtext")
+	// https://drafts.csswg.org/css-color-4/#deprecated-system-colors
+	Activeborder,        // atom!("activeborder")
+	Activecaption,       // atom!("activecaption")
+	Appworkspace,        // atom!("appworkspace")
+	Background,          // atom!("background")
+	Buttonhighlight,     // atom!("buttonhighlight")
+	Buttonshadow,        // atom!("buttonshadow")
+	Captiontext,         // atom!("captiontext")
+	Inactiveborder,      // atom!("inactiveborder")
+	Inactivecaption,     // atom!("inactivecaption")
+	Inactivecaptiontext, // atom!("inactivecaptiontext")
+	Infobackground,      // atom!("infobackground")
+	Infotext,            // atom!("infotext")
+	Menu,                // atom!("menu")
+	Menutext,            // atom!("menutext")
+	Scrollbar,           // atom!("scrollbar")
+	Threeddarkshadow,    // atom!("threeddarkshadow")
+	Threedface,          // atom!("threedface")
+	Threedhighlight,     // atom!("threedhighlight")
+	Threedlightshadow,   // atom!("threedlightshadow")
+	Threedshadow,        // atom!("threedshadow")
+	Window,              // atom!("window")
+	Windowframe,         // atom!("windowframe")
+	Windowtext,          // atom!("windowtext")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_helpers::*;
+
+	#[test]
+	fn size_test() {
+		assert_size!(SystemColor, 1);
+	}
+}
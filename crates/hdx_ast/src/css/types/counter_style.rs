@@ -1,12 +1,12 @@
-use hdx_atom::{Atom, Atomizable};
-use hdx_derive::{Atomizable, Writable};
+use hdx_atom::{Atom, Atomizable, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{Parse, Parser, Result as ParserResult, Spanned};
 
 use super::Symbols;
 
-#[derive(Writable, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum CounterStyle {
 	Named(Atom),
 	Symbols(Spanned<Symbols>),
@@ -24,10 +24,10 @@ impl<'a> Parse<'a> for CounterStyle {
 		match parser.peek().clone() {
 			Token::Ident(atom) => {
 				parser.advance();
-				if let Some(style) = PredefinedCounterStyle::from_atom(&atom) {
+				if let Some(style) = PredefinedCounterStyle::from_atom(&atom.to_atom()) {
 					Ok(Self::Predefined(style))
 				} else {
-					Ok(Self::Named(atom))
+					Ok(Self::Named(atom.to_atom()))
 				}
 			}
 			_ => Ok(Self::Symbols(Symbols::parse_spanned(parser)?)),
@@ -36,8 +36,8 @@ impl<'a> Parse<'a> for CounterStyle {
 }
 
 // https://drafts.csswg.org/css-counter-styles-3/#predefined-counters
-#[derive(Writable, Atomizable, Default, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum PredefinedCounterStyle {
 	Decimal,            // atom!("decimal"),
 	DecimalLeadingZero, // atom!("decimal-leading-zero"),
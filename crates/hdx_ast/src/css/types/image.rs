@@ -1,6 +1,6 @@
 use bitmask_enum::bitmask;
-use hdx_atom::{atom, Atom, Atomizable};
-use hdx_derive::{Atomizable, Parsable, Writable};
+use hdx_atom::{atom, Atom, Atomizable, ToAtom};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Visitable, Writable};
 use hdx_lexer::{QuoteStyle, Token};
 use hdx_parser::{
 	discard, expect, expect_ignore_case, peek, unexpected, unexpected_ident, Parse, Parser, Result as ParserResult,
@@ -16,8 +16,9 @@ use crate::css::{
 use super::Color;
 
 // https://drafts.csswg.org/css-images-3/#typedef-image
-#[derive(Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Image {
 	Url(Atom, QuoteStyle),
 	Gradient(Gradient),
@@ -28,15 +29,15 @@ impl<'a> Parse<'a> for Image {
 		Ok(match parser.peek().clone() {
 			Token::Url(atom, style) => {
 				parser.advance();
-				Self::Url(atom.clone(), style)
+				Self::Url(atom.to_atom(), style)
 			}
-			Token::Function(atom) => match atom.to_ascii_lowercase() {
+			Token::Function(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("url") => {
 					parser.advance();
-					match parser.next().clone() {
+					match parser.next_token().clone() {
 						Token::String(atom, style) => {
-							expect!(parser.next(), Token::RightParen);
-							Self::Url(atom, style)
+							expect!(parser.next_token(), Token::RightParen);
+							Self::Url(atom.to_atom(), style)
 						}
 						token => unexpected!(parser, token),
 					}
@@ -63,8 +64,8 @@ impl<'a> WriteCss<'a> for Image {
 }
 
 // https://drafts.csswg.org/css-images-3/#typedef-gradient
-#[derive(PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Gradient {
 	Linear(LinearDirection, SmallVec<[ColorStopOrHint; 0]>),
 	RepeatingLinear(LinearDirection, SmallVec<[ColorStopOrHint; 0]>),
@@ -80,7 +81,7 @@ impl<'a> Gradient {
 			if let Some(hint) = LengthPercentage::try_parse(parser).ok() {
 				if allow_hint {
 					stops.push(ColorStopOrHint::Hint(hint));
-					expect!(parser.next(), Token::Comma);
+					expect!(parser.next_token(), Token::Comma);
 				} else {
 					unexpected!(parser);
 				}
@@ -99,10 +100,10 @@ impl<'a> Gradient {
 
 impl<'a> Parse<'a> for Gradient {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		let gradient = expect_ignore_case! { parser.next(), Token::Function(_):
+		let gradient = expect_ignore_case! { parser.next_token(), Token::Function(_):
 			atom @ atom!("linear-gradient") | atom @ atom!("repeating-linear-gradient") => {
 				let dir = if let Ok(dir) = LinearDirection::try_parse(parser) {
-					expect!(parser.next(), Token::Comma);
+					expect!(parser.next_token(), Token::Comma);
 					dir
 				} else {
 					LinearDirection::default()
@@ -121,7 +122,7 @@ impl<'a> Parse<'a> for Gradient {
 				if size.is_none() && shape.is_some() {
 					size = RadialSize::parse(parser).ok();
 				}
-				let position = if matches!(parser.cur(), Token::Ident(atom) if atom.to_ascii_lowercase() == atom!("at"))
+				let position = if matches!(parser.cur(), Token::Ident(atom) if atom.to_ascii_lowercase_atom() == atom!("at"))
 				{
 					parser.advance();
 					Some(Position::parse(parser)?)
@@ -129,7 +130,7 @@ impl<'a> Parse<'a> for Gradient {
 					None
 				};
 				if size.is_some() || shape.is_some() {
-					expect!(parser.next(), Token::Comma);
+					expect!(parser.next_token(), Token::Comma);
 				}
 				match atom {
 					atom!("radial-gradient") => Self::Radial(
@@ -148,7 +149,7 @@ impl<'a> Parse<'a> for Gradient {
 				}
 			},
 		};
-		expect!(parser.next(), Token::RightParen);
+		expect!(parser.next_token(), Token::RightParen);
 		Ok(gradient)
 	}
 }
@@ -236,8 +237,8 @@ impl<'a> WriteCss<'a> for Gradient {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum LinearDirection {
 	Angle(Angle),
 	Named(NamedDirection),
@@ -249,9 +250,10 @@ impl Default for LinearDirection {
 	}
 }
 
+#[derive(Visitable, ShiftSpans)]
 #[bitmask(u8)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum NamedDirection {
 	Bottom,
 	Top,
@@ -262,20 +264,20 @@ pub enum NamedDirection {
 impl<'a> Parse<'a> for LinearDirection {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		match parser.peek() {
-			Token::Dimension(_, _, _) => return Ok(Self::Angle(Angle::parse(parser)?)),
+			Token::Dimension(_, _, _, _) => return Ok(Self::Angle(Angle::parse(parser)?)),
 			Token::Ident(_) => {}
 			token => unexpected!(parser, token.clone()),
 		};
-		expect_ignore_case!(parser.next(), Token::Ident(atom!("to")));
+		expect_ignore_case!(parser.next_token(), Token::Ident(atom!("to")));
 		let mut dir = NamedDirection::none();
-		dir |= expect_ignore_case! { parser.next(), Token::Ident(_):
+		dir |= expect_ignore_case! { parser.next_token(), Token::Ident(_):
 			atom!("top") => NamedDirection::Top,
 			atom!("left") => NamedDirection::Left,
 			atom!("right") => NamedDirection::Right,
 			atom!("bottom") => NamedDirection::Bottom,
 		};
 		if peek!(parser, Token::Ident(_)) {
-			dir |= expect_ignore_case! { parser.next(), Token::Ident(_):
+			dir |= expect_ignore_case! { parser.next_token(), Token::Ident(_):
 				atom @ atom!("top") => {
 					if dir.contains(NamedDirection::Top) || dir.contains(NamedDirection::Bottom) {
 						unexpected_ident!(parser, atom)
@@ -338,8 +340,8 @@ impl<'a> WriteCss<'a> for LinearDirection {
 }
 
 // https://drafts.csswg.org/css-images-3/#typedef-rg-size
-#[derive(Writable, Default, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Default, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum RadialSize {
 	#[default]
 	ClosestCorner, // atom!("closest-corner")
@@ -354,8 +356,8 @@ impl<'a> Parse<'a> for RadialSize {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek().clone() {
 			Token::Ident(atom) => {
-				parser.next();
-				match atom.to_ascii_lowercase() {
+				parser.next_token();
+				match atom.to_ascii_lowercase_atom() {
 					atom!("closest-corner") => RadialSize::ClosestCorner,
 					atom!("closest-side") => RadialSize::ClosestSide,
 					atom!("farthest-corner") => RadialSize::FarthestCorner,
@@ -363,40 +365,42 @@ impl<'a> Parse<'a> for RadialSize {
 					_ => unexpected_ident!(parser, atom),
 				}
 			}
-			first @ Token::Number(_, _) | first @ Token::Dimension(_, atom!("%"), _) => {
-				match parser.peek_n(2).clone() {
-					second @ Token::Number(_, _) | second @ Token::Dimension(_, atom!("%"), _) => {
-						if matches!(first, Token::Number(_, _)) != matches!(second, Token::Number(_, _)) {
-							unexpected!(parser);
-						}
-						let first_len = LengthPercentage::parse(parser)?;
-						let second_len = LengthPercentage::parse(parser)?;
-						Self::Elliptical(first_len, second_len)
+			first @ Token::Number(_, _, _) | first @ Token::Dimension(_, "%", _, _) => match parser.peek_n(2).clone() {
+				second @ Token::Number(_, _, _) | second @ Token::Dimension(_, "%", _, _) => {
+					if matches!(first, Token::Number(_, _, _)) != matches!(second, Token::Number(_, _, _)) {
+						unexpected!(parser);
 					}
-					_ => {
-						if matches!(first, Token::Dimension(_, _, _)) {
-							unexpected!(parser, first);
-						}
-						Self::Circular(Length::parse(parser)?)
+					let first_len = LengthPercentage::parse(parser)?;
+					let second_len = LengthPercentage::parse(parser)?;
+					Self::Elliptical(first_len, second_len)
+				}
+				_ => {
+					if matches!(first, Token::Dimension(_, _, _, _)) {
+						unexpected!(parser, first);
 					}
+					Self::Circular(Length::parse(parser)?)
 				}
-			}
+			},
 			token => unexpected!(parser, token),
 		})
 	}
 }
 
 // https://drafts.csswg.org/css-images-3/#typedef-rg-ending-shape
-#[derive(Atomizable, Parsable, Default, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Atomizable, Parsable, Visitable, ShiftSpans, Default, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum RadialShape {
 	#[default]
 	Circle, // atom!("circle")
 	Ellipse, // atom!("ellipse")
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum ColorStopOrHint {
 	Stop(Color, Option<LengthPercentage>),
 	Hint(LengthPercentage),
@@ -424,11 +428,11 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Image, 72);
-		assert_size!(Gradient, 72);
-		assert_size!(LinearDirection, 8);
-		assert_size!(RadialSize, 16);
-		assert_size!(ColorStopOrHint, 44);
+		assert_size!(Image, 112);
+		assert_size!(Gradient, 112);
+		assert_size!(LinearDirection, 16);
+		assert_size!(RadialSize, 32);
+		assert_size!(ColorStopOrHint, 32);
 	}
 
 	#[test]
@@ -1,12 +1,14 @@
-use hdx_atom::atom;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{match_ignore_case, unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 use crate::css::units::LengthPercentage;
 
-#[derive(Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Position(pub HorizontalPosition, pub VerticalPosition);
 
 impl<'a> Parse<'a> for Position {
@@ -45,10 +47,10 @@ impl<'a> WriteCss<'a> for Position {
 	}
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Hash)]
+#[derive(Visitable, ShiftSpans, Debug, Default, Clone, PartialEq, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum HorizontalPosition {
@@ -62,7 +64,7 @@ pub enum HorizontalPosition {
 impl<'a> Parse<'a> for HorizontalPosition {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("center") => {
 					parser.advance();
 					Self::Center
@@ -109,10 +111,10 @@ impl<'a> WriteCss<'a> for HorizontalPosition {
 	}
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Hash)]
+#[derive(Visitable, ShiftSpans, Debug, Default, Clone, PartialEq, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum VerticalPosition {
@@ -126,7 +128,7 @@ pub enum VerticalPosition {
 impl<'a> Parse<'a> for VerticalPosition {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("center") => {
 					parser.advance();
 					Self::Center
@@ -180,7 +182,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Position, 24);
+		assert_size!(Position, 48);
 	}
 
 	#[test]
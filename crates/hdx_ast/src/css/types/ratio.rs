@@ -1,12 +1,13 @@
-use hdx_atom::atom;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{match_ignore_case, unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 use crate::css::units::LengthPercentage;
 
-#[derive(Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Ratio(pub HorizontalRatio, pub VerticalRatio);
 
 impl<'a> Parse<'a> for Ratio {
@@ -45,10 +46,10 @@ impl<'a> WriteCss<'a> for Ratio {
 	}
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Hash)]
+#[derive(Visitable, ShiftSpans, Debug, Default, Clone, PartialEq, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum HorizontalRatio {
@@ -62,7 +63,7 @@ pub enum HorizontalRatio {
 impl<'a> Parse<'a> for HorizontalRatio {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("center") => {
 					parser.advance();
 					Self::Center
@@ -109,10 +110,10 @@ impl<'a> WriteCss<'a> for HorizontalRatio {
 	}
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Hash)]
+#[derive(Visitable, ShiftSpans, Debug, Default, Clone, PartialEq, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum VerticalRatio {
@@ -126,7 +127,7 @@ pub enum VerticalRatio {
 impl<'a> Parse<'a> for VerticalRatio {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("center") => {
 					parser.advance();
 					Self::Center
@@ -180,7 +181,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Ratio, 24);
+		assert_size!(Ratio, 48);
 	}
 
 	#[test]
@@ -1,5 +1,5 @@
-use hdx_atom::{atom, Atom, Atomizable};
-use hdx_derive::{Atomizable, Writable};
+use hdx_atom::{atom, Atom, Atomizable, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Visitable, Writable};
 use hdx_lexer::{QuoteStyle, Token};
 use hdx_parser::{expect_ignore_case, unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{OutputOption, Result as WriterResult, WriteCss};
@@ -8,18 +8,18 @@ use smallvec::{smallvec, SmallVec};
 use crate::css::types::Image;
 
 // https://drafts.csswg.org/css-counter-styles-3/#funcdef-symbols
-#[derive(Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Symbols(pub SymbolsType, SmallVec<[Symbol; 0]>);
 
 impl<'a> Parse<'a> for Symbols {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case!(parser.next(), Token::Function(atom!("symbols")));
+		expect_ignore_case!(parser.next_token(), Token::Function(atom!("symbols")));
 		let mut symbol_type = SymbolsType::default();
 		let mut symbols = smallvec![];
 		match parser.peek() {
 			Token::Ident(atom) => {
-				if let Some(st) = SymbolsType::from_atom(atom) {
+				if let Some(st) = SymbolsType::from_atom(&atom.to_atom()) {
 					parser.advance();
 					symbol_type = st;
 				}
@@ -34,7 +34,7 @@ impl<'a> Parse<'a> for Symbols {
 			match parser.peek().clone() {
 				Token::String(atom, style) => {
 					parser.advance();
-					symbols.push(Symbol::String(atom, style));
+					symbols.push(Symbol::String(atom.to_atom(), style));
 				}
 				Token::Function(_) => {
 					symbols.push(Symbol::Image(Image::parse(parser)?));
@@ -69,8 +69,8 @@ impl<'a> WriteCss<'a> for Symbols {
 }
 
 // https://drafts.csswg.org/css-counter-styles-3/#funcdef-symbols
-#[derive(Writable, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Symbol {
 	#[writable(String)]
 	String(Atom, QuoteStyle),
@@ -78,8 +78,12 @@ pub enum Symbol {
 }
 
 // https://drafts.csswg.org/css-counter-styles-3/#typedef-symbols-type
-#[derive(Atomizable, Default, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Atomizable, Visitable, ShiftSpans, Default, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum SymbolsType {
 	Cyclic,     // atom!("cyclic")
 	Numeric,    // atom!("numeric")
@@ -97,7 +101,7 @@ mod tests {
 	#[test]
 	fn size_test() {
 		assert_size!(Symbols, 32);
-		assert_size!(Symbol, 72);
+		assert_size!(Symbol, 112);
 		assert_size!(SymbolsType, 1);
 	}
 
@@ -1,5 +1,5 @@
-use hdx_atom::atom;
-use hdx_derive::Writable;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 
@@ -10,8 +10,8 @@ const DEG_RAD: f32 = 57.295_78;
 const DEG_TURN: f32 = 360.0;
 
 // https://drafts.csswg.org/css-values/#angles
-#[derive(Writable, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Angle {
 	#[writable(suffix = "grad")]
 	Grad(CSSFloat),
@@ -25,8 +25,8 @@ pub enum Angle {
 
 impl<'a> Parse<'a> for Angle {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
-			token @ Token::Dimension(n, unit, _) => match unit.to_ascii_lowercase() {
+		match parser.next_token() {
+			token @ Token::Dimension(n, unit, _, _) => match unit.to_ascii_lowercase_atom() {
 				atom!("grad") => Ok(Angle::Grad(n.into())),
 				atom!("rad") => Ok(Angle::Rad(n.into())),
 				atom!("turn") => Ok(Angle::Turn(n.into())),
@@ -1,10 +1,10 @@
 use hdx_atom::Atom;
-use hdx_derive::Writable;
+use hdx_derive::{ShiftSpans, Visitable, Writable};
 
 use super::CSSFloat;
 
-#[derive(Writable, Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Custom(CSSFloat, Atom);
 
 impl Into<CSSFloat> for Custom {
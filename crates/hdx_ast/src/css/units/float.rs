@@ -1,25 +1,32 @@
-use hdx_derive::Writable;
 use hdx_lexer::Token;
-use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult};
+use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult, ShiftSpans};
+use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 use std::{
 	fmt::{Display, Result as DisplayResult},
 	hash::{Hash, Hasher},
 	ops::{Add, Div, Mul, Sub},
 };
 
-// CSS floats are different to f32s in that they do not represent NaN
-#[derive(Writable, Debug, Clone, Copy, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
-pub struct CSSFloat(f32);
+// CSS floats are different to f64s in that they do not represent NaN. Only the value is kept, so how a number was
+// written (such as `.5` or `1e3`) is only written back out as it was when parsing with Features::Lossless, which
+// copies untouched nodes from the source text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct CSSFloat(f64);
 
 impl CSSFloat {
 	pub fn normalize(&self) -> Self {
 		if self.0.is_nan() {
 			Self(0.0)
 		} else {
-			Self(self.0)
+			*self
 		}
 	}
+
+	#[inline]
+	pub fn to_f64(&self) -> f64 {
+		self.0
+	}
 }
 
 impl Display for CSSFloat {
@@ -30,22 +37,45 @@ impl Display for CSSFloat {
 
 impl Hash for CSSFloat {
 	fn hash<H: Hasher>(&self, state: &mut H) {
-		let n = if self.0.is_nan() { 0.0 } else { self.0 };
-		let sign = n.signum() as i8;
-		sign.hash(state);
-		(n as u32).hash(state);
+		// -0.0 == 0.0, so they must hash the same.
+		let n = if self.0 == 0.0 { 0.0 } else { self.0 };
+		n.to_bits().hash(state);
+	}
+}
+
+impl ShiftSpans for CSSFloat {
+	#[inline]
+	fn shift_spans(&mut self, _delta: i32) {}
+}
+
+impl<'a> WriteCss<'a> for CSSFloat {
+	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
+		self.0.write_css(sink)
 	}
 }
 
 impl From<f32> for CSSFloat {
 	fn from(f: f32) -> Self {
-		Self(f).normalize()
+		// Going through the shortest text of the f32 keeps `0.1` as 0.1, rather than 0.10000000149011612.
+		Self(f.to_string().parse().unwrap_or(0.0)).normalize()
 	}
 }
 
 impl From<&f32> for CSSFloat {
 	fn from(f: &f32) -> Self {
-		Self(*f).normalize()
+		(*f).into()
+	}
+}
+
+impl From<f64> for CSSFloat {
+	fn from(f: f64) -> Self {
+		Self(f).normalize()
+	}
+}
+
+impl From<&f64> for CSSFloat {
+	fn from(f: &f64) -> Self {
+		(*f).into()
 	}
 }
 
@@ -53,7 +83,7 @@ impl Mul<f32> for CSSFloat {
 	type Output = Self;
 
 	fn mul(self, rhs: f32) -> Self::Output {
-		Self(self.0 * rhs).normalize()
+		Self(self.0 * rhs as f64).normalize()
 	}
 }
 
@@ -61,7 +91,7 @@ impl Div<f32> for CSSFloat {
 	type Output = Self;
 
 	fn div(self, rhs: f32) -> Self::Output {
-		Self(self.0 / rhs).normalize()
+		Self(self.0 / rhs as f64).normalize()
 	}
 }
 
@@ -69,7 +99,7 @@ impl Add<f32> for CSSFloat {
 	type Output = Self;
 
 	fn add(self, rhs: f32) -> Self::Output {
-		Self(self.0 + rhs).normalize()
+		Self(self.0 + rhs as f64).normalize()
 	}
 }
 
@@ -77,42 +107,42 @@ impl Sub<f32> for CSSFloat {
 	type Output = Self;
 
 	fn sub(self, rhs: f32) -> Self::Output {
-		Self(self.0 - rhs).normalize()
+		Self(self.0 - rhs as f64).normalize()
 	}
 }
 
 impl PartialEq<f32> for CSSFloat {
 	fn eq(&self, rhs: &f32) -> bool {
-		self.0.eq(rhs)
+		(self.0 as f32).eq(rhs)
 	}
 }
 
 impl PartialOrd<f32> for CSSFloat {
 	fn lt(&self, rhs: &f32) -> bool {
-		self.0.lt(rhs)
+		(self.0 as f32).lt(rhs)
 	}
 
 	fn le(&self, rhs: &f32) -> bool {
-		self.0.le(rhs)
+		(self.0 as f32).le(rhs)
 	}
 
 	fn gt(&self, rhs: &f32) -> bool {
-		self.0.gt(rhs)
+		(self.0 as f32).gt(rhs)
 	}
 
 	fn ge(&self, rhs: &f32) -> bool {
-		self.0.ge(rhs)
+		(self.0 as f32).ge(rhs)
 	}
 
 	fn partial_cmp(&self, rhs: &f32) -> Option<std::cmp::Ordering> {
-		self.0.partial_cmp(rhs)
+		(self.0 as f32).partial_cmp(rhs)
 	}
 }
 
 impl<'a> Parse<'a> for CSSFloat {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
-			Token::Number(f, _) => Ok(f.into()),
+		match parser.next_token() {
+			Token::Number(f, _, _) => Ok(f.into()),
 			token => unexpected!(parser, token),
 		}
 	}
@@ -1,12 +1,12 @@
 use hdx_atom::atom;
-use hdx_derive::Parsable;
+use hdx_derive::{Parsable, ShiftSpans, Visitable};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 use super::{AbsoluteUnit, CSSFloat};
 
 // https://drafts.csswg.org/css-values/#resolution
-#[derive(Parsable, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Parsable, Visitable, ShiftSpans, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Frequency {
 	#[parsable(Dimension)]
 	Hz(CSSFloat),
@@ -50,15 +50,15 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Frequency, 8);
+		assert_size!(Frequency, 16);
 	}
 
 	#[test]
 	fn test_writes() {
 		assert_parse!(Frequency, "40hz");
-		// Truncates to 7dp
-		assert_parse!(Frequency, "1.2345678901234hz", "1.2345679hz");
+		assert_parse!(Frequency, "1.2345678901234hz");
 		// Removes redundant dp
 		assert_parse!(Frequency, "-1.0hz", "-1hz");
+		assert_parse!(Frequency, "1e3hz", "1000hz");
 	}
 }
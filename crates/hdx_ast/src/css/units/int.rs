@@ -1,4 +1,4 @@
-use hdx_derive::Writable;
+use hdx_derive::{ShiftSpans, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult};
 use std::{
@@ -7,8 +7,8 @@ use std::{
 };
 
 // CSS floats are different to i32s in that they do not represent NaN
-#[derive(Writable, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+#[derive(Writable, Visitable, ShiftSpans, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct CSSInt(i32);
 
 impl Display for CSSInt {
@@ -29,6 +29,18 @@ impl From<&f32> for CSSInt {
 	}
 }
 
+impl From<f64> for CSSInt {
+	fn from(f: f64) -> Self {
+		Self(f as i32)
+	}
+}
+
+impl From<&f64> for CSSInt {
+	fn from(f: &f64) -> Self {
+		Self(*f as i32)
+	}
+}
+
 impl From<i32> for CSSInt {
 	fn from(f: i32) -> Self {
 		Self(f)
@@ -103,8 +115,8 @@ impl PartialOrd<i32> for CSSInt {
 
 impl<'a> Parse<'a> for CSSInt {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
-			Token::Number(f, ty) if !ty.is_float() => Ok(f.into()),
+		match parser.next_token() {
+			Token::Number(f, ty, _) if !ty.is_float() => Ok(f.into()),
 			token => unexpected!(parser, token),
 		}
 	}
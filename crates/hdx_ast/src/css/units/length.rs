@@ -1,5 +1,5 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::Writable;
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 
@@ -17,8 +17,9 @@ macro_rules! length {
         $name: ident: $atom: tt,
     )+ ) => {
 
-		#[derive(Writable, Default, Debug, Clone, Copy, PartialEq, Hash)]
-		#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", content = "value", rename_all = "kebab-case"))]
+		#[derive(Writable, Visitable, ShiftSpans, Default, Debug, Clone, Copy, PartialEq, Hash)]
+		#[visitable(call)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "kebab-case"))]
 		pub enum Length {
 			#[writable(rename = "0")]
 			#[default]
@@ -50,10 +51,10 @@ macro_rules! length {
 
 		impl<'a> Parse<'a> for Length {
 			fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-				match parser.next() {
-					Token::Number(n, _) if *n == 0.0 => Ok(Self::Zero),
-					token @ Token::Dimension(n, unit, _) => {
-						if let Some(d) = Self::new(n.into(), unit.clone()) {
+				match parser.next_token() {
+					Token::Number(n, _, _) if *n == 0.0 => Ok(Self::Zero),
+					token @ Token::Dimension(n, unit, _, _) => {
+						if let Some(d) = Self::new(n.into(), unit.to_atom()) {
 							Ok(d)
 						} else {
 							unexpected!(parser, token)
@@ -64,8 +65,9 @@ macro_rules! length {
 			}
 		}
 
-		#[derive(Writable, Default, Debug, Clone, Copy, PartialEq, Hash)]
-		#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", content = "value", rename_all = "kebab-case"))]
+		#[derive(Writable, Visitable, ShiftSpans, Default, Debug, Clone, Copy, PartialEq, Hash)]
+		#[visitable(call)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "kebab-case"))]
 		pub enum LengthPercentage {
 			#[default]
 			#[writable(rename = "0")]
@@ -101,10 +103,10 @@ macro_rules! length {
 
 		impl<'a> Parse<'a> for LengthPercentage {
 			fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-				match parser.next() {
-					Token::Number(n, _) if *n == 0.0 => Ok(Self::Zero),
-					token @ Token::Dimension(n, unit, _) => {
-						if let Some(d) = Self::new(n.into(), unit.clone()) {
+				match parser.next_token() {
+					Token::Number(n, _, _) if *n == 0.0 => Ok(Self::Zero),
+					token @ Token::Dimension(n, unit, _, _) => {
+						if let Some(d) = Self::new(n.into(), unit.to_atom()) {
 							Ok(d)
 						} else {
 							unexpected!(parser, token)
@@ -176,8 +178,9 @@ length! {
 	Cqmax: "cqmax", // atom!("cqmax")
 }
 
-#[derive(Writable, Default, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Writable, Visitable, ShiftSpans, Default, Debug, Clone, Copy, PartialEq, Hash)]
+#[visitable(call)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum LengthPercentageOrAuto {
 	#[default]
 	Auto,
@@ -186,26 +189,26 @@ pub enum LengthPercentageOrAuto {
 
 impl<'a> Parse<'a> for LengthPercentageOrAuto {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+		match parser.next_token() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("auto") => Ok(Self::Auto),
 				_ => unexpected_ident!(parser, atom),
 			},
-			token @ Token::Dimension(val, unit, _) => {
-				if let Some(l) = LengthPercentage::new(val.into(), unit.clone()) {
+			token @ Token::Dimension(val, unit, _, _) => {
+				if let Some(l) = LengthPercentage::new(val.into(), unit.to_atom()) {
 					Ok(Self::LengthPercentage(l))
 				} else {
 					unexpected!(parser, token)
 				}
 			}
-			Token::Number(val, _) if *val == 0.0 => Ok(Self::LengthPercentage(LengthPercentage::Zero)),
+			Token::Number(val, _, _) if *val == 0.0 => Ok(Self::LengthPercentage(LengthPercentage::Zero)),
 			token => unexpected!(parser, token),
 		}
 	}
 }
 
-#[derive(Writable, Default, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Writable, Visitable, ShiftSpans, Default, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum LineWidth {
 	Thin,
 	#[default]
@@ -216,21 +219,21 @@ pub enum LineWidth {
 
 impl<'a> Parse<'a> for LineWidth {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+		match parser.next_token() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("thin") => Ok(Self::Thin),
 				atom!("medium") => Ok(Self::Medium),
 				atom!("thick") => Ok(Self::Thick),
 				_ => unexpected_ident!(parser, atom),
 			},
-			token @ Token::Dimension(val, unit, _) => {
-				if let Some(l) = Length::new(val.into(), unit.clone()).map(Self::Length) {
+			token @ Token::Dimension(val, unit, _, _) => {
+				if let Some(l) = Length::new(val.into(), unit.to_atom()).map(Self::Length) {
 					Ok(l)
 				} else {
 					unexpected!(parser, token)
 				}
 			}
-			Token::Number(val, _) if *val == 0.0 => Ok(Self::Length(Length::Zero)),
+			Token::Number(val, _, _) if *val == 0.0 => Ok(Self::Length(Length::Zero)),
 			token => unexpected!(parser, token),
 		}
 	}
@@ -243,10 +246,10 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Length, 8);
-		assert_size!(LengthPercentage, 8);
-		assert_size!(LengthPercentageOrAuto, 8);
-		assert_size!(LineWidth, 8);
+		assert_size!(Length, 16);
+		assert_size!(LengthPercentage, 16);
+		assert_size!(LengthPercentageOrAuto, 16);
+		assert_size!(LineWidth, 16);
 	}
 
 	#[test]
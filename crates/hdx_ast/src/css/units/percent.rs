@@ -1,9 +1,9 @@
-use hdx_derive::Writable;
+use hdx_derive::{ShiftSpans, Visitable, Writable};
 
 use super::CSSFloat;
 
-#[derive(Writable, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 #[writable(suffix = "%")]
 pub struct Percent(CSSFloat);
 
@@ -19,6 +19,18 @@ impl From<&f32> for Percent {
 	}
 }
 
+impl From<f64> for Percent {
+	fn from(value: f64) -> Self {
+		Self(value.into())
+	}
+}
+
+impl From<&f64> for Percent {
+	fn from(value: &f64) -> Self {
+		Self(value.into())
+	}
+}
+
 impl Into<CSSFloat> for Percent {
 	fn into(self) -> CSSFloat {
 		self.0
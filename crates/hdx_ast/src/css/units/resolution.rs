@@ -1,4 +1,4 @@
-use hdx_derive::{Parsable, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Visitable, Writable};
 
 use super::{AbsoluteUnit, CSSFloat};
 
@@ -6,8 +6,8 @@ const DPPX_IN: f32 = 96.0;
 const DPPX_CM: f32 = DPPX_IN / 2.54;
 
 // https://drafts.csswg.org/css-values/#resolution
-#[derive(Parsable, Writable, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Parsable, Writable, Visitable, ShiftSpans, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Resolution {
 	#[writable(suffix = "dpi")]
 	#[parsable(Dimension)]
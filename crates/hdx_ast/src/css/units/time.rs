@@ -1,13 +1,13 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::Writable;
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult};
 
 use super::{AbsoluteUnit, CSSFloat};
 
 // https://drafts.csswg.org/css-values/#resolution
-#[derive(Writable, Debug, Clone, Copy, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Debug, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Time {
 	#[writable(suffix = "ms")]
 	Ms(CSSFloat),
@@ -18,8 +18,8 @@ pub enum Time {
 impl Time {
 	pub fn new(val: CSSFloat, unit: Atom) -> Option<Self> {
 		match unit {
-			atom!("ms") => Some(Self::Ms(val.into())),
-			atom!("s") => Some(Self::S(val.into())),
+			atom!("ms") => Some(Self::Ms(val)),
+			atom!("s") => Some(Self::S(val)),
 			_ => None,
 		}
 	}
@@ -44,9 +44,9 @@ impl AbsoluteUnit for Time {
 
 impl<'a> Parse<'a> for Time {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
-			token @ Token::Dimension(n, unit, _) => {
-				if let Some(t) = Self::new(n.into(), unit.clone()) {
+		match parser.next_token() {
+			token @ Token::Dimension(n, unit, _, _) => {
+				if let Some(t) = Self::new(n.into(), unit.to_atom()) {
 					Ok(t)
 				} else {
 					unexpected!(parser, token)
@@ -64,15 +64,23 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Time, 8);
+		assert_size!(Time, 16);
 	}
 
 	#[test]
 	fn test_writes() {
 		assert_parse!(Time, "0s");
-		// Truncates to 7dp
-		assert_parse!(Time, "1.2345678901234s", "1.2345679s");
+		assert_parse!(Time, "1.2345678901234s");
 		// Removes redundant dp
 		assert_parse!(Time, "-1.0s", "-1s");
+		assert_parse!(Time, "1e3s", "1000s");
+	}
+
+	#[test]
+	fn test_minify() {
+		assert_minify!(Time, "0.5s", ".5s");
+		assert_minify!(Time, "-0.25s", "-.25s");
+		assert_minify!(Time, "1000ms", "1e3ms");
+		assert_minify!(Time, "1200ms", "1200ms");
 	}
 }
@@ -1,4 +1,4 @@
-use hdx_derive::Value;
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{discard, unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -7,14 +7,14 @@ use smallvec::{smallvec, SmallVec};
 use crate::css::units::Time;
 
 // https://drafts.csswg.org/css-animations-2/#animation-duration
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct AnimationDelay(pub SmallVec<[Time; 2]>);
 
 impl<'a> Parse<'a> for AnimationDelay {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek() {
-			Token::Dimension(_, _, _) => {
+			Token::Dimension(_, _, _, _) => {
 				let mut values = smallvec![];
 				loop {
 					values.push(Time::parse(parser)?);
@@ -50,7 +50,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(AnimationDelay, 32);
+		assert_size!(AnimationDelay, 48);
 	}
 
 	#[test]
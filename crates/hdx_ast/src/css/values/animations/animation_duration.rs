@@ -1,5 +1,5 @@
-use hdx_atom::atom;
-use hdx_derive::Value;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{discard, unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -8,8 +8,8 @@ use smallvec::{smallvec, SmallVec};
 use crate::css::units::Time;
 
 // https://drafts.csswg.org/css-animations-2/#animation-duration
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum AnimationDuration {
 	#[default]
 	Auto,
@@ -19,14 +19,14 @@ pub enum AnimationDuration {
 impl<'a> Parse<'a> for AnimationDuration {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("auto") => {
 					parser.advance();
 					AnimationDuration::Auto
 				}
 				atom => unexpected_ident!(parser, atom),
 			},
-			Token::Dimension(_, _, _) => {
+			Token::Dimension(_, _, _, _) => {
 				let mut values = smallvec![];
 				loop {
 					values.push(Time::parse(parser)?);
@@ -57,7 +57,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(AnimationDuration, 32);
+		assert_size!(AnimationDuration, 48);
 	}
 
 	#[test]
@@ -1,17 +1,17 @@
-use hdx_atom::Atomizable;
-use hdx_derive::{Atomizable, Value, Writable};
+use hdx_atom::{Atomizable, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{discard, unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 use smallvec::{smallvec, SmallVec};
 
 // https://drafts.csswg.org/css-animations-2/#animation-fill-mode
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct AnimationFillMode(pub SmallVec<[SingleAnimationFillMode; 8]>);
 
-#[derive(Atomizable, Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Atomizable, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum SingleAnimationFillMode {
 	#[default]
 	None, // atom!("none")
@@ -24,9 +24,9 @@ impl<'a> Parse<'a> for AnimationFillMode {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		let mut values = smallvec![];
 		loop {
-			match parser.next() {
+			match parser.next_token() {
 				Token::Ident(atom) => {
-					if let Some(fill) = SingleAnimationFillMode::from_atom(&atom) {
+					if let Some(fill) = SingleAnimationFillMode::from_atom(&atom.to_atom()) {
 						values.push(fill);
 					} else {
 						unexpected_ident!(parser, atom);
@@ -1,5 +1,5 @@
-use hdx_atom::atom;
-use hdx_derive::{Value, Writable};
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{discard, unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -8,12 +8,12 @@ use smallvec::{smallvec, SmallVec};
 use crate::css::units::CSSFloat;
 
 // https://drafts.csswg.org/css-animations-2/#animation-fill-mode
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct AnimationIterationCount(pub SmallVec<[SingleAnimationIterationCount; 1]>);
 
-#[derive(Writable, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum SingleAnimationIterationCount {
 	Infinite, // atom!("infinite")
 	Number(CSSFloat),
@@ -29,12 +29,12 @@ impl<'a> Parse<'a> for AnimationIterationCount {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		let mut values = smallvec![];
 		loop {
-			match parser.next() {
-				Token::Ident(ident) => match ident.to_ascii_lowercase() {
+			match parser.next_token() {
+				Token::Ident(ident) => match ident.to_ascii_lowercase_atom() {
 					atom!("infinite") => values.push(SingleAnimationIterationCount::Infinite),
 					atom => unexpected_ident!(parser, atom),
 				},
-				Token::Number(val, ty) if ty.is_int() && !ty.is_signed() => {
+				Token::Number(val, ty, _) if ty.is_int() && !ty.is_signed() => {
 					values.push(SingleAnimationIterationCount::Number(val.into()))
 				}
 				token => unexpected!(parser, token),
@@ -68,7 +68,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(AnimationIterationCount, 24);
+		assert_size!(AnimationIterationCount, 32);
 	}
 
 	#[test]
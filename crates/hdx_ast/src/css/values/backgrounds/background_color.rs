@@ -1,10 +1,10 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::types::Color;
 
 // https://drafts.csswg.org/css-backgrounds/#background-color
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BackgroundColor(pub Color);
 
 #[cfg(test)]
@@ -14,6 +14,6 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(BackgroundColor, 36);
+		assert_size!(BackgroundColor, 16);
 	}
 }
@@ -1,13 +1,13 @@
-use hdx_atom::atom;
-use hdx_derive::{Value, Writable};
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{discard, Parse, Parser, Result as ParserResult, Spanned};
 use smallvec::{smallvec, SmallVec};
 
 use crate::css::types::Image;
 
-#[derive(Value, Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BackgroundImage(pub SmallVec<[Spanned<SingleBackgroundImage>; 1]>);
 
 impl<'a> Parse<'a> for BackgroundImage {
@@ -24,8 +24,8 @@ impl<'a> Parse<'a> for BackgroundImage {
 	}
 }
 
-#[derive(Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum SingleBackgroundImage {
 	#[default]
 	None,
@@ -34,7 +34,7 @@ pub enum SingleBackgroundImage {
 impl<'a> Parse<'a> for SingleBackgroundImage {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek().clone() {
-			Token::Ident(ident) if ident.to_ascii_lowercase() == atom!("none") => {
+			Token::Ident(ident) if ident.to_ascii_lowercase_atom() == atom!("none") => {
 				parser.advance();
 				Self::None
 			}
@@ -50,7 +50,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(BackgroundImage, 96);
+		assert_size!(BackgroundImage, 136);
 	}
 
 	#[test]
@@ -1,4 +1,4 @@
-use hdx_derive::Value;
+use hdx_derive::{ShiftSpans, Value, Visitable};
 
 use crate::{
 	css::{keywords::LineStyle, types::Color, units::LineWidth},
@@ -6,8 +6,8 @@ use crate::{
 };
 
 // https://drafts.csswg.org/css-backgrounds/#border-shorthands
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBottom(pub Option<LineWidth>, pub Option<LineStyle>, pub Option<Color>);
 
 parse_option_shorthand!(BorderBottom, LineWidth, LineStyle, Color);
@@ -20,7 +20,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(BorderBottom, 48);
+		assert_size!(BorderBottom, 40);
 	}
 
 	#[test]
@@ -1,58 +1,58 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::{css::types::Color, macros::*};
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderTopColor(pub Color);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBottomColor(pub Color);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderLeftColor(pub Color);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderRightColor(pub Color);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockStartColor(pub Color);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockEndColor(pub Color);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineStartColor(pub Color);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineEndColor(pub Color);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-block-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockColor(pub BorderBlockStartColor, pub BorderBlockEndColor);
 
 parse_logical_sides!(BorderBlockColor, Color, BorderBlockStartColor, BorderBlockEndColor);
 write_logical_sides!(BorderBlockColor);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-inline-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineColor(pub BorderInlineStartColor, pub BorderInlineEndColor);
 
 parse_logical_sides!(BorderInlineColor, Color, BorderInlineStartColor, BorderInlineEndColor);
 write_logical_sides!(BorderInlineColor);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderColor(
 	pub BorderBlockStartColor,
 	pub BorderInlineEndColor,
@@ -77,17 +77,17 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(BorderColor, 144);
-		assert_size!(BorderBlockColor, 72);
-		assert_size!(BorderInlineColor, 72);
-		assert_size!(BorderBlockStartColor, 36);
-		assert_size!(BorderBlockEndColor, 36);
-		assert_size!(BorderInlineStartColor, 36);
-		assert_size!(BorderInlineEndColor, 36);
-		assert_size!(BorderTopColor, 36);
-		assert_size!(BorderRightColor, 36);
-		assert_size!(BorderLeftColor, 36);
-		assert_size!(BorderBottomColor, 36);
+		assert_size!(BorderColor, 64);
+		assert_size!(BorderBlockColor, 32);
+		assert_size!(BorderInlineColor, 32);
+		assert_size!(BorderBlockStartColor, 16);
+		assert_size!(BorderBlockEndColor, 16);
+		assert_size!(BorderInlineStartColor, 16);
+		assert_size!(BorderInlineEndColor, 16);
+		assert_size!(BorderTopColor, 16);
+		assert_size!(BorderRightColor, 16);
+		assert_size!(BorderLeftColor, 16);
+		assert_size!(BorderBottomColor, 16);
 	}
 
 	#[test]
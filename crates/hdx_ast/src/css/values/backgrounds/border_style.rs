@@ -1,58 +1,58 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::{css::keywords::LineStyle, macros::*};
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderTopStyle(pub LineStyle);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBottomStyle(pub LineStyle);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderLeftStyle(pub LineStyle);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderRightStyle(pub LineStyle);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockStartStyle(pub LineStyle);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockEndStyle(pub LineStyle);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineStartStyle(pub LineStyle);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineEndStyle(pub LineStyle);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-block-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockStyle(pub BorderBlockStartStyle, pub BorderBlockEndStyle);
 
 parse_logical_sides!(BorderBlockStyle, LineStyle, BorderBlockStartStyle, BorderBlockEndStyle);
 write_logical_sides!(BorderBlockStyle);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-inline-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineStyle(pub BorderInlineStartStyle, pub BorderInlineEndStyle);
 
 parse_logical_sides!(BorderInlineStyle, LineStyle, BorderInlineStartStyle, BorderInlineEndStyle);
 write_logical_sides!(BorderInlineStyle);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderStyle(
 	pub BorderBlockStartStyle,
 	pub BorderInlineEndStyle,
@@ -1,58 +1,58 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::{css::units::LineWidth, macros::*};
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderTopWidth(LineWidth);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBottomWidth(LineWidth);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderLeftWidth(LineWidth);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderRightWidth(LineWidth);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockStartWidth(LineWidth);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockEndWidth(LineWidth);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineStartWidth(LineWidth);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineEndWidth(LineWidth);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-block-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderBlockWidth(pub BorderBlockStartWidth, pub BorderBlockEndWidth);
 
 parse_logical_sides!(BorderBlockWidth, LineWidth, BorderBlockStartWidth, BorderBlockEndWidth);
 write_logical_sides!(BorderBlockWidth);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-inline-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderInlineWidth(pub BorderInlineStartWidth, pub BorderInlineEndWidth);
 
 parse_logical_sides!(BorderInlineWidth, LineWidth, BorderInlineStartWidth, BorderInlineEndWidth);
 write_logical_sides!(BorderInlineWidth);
 
 // https://drafts.csswg.org/css-backgrounds/#propdef-border-width
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderWidth(
 	pub BorderBlockStartWidth,
 	pub BorderInlineEndWidth,
@@ -77,15 +77,15 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(BorderWidth, 32);
-		assert_size!(BorderBlockStartWidth, 8);
-		assert_size!(BorderBlockEndWidth, 8);
-		assert_size!(BorderInlineStartWidth, 8);
-		assert_size!(BorderInlineEndWidth, 8);
-		assert_size!(BorderTopWidth, 8);
-		assert_size!(BorderRightWidth, 8);
-		assert_size!(BorderLeftWidth, 8);
-		assert_size!(BorderBottomWidth, 8);
+		assert_size!(BorderWidth, 64);
+		assert_size!(BorderBlockStartWidth, 16);
+		assert_size!(BorderBlockEndWidth, 16);
+		assert_size!(BorderInlineStartWidth, 16);
+		assert_size!(BorderInlineEndWidth, 16);
+		assert_size!(BorderTopWidth, 16);
+		assert_size!(BorderRightWidth, 16);
+		assert_size!(BorderLeftWidth, 16);
+		assert_size!(BorderBottomWidth, 16);
 	}
 
 	#[test]
@@ -1,61 +1,61 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::{css::units::LengthPercentageOrAuto, macros::*};
 
 // https://drafts.csswg.org/css-box-4/#margin-physical
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Margin(pub MarginBlockStart, pub MarginBlockEnd, pub MarginInlineStart, pub MarginInlineEnd);
 
 parse_rect!(Margin, LengthPercentageOrAuto, MarginBlockStart, MarginBlockEnd, MarginInlineStart, MarginInlineEnd);
 write_rect!(Margin);
 
 // https://drafts.csswg.org/css-logical-1/#propdef-margin-inline
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginInline(pub MarginInlineStart, pub MarginInlineEnd);
 
 parse_logical_sides!(MarginInline, LengthPercentageOrAuto, MarginInlineStart, MarginInlineEnd);
 write_logical_sides!(MarginInline);
 
 // https://drafts.csswg.org/css-logical-1/#propdef-margin-inline
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginBlock(pub MarginBlockStart, pub MarginBlockEnd);
 
 parse_logical_sides!(MarginBlock, LengthPercentageOrAuto, MarginBlockStart, MarginBlockEnd);
 write_logical_sides!(MarginBlock);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginBlockStart(pub LengthPercentageOrAuto);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginBlockEnd(pub LengthPercentageOrAuto);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginInlineStart(pub LengthPercentageOrAuto);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginInlineEnd(pub LengthPercentageOrAuto);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginTop(LengthPercentageOrAuto);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginBottom(LengthPercentageOrAuto);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginLeft(LengthPercentageOrAuto);
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct MarginRight(LengthPercentageOrAuto);
 
 #[cfg(test)]
@@ -65,15 +65,15 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Margin, 32);
-		assert_size!(MarginBlockStart, 8);
-		assert_size!(MarginBlockEnd, 8);
-		assert_size!(MarginInlineStart, 8);
-		assert_size!(MarginInlineEnd, 8);
-		assert_size!(MarginTop, 8);
-		assert_size!(MarginRight, 8);
-		assert_size!(MarginLeft, 8);
-		assert_size!(MarginBottom, 8);
+		assert_size!(Margin, 64);
+		assert_size!(MarginBlockStart, 16);
+		assert_size!(MarginBlockEnd, 16);
+		assert_size!(MarginInlineStart, 16);
+		assert_size!(MarginInlineEnd, 16);
+		assert_size!(MarginTop, 16);
+		assert_size!(MarginRight, 16);
+		assert_size!(MarginLeft, 16);
+		assert_size!(MarginBottom, 16);
 	}
 
 	#[test]
@@ -1,14 +1,14 @@
 use bitmask_enum::bitmask;
 use hdx_atom::atom;
-use hdx_derive::{Atomizable, Value};
+use hdx_derive::{Atomizable, ShiftSpans, Value, Visitable};
 use hdx_parser::{expect_ignore_case, unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-box-4/#propdef-margin-trim
-#[derive(Value, Atomizable, Default)]
+#[derive(Value, Atomizable, Visitable, ShiftSpans, Default)]
 #[bitmask(u8)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum MarginTrim {
 	#[default]
 	None,
@@ -27,7 +27,7 @@ impl<'a> Parse<'a> for MarginTrim {
 			if value.is_all() {
 				break;
 			}
-			expect_ignore_case! { parser.next(), Token::Ident(_):
+			expect_ignore_case! { parser.next_token(), Token::Ident(_):
 				atom!("block") => value |= MarginTrim::Block,
 				atom!("inline") => value |= MarginTrim::Inline,
 				atom!("block-start") => value |= MarginTrim::BlockStart,
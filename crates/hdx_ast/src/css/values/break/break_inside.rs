@@ -1,8 +1,12 @@
-use hdx_derive::{Atomizable, Parsable, Value, Writable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Value, Visitable, Writable};
 
 // https://drafts.csswg.org/css-break-4/#propdef-break-inside
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum BreakInside {
 	#[default]
 	Auto, // atom!("auto")
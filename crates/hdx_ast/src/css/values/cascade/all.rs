@@ -1,10 +1,10 @@
-use hdx_derive::Value;
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_parser::{Parse, Parser, Result as ParserResult, Span, Spanned};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-cascade-5/#propdef-all
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct All();
 
 impl<'a> Parse<'a> for All {
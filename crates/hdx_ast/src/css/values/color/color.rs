@@ -1,10 +1,10 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::types::Color as ColorType;
 
 // https://drafts.csswg.org/css-color/#the-color-property
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct Color(pub ColorType);
 
 #[cfg(test)]
@@ -14,6 +14,6 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Color, 36);
+		assert_size!(Color, 16);
 	}
 }
@@ -1,12 +1,12 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::{CSSFloat, Percent};
 
 // https://drafts.csswg.org/css-color/#transparency
-#[derive(Value, Parsable, Writable, Debug, PartialEq, Clone, Hash)]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum Opacity {
@@ -29,7 +29,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Opacity, 8);
+		assert_size!(Opacity, 16);
 	}
 
 	#[test]
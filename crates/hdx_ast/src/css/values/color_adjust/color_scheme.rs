@@ -1,16 +1,16 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::Value;
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 use smallvec::{smallvec, SmallVec};
 
 // https://drafts.csswg.org/css-color-adjust/#color-scheme-prop
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
 #[value(Inherits)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum ColorScheme {
@@ -25,7 +25,7 @@ impl<'a> Parse<'a> for ColorScheme {
 		let mut only = false;
 		let mut keywords = smallvec![];
 		while let Token::Ident(ident) = parser.peek() {
-			match ident.to_ascii_lowercase() {
+			match ident.to_ascii_lowercase_atom() {
 				atom!("normal") => {
 					parser.advance();
 					return Ok(Self::Normal);
@@ -38,7 +38,7 @@ impl<'a> Parse<'a> for ColorScheme {
 				}
 				atom!("light") => keywords.push(ColorSchemeKeyword::Light),
 				atom!("dark") => keywords.push(ColorSchemeKeyword::Dark),
-				_ => keywords.push(ColorSchemeKeyword::Custom(ident.clone())),
+				_ => keywords.push(ColorSchemeKeyword::Custom(ident.to_atom())),
 			}
 			parser.advance();
 		}
@@ -75,8 +75,8 @@ impl<'a> WriteCss<'a> for ColorScheme {
 	}
 }
 
-#[derive(Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum ColorSchemeKeyword {
 	Light,
 	Dark,
@@ -85,11 +85,11 @@ pub enum ColorSchemeKeyword {
 
 impl<'a> Parse<'a> for ColorSchemeKeyword {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next() {
-			Token::Ident(ident) => match ident.to_ascii_lowercase() {
+		match parser.next_token() {
+			Token::Ident(ident) => match ident.to_ascii_lowercase_atom() {
 				atom!("light") => Ok(Self::Light),
 				atom!("dark") => Ok(Self::Dark),
-				_ => Ok(Self::Custom(ident.clone())),
+				_ => Ok(Self::Custom(ident.to_atom())),
 			},
 			token => unexpected!(parser, token),
 		}
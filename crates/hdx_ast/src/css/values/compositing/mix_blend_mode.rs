@@ -1,8 +1,12 @@
-use hdx_derive::{Atomizable, Parsable, Value, Writable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Value, Visitable, Writable};
 
 // https://drafts.fxtf.org/compositing/#propdef-mix-blend-mode
-#[derive(Value, Parsable, Writable, Atomizable, Debug, Default, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Debug, Default, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum MixBlendMode {
 	#[default]
 	Normal, // atom!("normal")
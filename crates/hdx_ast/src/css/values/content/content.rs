@@ -1,11 +1,11 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::Value;
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::{QuoteStyle, Token};
 use hdx_parser::{unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::WriteCss;
 
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Content {
 	#[default]
 	Normal,
@@ -20,13 +20,13 @@ pub enum Content {
 
 impl<'a> Parse<'a> for Content {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		Ok(match parser.next() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+		Ok(match parser.next_token() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("normal") => Self::Normal,
 				atom!("none") => Self::None,
 				atom => unexpected_ident!(parser, atom),
 			},
-			Token::String(atom, quote) => Self::String(atom.clone(), *quote),
+			Token::String(atom, quote) => Self::String(atom.to_atom(), *quote),
 			token => unexpected!(parser, token),
 		})
 	}
@@ -1,13 +1,13 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::Value;
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::{QuoteStyle, Token};
 use hdx_parser::{unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 use smallvec::{smallvec, SmallVec};
 
-#[derive(Value, Default, Debug, Clone, PartialEq, Hash)]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, Clone, PartialEq, Hash)]
 #[value(Inherits)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Quotes {
 	None,
 	#[default]
@@ -18,19 +18,19 @@ pub enum Quotes {
 
 impl<'a> Parse<'a> for Quotes {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		Ok(match parser.next() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+		Ok(match parser.next_token() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("none") => Quotes::None,
 				atom!("auto") => Quotes::Auto,
 				atom!("match-parent") => Quotes::MatchParent,
 				_ => unexpected_ident!(parser, atom),
 			},
 			Token::String(atom, style) => {
-				let mut quotes = smallvec![(atom.clone(), *style)];
+				let mut quotes = smallvec![(atom.to_atom(), *style)];
 				loop {
 					if let Token::String(atom, style) = parser.peek().clone() {
 						parser.advance();
-						quotes.push((atom, style));
+						quotes.push((atom.to_atom(), style));
 					} else {
 						break;
 					};
@@ -1,15 +1,15 @@
 use bitmask_enum::bitmask;
-use hdx_atom::{atom, Atom};
-use hdx_derive::Value;
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{diagnostics, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-display-4/#propdef-display
-#[derive(Value, Default)]
+#[derive(Value, Visitable, ShiftSpans, Default)]
 #[bitmask(u8)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Display {
 	// The anatomy of the u8 for Display values is:
 	//
@@ -174,7 +174,7 @@ impl<'a> Parse<'a> for Display {
 		// Certain values can only be used in a "standalone way" and so complete the
 		// value:
 		let single_value = match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				// <display-box>
 				atom!("none") => Some(Self::None),
 				atom!("contents") => Some(Self::Contents),
@@ -207,8 +207,9 @@ impl<'a> Parse<'a> for Display {
 
 		// If a legacy/internal/box value is not applied then it must be a pair/triplet
 		let mut value = Self::None;
-		while let Token::Ident(atom) = parser.next() {
-			match atom.to_ascii_lowercase() {
+		while let Token::Ident(atom) = parser.peek().clone() {
+			parser.advance();
+			match atom.to_ascii_lowercase_atom() {
 				// <display-outside>
 				atom!("block") if !value.has_outside() => value |= Self::Block,
 				atom!("inline") if !value.has_outside() => value |= Self::Inline,
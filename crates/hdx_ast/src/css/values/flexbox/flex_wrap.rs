@@ -1,11 +1,18 @@
-use hdx_derive::{Atomizable, Parsable, Value, Writable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Value, Visitable, Writable};
 
 // https://drafts.csswg.org/css-flexbox/#flex-wrap-property
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum FlexWrap {
 	#[default]
-	NoWrap, // atom!("no-wrap")
+	#[atomizable("nowrap")]
+	#[parsable(atom = "nowrap")]
+	#[writable(rename = "nowrap")]
+	NoWrap,
 	Wrap,        // atom!("wrap")
 	WrapReverse, // atom!("wrap-reverse")
 }
@@ -23,6 +30,7 @@ mod tests {
 
 	#[test]
 	fn test_writes() {
+		assert_parse!(FlexWrap, "nowrap");
 		assert_parse!(FlexWrap, "wrap-reverse");
 	}
 }
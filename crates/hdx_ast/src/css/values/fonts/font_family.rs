@@ -1,18 +1,18 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::{Value, Writable};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::{QuoteStyle, Token};
 use hdx_parser::{discard, expect, unexpected, Parse, Parser, Result as ParserResult, Spanned};
-use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
+use hdx_writer::{write_comma_list, CssWriter, Result as WriterResult, WriteCss};
 
 use smallvec::{smallvec, SmallVec};
 
 // https://drafts.csswg.org/css-animations-2/#animation-duration
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct FontFamily(pub SmallVec<[Spanned<SingleFontFamily>; 1]>);
 
-#[derive(Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum SingleFontFamily {
 	#[writable(String)]
 	Named(Atom, QuoteStyle),
@@ -44,9 +44,9 @@ pub enum SingleFontFamily {
 
 impl<'a> Parse<'a> for SingleFontFamily {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		let value = match parser.next() {
-			Token::String(atom, quote) => Self::Named(atom.clone(), *quote),
-			Token::Ident(ident) => match ident.to_ascii_lowercase() {
+		let value = match parser.next_token() {
+			Token::String(atom, quote) => Self::Named(atom.to_atom(), *quote),
+			Token::Ident(ident) => match ident.to_ascii_lowercase_atom() {
 				atom!("serif") => Self::Serif,
 				atom!("sans-serif") => Self::SansSerif,
 				atom!("cursive") => Self::Cursive,
@@ -63,12 +63,21 @@ impl<'a> Parse<'a> for SingleFontFamily {
 				atom!("message-box") => Self::MessageBox,
 				atom!("small-caption") => Self::SmallCaption,
 				atom!("status-bar") => Self::StatusBar,
-				_ => Self::Named(ident.clone(), QuoteStyle::None),
+				_ => {
+					// Names can be written unquoted, as a sequence of idents.
+					let mut name = ident.to_string();
+					while let Token::Ident(ident) = parser.peek().clone() {
+						parser.advance();
+						name.push(' ');
+						name.push_str(ident);
+					}
+					Self::Named(name.to_atom(), QuoteStyle::None)
+				}
 			},
-			Token::Function(atom!("generic")) => match parser.next().clone() {
+			Token::Function("generic") => match parser.next_token().clone() {
 				Token::Ident(ident) => {
-					expect!(parser.next(), Token::RightParen);
-					Self::Generic(ident)
+					expect!(parser.next_token(), Token::RightParen);
+					Self::Generic(ident.to_atom())
 				}
 				token => unexpected!(parser, token),
 			},
@@ -93,15 +102,7 @@ impl<'a> Parse<'a> for FontFamily {
 
 impl<'a> WriteCss<'a> for FontFamily {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
-		let mut iter = self.0.iter().peekable();
-		while let Some(time) = iter.next() {
-			time.write_css(sink)?;
-			if iter.peek().is_some() {
-				sink.write_char(',')?;
-				sink.write_whitespace()?;
-			}
-		}
-		Ok(())
+		write_comma_list(sink, &self.0)
 	}
 }
 
@@ -120,6 +121,8 @@ mod tests {
 		assert_parse!(FontFamily, "serif");
 		assert_parse!(FontFamily, "Arial, sans-serif");
 		assert_parse!(FontFamily, "'Gill Sans MS', Arial, system-ui, sans-serif");
+		assert_parse!(FontFamily, "Gill Sans MS, Arial, sans-serif");
+		assert_parse!(FontFamily, "Gill  Sans,serif", "Gill Sans, serif");
 		assert_parse!(FontFamily, "generic(foo)");
 	}
 
@@ -1,12 +1,12 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::LengthPercentage;
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
 #[value(Inherits)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum FontSize {
@@ -31,7 +31,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(FontSize, 8);
+		assert_size!(FontSize, 16);
 	}
 
 	#[test]
@@ -1,5 +1,5 @@
-use hdx_atom::atom;
-use hdx_derive::Value;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{diagnostics, expect_ignore_case, Parse, Parser, Result as ParserResult};
 use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
@@ -7,9 +7,13 @@ use hdx_writer::{write_css, CssWriter, Result as WriterResult, WriteCss};
 use crate::css::units::Angle;
 
 // https://drafts.csswg.org/css-fonts/#font-style-prop
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
 #[value(Inherits)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontStyle {
 	#[default]
 	Normal,
@@ -20,14 +24,14 @@ pub enum FontStyle {
 
 impl<'a> Parse<'a> for FontStyle {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect_ignore_case! { parser.next(), Token::Ident(_):
+		expect_ignore_case! { parser.next_token(), Token::Ident(_):
 			atom!("normal") => Ok(Self::Normal),
 			atom!("italic") => Ok(Self::Italic),
 			atom!("oblique") => match parser.peek().clone() {
-				Token::Dimension(val, unit, _) => {
+				Token::Dimension(val, unit, _, _) => {
 					parser.advance();
-					if !matches!(unit.to_ascii_lowercase(), atom!("deg")) {
-						Err(diagnostics::UnexpectedDimension(unit, parser.span()))?
+					if !matches!(unit.to_ascii_lowercase_atom(), atom!("deg")) {
+						Err(diagnostics::UnexpectedDimension(unit.to_atom(), parser.span()))?
 					}
 					if !(-90.0..=90.0).contains(&val) {
 						Err(diagnostics::NumberOutOfBounds(val, "-90..=90".into(), parser.span()))?
@@ -61,7 +65,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(FontStyle, 8);
+		assert_size!(FontStyle, 16);
 	}
 
 	#[test]
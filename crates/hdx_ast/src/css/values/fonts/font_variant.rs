@@ -1,5 +1,5 @@
-use hdx_atom::atom;
-use hdx_derive::Value;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult, Spanned};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -10,8 +10,8 @@ use super::{
 };
 
 // https://drafts.csswg.org/css-fonts/#font-variant-prop
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct FontVariant(
 	pub Spanned<FontVariantLigatures>,
 	pub Spanned<FontVariantPosition>,
@@ -26,7 +26,7 @@ impl<'a> Parse<'a> for FontVariant {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		let mut value = Self { ..Default::default() };
 		match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("normal") => {
 					parser.advance();
 					return Ok(Self { ..Default::default() });
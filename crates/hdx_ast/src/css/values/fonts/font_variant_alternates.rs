@@ -1,5 +1,5 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::{Value, Writable};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{
 	discard, expect, unexpected, unexpected_function, unexpected_ident, Parse, Parser, Result as ParserResult,
@@ -7,8 +7,12 @@ use hdx_parser::{
 use smallvec::{smallvec, SmallVec};
 
 // https://drafts.csswg.org/css-fonts/#font-variant-alternates-prop
-#[derive(Value, Writable, Debug, Default, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Writable, Visitable, ShiftSpans, Debug, Default, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontVariantAlternates {
 	#[default]
 	Normal,
@@ -31,61 +35,61 @@ pub enum FontVariantAlternates {
 
 impl<'a> Parse<'a> for FontVariantAlternates {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		Ok(match parser.next().clone() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+		Ok(match parser.next_token().clone() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("normal") => Self::Normal,
 				atom!("historical-forms") => Self::HistoricalForms,
 				_ => unexpected_ident!(parser, atom),
 			},
-			Token::Function(atom) => match atom.to_ascii_lowercase() {
-				atom!("stylistic") => match parser.next().clone() {
+			Token::Function(atom) => match atom.to_ascii_lowercase_atom() {
+				atom!("stylistic") => match parser.next_token().clone() {
 					Token::Ident(atom) => {
-						expect!(parser.next(), Token::RightParen);
-						Self::Stylistic(atom.clone())
+						expect!(parser.next_token(), Token::RightParen);
+						Self::Stylistic(atom.to_atom())
 					}
 					token => unexpected!(parser, token),
 				},
-				atom!("swash") => match parser.next().clone() {
+				atom!("swash") => match parser.next_token().clone() {
 					Token::Ident(atom) => {
-						expect!(parser.next(), Token::RightParen);
-						Self::Swash(atom.clone())
+						expect!(parser.next_token(), Token::RightParen);
+						Self::Swash(atom.to_atom())
 					}
 					token => unexpected!(parser, token),
 				},
-				atom!("ornaments") => match parser.next().clone() {
+				atom!("ornaments") => match parser.next_token().clone() {
 					Token::Ident(atom) => {
-						expect!(parser.next(), Token::RightParen);
-						Self::Ornaments(atom.clone())
+						expect!(parser.next_token(), Token::RightParen);
+						Self::Ornaments(atom.to_atom())
 					}
 					token => unexpected!(parser, token),
 				},
-				atom!("annotation") => match parser.next().clone() {
+				atom!("annotation") => match parser.next_token().clone() {
 					Token::Ident(atom) => {
-						expect!(parser.next(), Token::RightParen);
-						Self::Annotation(atom.clone())
+						expect!(parser.next_token(), Token::RightParen);
+						Self::Annotation(atom.to_atom())
 					}
 					token => unexpected!(parser, token),
 				},
 				atom!("styleset") => {
 					let mut idents = smallvec![];
-					while let Token::Ident(atom) = parser.next() {
-						idents.push(atom.clone());
+					while let Token::Ident(atom) = parser.next_token() {
+						idents.push(atom.to_atom());
 						if !discard!(parser, Token::Comma) {
 							break;
 						}
 					}
-					expect!(parser.next(), Token::RightParen);
+					expect!(parser.next_token(), Token::RightParen);
 					Self::Styleset(idents)
 				}
 				atom!("character-variant") => {
 					let mut idents = smallvec![];
-					while let Token::Ident(atom) = parser.next() {
-						idents.push(atom.clone());
+					while let Token::Ident(atom) = parser.next_token() {
+						idents.push(atom.to_atom());
 						if !discard!(parser, Token::Comma) {
 							break;
 						}
 					}
-					expect!(parser.next(), Token::RightParen);
+					expect!(parser.next_token(), Token::RightParen);
 					Self::CharacterVariant(idents)
 				}
 				_ => unexpected_function!(parser, atom),
@@ -1,15 +1,19 @@
 use bitmask_enum::bitmask;
-use hdx_atom::atom;
-use hdx_derive::Value;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{match_ignore_case, unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-fonts/#font-variant-east-asian-prop
-#[derive(Value, Default)]
+#[derive(Value, Visitable, ShiftSpans, Default)]
 #[bitmask(u16)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum FontVariantEastAsian {
 	#[default]
 	Normal = 0b0000_0000,
@@ -45,7 +49,7 @@ impl<'a> Parse<'a> for FontVariantEastAsian {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		let mut value = Self::Normal;
 		while let Token::Ident(atom) = parser.peek() {
-			match atom.to_ascii_lowercase() {
+			match atom.to_ascii_lowercase_atom() {
 				atom!("normal") if value == Self::Normal => {
 					parser.advance();
 					return Ok(value);
@@ -1,16 +1,16 @@
 use bitmask_enum::bitmask;
-use hdx_atom::atom;
-use hdx_derive::Value;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-fonts/#font-variant-ligatures-prop
-#[derive(Value, Default)]
+#[derive(Value, Visitable, ShiftSpans, Default)]
 #[value(Inherits)]
 #[bitmask(u16)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum FontVariantLigatures {
 	#[default]
 	Normal = 0b0000_0000_0000,
@@ -54,7 +54,7 @@ impl FontVariantLigatures {
 impl<'a> Parse<'a> for FontVariantLigatures {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("none") => {
 					parser.advance();
 					return Ok(Self::None);
@@ -69,7 +69,7 @@ impl<'a> Parse<'a> for FontVariantLigatures {
 		}
 		let mut value = Self::Normal;
 		while let Token::Ident(atom) = parser.peek() {
-			match atom.to_ascii_lowercase() {
+			match atom.to_ascii_lowercase_atom() {
 				atom!("common-ligatures") if !value.has_common_lig() => value |= Self::CommonLigatures,
 				atom!("no-common-ligatures") if !value.has_common_lig() => value |= Self::NoCommonLigatures,
 				atom!("discretionary-ligatures") if !value.has_discretionary_lig() => {
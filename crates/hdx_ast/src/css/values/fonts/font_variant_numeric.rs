@@ -1,16 +1,16 @@
 use bitmask_enum::bitmask;
-use hdx_atom::atom;
-use hdx_derive::Value;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-fonts/#font-variant-ligatures-prop
-#[derive(Value, Default)]
+#[derive(Value, Visitable, ShiftSpans, Default)]
 #[value(Inherits)]
 #[bitmask(u16)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum FontVariantNumeric {
 	#[default]
 	Normal = 0b0000_0000_0000,
@@ -51,7 +51,7 @@ impl<'a> Parse<'a> for FontVariantNumeric {
 		let mut value = Self::Normal;
 		loop {
 			match parser.peek() {
-				Token::Ident(atom) => match atom.to_ascii_lowercase() {
+				Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 					atom!("normal") => {
 						parser.advance();
 						return Ok(Self::Normal);
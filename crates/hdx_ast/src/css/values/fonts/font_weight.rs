@@ -1,12 +1,12 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::CSSFloat;
 
 // https://drafts.csswg.org/css-inline/#propdef-alignment-baseline
-#[derive(Value, Parsable, Writable, Default, Debug, PartialEq, Clone, Hash)]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum FontWeight {
@@ -26,7 +26,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(FontWeight, 8);
+		assert_size!(FontWeight, 16);
 	}
 
 	#[test]
@@ -1,10 +1,14 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::LengthPercentage;
 
 // https://drafts.csswg.org/css-inline/#propdef-alignment-baseline
-#[derive(Value, Parsable, Writable, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum BaselineShift {
 	#[parsable(DimensionOrZero, parse_inner)]
 	LengthPercentage(LengthPercentage),
@@ -28,7 +32,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(BaselineShift, 8);
+		assert_size!(BaselineShift, 16);
 	}
 
 	#[test]
@@ -1,11 +1,11 @@
 use crate::css::units::{CSSFloat, LengthPercentage};
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
 #[value(Inherits)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum LineHeight {
@@ -24,7 +24,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(LineHeight, 8);
+		assert_size!(LineHeight, 16);
 	}
 
 	#[test]
@@ -34,5 +34,16 @@ mod tests {
 		assert_parse!(LineHeight, "10px");
 		assert_parse!(LineHeight, "1.25");
 		assert_parse!(LineHeight, "normal");
+		assert_parse!(LineHeight, "1e3", "1000");
+		assert_parse!(LineHeight, "1.50", "1.5");
+		assert_parse!(LineHeight, "1.2345678901234");
+		assert_parse!(LineHeight, "0.1234567890123456789", "0.12345678901234568");
+	}
+
+	#[test]
+	fn test_minify() {
+		assert_minify!(LineHeight, "1000", "1e3");
+		assert_minify!(LineHeight, "0.50", ".5");
+		assert_minify!(LineHeight, "1.50em", "1.5em");
 	}
 }
@@ -1,12 +1,12 @@
-use hdx_derive::Value;
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_parser::{Parse, Parser, Result as ParserResult, Span, Spanned};
 use hdx_writer::{CssWriter, OutputOption, Result as WriterResult, WriteCss};
 
 use super::{AlignmentBaseline, BaselineShift, BaselineSource};
 
 // https://drafts.csswg.org/css-box-4/#padding-physical
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct VerticalAlign(pub Spanned<BaselineSource>, pub Spanned<AlignmentBaseline>, pub Spanned<BaselineShift>);
 
 impl<'a> Parse<'a> for VerticalAlign {
@@ -73,7 +73,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(VerticalAlign, 40);
+		assert_size!(VerticalAlign, 48);
 	}
 
 	#[test]
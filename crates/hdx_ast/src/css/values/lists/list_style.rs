@@ -1,5 +1,5 @@
-use hdx_atom::atom;
-use hdx_derive::Value;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult};
 
@@ -8,8 +8,8 @@ use crate::macros::*;
 use super::{ListStyleImage, ListStylePosition, ListStyleType};
 
 // https://drafts.csswg.org/css-lists/#list-style-property
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct ListStyle(pub ListStylePosition, pub ListStyleImage, pub ListStyleType);
 
 impl<'a> Parse<'a> for ListStyle {
@@ -22,7 +22,7 @@ impl<'a> Parse<'a> for ListStyle {
 			if matches!(parser.peek(), Token::Semicolon | Token::Eof | Token::RightParen | Token::RightCurly) {
 				break;
 			}
-			if matches!(parser.peek(), Token::Ident(atom) if atom.to_ascii_lowercase() == atom!("none")) {
+			if matches!(parser.peek(), Token::Ident(atom) if atom.to_ascii_lowercase_atom() == atom!("none")) {
 				parser.advance();
 				nones += 1;
 				if nones > 2 {
@@ -72,7 +72,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(ListStyle, 128);
+		assert_size!(ListStyle, 168);
 	}
 
 	#[test]
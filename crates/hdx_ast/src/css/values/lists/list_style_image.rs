@@ -1,13 +1,13 @@
-use hdx_atom::atom;
-use hdx_derive::{Value, Writable};
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 
 use crate::css::types::Image;
 
 // https://drafts.csswg.org/css-lists/#list-style-property
-#[derive(Value, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum ListStyleImage {
 	Image(Image),
 	#[default]
@@ -17,7 +17,7 @@ pub enum ListStyleImage {
 impl<'a> Parse<'a> for ListStyleImage {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("none") => {
 					parser.advance();
 					Self::None
@@ -37,7 +37,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(ListStyleImage, 72);
+		assert_size!(ListStyleImage, 112);
 	}
 
 	#[test]
@@ -1,13 +1,13 @@
-use hdx_atom::{atom, Atom};
-use hdx_derive::{Value, Writable};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::{QuoteStyle, Token};
 use hdx_parser::{Parse, Parser, Result as ParserResult, Spanned};
 
 use crate::css::types::CounterStyle;
 
 // https://drafts.csswg.org/css-lists/#list-style-property
-#[derive(Writable, Value, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Writable, Value, Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum ListStyleType {
 	CounterStyle(Spanned<CounterStyle>),
 	#[writable(String)]
@@ -18,7 +18,7 @@ pub enum ListStyleType {
 impl<'a> Parse<'a> for ListStyleType {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.peek().clone() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("none") => {
 					parser.advance();
 					Self::None
@@ -27,7 +27,7 @@ impl<'a> Parse<'a> for ListStyleType {
 			},
 			Token::String(atom, style) => {
 				parser.advance();
-				Self::String(atom, style)
+				Self::String(atom.to_atom(), style)
 			}
 			_ => Self::CounterStyle(CounterStyle::parse_spanned(parser)?),
 		})
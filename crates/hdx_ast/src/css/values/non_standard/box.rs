@@ -1,10 +1,14 @@
-use hdx_derive::{Atomizable, Parsable, Value, Writable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::CSSFloat;
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/box-align
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum BoxAlign {
 	Start,
 	Center,
@@ -15,8 +19,12 @@ pub enum BoxAlign {
 }
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/box-direction
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum BoxDirection {
 	#[default]
 	Normal, // atom!("normal")
@@ -24,8 +32,8 @@ pub enum BoxDirection {
 }
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/box-flex
-#[derive(Value, Parsable, Writable, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BoxFlex(pub CSSFloat);
 
 impl Default for BoxFlex {
@@ -35,8 +43,8 @@ impl Default for BoxFlex {
 }
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/box-flex-group
-#[derive(Value, Parsable, Writable, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BoxFlexGroup(pub CSSFloat);
 
 impl Default for BoxFlexGroup {
@@ -46,8 +54,12 @@ impl Default for BoxFlexGroup {
 }
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/box-lines
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum BoxLines {
 	#[default]
 	Single, // atom!("single")
@@ -55,8 +67,8 @@ pub enum BoxLines {
 }
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/box-ordinal-group
-#[derive(Value, Parsable, Writable, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BoxOrdinalGroup(pub CSSFloat);
 
 impl Default for BoxOrdinalGroup {
@@ -66,8 +78,12 @@ impl Default for BoxOrdinalGroup {
 }
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/box-orient
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum BoxOrient {
 	Horizontal, // atom!("horizontal")
 	Vertical,   // atom!("vertical")
@@ -77,8 +93,12 @@ pub enum BoxOrient {
 }
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/box-orient
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum BoxPack {
 	#[default]
 	Start, // atom!("start")
@@ -1,4 +1,4 @@
-use hdx_derive::{Atomizable, Parsable, Value, Writable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::values::{Appearance, BoxSizing, ColumnCount, ColumnGap, TabSize, Todo, Transition, UserSelect};
 
@@ -11,8 +11,12 @@ pub type MozForceBrokenImageIcon = Todo;
 pub type MozImageRegion = Todo;
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/font-smooth
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum MozOsxFontSmoothing {
 	#[default]
 	Auto, // atom!("auto")
@@ -1,5 +1,7 @@
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Value, Visitable, Writable};
+
 use crate::css::values::{
-	AlignContent, AlignSelf, BoxAlign, BoxPack, BoxSizing, Filter, Flex, FlexBasis, FlexDirection, FlexFlow, FlexGrow,
+	AlignContent, AlignSelf, BoxAlign, BoxSizing, Filter, Flex, FlexBasis, FlexDirection, FlexFlow, FlexGrow,
 	FlexShrink, FlexWrap, Order, TextSizeAdjust, Todo, Transition, UserSelect,
 };
 
@@ -13,7 +15,23 @@ pub type MsFlexItemAlign = AlignSelf;
 pub type MsFlexLinePack = AlignContent;
 pub type MsFlexNegative = FlexShrink;
 pub type MsFlexOrder = Order;
-pub type MsFlexPack = BoxPack;
+
+// https://learn.microsoft.com/en-us/previous-versions/windows/internet-explorer/ie-developer/dev-guides/hh772069(v=vs.85)
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
+pub enum MsFlexPack {
+	#[default]
+	Start, // atom!("start")
+	End,        // atom!("end")
+	Center,     // atom!("center")
+	Justify,    // atom!("justify")
+	Distribute, // atom!("distribute")
+}
+
 pub type MsFlexPositive = FlexGrow;
 pub type MsFlexPreferredSize = FlexBasis;
 pub type MsFlexWrap = FlexWrap;
@@ -25,3 +43,20 @@ pub type MsTransform = Todo;
 pub type MsTransformOrigin = Todo;
 pub type MsTransition = Transition;
 pub type MsUserSelect = UserSelect;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_helpers::*;
+
+	#[test]
+	fn size_test() {
+		assert_size!(MsFlexPack, 1);
+	}
+
+	#[test]
+	fn test_writes() {
+		assert_parse!(MsFlexPack, "justify");
+		assert_parse!(MsFlexPack, "distribute");
+	}
+}
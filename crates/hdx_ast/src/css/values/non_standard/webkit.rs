@@ -1,11 +1,11 @@
-use hdx_derive::{Atomizable, Parsable, Value, Writable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::types::Color;
 use crate::css::values::{
 	AlignContent, AlignItems, AlignSelf, Animation, AnimationDelay, AnimationDuration, AnimationFillMode,
 	AnimationIterationCount, AnimationName, AnimationTimingFunction, Appearance, BackdropFilter, BackgroundClip,
 	BoxAlign, BoxDecorationBreak, BoxDirection, BoxFlex, BoxOrdinalGroup, BoxOrient, BoxPack, BoxShadow, BoxSizing,
-	FlexBasis, FlexDirection, FlexFlow, FlexGrow, FlexWrap, JustifyContent, TextDecoration, TextDecorationColor,
+	Flex, FlexBasis, FlexDirection, FlexFlow, FlexGrow, FlexWrap, JustifyContent, TextDecoration, TextDecorationColor,
 	TextDecorationSkipInk, TextSizeAdjust, Todo, TransitionDuration,
 };
 
@@ -38,7 +38,7 @@ pub type WebkitClipPath = Todo;
 pub type WebkitColumnCount = Todo;
 pub type WebkitColumnGap = Todo;
 pub type WebkitFilter = Todo;
-pub type WebkitFlex = BoxFlex;
+pub type WebkitFlex = Flex;
 pub type WebkitFlexBasis = FlexBasis;
 pub type WebkitFlexDirection = FlexDirection;
 pub type WebkitFlexFlow = FlexFlow;
@@ -46,14 +46,19 @@ pub type WebkitFlexGrow = FlexGrow;
 pub type WebkitFlexWrap = FlexWrap;
 
 // https://developer.mozilla.org/en-US/docs/Web/CSS/font-smooth
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum WebkitFontSmoothing {
 	#[default]
 	Auto, // atom!("auto")
-	None,        // atom!("none")
-	Antialiased, // atom!("antialiased")
-	Grayscale,   // atom!("grayscale")
+	None,                // atom!("none")
+	Antialiased,         // atom!("antialiased")
+	Grayscale,           // atom!("grayscale")
+	SubpixelAntialiased, // atom!("subpixel-antialiased")
 }
 
 pub type WebkitJustifyContent = JustifyContent;
@@ -74,8 +79,8 @@ pub type WebkitOverflowScrolling = Todo;
 pub type WebkitPrintColorAdjust = Todo;
 pub type WebkitSlideThumb = Todo;
 
-#[derive(Value, Parsable, Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct WebkitTapHighlightColor(pub Color);
 
 pub type WebkitTextDecoration = TextDecoration;
@@ -97,3 +102,20 @@ pub type WebkitTransitionProperty = Todo;
 pub type WebkitTransitionTimingFunction = Todo;
 pub type WebkitUserDrag = Todo;
 pub type WebkitUserSelect = Todo;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_helpers::*;
+
+	#[test]
+	fn size_test() {
+		assert_size!(WebkitFontSmoothing, 1);
+	}
+
+	#[test]
+	fn test_writes() {
+		assert_parse!(WebkitFontSmoothing, "antialiased");
+		assert_parse!(WebkitFontSmoothing, "subpixel-antialiased");
+	}
+}
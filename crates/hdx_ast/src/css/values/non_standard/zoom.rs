@@ -1,11 +1,11 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::CSSFloat;
 
-#[derive(Value, Parsable, Writable, Default, Debug, PartialEq, Clone, Hash)]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
 #[cfg_attr(
 	feature = "serde",
-	derive(serde::Serialize),
+	derive(serde::Serialize, serde::Deserialize),
 	serde(tag = "type", content = "value", rename_all = "kebab-case")
 )]
 pub enum Zoom {
@@ -27,6 +27,6 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Zoom, 8);
+		assert_size!(Zoom, 16);
 	}
 }
@@ -1,5 +1,5 @@
-use hdx_atom::{atom, Atomizable};
-use hdx_derive::{Atomizable, Value};
+use hdx_atom::{atom, Atomizable, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{diagnostics, expect, unexpected, unexpected_ident, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -7,8 +7,8 @@ use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 use crate::css::units::Length;
 
 // https://drafts.csswg.org/css-page-floats-3/#float-property
-#[derive(Value, Debug, PartialEq, Default, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "kebab-case"))]
+#[derive(Value, Visitable, ShiftSpans, Debug, PartialEq, Default, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
 pub enum Float {
 	#[default]
 	None,
@@ -28,8 +28,8 @@ pub enum Float {
 
 impl<'a> Parse<'a> for Float {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		let value = match parser.next() {
-			Token::Ident(atom) => match atom.to_ascii_lowercase() {
+		let value = match parser.next_token() {
+			Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("none") => Self::None,
 				atom!("left") => Self::Left,
 				atom!("right") => Self::Right,
@@ -43,14 +43,14 @@ impl<'a> Parse<'a> for Float {
 				atom!("snap-inline") => Self::SnapInline,
 				atom => unexpected_ident!(parser, atom),
 			},
-			Token::Function(atom) => match atom.to_ascii_lowercase() {
+			Token::Function(atom) => match atom.to_ascii_lowercase_atom() {
 				atom!("snap-block") => {
 					let length = Length::parse(parser)?;
-					match parser.next() {
-						Token::Comma => match parser.next() {
+					match parser.next_token() {
+						Token::Comma => match parser.next_token() {
 							Token::Ident(atom) => {
-								if let Some(dir) = SnapBlockDirection::from_atom(&atom.to_ascii_lowercase()) {
-									expect!(parser.next(), Token::RightParen);
+								if let Some(dir) = SnapBlockDirection::from_atom(&atom.to_ascii_lowercase_atom()) {
+									expect!(parser.next_token(), Token::RightParen);
 									Self::SnapBlockFunction(length, Some(dir))
 								} else {
 									unexpected_ident!(parser, atom)
@@ -64,11 +64,11 @@ impl<'a> Parse<'a> for Float {
 				}
 				atom!("snap-inline") => {
 					let length = Length::parse(parser)?;
-					match parser.next() {
-						Token::Comma => match parser.next() {
+					match parser.next_token() {
+						Token::Comma => match parser.next_token() {
 							Token::Ident(atom) => {
-								if let Some(dir) = SnapInlineDirection::from_atom(&atom.to_ascii_lowercase()) {
-									expect!(parser.next(), Token::RightParen);
+								if let Some(dir) = SnapInlineDirection::from_atom(&atom.to_ascii_lowercase_atom()) {
+									expect!(parser.next_token(), Token::RightParen);
 									Self::SnapInlineFunction(length, Some(dir))
 								} else {
 									unexpected_ident!(parser, atom)
@@ -128,16 +128,16 @@ impl<'a> WriteCss<'a> for Float {
 	}
 }
 
-#[derive(Atomizable, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum SnapBlockDirection {
 	Start, // atom!("start")
 	End,   // atom!("end")
 	Near,  // atom!("near")
 }
 
-#[derive(Atomizable, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum SnapInlineDirection {
 	Left,  // atom!("left")
 	Right, // atom!("right")
@@ -151,7 +151,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Float, 12);
+		assert_size!(Float, 24);
 	}
 
 	#[test]
@@ -1,10 +1,10 @@
-use hdx_derive::{Value, Parsable, Writable};
+use hdx_derive::{Value, Parsable, ShiftSpans, Visitable, Writable};
 
 use crate::css::units::CSSFloat;
 
 // https://drafts.csswg.org/css-page-floats-3/#propdef-float-defer
-#[derive(Value, Parsable, Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum FloatDefer {
 	#[default]
 	Last,
@@ -21,6 +21,6 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(FloatDefer, 8);
+		assert_size!(FloatDefer, 16);
 	}
 }
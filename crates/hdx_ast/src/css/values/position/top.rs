@@ -1,9 +1,9 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::LengthPercentage;
 
-#[derive(Value, Parsable, Writable, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum Top {
 	#[default]
 	Auto, // atom!("auto"),
@@ -18,7 +18,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Top, 8);
+		assert_size!(Top, 16);
 	}
 
 	#[test]
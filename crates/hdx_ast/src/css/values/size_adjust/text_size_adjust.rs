@@ -1,10 +1,10 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::Percent;
 
 // https://drafts.csswg.org/css-size-adjust-1/#propdef-text-size-adjust
-#[derive(Value, Parsable, Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum TextSizeAdjust {
 	None,
 	#[default]
@@ -20,7 +20,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(TextSizeAdjust, 8);
+		assert_size!(TextSizeAdjust, 16);
 	}
 
 	#[test]
@@ -1,8 +1,12 @@
-use hdx_derive::{Value, Atomizable, Parsable, Writable};
+use hdx_derive::{Value, Atomizable, Parsable, ShiftSpans, Visitable, Writable};
 
 // https://drafts.csswg.org/css-sizing-4/#propdef-min-intrinsic-sizing
-#[derive(Value, Parsable, Writable, Atomizable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum MinIntrinsicSizing {
 	#[default]
 	Legacy, // atom!("legacy")
@@ -1,10 +1,14 @@
-use hdx_derive::{Parsable, Value, Writable};
+use hdx_derive::{Parsable, ShiftSpans, Value, Visitable, Writable};
 
 use crate::css::units::LengthPercentage;
 
 // https://drafts.csswg.org/css-sizing-4/#sizing-values
-#[derive(Value, Parsable, Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "kebab-case"))]
+#[derive(Value, Parsable, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Width {
 	#[default]
 	Auto, // atom!("auto")
@@ -29,7 +33,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(Width, 12);
+		assert_size!(Width, 24);
 	}
 
 	#[test]
@@ -1,10 +1,10 @@
 use crate::css::units::Length;
-use hdx_derive::Value;
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_parser::{Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, OutputOption, Result as WriterResult, WriteCss};
 
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct BorderSpacing(Length, Length);
 
 impl<'a> Parse<'a> for BorderSpacing {
@@ -36,7 +36,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(BorderSpacing, 16);
+		assert_size!(BorderSpacing, 32);
 	}
 
 	#[test]
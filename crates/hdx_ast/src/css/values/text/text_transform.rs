@@ -1,17 +1,17 @@
 // https://drafts.csswg.org/css-text/#text-transform
 use bitmask_enum::bitmask;
-use hdx_atom::atom;
-use hdx_derive::Value;
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-text/#text-align-property
-#[derive(Value, Default)]
+#[derive(Value, Visitable, ShiftSpans, Default)]
 #[value(Inherits)]
 #[bitmask(u16)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum TextTransform {
 	#[default]
 	None = 0b0000,
@@ -37,7 +37,7 @@ impl<'a> Parse<'a> for TextTransform {
 				break;
 			}
 			match parser.peek() {
-				Token::Ident(atom) => match atom.to_ascii_lowercase() {
+				Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 					atom!("none") if value.is_none() => {
 						parser.advance();
 						return Ok(Self::None);
@@ -1,15 +1,15 @@
 use bitmask_enum::bitmask;
-use hdx_atom::atom;
-use hdx_derive::{Atomizable, Value};
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{Atomizable, ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{unexpected, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-text-4/#propdef-white-space-trim
-#[derive(Value, Default, Atomizable)]
+#[derive(Value, Default, Atomizable, Visitable, ShiftSpans)]
 #[bitmask(u8)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum WhiteSpaceTrim {
 	#[default]
 	None = 0b0000, // atom!("none")
@@ -25,8 +25,8 @@ impl<'a> Parse<'a> for WhiteSpaceTrim {
 			if value.is_all() {
 				break;
 			}
-			match parser.next() {
-				Token::Ident(atom) => match atom.to_ascii_lowercase() {
+			match parser.next_token() {
+				Token::Ident(atom) => match atom.to_ascii_lowercase_atom() {
 					atom!("none") if value.is_none() => return Ok(Self::None),
 					atom!("discard-before") if !value.contains(Self::DiscardBefore) => value |= Self::DiscardBefore,
 					atom!("discard-after") if !value.contains(Self::DiscardAfter) => value |= Self::DiscardAfter,
@@ -1,12 +1,12 @@
-use hdx_derive::Value;
+use hdx_derive::{ShiftSpans, Value, Visitable};
 
 use crate::macros::*;
 
 use super::{TextDecorationColor, TextDecorationLine, TextDecorationStyle};
 
 // https://drafts.csswg.org/css-text-decor/#text-decoration-property
-#[derive(Value, Default, PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct TextDecoration(
 	pub Option<TextDecorationLine>,
 	pub Option<TextDecorationStyle>,
@@ -23,7 +23,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(TextDecoration, 40);
+		assert_size!(TextDecoration, 24);
 	}
 
 	#[test]
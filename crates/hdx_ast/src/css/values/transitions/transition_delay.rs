@@ -1,4 +1,4 @@
-use hdx_derive::Value;
+use hdx_derive::{ShiftSpans, Value, Visitable};
 use hdx_lexer::Token;
 use hdx_parser::{discard, expect, Parse, Parser, Result as ParserResult};
 use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
@@ -7,13 +7,13 @@ use crate::css::units::Time;
 use smallvec::{smallvec, SmallVec};
 
 // https://drafts.csswg.org/css-transitions-1/#propdef-transition-delay
-#[derive(Value, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct TransitionDelay(pub SmallVec<[Time; 2]>);
 
 impl<'a> Parse<'a> for TransitionDelay {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect!(parser.peek(), Token::Dimension(_, _, _));
+		expect!(parser.peek(), Token::Dimension(_, _, _, _));
 		let mut values = smallvec![];
 		loop {
 			values.push(Time::parse(parser)?);
@@ -45,7 +45,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(TransitionDelay, 32);
+		assert_size!(TransitionDelay, 48);
 	}
 
 	#[test]
@@ -1,4 +1,4 @@
-use hdx_derive::{Value, Writable};
+use hdx_derive::{ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{discard, expect, Parse, Parser, Result as ParserResult};
 use smallvec::{smallvec, SmallVec};
@@ -6,13 +6,13 @@ use smallvec::{smallvec, SmallVec};
 use crate::css::units::Time;
 
 // https://drafts.csswg.org/css-transitions-1/#propdef-transition-duration
-#[derive(Value, Writable, Default, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[derive(Value, Writable, Visitable, ShiftSpans, Default, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub struct TransitionDuration(pub SmallVec<[Time; 2]>);
 
 impl<'a> Parse<'a> for TransitionDuration {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		expect!(parser.peek(), Token::Dimension(_, _, _));
+		expect!(parser.peek(), Token::Dimension(_, _, _, _));
 		let mut values = smallvec![];
 		loop {
 			values.push(Time::parse(parser)?);
@@ -30,7 +30,7 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(TransitionDuration, 32);
+		assert_size!(TransitionDuration, 48);
 	}
 
 	#[test]
@@ -1,14 +1,14 @@
-use hdx_atom::atom;
-use hdx_derive::{Value, Writable};
+use hdx_atom::{atom, ToAtom};
+use hdx_derive::{ShiftSpans, Value, Visitable, Writable};
 use hdx_lexer::Token;
 use hdx_parser::{Parse, Parser, Result as ParserResult};
 
 use crate::css::types::Color;
 
 // https://drafts.csswg.org/css-ui/#widget-accent
-#[derive(Value, Writable, Default, PartialEq, Debug, Clone, Hash)]
+#[derive(Value, Writable, Visitable, ShiftSpans, Default, PartialEq, Debug, Clone, Hash)]
 #[value(Inherits)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde())]
 pub enum AccentColor {
 	#[default]
 	Auto, // atom!("auto")
@@ -18,7 +18,7 @@ pub enum AccentColor {
 impl<'a> Parse<'a> for AccentColor {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
 		Ok(match parser.cur() {
-			Token::Ident(atom) if atom.to_ascii_lowercase() == atom!("auto") => Self::Auto,
+			Token::Ident(atom) if atom.to_ascii_lowercase_atom() == atom!("auto") => Self::Auto,
 			_ => Self::Color(Color::parse(parser)?),
 		})
 	}
@@ -31,6 +31,6 @@ mod tests {
 
 	#[test]
 	fn size_test() {
-		assert_size!(AccentColor, 36);
+		assert_size!(AccentColor, 16);
 	}
 }
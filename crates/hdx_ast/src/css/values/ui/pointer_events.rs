@@ -1,9 +1,38 @@
-use hdx_derive::{Atomizable, Parsable, Value, Writable};
+use hdx_derive::{Atomizable, Parsable, ShiftSpans, Value, Visitable, Writable};
 
-#[derive(Value, Default, Parsable, Writable, Atomizable, Debug, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Value, Default, Parsable, Writable, Atomizable, Visitable, ShiftSpans, Debug, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub enum PointerEvents {
 	#[default]
 	Auto, // atom!("auto")
 	None, // atom!("none")
+	// https://svgwg.org/svg2-draft/interact.html#PointerEventsProperty
+	BoundingBox,    // atom!("bounding-box")
+	Visiblepainted, // atom!("visiblepainted")
+	Visiblefill,    // atom!("visiblefill")
+	Visiblestroke,  // atom!("visiblestroke")
+	Visible,        // atom!("visible")
+	Painted,        // atom!("painted")
+	Fill,           // atom!("fill")
+	Stroke,         // atom!("stroke")
+	All,            // atom!("all")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_helpers::*;
+
+	#[test]
+	fn size_test() {
+		assert_size!(PointerEvents, 1);
+	}
+
+	#[test]
+	fn test_writes() {
+		assert_parse!(PointerEvents, "none");
+		assert_parse!(PointerEvents, "bounding-box");
+		assert_parse!(PointerEvents, "visiblePainted", "visiblepainted");
+		assert_parse!(PointerEvents, "all");
+	}
 }
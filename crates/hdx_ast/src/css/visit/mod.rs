@@ -1,30 +1,224 @@
-use hdx_parser::Spanned;
+use hdx_atom::Atom;
+use hdx_lexer::{NumType, PairWise, QuoteStyle, Token};
+use hdx_parser::{Box, Commented, Comparison, Remnants, Span, Spanned, Trivia, Vec};
+use smallvec::{Array, SmallVec};
 
 use crate::{
-	css::{properties::Property, stylerule::StyleRule, StyleSheet},
+	css::{
+		builder::AstBuilder,
+		properties::{Computed, Custom, Property, StyleValue, Unknown},
+		rules::{self, Keyframe, MediaQuery},
+		selector::{
+			Attribute, Combinator, FunctionalPseudoClass, FunctionalPseudoElement, PseudoClass, PseudoElement,
+			SelectorComponent, SelectorList, Tag,
+		},
+		stylerule::StyleRule,
+		types::{Color, Image, Position},
+		units::{CSSFloat, Length, LengthPercentage, LengthPercentageOrAuto},
+		values, StyleSheet,
+	},
 	syntax::{AtRule, QualifiedRule},
 };
 
+// The nodes with `#[visitable(call)]`, each of which is kept on the [VisitContext] while its children are visited.
 macro_rules! apply_visit_methods {
 	($macro: ident) => {
 		$macro! {
-			visit_style_sheet(StyleSheet<'a>),
-			visit_style_rule(StyleRule<'a>),
-			visit_unknown_at_rule(AtRule<'a>),
-			visit_unknown_rule(QualifiedRule<'a>),
-			visit_property(Property<'a>),
+			StyleSheet(StyleSheet<'a>),
+			Keyframe(Keyframe<'a>),
+			MediaQuery(MediaQuery),
+			Property(Property<'a>),
+			StyleValue(StyleValue<'a>),
+			SelectorList(SelectorList<'a>),
+			SelectorComponent(SelectorComponent<'a>),
+			Tag(Tag),
+			Attribute(Attribute),
+			Combinator(Combinator),
+			PseudoClass(PseudoClass),
+			PseudoElement(PseudoElement),
+			FunctionalPseudoClass(FunctionalPseudoClass<'a>),
+			FunctionalPseudoElement(FunctionalPseudoElement<'a>),
+			Color(Color),
+			Length(Length),
+			LengthPercentage(LengthPercentage),
+			LengthPercentageOrAuto(LengthPercentageOrAuto),
+			Image(Image),
+			Position(Position),
 		}
 	};
 }
 
+macro_rules! node_kind {
+	( $(
+		$name: ident($obj: ty),
+	)+ ) => {
+		/// The kind of each node kept on the [VisitContext].
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum NodeKind {
+			$($name,)+
+			Rule(RuleKind),
+		}
+	}
+}
+
+apply_visit_methods!(node_kind);
+
+macro_rules! rule_kind {
+	( $(
+		$name: ident$(<$a: lifetime>)?: $atom: pat,
+	)+ ) => {
+		/// The kind of each [Rule](crate::css::stylesheet::Rule), such as [RuleKind::Media] for an `@media` rule.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum RuleKind {
+			$($name,)+
+			UnknownAt,
+			Style,
+			Unknown,
+		}
+	}
+}
+
+crate::css::stylesheet::apply_rules!(rule_kind);
+
+/// Whether to visit the children of the node an `enter_` hook was called with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VisitFlow {
+	#[default]
+	Continue,
+	/// Skip the children of the node. Its `exit_` hook is still called.
+	SkipChildren,
+}
+
+/// A node around the one being visited, such as the `@media` rule a style rule is within.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ancestor {
+	pub kind: NodeKind,
+	pub span: Span,
+}
+
+/// Where a visitor is within the tree, given to each of its `enter_` and `exit_` hooks.
+#[derive(Debug, Default, Clone)]
+pub struct VisitContext<'a> {
+	ancestors: std::vec::Vec<Ancestor>,
+	span: Span,
+	builder: Option<AstBuilder<'a>>,
+}
+
+impl<'a> VisitContext<'a> {
+	/// A context for visiting with `builder`, so that visitors can build nodes in the arena of the tree they visit.
+	pub fn new(builder: AstBuilder<'a>) -> Self {
+		Self { builder: Some(builder), ..Default::default() }
+	}
+
+	/// The builder the tree is being visited with, if any.
+	#[inline]
+	pub fn builder(&self) -> Option<AstBuilder<'a>> {
+		self.builder
+	}
+
+	/// The span of the node being visited. Nodes without a span of their own, such as the value of a property, have
+	/// that of the nearest node around them with one.
+	#[inline]
+	pub fn span(&self) -> Span {
+		self.span
+	}
+
+	/// The nodes around the one being visited, from the outermost in.
+	#[inline]
+	pub fn ancestors(&self) -> &[Ancestor] {
+		&self.ancestors
+	}
+
+	/// The nearest node around the one being visited.
+	#[inline]
+	pub fn parent(&self) -> Option<&Ancestor> {
+		self.ancestors.last()
+	}
+
+	/// Whether the node being visited is within a node of `kind`, however deeply.
+	pub fn is_within(&self, kind: NodeKind) -> bool {
+		self.ancestors.iter().any(|ancestor| ancestor.kind == kind)
+	}
+
+	#[inline]
+	pub(crate) fn push(&mut self, kind: NodeKind) {
+		self.ancestors.push(Ancestor { kind, span: self.span });
+	}
+
+	#[inline]
+	pub(crate) fn pop(&mut self) {
+		self.ancestors.pop();
+	}
+}
+
+// The hooks of each typed at-rule, such as `enter_media_rule`, along with those of the other rules.
+macro_rules! rule_methods {
+	( $(
+		$name: ident$(<$a: lifetime>)?: $atom: pat,
+	)+ ) => {
+		hdx_derive::visit_methods!(
+			Rule;
+			$($name(rules::$name$(<$a>)?),)+
+			UnknownAt(AtRule<'a>),
+			Style(StyleRule<'a>),
+			Unknown(QualifiedRule<'a>),
+		);
+	}
+}
+
+macro_rules! rule_mut_methods {
+	( $(
+		$name: ident$(<$a: lifetime>)?: $atom: pat,
+	)+ ) => {
+		hdx_derive::visit_methods!(
+			mut Rule;
+			$($name(rules::$name$(<$a>)?),)+
+			UnknownAt(AtRule<'a>),
+			Style(StyleRule<'a>),
+			Unknown(QualifiedRule<'a>),
+		);
+	}
+}
+
+// The hooks of the value of each property, such as `enter_width_style_value`, along with those of the other values.
+macro_rules! style_value_methods {
+	( $(
+		$name: ident$(<$a: lifetime>)?: $atom: pat,
+	)+ ) => {
+		hdx_derive::visit_methods!(
+			StyleValue;
+			Custom(Custom<'a>),
+			Computed(Computed<'a>),
+			Unknown(Unknown<'a>),
+			$($name(values::$name$(<$a>)?),)+
+		);
+	}
+}
+
+macro_rules! style_value_mut_methods {
+	( $(
+		$name: ident$(<$a: lifetime>)?: $atom: pat,
+	)+ ) => {
+		hdx_derive::visit_methods!(
+			mut StyleValue;
+			Custom(Custom<'a>),
+			Computed(Computed<'a>),
+			Unknown(Unknown<'a>),
+			$($name(values::$name$(<$a>)?),)+
+		);
+	}
+}
+
+// Each node has a `visit_` hook, called by its `enter_` hook unless that is overridden, which can return
+// [VisitFlow::SkipChildren] to skip the children of the node, and an `exit_` hook called once they have been visited.
 macro_rules! visit_mut_trait {
 	( $(
 		$name: ident($obj: ty),
 	)+ ) => {
 		pub trait VisitMut<'a>: Sized + Default {
-			$(
-				fn $name(&mut self, _rule: &mut $obj) {}
-			)+
+			hdx_derive::visit_methods!(mut $($name($obj),)+);
+			crate::css::stylesheet::apply_rules!(rule_mut_methods);
+			crate::css::properties::apply_properties!(style_value_mut_methods);
 		}
 	}
 }
@@ -35,46 +229,328 @@ macro_rules! visit_trait {
 		$name: ident($obj: ty),
 	)+ ) => {
 		pub trait Visit<'a>: Sized + Default {
-			$(
-				fn $name(&mut self, _rule: &$obj) {}
-			)+
+			hdx_derive::visit_methods!($($name($obj),)+);
+			crate::css::stylesheet::apply_rules!(rule_methods);
+			crate::css::properties::apply_properties!(style_value_methods);
 		}
 	}
 }
 apply_visit_methods!(visit_trait);
 
 pub trait VisitableMut<'a>: Sized {
-	fn accept_mut<V: VisitMut<'a>>(&mut self, visitor: &mut V);
+	fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>);
+
+	fn accept_mut<V: VisitMut<'a>>(&mut self, visitor: &mut V) {
+		self.accept_mut_with(visitor, &mut VisitContext::default())
+	}
 }
 
 pub trait Visitable<'a>: Sized {
-	fn accept<V: Visit<'a>>(&self, visitor: &mut V);
+	fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>);
+
+	fn accept<V: Visit<'a>>(&self, visitor: &mut V) {
+		self.accept_with(visitor, &mut VisitContext::default())
+	}
 }
 
 impl<'a, T: VisitableMut<'a>> VisitableMut<'a> for Spanned<T> {
-	fn accept_mut<V: VisitMut<'a>>(&mut self, visitor: &mut V) {
-		self.node.accept_mut(visitor)
+	fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		let span = std::mem::replace(&mut ctx.span, self.span);
+		self.node.accept_mut_with(visitor, ctx);
+		ctx.span = span;
 	}
 }
 
 impl<'a, T: Visitable<'a>> Visitable<'a> for Spanned<T> {
-	fn accept<V: Visit<'a>>(&self, visitor: &mut V) {
-		self.node.accept(visitor)
+	fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		let span = std::mem::replace(&mut ctx.span, self.span);
+		self.node.accept_with(visitor, ctx);
+		ctx.span = span;
 	}
 }
 
-impl<'a, T: VisitableMut<'a>> VisitableMut<'a> for bumpalo::collections::Vec<'a, T> {
-	fn accept_mut<V: VisitMut<'a>>(&mut self, visitor: &mut V) {
+impl<'a, T: VisitableMut<'a>> VisitableMut<'a> for Commented<'a, T> {
+	fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		self.node.accept_mut_with(visitor, ctx)
+	}
+}
+
+impl<'a, T: Visitable<'a>> Visitable<'a> for Commented<'a, T> {
+	fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		self.node.accept_with(visitor, ctx)
+	}
+}
+
+impl<'a, T: VisitableMut<'a>> VisitableMut<'a> for Option<T> {
+	fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		if let Some(node) = self {
+			node.accept_mut_with(visitor, ctx)
+		}
+	}
+}
+
+impl<'a, T: Visitable<'a>> Visitable<'a> for Option<T> {
+	fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		if let Some(node) = self {
+			node.accept_with(visitor, ctx)
+		}
+	}
+}
+
+impl<'a, T: VisitableMut<'a>> VisitableMut<'a> for Box<'a, T> {
+	fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		self.as_mut().accept_mut_with(visitor, ctx)
+	}
+}
+
+impl<'a, T: Visitable<'a>> Visitable<'a> for Box<'a, T> {
+	fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		self.as_ref().accept_with(visitor, ctx)
+	}
+}
+
+impl<'a, T: VisitableMut<'a>> VisitableMut<'a> for std::boxed::Box<T> {
+	fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		self.as_mut().accept_mut_with(visitor, ctx)
+	}
+}
+
+impl<'a, T: Visitable<'a>> Visitable<'a> for std::boxed::Box<T> {
+	fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		self.as_ref().accept_with(visitor, ctx)
+	}
+}
+
+impl<'a, T: VisitableMut<'a>> VisitableMut<'a> for Vec<'a, T> {
+	fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
 		for node in self {
-			node.accept_mut(visitor)
+			node.accept_mut_with(visitor, ctx)
 		}
 	}
 }
 
-impl<'a, T: Visitable<'a>> Visitable<'a> for bumpalo::collections::Vec<'a, T> {
-	fn accept<V: Visit<'a>>(&self, visitor: &mut V) {
+impl<'a, T: Visitable<'a>> Visitable<'a> for Vec<'a, T> {
+	fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
 		for node in self {
-			node.accept(visitor)
+			node.accept_with(visitor, ctx)
 		}
 	}
 }
+
+impl<'a, A: Array> VisitableMut<'a> for SmallVec<A>
+where
+	A::Item: VisitableMut<'a>,
+{
+	fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		for node in self {
+			node.accept_mut_with(visitor, ctx)
+		}
+	}
+}
+
+impl<'a, A: Array> Visitable<'a> for SmallVec<A>
+where
+	A::Item: Visitable<'a>,
+{
+	fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+		for node in self {
+			node.accept_with(visitor, ctx)
+		}
+	}
+}
+
+macro_rules! impl_tuple {
+	($($name: ident: $index: tt),+) => {
+		impl<'a, $($name: VisitableMut<'a>),+> VisitableMut<'a> for ($($name,)+) {
+			fn accept_mut_with<V: VisitMut<'a>>(&mut self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+				$(self.$index.accept_mut_with(visitor, ctx);)+
+			}
+		}
+
+		impl<'a, $($name: Visitable<'a>),+> Visitable<'a> for ($($name,)+) {
+			fn accept_with<V: Visit<'a>>(&self, visitor: &mut V, ctx: &mut VisitContext<'a>) {
+				$(self.$index.accept_with(visitor, ctx);)+
+			}
+		}
+	};
+}
+
+impl_tuple!(A: 0, B: 1);
+impl_tuple!(A: 0, B: 1, C: 2);
+impl_tuple!(A: 0, B: 1, C: 2, D: 3);
+
+// Leaves of the tree, which have nothing within them to visit.
+macro_rules! impl_leaf {
+	($($ty: ty),+ $(,)?) => {
+		$(
+			impl<'a> VisitableMut<'a> for $ty {
+				#[inline]
+				fn accept_mut_with<V: VisitMut<'a>>(&mut self, _visitor: &mut V, _ctx: &mut VisitContext<'a>) {}
+			}
+
+			impl<'a> Visitable<'a> for $ty {
+				#[inline]
+				fn accept_with<V: Visit<'a>>(&self, _visitor: &mut V, _ctx: &mut VisitContext<'a>) {}
+			}
+		)+
+	};
+}
+
+impl_leaf!(
+	(),
+	bool,
+	char,
+	u8,
+	u16,
+	u32,
+	i32,
+	f32,
+	f64,
+	Atom,
+	QuoteStyle,
+	NumType,
+	PairWise,
+	Comparison,
+	Remnants,
+	CSSFloat,
+	Token<'a>,
+	Trivia<'a>,
+	&'a str
+);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bumpalo::Bump;
+	use hdx_parser::{Features, Parser};
+
+	#[derive(Default)]
+	struct Counter {
+		media: usize,
+		style_rules: usize,
+		components: usize,
+		colors: usize,
+		lengths: std::vec::Vec<Length>,
+		length_percentages: std::vec::Vec<LengthPercentage>,
+		length_percentages_or_auto: usize,
+		widths: usize,
+	}
+
+	impl<'a> Visit<'a> for Counter {
+		fn visit_media_rule(&mut self, _node: &rules::Media<'a>) {
+			self.media += 1;
+		}
+
+		fn visit_style_rule(&mut self, _rule: &StyleRule<'a>) {
+			self.style_rules += 1;
+		}
+
+		fn visit_selector_component(&mut self, _rule: &SelectorComponent<'a>) {
+			self.components += 1;
+		}
+
+		fn visit_color(&mut self, _rule: &Color) {
+			self.colors += 1;
+		}
+
+		fn visit_length(&mut self, node: &Length) {
+			self.lengths.push(*node);
+		}
+
+		fn visit_length_percentage(&mut self, node: &LengthPercentage) {
+			self.length_percentages.push(*node);
+		}
+
+		fn visit_length_percentage_or_auto(&mut self, _node: &LengthPercentageOrAuto) {
+			self.length_percentages_or_auto += 1;
+		}
+
+		fn visit_width_style_value(&mut self, _node: &values::Width) {
+			self.widths += 1;
+		}
+	}
+
+	#[derive(Default)]
+	struct Context {
+		colors: std::vec::Vec<(bool, std::vec::Vec<NodeKind>)>,
+		spans: std::vec::Vec<Span>,
+		exits: usize,
+	}
+
+	impl<'a> Visit<'a> for Context {
+		fn enter_media_rule(&mut self, node: &rules::Media<'a>, ctx: &VisitContext) -> VisitFlow {
+			// Skip the media rules within supports rules.
+			if ctx.is_within(NodeKind::Rule(RuleKind::Supports)) {
+				VisitFlow::SkipChildren
+			} else {
+				self.visit_media_rule(node);
+				VisitFlow::Continue
+			}
+		}
+
+		fn exit_media_rule(&mut self, _node: &rules::Media<'a>, _ctx: &VisitContext) {
+			self.exits += 1;
+		}
+
+		fn enter_color(&mut self, _node: &Color, ctx: &VisitContext) -> VisitFlow {
+			let kinds = ctx.ancestors().iter().map(|ancestor| ancestor.kind).collect();
+			self.spans.extend(ctx.parent().map(|parent| parent.span));
+			self.colors.push((ctx.is_within(NodeKind::Rule(RuleKind::Media)), kinds));
+			VisitFlow::Continue
+		}
+	}
+
+	#[test]
+	fn test_visit_context() {
+		let allocator = Bump::default();
+		let source_text = "@media print { a { color: red } } b { color: blue } @supports (color: red) { @media print { c { color: green } } }";
+		let result = Parser::new(&allocator, source_text, Features::default()).parse_with::<StyleSheet>();
+		let mut context = Context::default();
+		result.output.unwrap().accept(&mut context);
+		assert_eq!(context.exits, 2);
+		assert_eq!(context.colors.len(), 2);
+		assert_eq!(
+			context.colors[0],
+			(
+				true,
+				vec![
+					NodeKind::StyleSheet,
+					NodeKind::Rule(RuleKind::Media),
+					NodeKind::Rule(RuleKind::Style),
+					NodeKind::Property,
+					NodeKind::StyleValue
+				]
+			)
+		);
+		assert!(!context.colors[1].0);
+		assert_eq!(context.spans[0].source_text(source_text).trim(), "color: red");
+		assert_eq!(context.spans[1].source_text(source_text).trim(), "color: blue");
+	}
+
+	#[test]
+	fn test_visit() {
+		let allocator = Bump::default();
+		let source_text =
+			"@media screen { a.b { color: red; width: 1px; margin: 1px 2px } } c { border: 2px solid blue }";
+		let result = Parser::new(&allocator, source_text, Features::default()).parse_with::<StyleSheet>();
+		let mut counter = Counter::default();
+		result.output.unwrap().accept(&mut counter);
+		assert_eq!(counter.media, 1);
+		assert_eq!(counter.style_rules, 2);
+		assert_eq!(counter.components, 3);
+		assert_eq!(counter.colors, 2);
+		assert_eq!(counter.lengths, vec![Length::Px(2.0.into())]);
+		assert_eq!(
+			counter.length_percentages,
+			vec![
+				LengthPercentage::Px(1.0.into()),
+				LengthPercentage::Px(1.0.into()),
+				LengthPercentage::Px(1.0.into()),
+				LengthPercentage::Px(2.0.into()),
+				LengthPercentage::Px(2.0.into())
+			]
+		);
+		assert_eq!(counter.length_percentages_or_auto, 4);
+		assert_eq!(counter.widths, 1);
+	}
+}
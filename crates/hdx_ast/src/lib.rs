@@ -9,6 +9,7 @@ pub mod traits;
 pub mod test_helpers;
 
 extern crate self as hdx_ast;
+use hdx_derive::{ShiftSpans, Visitable};
 use hdx_parser::Spanned;
 pub use traits::Value;
 
@@ -22,7 +23,7 @@ impl<T: ToSpecificity> ToSpecificity for Spanned<T> {
 	}
 }
 
-#[derive(Debug, PartialEq, Hash)]
+#[derive(Visitable, ShiftSpans, Debug, PartialEq, Hash)]
 pub struct Specificity(u8, u8, u8);
 
 impl std::ops::AddAssign for Specificity {
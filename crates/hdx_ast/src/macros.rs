@@ -495,8 +495,8 @@ pub(crate) use write_simple_shorthand;
 
 macro_rules! discrete_media_feature {
 	($feat: tt[atom!($atom: tt)] { $( $name: ident: atom!($name_atom: tt),)+ }) => {
-		#[derive(PartialEq, Default, Debug, Hash)]
-		#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+		#[derive(hdx_derive::Visitable, hdx_derive::ShiftSpans, PartialEq, Default, Debug, Hash)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 		pub enum $feat {
 			#[default]
 			Any,
@@ -512,7 +512,7 @@ macro_rules! discrete_media_feature {
 
 		impl<'a> hdx_parser::DiscreteMediaFeature<'a> for $feat {
 			fn parse_media_feature_value(parser: &mut hdx_parser::Parser<'a>) -> hdx_parser::Result<Self> {
-				hdx_parser::expect_ignore_case!{ parser.next(), Token::Ident(_):
+				hdx_parser::expect_ignore_case!{ parser.next_token(), Token::Ident(_):
 					$(
 						hdx_atom::atom!($name_atom) => Ok(Self::$name),
 					)+
@@ -543,8 +543,8 @@ pub(crate) use discrete_media_feature;
 
 macro_rules! bool_media_feature {
 	($feat: tt[atom!($atom: tt)]) => {
-		#[derive(PartialEq, Default, Debug, Hash)]
-		#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+		#[derive(hdx_derive::Visitable, hdx_derive::ShiftSpans, PartialEq, Default, Debug, Hash)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 		pub enum $feat {
 			#[default]
 			Zero,
@@ -560,8 +560,8 @@ macro_rules! bool_media_feature {
 
 		impl<'a> hdx_parser::DiscreteMediaFeature<'a> for $feat {
 			fn parse_media_feature_value(parser: &mut hdx_parser::Parser<'a>) -> hdx_parser::Result<Self> {
-				match parser.next() {
-					hdx_lexer::Token::Number(val, ty) => {
+				match parser.next_token() {
+					hdx_lexer::Token::Number(val, ty, _) => {
 						if *val == 1.0 && ty.is_int() {
 							Ok(Self::One)
 						} else if *val == 0.0 && ty.is_int() {
@@ -596,8 +596,12 @@ pub(crate) use bool_media_feature;
 
 macro_rules! ranged_media_feature {
 	($feat: tt[atom!($atom: tt)], $ty: ty) => {
-		#[derive(PartialEq, Debug, Hash)]
-		#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", content = "value"))]
+		#[derive(hdx_derive::Visitable, hdx_derive::ShiftSpans, PartialEq, Debug, Hash)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde::Serialize, serde::Deserialize),
+			serde(tag = "type", content = "value")
+		)]
 		pub enum $feat {
 			Legacy((hdx_parser::Comparison, $ty)),
 			Single((hdx_parser::Comparison, $ty)),
@@ -1,16 +1,24 @@
-use hdx_atom::{atom, Atom};
-use hdx_lexer::{Include, PairWise, Token};
+use hdx_atom::{atom, Atom, ToAtom};
+use hdx_derive::{ShiftSpans, Visitable};
+use hdx_lexer::{Include, NumType, PairWise, Token};
 use hdx_parser::{
 	expect, unexpected, AtRule as AtRuleTrait, Block as BlockTrait, Parse, Parser, QualifiedRule as QualifiedRuleTrait,
-	Result as ParserResult, Span, Spanned, State, Vec,
+	Remnants, Result as ParserResult, Span, Spanned, State, Vec,
 };
-use hdx_writer::{CssWriter, Result as WriterResult, WriteCss};
+use hdx_writer::{shortest_number, CssWriter, OutputOption, Result as WriterResult, WriteCss};
 
 // https://drafts.csswg.org/css-syntax-3/#consume-list-of-components
-#[derive(PartialEq, Debug, Clone, Hash)]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize), serde())]
 pub struct ComponentValues<'a>(pub Vec<'a, Spanned<ComponentValue<'a>>>);
 
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for ComponentValues<'a> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		crate::css::arena::vec(deserializer).map(Self)
+	}
+}
+
 impl<'a> Parse<'a> for ComponentValues<'a> {
 	// https://drafts.csswg.org/css-syntax-3/#consume-list-of-components
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
@@ -26,6 +34,7 @@ impl<'a> Parse<'a> for ComponentValues<'a> {
 				// In reality it is only ever called with a comma-token or semicolon-token.
 				Token::Semicolon if parser.is(State::StopOnSemicolon) => break,
 				Token::Comma if parser.is(State::StopOnComma) => break,
+				Token::LeftCurly if parser.is(State::StopOnCurly) => break,
 				_ => values.push(ComponentValue::parse_spanned(parser)?),
 			}
 		}
@@ -43,12 +52,13 @@ impl<'a> WriteCss<'a> for ComponentValues<'a> {
 }
 
 // https://drafts.csswg.org/css-syntax-3/#consume-component-value
-#[derive(PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(untagged))]
 pub enum ComponentValue<'a> {
 	SimpleBlock(SimpleBlock<'a>),
 	Function(Function<'a>),
-	Token(Token),
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::token"))]
+	Token(Token<'a>),
 }
 
 // https://drafts.csswg.org/css-syntax-3/#consume-component-value
@@ -59,7 +69,7 @@ impl<'a> Parse<'a> for ComponentValue<'a> {
 				Ok(Self::SimpleBlock(SimpleBlock::parse_with_state(parser, State::Nested)?))
 			}
 			Token::Function(_) => Ok(Self::Function(Function::parse(parser)?)),
-			_ => Ok(Self::Token(parser.next_with(Include::Whitespace).clone())),
+			_ => Ok(Self::Token(parser.next_token_with(Include::Whitespace).clone())),
 		}
 	}
 }
@@ -92,9 +102,9 @@ impl<'a> WriteCss<'a> for ComponentValue<'a> {
 					Token::Delim(ch) => {
 						sink.write_char(*ch)?;
 					}
-					Token::Number(n, _) => sink.write_str(&format!("{}", n))?,
-					Token::Dimension(n, unit, _) => {
-						sink.write_str(&format!("{}", n))?;
+					Token::Number(n, ty, raw) => write_number(sink, *n, *ty, raw)?,
+					Token::Dimension(n, unit, ty, raw) => {
+						write_number(sink, *n, *ty, raw)?;
 						sink.write_str(unit)?;
 					}
 					Token::Whitespace => sink.write_char(' ')?,
@@ -123,17 +133,35 @@ impl<'a> WriteCss<'a> for ComponentValue<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+// Numbers are written as they were in the source text, unless minifying where the shortest form is used. That form
+// mustn't turn a float into an integer though (`1.0` into `1`), as the token's type can matter wherever it ends up.
+fn write_number<W: CssWriter>(sink: &mut W, n: f64, ty: NumType, raw: &str) -> WriterResult {
+	if sink.can_output(OutputOption::ExpandedNumbers) {
+		return sink.write_str(raw);
+	}
+	let mut shortest = shortest_number(n);
+	if ty.is_float() && !shortest.contains(['.', 'e']) {
+		shortest.push_str(".0");
+	}
+	if shortest.len() > raw.len() {
+		sink.write_str(raw)
+	} else {
+		sink.write_str(&shortest)
+	}
+}
+
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct SimpleBlock<'a> {
 	pub pairwise: PairWise,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	pub values: Vec<'a, Spanned<ComponentValue<'a>>>,
 }
 
 // https://drafts.csswg.org/css-syntax-3/#consume-a-simple-block
 impl<'a> Parse<'a> for SimpleBlock<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		if let Some(pairwise) = parser.next().to_pairwise() {
+		if let Some(pairwise) = parser.next_token().to_pairwise() {
 			let mut values = parser.new_vec();
 			let ending_token = pairwise.end();
 			loop {
@@ -143,7 +171,7 @@ impl<'a> Parse<'a> for SimpleBlock<'a> {
 					_ => values.push(ComponentValue::parse_spanned(parser)?),
 				}
 			}
-			if parser.next() != pairwise.end() {
+			if parser.next_token() != pairwise.end() {
 				unexpected!(parser)
 			}
 			Ok(Self { values, pairwise })
@@ -172,16 +200,24 @@ impl<'a> WriteCss<'a> for SimpleBlock<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub enum Rule<'a> {
 	AtRule(AtRule<'a>),
 	QualifiedRule(QualifiedRule<'a>),
+	#[cfg_attr(feature = "serde", serde(rename = "remnants"))]
+	Remnants(Remnants),
+}
+
+impl<'a> From<Remnants> for Rule<'a> {
+	fn from(remnants: Remnants) -> Self {
+		Self::Remnants(remnants)
+	}
 }
 
 impl<'a> Parse<'a> for Rule<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		Ok(match parser.next() {
+		Ok(match parser.next_token() {
 			Token::AtKeyword(_) => Rule::AtRule(AtRule::parse(parser)?),
 			_ => Rule::QualifiedRule(QualifiedRule::parse(parser)?),
 		})
@@ -193,14 +229,17 @@ impl<'a> WriteCss<'a> for Rule<'a> {
 		match self {
 			Self::AtRule(value) => value.write_css(sink),
 			Self::QualifiedRule(value) => value.write_css(sink),
+			Self::Remnants(value) => value.write_css(sink),
 		}
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct Block<'a> {
-	pub declarations: Vec<'a, Spanned<Declaration<'a>>>,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
+	pub declarations: Vec<'a, Spanned<BlockDeclaration<'a>>>,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	pub rules: Vec<'a, Spanned<Rule<'a>>>,
 }
 
@@ -212,12 +251,44 @@ impl<'a> Parse<'a> for Block<'a> {
 }
 
 impl<'a> BlockTrait<'a> for Block<'a> {
-	type Declaration = Declaration<'a>;
+	type Declaration = BlockDeclaration<'a>;
 	type Rule = Rule<'a>;
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(untagged))]
+pub enum BlockDeclaration<'a> {
+	Declaration(Declaration<'a>),
+	#[cfg_attr(feature = "serde", serde(rename = "remnants"))]
+	Remnants(Remnants),
+}
+
+impl<'a> From<Remnants> for BlockDeclaration<'a> {
+	fn from(remnants: Remnants) -> Self {
+		Self::Remnants(remnants)
+	}
+}
+
+impl<'a> Parse<'a> for BlockDeclaration<'a> {
+	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
+		Ok(Self::Declaration(Declaration::parse(parser)?))
+	}
+}
+
+impl<'a> WriteCss<'a> for BlockDeclaration<'a> {
+	fn write_css<W: CssWriter>(&self, sink: &mut W) -> WriterResult {
+		match self {
+			Self::Declaration(value) => value.write_css(sink),
+			Self::Remnants(value) => {
+				value.write_css(sink)?;
+				sink.write_char(';')
+			}
+		}
+	}
+}
+
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct Declaration<'a> {
 	pub name: Atom,
 	pub value: Spanned<ComponentValues<'a>>,
@@ -227,20 +298,20 @@ pub struct Declaration<'a> {
 // https://drafts.csswg.org/css-syntax-3/#consume-a-declaration
 impl<'a> Parse<'a> for Declaration<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next().clone() {
+		match parser.next_token().clone() {
 			Token::Ident(name) => {
-				expect!(parser.next(), Token::Colon);
+				expect!(parser.next_token(), Token::Colon);
 				let mut value =
 					ComponentValues::parse_spanned_with_state(parser, State::StopOnSemicolon | State::Nested)?;
 				let mut iter = value.node.0.iter_mut();
 				let important = matches!(
 					iter.nth_back(1),
-					Some(Spanned { node: ComponentValue::Token(Token::Ident(atom!("important"))), .. })
+					Some(Spanned { node: ComponentValue::Token(Token::Ident("important")), .. })
 				) && matches!(
 					iter.nth_back(2),
 					Some(Spanned { node: ComponentValue::Token(Token::Delim('!')), .. })
 				);
-				Ok(Self { name: name.clone(), value, important })
+				Ok(Self { name: name.to_atom(), value, important })
 			}
 			token => unexpected!(parser, token),
 		}
@@ -283,8 +354,8 @@ impl<'a> WriteCss<'a> for Block<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct AtRule<'a> {
 	pub name: Atom,
 	pub prelude: Spanned<ComponentValues<'a>>,
@@ -294,7 +365,7 @@ pub struct AtRule<'a> {
 // https://drafts.csswg.org/css-syntax-3/#consume-an-at-rule
 impl<'a> Parse<'a> for AtRule<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next().clone() {
+		match parser.next_token().clone() {
 			Token::AtKeyword(name) => {
 				let (prelude_opt, block_opt) = Self::parse_at_rule(parser)?;
 				let prelude = prelude_opt
@@ -303,7 +374,7 @@ impl<'a> Parse<'a> for AtRule<'a> {
 					node: Block { declarations: parser.new_vec(), rules: parser.new_vec() },
 					span: Span::dummy(),
 				});
-				Ok(Self { name, prelude, block })
+				Ok(Self { name: name.to_atom(), prelude, block })
 			}
 			token => unexpected!(parser, token),
 		}
@@ -313,6 +384,16 @@ impl<'a> Parse<'a> for AtRule<'a> {
 impl<'a> AtRuleTrait<'a> for AtRule<'a> {
 	type Block = Block<'a>;
 	type Prelude = ComponentValues<'a>;
+
+	fn parse_prelude(parser: &mut Parser<'a>) -> ParserResult<Option<Spanned<Self::Prelude>>> {
+		match parser.peek() {
+			Token::LeftCurly | Token::Semicolon | Token::Eof => Ok(None),
+			_ => Ok(Some(ComponentValues::parse_spanned_with_state(
+				parser,
+				State::StopOnSemicolon | State::StopOnCurly,
+			)?)),
+		}
+	}
 }
 
 impl<'a> WriteCss<'a> for AtRule<'a> {
@@ -326,8 +407,8 @@ impl<'a> WriteCss<'a> for AtRule<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct QualifiedRule<'a> {
 	pub prelude: Spanned<ComponentValues<'a>>,
 	pub block: Spanned<Block<'a>>,
@@ -344,6 +425,10 @@ impl<'a> Parse<'a> for QualifiedRule<'a> {
 impl<'a> QualifiedRuleTrait<'a> for QualifiedRule<'a> {
 	type Block = Block<'a>;
 	type Prelude = ComponentValues<'a>;
+
+	fn parse_prelude(parser: &mut Parser<'a>) -> ParserResult<Spanned<Self::Prelude>> {
+		ComponentValues::parse_spanned_with_state(parser, State::StopOnCurly)
+	}
 }
 
 impl<'a> WriteCss<'a> for QualifiedRule<'a> {
@@ -355,17 +440,18 @@ impl<'a> WriteCss<'a> for QualifiedRule<'a> {
 	}
 }
 
-#[derive(PartialEq, Debug, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[derive(Visitable, ShiftSpans, PartialEq, Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub struct Function<'a> {
 	pub name: Atom,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "crate::css::arena::vec"))]
 	pub values: Vec<'a, Spanned<ComponentValue<'a>>>,
 }
 
 // https://drafts.csswg.org/css-syntax-3/#consume-function
 impl<'a> Parse<'a> for Function<'a> {
 	fn parse(parser: &mut Parser<'a>) -> ParserResult<Self> {
-		match parser.next().clone() {
+		match parser.next_token().clone() {
 			Token::Function(name) => {
 				let mut values = parser.new_vec();
 				loop {
@@ -375,8 +461,8 @@ impl<'a> Parse<'a> for Function<'a> {
 						_ => values.push(ComponentValue::parse_spanned(parser)?),
 					}
 				}
-				expect!(parser.next(), Token::RightParen);
-				Ok(Self { name: name.clone(), values })
+				expect!(parser.next_token(), Token::RightParen);
+				Ok(Self { name: name.to_atom(), values })
 			}
 			token => unexpected!(parser, token),
 		}
@@ -425,10 +511,12 @@ mod tests {
 		assert_parse!(Block, "{\n}");
 		assert_parse!(Block, "{\n\tfoo: bar;\n}");
 		assert_parse!(Block, "{\n\tfoo: bar;\n\tbaz: bing;\n}");
+		assert_parse!(ComponentValues, "1e3 .50px +1");
 	}
 
 	#[test]
 	fn test_minify() {
 		assert_minify!(Block, "{\n\tfoo: bar;\n\tbaz:bing;\n}", "{foo:bar;baz:bing;}");
+		assert_minify!(ComponentValues, "1000 0.50px 1.0 1.50e1", "1e3 .5px 1.0 15.0");
 	}
 }
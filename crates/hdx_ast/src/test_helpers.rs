@@ -1,6 +1,6 @@
 use bumpalo::Bump;
 use hdx_parser::{Features, Parse, Parser};
-use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
+use hdx_writer::{BaseCssWriter, FormatOptions, IdentityWriter, OutputOption, WriteCss};
 
 #[cfg(test)]
 macro_rules! assert_size {
@@ -16,11 +16,12 @@ pub fn test_write_with_options<'a, T: Parse<'a> + WriteCss<'a>>(
 	source_text: &'a str,
 	expected: &'a str,
 	opts: OutputOption,
+	format: FormatOptions,
 	file: &str,
 	line: u32,
 ) {
 	let mut string = String::new();
-	let mut writer = BaseCssWriter::new(&mut string, opts);
+	let mut writer = BaseCssWriter::with_format_options(&mut string, opts, format);
 	let parser = Parser::new(allocator, source_text, Features::default());
 	let result = parser.parse_entirely_with::<T>();
 	if !result.errors.is_empty() {
@@ -41,6 +42,7 @@ macro_rules! assert_parse {
 			$str,
 			$str2,
 			hdx_writer::OutputOption::all(),
+			hdx_writer::FormatOptions::default(),
 			file!(),
 			line!(),
 		);
@@ -52,6 +54,7 @@ macro_rules! assert_parse {
 			$str,
 			$str,
 			hdx_writer::OutputOption::all(),
+			hdx_writer::FormatOptions::default(),
 			file!(),
 			line!(),
 		);
@@ -69,6 +72,7 @@ macro_rules! assert_minify {
 			$str,
 			$str2,
 			hdx_writer::OutputOption::none(),
+			hdx_writer::FormatOptions::default(),
 			file!(),
 			line!(),
 		);
@@ -77,6 +81,58 @@ macro_rules! assert_minify {
 #[cfg(test)]
 pub(crate) use assert_minify;
 
+#[cfg(test)]
+macro_rules! assert_format {
+	($ty: ty, $format: expr, $str: literal, $str2: literal) => {
+		let allocator = bumpalo::Bump::default();
+		$crate::test_helpers::test_write_with_options::<$ty>(
+			&allocator,
+			$str,
+			$str2,
+			hdx_writer::OutputOption::all(),
+			$format,
+			file!(),
+			line!(),
+		);
+	};
+}
+#[cfg(test)]
+pub(crate) use assert_format;
+
+#[cfg(test)]
+pub fn test_identity<'a, T: Parse<'a> + WriteCss<'a>>(
+	allocator: &'a Bump,
+	source_text: &'a str,
+	file: &str,
+	line: u32,
+) {
+	let parser = Parser::new(allocator, source_text, Features::Lossless);
+	let result = parser.parse_entirely_with::<T>();
+	if !result.errors.is_empty() {
+		panic!("\n\nParse on {}:{} failed. ({:?}) saw error {:?}", file, line, source_text, result.errors[0]);
+	}
+	let fingerprints = result.fingerprints.unwrap();
+	let mut writer = IdentityWriter::new(String::new(), source_text, &fingerprints);
+	result.output.unwrap().write_css(&mut writer).unwrap();
+	let string = writer.finish().unwrap();
+	if source_text != string {
+		panic!(
+			"\n\nParse on {}:{} failed: did not reproduce the input:\n\n   parser input: {:?}\n  parser output: {:?}\n",
+			file, line, source_text, string
+		);
+	}
+}
+
+#[cfg(test)]
+macro_rules! assert_identity {
+	($ty: ty, $str: literal) => {
+		let allocator = bumpalo::Bump::default();
+		$crate::test_helpers::test_identity::<$ty>(&allocator, $str, file!(), line!());
+	};
+}
+#[cfg(test)]
+pub(crate) use assert_identity;
+
 #[cfg(test)]
 pub fn test_error<'a, T: Parse<'a> + WriteCss<'a>>(allocator: &'a Bump, source_text: &'a str, file: &str, line: u32) {
 	let parser = Parser::new(allocator, source_text, Features::default());
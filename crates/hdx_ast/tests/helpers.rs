@@ -19,3 +19,153 @@ macro_rules! assert_snap_ast {
 	}
 }
 
+#[macro_export]
+macro_rules! assert_lossless {
+	($source_path: literal) => {{
+		use bumpalo::Bump;
+		use hdx_ast::css::StyleSheet;
+		use hdx_parser::{Features, Parser};
+		use hdx_writer::{IdentityWriter, WriteCss};
+		use std::fs::read_to_string;
+
+		let allocator = Bump::default();
+		let source_text = read_to_string($source_path).unwrap();
+		let parser = Parser::new(&allocator, &source_text, Features::Lossless);
+		let result = parser.parse_entirely_with::<StyleSheet>();
+		if !result.errors.is_empty() {
+			panic!("\n\nParse {:?} failed. Saw error {:?}", $source_path, result.errors[0]);
+		}
+		let fingerprints = result.fingerprints.unwrap();
+		let mut writer = IdentityWriter::new(String::new(), &source_text, &fingerprints);
+		result.output.unwrap().write_css(&mut writer).unwrap();
+		let output = writer.finish().unwrap();
+		if output != source_text {
+			let at = output.bytes().zip(source_text.bytes()).take_while(|(a, b)| a == b).count();
+			panic!(
+				"\n\nWriting {:?} losslessly differed from the source at byte {}: {:?}",
+				$source_path,
+				at,
+				&output[at..]
+			);
+		}
+	}};
+}
+
+#[macro_export]
+macro_rules! assert_reparse {
+	($source_path: literal) => {{
+		use bumpalo::Bump;
+		use hdx_ast::css::StyleSheet;
+		use hdx_parser::{Diagnostic, Features, Parser, ParserReturn, Severity, Span, Spanned, TextEdit};
+		use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
+		use std::fs::read_to_string;
+
+		// What is written is compared too, which catches anything the nodes' PartialEq skips.
+		let write = |stylesheet: &Spanned<StyleSheet>| {
+			let mut string = String::new();
+			stylesheet.write_css(&mut BaseCssWriter::new(&mut string, OutputOption::all())).unwrap();
+			string
+		};
+		let diagnostics = |result: ParserReturn<Spanned<StyleSheet>>| {
+			let errors = result.errors.into_iter().map(|error| Diagnostic::new(error, Severity::Error));
+			let warnings = result.warnings.into_iter().map(|warning| Diagnostic::new(warning, Severity::Warning));
+			errors.chain(warnings).map(|diagnostic| (diagnostic.code, diagnostic.span())).collect::<Vec<_>>()
+		};
+		let source_text = read_to_string($source_path).unwrap();
+		let len = source_text.len();
+		let floor = |mut pos: usize| {
+			while !source_text.is_char_boundary(pos) {
+				pos -= 1;
+			}
+			pos as u32
+		};
+		for at in [0, len / 3, len * 2 / 3, len].map(floor) {
+			let until = floor((at as usize + 5).min(len));
+			let edits = [
+				TextEdit::new(Span::new(at, at), " "),
+				TextEdit::new(Span::new(at, at), "a{}"),
+				TextEdit::new(Span::new(at, at), "/* c */"),
+				TextEdit::new(Span::new(at, until), ""),
+			];
+			for edit in edits {
+				let allocator = Bump::default();
+				let previous =
+					Parser::new(&allocator, &source_text, Features::default()).parse_entirely_with::<StyleSheet>();
+				let edited = allocator.alloc_str(&edit.apply(&source_text));
+				let mut reparsed =
+					Parser::new(&allocator, edited, Features::default()).reparse_with(previous, &source_text, &edit);
+				let mut parsed =
+					Parser::new(&allocator, edited, Features::default()).parse_entirely_with::<StyleSheet>();
+				let (reparsed, reparsed_diagnostics) = (reparsed.output.take().unwrap(), diagnostics(reparsed));
+				let (parsed, parsed_diagnostics) = (parsed.output.take().unwrap(), diagnostics(parsed));
+				assert_eq!(
+					reparsed_diagnostics, parsed_diagnostics,
+					"\n\nReparsing {:?} after {:?} reported different diagnostics from parsing it again",
+					$source_path, edit
+				);
+				assert_eq!(write(&reparsed), write(&parsed));
+				if reparsed != parsed {
+					let at =
+						reparsed.node.rules.iter().zip(parsed.node.rules.iter()).take_while(|(a, b)| a == b).count();
+					panic!(
+						"\n\nReparsing {:?} after {:?} differed from parsing it again, from rule {} at {:?}",
+						$source_path,
+						edit,
+						at,
+						parsed.node.rules.get(at).map(|rule| rule.span)
+					);
+				}
+			}
+		}
+	}};
+}
+
+#[macro_export]
+macro_rules! assert_round_trip {
+	($source_path: literal) => {
+		assert_round_trip!($source_path, &std::fs::read_to_string($source_path).unwrap())
+	};
+	($source_path: expr, $source_text: expr) => {{
+		use bumpalo::Bump;
+		use hdx_ast::css::StyleSheet;
+		use hdx_parser::{ArenaSeed, Features, Parser, Spanned};
+		use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
+		use serde::de::DeserializeSeed;
+
+		let write = |stylesheet: &Spanned<StyleSheet>, opts: OutputOption| {
+			let mut string = String::new();
+			stylesheet.write_css(&mut BaseCssWriter::new(&mut string, opts)).unwrap();
+			string
+		};
+		let allocator = Bump::default();
+		let source_text: &str = $source_text;
+		let parsed =
+			Parser::new(&allocator, source_text, Features::default()).parse_with::<StyleSheet>().output.unwrap();
+		let json = serde_json::to_value(&parsed).unwrap();
+		let allocator = Bump::default();
+		// SAFETY: serde_json only deserializes the style sheet.
+		let seed = unsafe { ArenaSeed::<Spanned<StyleSheet>>::new(&allocator) };
+		let deserialized = match seed.deserialize(&json) {
+			Ok(stylesheet) => stylesheet,
+			Err(error) => panic!("\n\nDeserializing the AST of {:?} failed: {}", $source_path, error),
+		};
+		let at = serde_json::to_value(&deserialized.node.rules)
+			.unwrap()
+			.as_array()
+			.unwrap()
+			.iter()
+			.zip(json["node"]["rules"].as_array().unwrap())
+			.take_while(|(a, b)| a == b)
+			.count();
+		if at != parsed.node.rules.len() || serde_json::to_value(&deserialized).unwrap() != json {
+			panic!(
+				"\n\nDeserializing the AST of {:?} differed from rule {} at {:?}",
+				$source_path,
+				at,
+				parsed.node.rules.get(at).map(|rule| rule.span)
+			);
+		}
+		assert_eq!(write(&deserialized, OutputOption::none()), write(&parsed, OutputOption::none()));
+		assert_eq!(write(&deserialized, OutputOption::all()), write(&parsed, OutputOption::all()));
+	}};
+}
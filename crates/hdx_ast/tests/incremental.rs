@@ -0,0 +1,77 @@
+mod helpers;
+
+#[test]
+fn reparse_960() {
+	assert_reparse!("../../tasks/coverage/popular/960.css");
+}
+
+#[test]
+fn reparse_animate() {
+	assert_reparse!("../../tasks/coverage/popular/animate.4.1.1.css");
+}
+
+#[test]
+fn reparse_blueprint() {
+	assert_reparse!("../../tasks/coverage/popular/blueprint.1.0.1.css");
+}
+
+#[test]
+fn reparse_bootstrap() {
+	assert_reparse!("../../tasks/coverage/popular/bootstrap.5.3.0.css");
+}
+
+#[test]
+fn reparse_foundation() {
+	assert_reparse!("../../tasks/coverage/popular/foundation.6.7.5.css");
+}
+
+#[test]
+fn reparse_inuitcss() {
+	assert_reparse!("../../tasks/coverage/popular/inuitcss.6.0.0.css");
+}
+
+#[test]
+fn reparse_mini() {
+	assert_reparse!("../../tasks/coverage/popular/mini.css.3.0.1.css");
+}
+
+#[test]
+fn reparse_open_props() {
+	assert_reparse!("../../tasks/coverage/popular/open-props.1.5.10.min.css");
+}
+
+#[test]
+fn reparse_primer() {
+	assert_reparse!("../../tasks/coverage/popular/primer.20.8.3.css");
+}
+
+#[test]
+fn reparse_pure() {
+	assert_reparse!("../../tasks/coverage/popular/pure.2.0.3.css");
+}
+
+#[test]
+fn reparse_reset() {
+	assert_reparse!("../../tasks/coverage/popular/reset.2.0.css");
+}
+
+#[test]
+fn reparse_diagnostics() {
+	use bumpalo::Bump;
+	use hdx_ast::css::StyleSheet;
+	use hdx_parser::{Features, Parser, Span, TextEdit};
+
+	let allocator = Bump::default();
+	let source_text = "a { colr: red }\nb { widht: 1px }\nc { heigth: 2px }\n";
+	let previous = Parser::new(&allocator, source_text, Features::default()).parse_entirely_with::<StyleSheet>();
+	assert_eq!(previous.warnings.len(), 3);
+	// Fixing the typo in `b` drops its warning, and moves the one for `c` back a byte.
+	let edit = TextEdit::new(Span::new(20, 25), "width");
+	let edited = allocator.alloc_str(&edit.apply(source_text));
+	let reparsed = Parser::new(&allocator, edited, Features::default()).reparse_with(previous, source_text, &edit);
+	let spans: Vec<_> = reparsed.warnings.iter().map(|warning| warning.labels().unwrap().next().unwrap()).collect();
+	assert_eq!(
+		spans.iter().map(|span| &edited[span.offset()..span.offset() + span.len()]).collect::<Vec<_>>(),
+		["colr", "heigth"]
+	);
+}
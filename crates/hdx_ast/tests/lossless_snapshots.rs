@@ -0,0 +1,56 @@
+mod helpers;
+
+#[test]
+fn lossless_960() {
+	assert_lossless!("../../tasks/coverage/popular/960.css");
+}
+
+#[test]
+fn lossless_animate() {
+	assert_lossless!("../../tasks/coverage/popular/animate.4.1.1.css");
+}
+
+#[test]
+fn lossless_blueprint() {
+	assert_lossless!("../../tasks/coverage/popular/blueprint.1.0.1.css");
+}
+
+#[test]
+fn lossless_bootstrap() {
+	assert_lossless!("../../tasks/coverage/popular/bootstrap.5.3.0.css");
+}
+
+#[test]
+fn lossless_foundation() {
+	assert_lossless!("../../tasks/coverage/popular/foundation.6.7.5.css");
+}
+
+#[test]
+fn lossless_inuitcss() {
+	assert_lossless!("../../tasks/coverage/popular/inuitcss.6.0.0.css");
+}
+
+#[test]
+fn lossless_mini() {
+	assert_lossless!("../../tasks/coverage/popular/mini.css.3.0.1.css");
+}
+
+#[test]
+fn lossless_open_props() {
+	assert_lossless!("../../tasks/coverage/popular/open-props.1.5.10.min.css");
+}
+
+#[test]
+fn lossless_primer() {
+	assert_lossless!("../../tasks/coverage/popular/primer.20.8.3.css");
+}
+
+#[test]
+fn lossless_pure() {
+	assert_lossless!("../../tasks/coverage/popular/pure.2.0.3.css");
+}
+
+#[test]
+fn lossless_reset() {
+	assert_lossless!("../../tasks/coverage/popular/reset.2.0.css");
+}
@@ -0,0 +1,81 @@
+mod helpers;
+
+#[test]
+fn round_trip_numbers() {
+	assert_round_trip!("numbers", "a{--x: 1e3 0.10; foo: 1.50px; line-height: 1e3; width: .50px; opacity: 50.0%}");
+}
+
+#[test]
+fn round_trip_media() {
+	assert_round_trip!("../../tasks/coverage/basic/media.css");
+}
+
+#[test]
+fn round_trip_nth() {
+	assert_round_trip!("../../tasks/coverage/basic/nth.css");
+}
+
+#[test]
+fn round_trip_rule() {
+	assert_round_trip!("../../tasks/coverage/basic/rule.css");
+}
+
+#[test]
+fn round_trip_vars() {
+	assert_round_trip!("../../tasks/coverage/basic/vars.css");
+}
+
+#[test]
+fn round_trip_960() {
+	assert_round_trip!("../../tasks/coverage/popular/960.css");
+}
+
+#[test]
+fn round_trip_animate() {
+	assert_round_trip!("../../tasks/coverage/popular/animate.4.1.1.css");
+}
+
+#[test]
+fn round_trip_blueprint() {
+	assert_round_trip!("../../tasks/coverage/popular/blueprint.1.0.1.css");
+}
+
+#[test]
+fn round_trip_bootstrap() {
+	assert_round_trip!("../../tasks/coverage/popular/bootstrap.5.3.0.css");
+}
+
+#[test]
+fn round_trip_foundation() {
+	assert_round_trip!("../../tasks/coverage/popular/foundation.6.7.5.css");
+}
+
+#[test]
+fn round_trip_inuitcss() {
+	assert_round_trip!("../../tasks/coverage/popular/inuitcss.6.0.0.css");
+}
+
+#[test]
+fn round_trip_mini() {
+	assert_round_trip!("../../tasks/coverage/popular/mini.css.3.0.1.css");
+}
+
+#[test]
+fn round_trip_open_props() {
+	assert_round_trip!("../../tasks/coverage/popular/open-props.1.5.10.min.css");
+}
+
+#[test]
+fn round_trip_primer() {
+	assert_round_trip!("../../tasks/coverage/popular/primer.20.8.3.css");
+}
+
+#[test]
+fn round_trip_pure() {
+	assert_round_trip!("../../tasks/coverage/popular/pure.2.0.3.css");
+}
+
+#[test]
+fn round_trip_reset() {
+	assert_round_trip!("../../tasks/coverage/popular/reset.2.0.css");
+}
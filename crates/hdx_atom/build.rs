@@ -7,7 +7,7 @@ use grep_searcher::{sinks::UTF8, Searcher};
 
 fn main() {
 	let matcher = RegexMatcher::new_line_matcher(
-		"(?:atom!\\(\"|atomizable\\(\"|atom = \"|suffix = \"|prefix = \"|rename = \")([^\"]+)",
+		"(?:atom!\\(\"|atomizable\\(\"|atom = \"|suffix = \"|prefix = \"|rename = \"|as_function = \")([^\"]+)",
 	)
 	.unwrap();
 	let mut matches = vec![];
@@ -5,6 +5,36 @@ include!(concat!(env!("OUT_DIR"), "/hdx_atom.rs"));
 pub trait Atomizable: Sized {
 	fn from_atom(atom: &Atom) -> Option<Self>;
 	fn to_atom(&self) -> Atom;
+
+	/// The text of every atom which [from_atom](Atomizable::from_atom) knows of, such as to suggest one in place of a
+	/// misspelling.
+	fn atoms() -> &'static [&'static str] {
+		&[]
+	}
+}
+
+/// Makes [Atom]s from borrowed text, such as the text of a token.
+pub trait ToAtom {
+	fn to_atom(&self) -> Atom;
+
+	/// Lowercases ASCII letters, only making a new string to intern when there are uppercase letters.
+	fn to_ascii_lowercase_atom(&self) -> Atom;
+}
+
+impl ToAtom for str {
+	#[inline]
+	fn to_atom(&self) -> Atom {
+		Atom::from(self)
+	}
+
+	#[inline]
+	fn to_ascii_lowercase_atom(&self) -> Atom {
+		if self.bytes().any(|b| b.is_ascii_uppercase()) {
+			Atom::from(self.to_ascii_lowercase())
+		} else {
+			Atom::from(self)
+		}
+	}
 }
 
 #[cfg(test)]
@@ -15,5 +45,6 @@ mod tests {
 	fn smoke_test() {
 		assert_eq!(atom!("color"), Atom::from("color"));
 		assert_eq!(::std::mem::size_of::<Atom>(), 8);
+		assert_eq!("Color".to_ascii_lowercase_atom(), atom!("color"));
 	}
 }
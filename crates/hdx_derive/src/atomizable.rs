@@ -49,11 +49,13 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 		Data::Enum(DataEnum { variants, .. }) => {
 			let mut match_atom_to_enum_variant = Vec::new();
 			let mut match_enum_variant_to_atom = Vec::new();
+			let mut atoms = Vec::new();
 			for var in variants {
 				let var_ident = var.ident;
 				let var_args = AtomizableArgs::parse(&var.attrs);
 				let ident = if let Some(name) = var_args.atom { name } else { kebab(format!("{}", var_ident)) };
 				let str = LitStr::new(&ident, var_ident.span());
+				atoms.push(str.clone());
 				match_atom_to_enum_variant.push(quote! {
 					hdx_atom::atom!(#str) => Some(Self::#var_ident),
 				});
@@ -83,6 +85,9 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 					fn to_atom(&self) -> hdx_atom::Atom {
 						#to_atom_match
 					}
+					fn atoms() -> &'static [&'static str] {
+						&[#(#atoms),*]
+					}
 				}
 			}
 		}
@@ -104,6 +109,9 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 					fn to_atom(&self) -> hdx_atom::Atom {
 						hdx_atom::atom!(#str)
 					}
+					fn atoms() -> &'static [&'static str] {
+						&[#str]
+					}
 				}
 			}
 		}
@@ -4,6 +4,7 @@ mod string_transform;
 
 mod atomizable;
 mod parsable;
+mod shift_spans;
 mod value;
 mod writable;
 mod visitable;
@@ -36,12 +37,27 @@ pub fn derive_writable(stream: TokenStream) -> TokenStream {
 	writable::derive(input).into()
 }
 
+#[proc_macro_derive(ShiftSpans)]
+pub fn derive_shift_spans(stream: TokenStream) -> TokenStream {
+	let input = syn::parse(stream).unwrap();
+	shift_spans::derive(input).into()
+}
+
 #[proc_macro_derive(Visitable, attributes(visitable))]
 pub fn derive_visitable(stream: TokenStream) -> TokenStream {
 	let input = syn::parse(stream).unwrap();
 	visitable::derive(input).into()
 }
 
+/// Declares the `visit_`, `enter_` and `exit_` hooks of each node which derives [Visitable] with `#[visitable(call)]`,
+/// named as the derive names them, e.g. `enter_media_rule` for `Rule::Media`. Takes `[mut] [Enum;] Name(Type), ...`,
+/// `mut` being for the hooks of `VisitMut`, and `Enum` for the variants of an enum.
+#[proc_macro]
+pub fn visit_methods(stream: TokenStream) -> TokenStream {
+	let input = syn::parse(stream).unwrap();
+	visitable::visit_methods(input).into()
+}
+
 fn err(span: Span, msg: &str) -> proc_macro2::TokenStream {
 	let err = Error::new(span, msg).into_compile_error();
 	quote::quote! {#err}
@@ -237,20 +237,20 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 								let field = unnamed[0].clone().ty;
 								if args.parse_inner {
 									dimension_matcher = Some(quote! {
-										hdx_lexer::Token::Dimension(val, _, ty) => {
+										hdx_lexer::Token::Dimension(val, _, ty, _) => {
 											#(#checks)*
 											Ok(Self::#var_ident(#field::parse(parser)?))
 										},
 									});
 									number_matcher = match args.kind {
 										Kind::DimensionOrZero => Some(quote! {
-											hdx_lexer::Token::Number(val, ty) if val == 0.0 => {
+											hdx_lexer::Token::Number(val, ty, _) if val == 0.0 => {
 												#(#checks)*
 												Ok(Self::#var_ident(#field::parse(parser)?))
 											}
 										}),
 										Kind::DimensionOrNumber => Some(quote! {
-											hdx_lexer::Token::Number(val, ty) {
+											hdx_lexer::Token::Number(val, ty, _) {
 												#(#checks)*
 												Ok(Self::#var_ident(#field::parse(parser)?))
 											}
@@ -320,14 +320,14 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 									.collect();
 								if args.parse_inner {
 									quote! {
-										hdx_lexer::Token::Number(val, ty) => {
+										hdx_lexer::Token::Number(val, ty, _) => {
 											#(#checks)*
 											Ok(Self::#var_ident(#field::parse(parser)?))
 										},
 									}
 								} else {
 									quote! {
-										hdx_lexer::Token::Number(val, ty) => {
+										hdx_lexer::Token::Number(val, ty, _) => {
 											parser.advance();
 											#(#checks)*
 											Ok(Self::#var_ident(val.into()))
@@ -385,7 +385,7 @@ pub fn derive(input: DeriveInput) -> TokenStream {
                                 quote! {
                                     hdx_atom::atom!(#str) => {
                                         let val = #field::parse(parser)?;
-                                        hdx_parser::expect!(parser.next(), hdx_lexer::Token::RightParen);
+                                        hdx_parser::expect!(parser.next_token(), hdx_lexer::Token::RightParen);
                                         Ok(Self::#var_ident(val))
                                     }
                                 }
@@ -431,9 +431,9 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 				quote! {
 					hdx_lexer::Token::Ident(atom) => {
 						parser.advance();
-						match atom.to_ascii_lowercase() {
+						match hdx_atom::ToAtom::to_ascii_lowercase_atom(atom) {
 							#(#ident_matchers)*
-							_ => Err(hdx_parser::diagnostics::UnexpectedIdent(atom, parser.span()))?
+							_ => Err(hdx_parser::diagnostics::UnexpectedIdent(hdx_atom::ToAtom::to_atom(atom), parser.span()))?
 						}
 					}
 				}
@@ -444,9 +444,9 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 				quote! {
 					hdx_lexer::Token::Function(atom) => {
 						parser.advance();
-						match atom.to_ascii_lowercase() {
+						match hdx_atom::ToAtom::to_ascii_lowercase_atom(atom) {
 							#(#function_matchers)*
-							_ => Err(hdx_parser::diagnostics::UnexpectedFunction(atom, parser.span()))?
+							_ => Err(hdx_parser::diagnostics::UnexpectedFunction(hdx_atom::ToAtom::to_atom(atom), parser.span()))?
 						}
 					}
 				}
@@ -455,9 +455,9 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 				quote! {}
 			} else {
 				quote! {
-					hdx_lexer::Token::AtKeyword(atom) => match atom.to_ascii_lowercase() {
+					hdx_lexer::Token::AtKeyword(atom) => match hdx_atom::ToAtom::to_ascii_lowercase_atom(atom) {
 						#(#at_matchers)*
-						_ => Err(hdx_parser::diagnostics::UnexpectedAtRule(atom, parser.span()))?
+						_ => Err(hdx_parser::diagnostics::UnexpectedAtRule(hdx_atom::ToAtom::to_atom(atom), parser.span()))?
 					}
 				}
 			};
@@ -467,9 +467,9 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 				quote
 			} else {
 				quote! {
-					hdx_lexer::Token::Dimension(val, unit, ty) => match unit.to_ascii_lowercase() {
+					hdx_lexer::Token::Dimension(val, unit, ty, _) => match hdx_atom::ToAtom::to_ascii_lowercase_atom(unit) {
 						#(#dimension_matchers)*
-						_ => Err(hdx_parser::diagnostics::UnexpectedDimension(unit, parser.span()))?
+						_ => Err(hdx_parser::diagnostics::UnexpectedDimension(hdx_atom::ToAtom::to_atom(unit), parser.span()))?
 					}
 				}
 			};
@@ -0,0 +1,64 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DataEnum, DataStruct, DeriveInput, Fields, GenericParam};
+
+use crate::err;
+
+// Destructures `fields`, returning the pattern along with a call to shift each of the bindings.
+fn shift_fields(fields: &Fields) -> (TokenStream, Vec<TokenStream>) {
+	match fields {
+		Fields::Named(fields) => {
+			let idents: Vec<_> = fields.named.iter().map(|f| f.ident.clone().unwrap()).collect();
+			let shifts = idents.iter().map(|i| quote! { #i.shift_spans(delta); }).collect();
+			(quote! { { #(#idents),* } }, shifts)
+		}
+		Fields::Unnamed(fields) => {
+			let idents: Vec<_> = (0..fields.unnamed.len()).map(|i| format_ident!("f{}", i)).collect();
+			let shifts = idents.iter().map(|i| quote! { #i.shift_spans(delta); }).collect();
+			(quote! { ( #(#idents),* ) }, shifts)
+		}
+		Fields::Unit => (quote! {}, vec![]),
+	}
+}
+
+pub fn derive(input: DeriveInput) -> TokenStream {
+	let ident = input.ident;
+	let mut gen = input.generics;
+	for param in gen.params.iter_mut() {
+		if let GenericParam::Type(param) = param {
+			param.bounds.push(parse_quote!(hdx_parser::ShiftSpans));
+		}
+	}
+	let (impl_gen, type_gen, where_clause) = gen.split_for_impl();
+	let (body, shifts) = match input.data {
+		Data::Enum(DataEnum { variants, .. }) => {
+			let mut arms = vec![];
+			let mut shifts = false;
+			for variant in variants {
+				let variant_ident = variant.ident;
+				let (pattern, field_shifts) = shift_fields(&variant.fields);
+				shifts |= !field_shifts.is_empty();
+				arms.push(quote! { Self::#variant_ident #pattern => { #(#field_shifts)* } });
+			}
+			(quote! { match self { #(#arms)* } }, shifts)
+		}
+		Data::Struct(DataStruct { fields, .. }) => {
+			let (pattern, field_shifts) = shift_fields(&fields);
+			let shifts = !field_shifts.is_empty();
+			(quote! { let Self #pattern = self; #(#field_shifts)* }, shifts)
+		}
+		Data::Union(_) => return err(ident.span(), "Cannot derive ShiftSpans on a Union"),
+	};
+	// Nodes without any fields, such as keywords, have no spans to shift.
+	let body = if shifts {
+		quote! { fn shift_spans(&mut self, delta: i32) { #body } }
+	} else {
+		quote! { fn shift_spans(&mut self, _delta: i32) {} }
+	};
+	quote! {
+		#[automatically_derived]
+		impl #impl_gen hdx_parser::ShiftSpans for #ident #type_gen #where_clause {
+			#body
+		}
+	}
+}
@@ -1,8 +1,8 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
-	parse::Parse, punctuated::Punctuated, Attribute, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, Ident,
-	Index, LitStr, Meta, Token,
+	parse::Parse, parse_quote, punctuated::Punctuated, Attribute, Data, DataEnum, DataStruct, DeriveInput, Error,
+	Fields, GenericParam, Generics, Ident, Meta, Token, Type,
 };
 
 use crate::{err, snake};
@@ -60,164 +60,253 @@ impl VisitableArgs {
 		}
 		ret
 	}
-}
 
-pub fn derive(input: DeriveInput) -> TokenStream {
-	let ident = input.ident;
-	let gen = input.generics;
-	let input_args = VisitableArgs::parse(&input.attrs);
-	let call = match input_args.call {
-		AutoOrNamed::None => None,
-		AutoOrNamed::Named(s) => Some(quote! { v.#s(self); }),
-		AutoOrNamed::Auto => {
-			let s = Ident::new(&snake(format!("visit{}", ident)), ident.span());
-			Some(quote! { v.#s(self); })
+	// The name the hooks are given after their `visit_`, `enter_` and `exit_` prefixes, `auto` being the name they
+	// get when the node isn't given one.
+	fn hook(&self, auto: impl FnOnce() -> String) -> Option<String> {
+		match &self.call {
+			AutoOrNamed::None => None,
+			AutoOrNamed::Named(s) => Some(s.clone()),
+			AutoOrNamed::Auto => Some(auto()),
 		}
+	}
+}
+
+// The name of the hooks for a variant of an enum, e.g. `media_rule` for `Rule::Media`.
+fn variant_hook(variant: &Ident, ident: &Ident) -> String {
+	format!("{}_{}", snake(variant.to_string()), snake(ident.to_string()))
+}
+
+// Calls the `enter_` hook of `node`, then visits its `children` with the `kind` of node pushed onto the context,
+// unless the hook skips them, then calls the `exit_` hook.
+fn hooked(hook: &str, node: TokenStream, kind: Option<TokenStream>, children: TokenStream) -> TokenStream {
+	let enter = format_ident!("enter_{}", hook);
+	let exit = format_ident!("exit_{}", hook);
+	let children = match kind {
+		Some(kind) => quote! { ctx.push(#kind); #children ctx.pop(); },
+		None => children,
 	};
-	if input_args.skip {
-		return err(ident.span(), "#[visitable(skip)] is not valid on the input");
+	quote! {
+		if v.#enter(#node, ctx) == hdx_ast::css::visit::VisitFlow::Continue {
+			#children
+		}
+		v.#exit(#node, ctx);
 	}
-	match input.data {
-		Data::Enum(DataEnum { variants, .. }) => {
-			let mut field_accepts = vec![];
-			let mut field_accept_muts = vec![];
-			let mut accepts = call.is_some();
-			for field in variants {
-				let field_ident = field.ident;
-				let args = VisitableArgs::parse(&field.attrs);
-				let call = match args.call {
-					AutoOrNamed::None => None,
-					AutoOrNamed::Named(s) => {
-						accepts = true;
-						Some(quote! { Self::#field_ident(f) => v.#s(f), })
-					}
-					AutoOrNamed::Auto => {
-						accepts = true;
-						let s = Ident::new(&snake(format!("visit{}", ident)), ident.span());
-						Some(quote! { Self::#field_ident(f) => v.#s(f), })
-					}
-				};
-				field_accepts.push(if args.skip {
-					quote! { Self::#field_ident(f) => {}, }
-				} else {
-					accepts = true;
-					quote! { Self::#field_ident(f) => f.accept(v), }
-				});
-				field_accept_muts.push(if args.skip {
-					quote! { Self::#field_ident(f) => {}, }
+}
+
+// Destructures `fields`, returning the pattern along with the fields which are to be visited.
+fn visit_fields(fields: &Fields) -> (TokenStream, Vec<Ident>) {
+	match fields {
+		Fields::Named(fields) => {
+			let idents: Vec<_> = fields
+				.named
+				.iter()
+				.filter(|f| !VisitableArgs::parse(&f.attrs).skip)
+				.map(|f| f.ident.clone().unwrap())
+				.collect();
+			(quote! { { #(#idents,)* .. } }, idents)
+		}
+		Fields::Unnamed(fields) => {
+			let mut pattern = vec![];
+			let mut idents = vec![];
+			for (i, field) in fields.unnamed.iter().enumerate() {
+				if VisitableArgs::parse(&field.attrs).skip {
+					pattern.push(quote! { _ });
 				} else {
-					accepts = true;
-					quote! { Self::#field_ident(f) => f.accept_mut(v), }
-				});
-			}
-			if !accepts {
-				return err(ident.span(), "Visitable does not accept any variants");
-			}
-			quote! {
-				#[automatically_derived]
-				impl<'a> hdx_ast::css::visit::Visitable<'a> for #ident #gen {
-					fn accept<V: hdx_ast::css::visit::Visit<'a>>(&self, v: &mut V) {
-						#call
-						match self {
-							#(#field_accepts)*
-						}
-					}
-				}
-				#[automatically_derived]
-				impl<'a> hdx_ast::css::visit::VisitableMut<'a> for #ident #gen {
-					fn accept_mut<V: hdx_ast::css::visit::VisitMut<'a>>(&mut self, v: &mut V) {
-						#call
-						match self {
-							#(#field_accept_muts)*
-						}
-					}
+					let ident = format_ident!("f{}", i);
+					pattern.push(quote! { #ident });
+					idents.push(ident);
 				}
 			}
+			(quote! { ( #(#pattern),* ) }, idents)
 		}
-		Data::Struct(DataStruct { fields: Fields::Named(fields), .. }) => {
-			let mut field_accepts = vec![];
-			let mut field_accept_muts = vec![];
-			for field in fields.named {
-				let args = VisitableArgs::parse(&field.attrs);
-				if args.call != AutoOrNamed::None {
-					return err(ident.span(), "#[visitable(call = ...)] is not valid a struct field");
-				}
-				let field_ident = field.ident;
-				if !args.skip {
-					field_accepts.push(quote! {
-						self.#field_ident.accept(v);
-					});
-					field_accept_muts.push(quote! {
-						self.#field_ident.accept_mut(v);
-					});
-				}
-			}
-			if call.is_none() && field_accepts.is_empty() {
-				return err(
-					ident.span(),
-					"This visitable does nothing, it doesn't call and it doesn't accept any fields",
-				);
-			}
-			quote! {
-				#[automatically_derived]
-				impl<'a> hdx_ast::css::visit::Visitable<'a> for #ident #gen {
-					fn accept<V: hdx_ast::css::visit::Visit<'a>>(&self, v: &mut V) {
-						#call
-						#(#field_accepts)*
+		Fields::Unit => (quote! {}, vec![]),
+	}
+}
+
+// Adds the lifetime of the visitor, and bounds any type parameters by `bound`.
+fn generics(gen: &Generics, bound: TokenStream) -> Generics {
+	let mut gen = gen.clone();
+	if gen.lifetimes().next().is_none() {
+		gen.params.insert(0, parse_quote!('a));
+	}
+	for param in gen.params.iter_mut() {
+		if let GenericParam::Type(param) = param {
+			param.bounds.push(parse_quote!(#bound));
+		}
+	}
+	gen
+}
+
+// The bodies of `accept_with` and `accept_mut_with`, which differ only in the method called on each field.
+fn accept_body(ident: &Ident, input_args: &VisitableArgs, data: &Data, accept: &Ident) -> TokenStream {
+	let type_hook = input_args.hook(|| snake(ident.to_string()));
+	let children = match data {
+		// Enums without any fields, such as keywords, have nothing within them to visit.
+		Data::Enum(DataEnum { variants, .. })
+			if variants.iter().all(|variant| {
+				variant.fields.is_empty() && VisitableArgs::parse(&variant.attrs).call == AutoOrNamed::None
+			}) =>
+		{
+			quote! {}
+		}
+		Data::Enum(DataEnum { variants, .. }) => {
+			let mut arms = vec![];
+			for variant in variants {
+				let variant_ident = &variant.ident;
+				let args = VisitableArgs::parse(&variant.attrs);
+				let arm = match (args.hook(|| variant_hook(variant_ident, ident)), &variant.fields) {
+					(None, Fields::Unit) => quote! { Self::#variant_ident => {} },
+					(None, Fields::Named(_)) if args.skip => quote! { Self::#variant_ident { .. } => {} },
+					(None, Fields::Unnamed(_)) if args.skip => quote! { Self::#variant_ident(..) => {} },
+					(None, fields) => {
+						let (pattern, fields) = visit_fields(fields);
+						quote! { Self::#variant_ident #pattern => { #(#fields.#accept(v, ctx);)* } }
 					}
-				}
-				#[automatically_derived]
-				impl<'a> hdx_ast::css::visit::VisitableMut<'a> for #ident #gen {
-					fn accept_mut<V: hdx_ast::css::visit::VisitMut<'a>>(&mut self, v: &mut V) {
-						#call
-						#(#field_accept_muts)*
+					(Some(hook), Fields::Unnamed(fields)) if fields.unnamed.len() == 1 => {
+						let children = if args.skip {
+							quote! {}
+						} else {
+							quote! { f.#accept(v, ctx); }
+						};
+						// The variants of a node which is itself kept on the context are a part of that node, rather
+						// than one within it.
+						let kind = if type_hook.is_some() {
+							None
+						} else {
+							let kind = format_ident!("{}Kind", ident);
+							Some(
+								quote! { hdx_ast::css::visit::NodeKind::#ident(hdx_ast::css::visit::#kind::#variant_ident) },
+							)
+						};
+						let hooked = hooked(&hook, quote! { f }, kind, children);
+						quote! { Self::#variant_ident(f) => { #hooked } }
 					}
-				}
+					_ => {
+						return err(variant_ident.span(), "#[visitable(call)] needs a variant with a single field");
+					}
+				};
+				arms.push(arm);
 			}
+			quote! { match self { #(#arms)* } }
 		}
-		Data::Struct(DataStruct { fields: Fields::Unnamed(fields), .. }) => {
-			let mut field_accepts = vec![];
-			let mut field_accept_muts = vec![];
-			for i in 0..fields.unnamed.len() {
-				let idx = Index::from(i);
-				let args = VisitableArgs::parse(&fields.unnamed[i].attrs);
-				if args.call != AutoOrNamed::None {
-					return err(ident.span(), "#[visitable(call = ...)] is not valid a struct field");
-				}
-				if !args.skip {
-					field_accepts.push(quote! {
-						self.#idx.accept(v);
-					});
-					field_accept_muts.push(quote! {
-						self.#idx.accept_mut(v);
-					});
-				}
-			}
-			if call.is_none() && field_accepts.is_empty() {
-				return err(
-					ident.span(),
-					"This visitable does nothing, it doesn't call and it doesn't accept any fields",
-				);
+		Data::Struct(DataStruct { fields, .. }) => {
+			let (pattern, fields) = visit_fields(fields);
+			if fields.is_empty() {
+				quote! {}
+			} else {
+				quote! { let Self #pattern = self; #(#fields.#accept(v, ctx);)* }
 			}
-			quote! {
-				#[automatically_derived]
-				impl<'a> hdx_ast::css::visit::Visitable<'a> for #ident #gen {
-					fn accept<V: hdx_ast::css::visit::Visit<'a>>(&self, v: &mut V) {
-						#call;
-						#(#field_accepts)*
-					}
-				}
+		}
+		Data::Union(_) => return err(ident.span(), "Cannot derive Visitable on a Union"),
+	};
+	match type_hook {
+		Some(hook) => hooked(&hook, quote! { self }, Some(quote! { hdx_ast::css::visit::NodeKind::#ident }), children),
+		None => children,
+	}
+}
 
-				#[automatically_derived]
-				impl<'a> hdx_ast::css::visit::VisitableMut<'a> for #ident #gen {
-					fn accept_mut<V: hdx_ast::css::visit::VisitMut<'a>>(&mut self, v: &mut V) {
-						#call;
-						#(#field_accept_muts)*
-					}
-				}
+pub fn derive(input: DeriveInput) -> TokenStream {
+	let ident = input.ident;
+	let input_args = VisitableArgs::parse(&input.attrs);
+	if input_args.skip {
+		return err(ident.span(), "#[visitable(skip)] is not valid on the input");
+	}
+	let accept = accept_body(&ident, &input_args, &input.data, &format_ident!("accept_with"));
+	let accept_mut = accept_body(&ident, &input_args, &input.data, &format_ident!("accept_mut_with"));
+	let gen = generics(&input.generics, quote! { hdx_ast::css::visit::Visitable<'a> });
+	let (impl_gen, _, where_clause) = gen.split_for_impl();
+	let gen_mut = generics(&input.generics, quote! { hdx_ast::css::visit::VisitableMut<'a> });
+	let (impl_gen_mut, _, where_clause_mut) = gen_mut.split_for_impl();
+	let (_, type_gen, _) = input.generics.split_for_impl();
+	quote! {
+		#[automatically_derived]
+		impl #impl_gen hdx_ast::css::visit::Visitable<'a> for #ident #type_gen #where_clause {
+			#[allow(unused_variables)]
+			fn accept_with<V: hdx_ast::css::visit::Visit<'a>>(
+				&self,
+				v: &mut V,
+				ctx: &mut hdx_ast::css::visit::VisitContext<'a>,
+			) {
+				#accept
 			}
 		}
-		Data::Struct(_) => err(ident.span(), "Cannot derive Visitable on a Unit struct"),
-		Data::Union(_) => err(ident.span(), "Cannot derive Visitable on a Union"),
+		#[automatically_derived]
+		impl #impl_gen_mut hdx_ast::css::visit::VisitableMut<'a> for #ident #type_gen #where_clause_mut {
+			#[allow(unused_variables)]
+			fn accept_mut_with<V: hdx_ast::css::visit::VisitMut<'a>>(
+				&mut self,
+				v: &mut V,
+				ctx: &mut hdx_ast::css::visit::VisitContext<'a>,
+			) {
+				#accept_mut
+			}
+		}
+	}
+}
+
+// A variant of an enum along with the type it holds, such as `Media(rules::Media<'a>)`.
+struct Variant {
+	ident: Ident,
+	ty: Type,
+}
+
+impl Parse for Variant {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let ident = input.parse::<Ident>()?;
+		let content;
+		syn::parenthesized!(content in input);
+		Ok(Self { ident, ty: content.parse()? })
+	}
+}
+
+// `[mut] [Enum;] Name(Type), ...`, where the names are those of variants of `Enum` if it is given.
+pub struct VisitMethods {
+	mutable: bool,
+	ident: Option<Ident>,
+	variants: Punctuated<Variant, Token![,]>,
+}
+
+impl Parse for VisitMethods {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let mutable = input.parse::<Option<Token![mut]>>()?.is_some();
+		let ident = if input.peek2(Token![;]) {
+			let ident = input.parse::<Ident>()?;
+			input.parse::<Token![;]>()?;
+			Some(ident)
+		} else {
+			None
+		};
+		Ok(Self { mutable, ident, variants: Punctuated::parse_terminated(input)? })
 	}
 }
+
+pub fn visit_methods(input: VisitMethods) -> TokenStream {
+	let reference = if input.mutable {
+		quote! { &mut }
+	} else {
+		quote! { & }
+	};
+	let methods = input.variants.iter().map(|Variant { ident, ty }| {
+		let hook = match &input.ident {
+			Some(enum_ident) => variant_hook(ident, enum_ident),
+			None => snake(ident.to_string()),
+		};
+		let visit = Ident::new(&format!("visit_{}", hook), ident.span());
+		let enter = Ident::new(&format!("enter_{}", hook), ident.span());
+		let exit = Ident::new(&format!("exit_{}", hook), ident.span());
+		quote! {
+			fn #visit(&mut self, _node: #reference #ty) {}
+			fn #enter(
+				&mut self,
+				node: #reference #ty,
+				_ctx: &hdx_ast::css::visit::VisitContext<'a>,
+			) -> hdx_ast::css::visit::VisitFlow {
+				self.#visit(node);
+				hdx_ast::css::visit::VisitFlow::Continue
+			}
+			fn #exit(&mut self, _node: #reference #ty, _ctx: &hdx_ast::css::visit::VisitContext<'a>) {}
+		}
+	});
+	quote! { #(#methods)* }
+}
@@ -153,11 +153,12 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 							}
 							WritableKind::String => {
 								if unnamed.len() == 2 {
-									let quotes = field_writes.pop().unwrap();
-									// Writable unnamed types with two fields are using the second field as the quote style.
-									// We need to shuffle stuff around to take that field and make it the head/tail
-									head = Some(quotes.clone());
-									tail = Some(quotes);
+									// Writable unnamed types with two fields are using the second field as the quote style,
+									// which the writer may adjust to its preferred quotes.
+									let (str, quote) = (&field_extract[0], &field_extract[1]);
+									field_writes = vec![quote! {
+										sink.write_with_quotes(#str.as_ref(), *#quote, false)?;
+									}];
 								} else {
 									head = Some(quote! { sink.write_char('"')?; });
 									tail = Some(quote! { sink.write_char('"')?; });
@@ -43,11 +43,41 @@ fn popular(c: &mut Criterion) {
 	group.finish();
 }
 
+// Lexes each file the way the parser reads it, looking at each token before moving past it, and every few tokens
+// reading ahead then rewinding, as trying to parse something which doesn't match does.
+fn popular_rewinding(c: &mut Criterion) {
+	let mut group = c.benchmark_group("lex_popular_rewinding");
+	for file in get_files() {
+		group.throughput(Throughput::Bytes(file.source_text.len() as u64));
+		group.bench_with_input(BenchmarkId::from_parameter(&file.name), &file.source_text, |b, source_text| {
+			b.iter_with_large_drop(|| {
+				let allocator = Bump::default();
+				let mut lexer = Lexer::new(&allocator, source_text);
+				for i in 0.. {
+					if i % 4 == 0 {
+						let checkpoint = lexer.checkpoint();
+						for _ in 0..3 {
+							lexer.advance();
+						}
+						lexer.rewind(checkpoint);
+					}
+					if matches!(lexer.lookahead(1), hdx_lexer::Token::Eof) {
+						break;
+					}
+					lexer.advance();
+				}
+				allocator
+			});
+		});
+	}
+	group.finish();
+}
+
 criterion_group! {
 	name = benches;
 	config = Criterion::default()
 		.with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
-	targets = popular
+	targets = popular, popular_rewinding
 }
 
 criterion_main!(benches);
@@ -2,7 +2,7 @@ use super::Token;
 
 pub const SURROGATE_RANGE: std::ops::RangeInclusive<u32> = 0xd800..=0xdfff;
 
-pub const SINGLE_CHAR_TOKENS: &[Token; 128] = &[
+pub const SINGLE_CHAR_TOKENS: &[Token<'static>; 128] = &[
 	/*   0 */ Token::Undetermined,
 	/*   1 */ Token::Undetermined,
 	/*   2 */ Token::Undetermined,
@@ -3,16 +3,25 @@ mod private;
 mod string_builder;
 mod token;
 
-use std::{collections::VecDeque, str::Chars};
+use std::{cell::Cell, collections::VecDeque};
 
 use bitmask_enum::bitmask;
 use bumpalo::Bump;
 pub use token::{NumType, PairWise, QuoteStyle, Token};
 
-#[derive(Debug, Clone)]
-pub struct LexerCheckpoint<'a> {
-	chars: Chars<'a>,
-	token: Token,
+/// A position in the source text which the [Lexer] can be [rewound](Lexer::rewind) to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LexerCheckpoint {
+	pos: u32,
+}
+
+/// A [Token] along with where it was found in the source text. The `start` is that of the token itself, after any
+/// whitespace or comments which were skipped over to get to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lexeme<'a> {
+	pub token: Token<'a>,
+	pub start: u32,
+	pub end: u32,
 }
 
 #[bitmask(u8)]
@@ -22,109 +31,163 @@ pub enum Include {
 	Comments = 0b0010,
 }
 
+// How many of the most recent tokens are kept to be read again after a rewind.
+const LEXED_WINDOW: usize = 64;
+
 pub struct Lexer<'a> {
 	allocator: &'a Bump,
 	source: &'a str,
-	current: LexerCheckpoint<'a>,
-	lookahead: VecDeque<LexerCheckpoint<'a>>,
+	pos: u32,
 	include: Include,
+	// The most recent tokens lexed without trivia, along with the position each was lexed from, in order of that
+	// position. Parsing looks ahead and rewinds a lot, though rarely far, so this means the same text is seldom lexed
+	// twice, without keeping every token.
+	lexed: VecDeque<(u32, Lexeme<'a>)>,
+	// Where the last checkpoint was created, from which tokens might be read again after a rewind. Until there is one,
+	// only tokens looked ahead to are kept, so lexing through the text without rewinding doesn't keep any.
+	checkpointed: Cell<u32>,
+	// The last token looked ahead to with trivia included, which aren't kept in `lexed`.
+	peeked: Token<'a>,
 }
 
 impl<'a> Lexer<'a> {
 	pub fn new(allocator: &'a Bump, source: &'a str) -> Self {
-		let token = Token::default();
-		let current = LexerCheckpoint { chars: source.chars(), token };
-		Self { allocator, source, current, lookahead: VecDeque::with_capacity(4), include: Include::none() }
+		Self {
+			allocator,
+			source,
+			pos: 0,
+			include: Include::none(),
+			lexed: VecDeque::with_capacity(LEXED_WINDOW),
+			checkpointed: Cell::new(u32::MAX),
+			peeked: Token::default(),
+		}
 	}
 
-	/// Remaining string from `Chars`
+	/// The whole of the text being lexed
+	#[inline]
+	pub fn source(&self) -> &'a str {
+		self.source
+	}
+
+	/// Remaining text from the current position
+	#[inline]
 	fn remaining(&self) -> &'a str {
-		self.current.chars.as_str()
+		&self.source[self.pos as usize..]
 	}
 
 	/// Should only be used in severe edge cases, for legacy parse modes
 	pub fn legacy_peek_next_char(&self, n: usize) -> Option<char> {
-		self.current.chars.clone().nth(n)
+		self.remaining().chars().nth(n)
 	}
 
 	/// Current position in file
 	#[inline]
 	pub fn pos(&self) -> u32 {
-		(self.source.len() - self.remaining().len()) as u32
+		self.pos
 	}
 
 	/// Creates a checkpoint storing the current lexer state.
 	/// Use `rewind` to restore the lexer to the state stored in the checkpoint.
-	pub fn checkpoint(&self) -> LexerCheckpoint<'a> {
-		LexerCheckpoint { chars: self.current.chars.clone(), token: self.current.token.clone() }
+	#[inline]
+	pub fn checkpoint(&self) -> LexerCheckpoint {
+		self.checkpointed.set(self.pos);
+		LexerCheckpoint { pos: self.pos }
+	}
+
+	/// Rewinds the lexer to the same state as when the passed in `checkpoint` was created. The most recent tokens
+	/// lexed after the checkpoint are kept, so advancing past them again doesn't lex them again.
+	#[inline]
+	pub fn rewind(&mut self, checkpoint: LexerCheckpoint) {
+		self.pos = checkpoint.pos;
 	}
 
-	/// Rewinds the lexer to the same state as when the passed in `checkpoint` was created.
-	pub fn rewind(&mut self, checkpoint: LexerCheckpoint<'a>) {
-		self.current = checkpoint;
-		self.lookahead.clear();
+	/// Moves the lexer to `pos`, which must be the start of a token (or of the whitespace or comments before one).
+	#[inline]
+	pub fn seek(&mut self, pos: u32) {
+		debug_assert!(self.source.is_char_boundary(pos as usize));
+		self.pos = pos;
 	}
 
 	/// Find the nth lookahead token lazily
-	pub fn lookahead(&mut self, n: u8) -> &Token {
-		let n = n as usize;
+	pub fn lookahead(&mut self, n: u8) -> &Token<'a> {
 		debug_assert!(n > 0);
-
-		if self.lookahead.len() > n - 1 {
-			return &self.lookahead[n - 1].token;
+		let pos = self.pos;
+		let mut lexeme = self.lex(pos, true);
+		for _ in 1..n {
+			lexeme = self.lex(lexeme.end, true);
 		}
-
-		let checkpoint = self.checkpoint();
-
-		if let Some(checkpoint) = self.lookahead.back() {
-			self.current = checkpoint.clone();
-		}
-
-		// reset the current token for `read_next_token`,
-		// otherwise it will contain the token from
-		// `self.current = checkpoint`
-		self.current.token = Token::default();
-
-		for _i in self.lookahead.len()..n {
-			let peeked = self.read_next_token();
-			self.lookahead.push_back(LexerCheckpoint { chars: self.current.chars.clone(), token: peeked });
-		}
-
-		self.current = checkpoint;
-
-		&self.lookahead[n - 1].token
+		self.pos = pos;
+		self.peeked = lexeme.token;
+		&self.peeked
 	}
 
-	pub fn lookahead_with(&mut self, n: u8, inc: Include) -> &Token {
+	pub fn lookahead_with(&mut self, n: u8, inc: Include) -> &Token<'a> {
+		if inc == Include::none() {
+			return self.lookahead(n);
+		}
+		debug_assert!(n > 0);
+		let pos = self.pos;
 		self.include = inc;
-		// we need to clear the lookahead if different characteristics
-		// are used, as it will influence token count.
-		self.lookahead.clear();
-		self.lookahead(n);
+		for _ in 0..n {
+			self.peeked = self.read_next_token().token;
+		}
 		self.include = Include::none();
-		self.lookahead(n)
+		self.pos = pos;
+		&self.peeked
 	}
 
-	pub fn jump(&mut self) -> Token {
-		if let Some(checkpoint) = self.lookahead.pop_back() {
-			self.current.chars = checkpoint.chars;
-			self.lookahead.clear();
-			return checkpoint.token;
-		}
-		self.advance()
+	/// Moves past the next token, returning it along with where it was found.
+	pub fn advance_lexeme(&mut self) -> Lexeme<'a> {
+		let lexeme = self.lex(self.pos, false);
+		self.pos = lexeme.end;
+		lexeme
 	}
 
 	#[inline]
-	pub fn advance(&mut self) -> Token {
-		self.lookahead.clear();
-		self.read_next_token()
+	pub fn advance(&mut self) -> Token<'a> {
+		self.advance_lexeme().token
 	}
 
 	#[inline]
-	pub fn advance_with(&mut self, inc: Include) -> Token {
+	pub fn advance_with(&mut self, inc: Include) -> Token<'a> {
+		if inc == Include::none() {
+			return self.advance();
+		}
 		self.include = inc;
-		let token = self.advance();
+		let token = self.read_next_token().token;
 		self.include = Include::none();
 		token
 	}
+
+	// Finds the token following `pos` (skipping trivia) in the most recent tokens lexed, lexing it if it's not there,
+	// in which case it's kept if `lookahead` or there is a checkpoint to rewind to before it. This leaves the position
+	// at the end of the token.
+	fn lex(&mut self, pos: u32, lookahead: bool) -> Lexeme<'a> {
+		// Lexing carries on from the last token far more often than not, which can skip the search.
+		let index = match self.lexed.back() {
+			Some((last, _)) if *last < pos => None,
+			Some((last, lexeme)) if *last == pos => {
+				self.pos = lexeme.end;
+				return lexeme.clone();
+			}
+			None => None,
+			_ => Some(self.lexed.binary_search_by_key(&pos, |(from, _)| *from)),
+		};
+		if let Some(Ok(index)) = index {
+			let lexeme = self.lexed[index].1.clone();
+			self.pos = lexeme.end;
+			return lexeme;
+		}
+		self.pos = pos;
+		let lexeme = self.read_next_token();
+		// Only tokens after the last are kept, as those before it have either been read already, or are from before
+		// the window, which parsing is unlikely to rewind to again.
+		if index.is_none() && (lookahead || self.checkpointed.get() <= pos) {
+			if self.lexed.len() == LEXED_WINDOW {
+				self.lexed.pop_front();
+			}
+			self.lexed.push_back((pos, lexeme.clone()));
+		}
+		lexeme
+	}
 }
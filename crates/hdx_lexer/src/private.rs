@@ -1,6 +1,5 @@
-use hdx_atom::{atom, Atom};
 use hdx_syntax::{
-	identifier::{is_ident, is_ident_ascii_start, is_ident_start, is_ident_start_sequence},
+	identifier::{is_ident, is_ident_ascii, is_ident_ascii_start, is_ident_start, is_ident_start_sequence},
 	is_escape_sequence, is_newline, is_quote, is_sign, is_whitespace,
 	url::{is_non_printable, is_url_ident},
 	EOF, REPLACEMENT,
@@ -8,9 +7,9 @@ use hdx_syntax::{
 
 use crate::{
 	constants::{SINGLE_CHAR_TOKENS, SURROGATE_RANGE},
-	string_builder::AutoCow,
+	string_builder::StringBuilder,
 	token::{NumType, QuoteStyle, Token},
-	Include, Lexer,
+	Include, Lexeme, Lexer,
 };
 
 impl<'a> Lexer<'a> {
@@ -24,39 +23,84 @@ impl<'a> Lexer<'a> {
 		self.include & Include::Comments == Include::Comments
 	}
 
+	#[inline]
+	fn byte(&self, offset: usize) -> Option<u8> {
+		self.source.as_bytes().get(offset).copied()
+	}
+
+	#[inline]
+	fn char_at(&self, offset: usize) -> char {
+		match self.byte(offset) {
+			None => EOF,
+			Some(byte) if byte.is_ascii() => byte as char,
+			Some(_) => self.source[offset..].chars().next().unwrap(),
+		}
+	}
+
 	#[inline]
 	fn nth_char(&self, n: usize) -> char {
-		self.current.chars.clone().nth(n).unwrap_or(EOF)
+		let mut offset = self.pos as usize;
+		for _ in 0..n {
+			match self.byte(offset) {
+				None => return EOF,
+				Some(byte) if byte.is_ascii() => offset += 1,
+				Some(_) => offset += self.char_at(offset).len_utf8(),
+			}
+		}
+		self.char_at(offset)
 	}
 
-	pub(crate) fn read_next_token(&mut self) -> Token {
-		let remaining = self.current.chars.as_str();
-		if remaining.is_empty() {
-			return Token::Eof;
+	// Consumes the next character, if there is one.
+	#[inline]
+	fn bump(&mut self) -> char {
+		let c = self.char_at(self.pos as usize);
+		if (self.pos as usize) < self.source.len() {
+			self.pos += c.len_utf8() as u32;
 		}
-		let c = self.nth_char(0);
+		c
+	}
+
+	#[inline]
+	fn is_eof(&self) -> bool {
+		self.pos as usize >= self.source.len()
+	}
+
+	pub(crate) fn read_next_token(&mut self) -> Lexeme<'a> {
+		loop {
+			let start = self.pos;
+			if let Some(token) = self.read_token() {
+				return Lexeme { token, start, end: self.pos };
+			}
+		}
+	}
+
+	// Reads the next token, or None if it's whitespace or a comment which isn't included.
+	fn read_token(&mut self) -> Option<Token<'a>> {
+		let Some(byte) = self.byte(self.pos as usize) else {
+			return Some(Token::Eof);
+		};
 		// fast path for single character tokens
 		// '{'  '}'  '('  ')'  '['  ']'  ';' ',' ':'
-		let size = c as usize;
-		if size < 128 {
-			let token = &SINGLE_CHAR_TOKENS[size];
-			if token != &Token::Undetermined {
-				self.current.chars.next();
-				return token.clone();
+		if byte.is_ascii() {
+			let token = &SINGLE_CHAR_TOKENS[byte as usize];
+			if !matches!(token, Token::Undetermined) {
+				self.pos += 1;
+				return Some(token.clone());
 			}
 			// fast path for identifiers
-			if is_ident_ascii_start(c) {
-				return self.consume_ident_like_token();
+			if is_ident_ascii_start(byte as char) {
+				return Some(self.consume_ident_like_token());
 			}
 		}
-		match c {
+		let c = self.nth_char(0);
+		let token = match c {
 			// Whitespace Range
 			c if is_whitespace(c) => {
 				self.consume_whitespace();
-				if self.include_whitspace() {
-					return Token::Whitespace;
+				if !self.include_whitspace() {
+					return None;
 				}
-				self.read_next_token()
+				Token::Whitespace
 			}
 			// Quote Range
 			c if is_quote(c) => self.consume_string_token(),
@@ -65,122 +109,110 @@ impl<'a> Lexer<'a> {
 			// Sign Range
 			'-' => {
 				if self.nth_char(1) == '-' && self.nth_char(2) == '>' {
-					self.current.chars.next();
-					self.current.chars.next();
-					self.current.chars.next();
-					return Token::Cdc;
+					self.pos += 3;
+					return Some(Token::Cdc);
 				}
 				if is_ident_start_sequence(c, self.nth_char(1), self.nth_char(2)) {
-					return self.consume_ident_like_token();
+					return Some(self.consume_ident_like_token());
 				}
 				if self.is_number_start() {
-					return self.consume_numeric_token();
+					return Some(self.consume_numeric_token());
 				}
-				Token::Delim(self.current.chars.next().unwrap())
+				Token::Delim(self.bump())
 			}
 			// Dot or Plus
 			'.' | '+' => {
 				if self.is_number_start() {
-					return self.consume_numeric_token();
+					return Some(self.consume_numeric_token());
 				}
-				Token::Delim(self.current.chars.next().unwrap())
+				Token::Delim(self.bump())
 			}
 			// Less Than
 			'<' => {
-				if self.nth_char(1) == '!' && self.nth_char(2) == '-' && self.nth_char(3) == '-' {
-					self.current.chars.next();
-					self.current.chars.next();
-					self.current.chars.next();
-					self.current.chars.next();
-					return Token::Cdo;
+				if self.remaining().starts_with("<!--") {
+					self.pos += 4;
+					return Some(Token::Cdo);
 				}
-				Token::Delim(self.current.chars.next().unwrap())
+				Token::Delim(self.bump())
 			}
 			// Hash / Pound Sign
 			'#' => {
 				if is_ident(self.nth_char(1)) || is_escape_sequence(self.nth_char(1), self.nth_char(2)) {
-					self.current.chars.next();
+					self.bump();
 					self.consume_hash_token()
 				} else {
-					Token::Delim(self.current.chars.next().unwrap())
+					Token::Delim(self.bump())
 				}
 			}
 			// Commercial At
 			'@' => {
 				if is_ident_start_sequence(self.nth_char(1), self.nth_char(2), self.nth_char(3)) {
-					self.current.chars.next();
-					let ident = self.consume_ident_sequence();
-					return Token::AtKeyword(ident);
+					self.bump();
+					return Some(Token::AtKeyword(self.consume_ident_sequence()));
 				}
-				Token::Delim(self.current.chars.next().unwrap())
+				Token::Delim(self.bump())
 			}
 			// Reverse Solidus
 			'\\' => {
 				if is_escape_sequence(c, self.nth_char(1)) {
-					return self.consume_ident_like_token();
+					return Some(self.consume_ident_like_token());
 				}
-				Token::Delim(self.current.chars.next().unwrap())
+				Token::Delim(self.bump())
 			}
 			// Solidus
-			'/' => match self.nth_char(1) {
-				'*' => {
-					self.current.chars.next();
-					self.current.chars.next();
-					if self.include_comments() {
-						let mut builder = AutoCow::new(self);
-						loop {
-							if self.nth_char(0) == EOF || (self.nth_char(0) == '*' && self.nth_char(1) == '/') {
-								let str = builder.finish(self);
-								self.current.chars.next();
-								self.current.chars.next();
-								return Token::Comment(Atom::from(str));
-							}
-							builder.push_matching(self.current.chars.next().unwrap());
-						}
-					} else {
-						while let Some(c) = self.current.chars.next() {
-							if c == '*' && self.nth_char(0) == '/' {
-								self.current.chars.next();
-								break;
-							}
-						}
-						self.read_next_token()
-					}
+			'/' if self.nth_char(1) == '*' => {
+				self.pos += 2;
+				let start = self.pos as usize;
+				let end = self.remaining().find("*/").map_or(self.source.len(), |len| start + len);
+				self.pos = (end + 2).min(self.source.len()) as u32;
+				if !self.include_comments() {
+					return None;
 				}
-				_ => Token::Delim(self.current.chars.next().unwrap()),
-			},
+				Token::Comment(&self.source[start..end])
+			}
 			c if is_ident_start(c) => self.consume_ident_like_token(),
-			_ => Token::Delim(self.current.chars.next().unwrap()),
-		}
+			_ => Token::Delim(self.bump()),
+		};
+		Some(token)
 	}
 
 	fn consume_whitespace(&mut self) {
-		while is_whitespace(self.nth_char(0)) {
-			self.current.chars.next();
+		while let Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0C') = self.byte(self.pos as usize) {
+			self.pos += 1;
 		}
 	}
 
-	fn consume_ident_sequence(&mut self) -> Atom {
-		let mut builder = AutoCow::new(self);
+	fn consume_ident_sequence(&mut self) -> &'a str {
+		let mut builder = StringBuilder::new(self);
 		loop {
-			let mut c = self.nth_char(0);
+			// fast path for runs of ASCII characters, which most identifiers are made of
+			while let Some(byte) = self.byte(self.pos as usize) {
+				if !byte.is_ascii() || !is_ident_ascii(byte as char) {
+					break;
+				}
+				self.pos += 1;
+				builder.push_matching(byte as char);
+			}
+			let c = self.nth_char(0);
 			if is_ident(c) {
-				c = self.current.chars.next().unwrap();
+				self.bump();
 				builder.push_matching(c);
 			} else if is_escape_sequence(c, self.nth_char(1)) {
-				self.current.chars.next();
-				builder.force_allocation_without_current_ascii_char(self);
+				builder.force_allocation(self, self.pos);
+				self.bump();
 				builder.push_different(self.consume_escape_sequence());
 			} else {
-				return Atom::from(builder.finish(self));
+				return builder.finish(self, self.pos);
 			}
 		}
 	}
 
 	fn consume_escape_sequence(&mut self) -> char {
 		if !self.nth_char(0).is_ascii_hexdigit() {
-			let char = self.current.chars.next().unwrap_or(REPLACEMENT);
-			return char;
+			if self.is_eof() {
+				return REPLACEMENT;
+			}
+			return self.bump();
 		}
 		if let Some(n) = self.code_point() {
 			return n;
@@ -188,34 +220,25 @@ impl<'a> Lexer<'a> {
 		REPLACEMENT
 	}
 
-	fn consume_url_sequence(&mut self, quote: QuoteStyle) -> Token {
+	fn consume_url_sequence(&mut self, quote: QuoteStyle) -> Token<'a> {
 		self.consume_whitespace();
-		let mut builder = AutoCow::new(self);
-		builder.start = self.remaining();
-		builder.value = None;
+		let mut builder = StringBuilder::new(self);
 		loop {
-			let c = self.current.chars.next().unwrap_or(EOF);
+			let end = self.pos;
+			let c = self.bump();
 			match c {
 				')' => {
-					builder.force_allocation_without_current_ascii_char(self);
-					break;
+					return Token::Url(builder.finish(self, end), quote);
 				}
 				EOF => {
-					break;
+					return Token::Url(builder.finish(self, self.pos), quote);
 				}
 				_ if is_whitespace(c) => {
-					builder.force_allocation_without_current_ascii_char(self);
+					let url = builder.finish(self, end);
 					self.consume_whitespace();
-					match self.current.chars.next().unwrap_or(EOF) {
-						')' => {
-							break;
-						}
-						EOF => {
-							break;
-						}
-						_ => {
-							return self.consume_remnants_of_bad_url();
-						}
+					return match self.bump() {
+						')' | EOF => Token::Url(url, quote),
+						_ => self.consume_remnants_of_bad_url(),
 					};
 				}
 				'\'' | '"' | '(' => {
@@ -226,11 +249,11 @@ impl<'a> Lexer<'a> {
 				}
 				'\\' => {
 					if is_escape_sequence(c, self.nth_char(0)) {
-						builder.force_allocation_without_current_ascii_char(self);
+						builder.force_allocation(self, end);
 						let c = self.consume_escape_sequence();
 						builder.push_different(c);
 					} else {
-						self.current.chars.next();
+						self.bump();
 						return self.consume_remnants_of_bad_url();
 					}
 				}
@@ -239,12 +262,11 @@ impl<'a> Lexer<'a> {
 				}
 			}
 		}
-		Token::Url(Atom::from(builder.finish(self)), quote)
 	}
 
-	fn consume_remnants_of_bad_url(&mut self) -> Token {
+	fn consume_remnants_of_bad_url(&mut self) -> Token<'a> {
 		loop {
-			match self.current.chars.next().unwrap_or(EOF) {
+			match self.bump() {
 				')' => {
 					break;
 				}
@@ -253,7 +275,7 @@ impl<'a> Lexer<'a> {
 				}
 				c @ '\\' => {
 					if is_escape_sequence(c, self.nth_char(0)) {
-						self.current.chars.next();
+						self.bump();
 						self.consume_escape_sequence();
 					}
 				}
@@ -263,10 +285,9 @@ impl<'a> Lexer<'a> {
 		Token::BadUrl
 	}
 
-	fn consume_numeric_token(&mut self) -> Token {
-		let mut builder = AutoCow::new(self);
-		let c = self.current.chars.next().unwrap();
-		builder.push_matching(c);
+	fn consume_numeric_token(&mut self) -> Token<'a> {
+		let start = self.pos as usize;
+		let c = self.bump();
 		let mut num_type = NumType::none();
 		if is_sign(c) {
 			num_type = num_type.signed();
@@ -276,7 +297,7 @@ impl<'a> Lexer<'a> {
 		}
 		self.consume_decimal_digits();
 		if num_type.is_int() && self.nth_char(0) == '.' && self.nth_char(1).is_ascii_digit() {
-			self.current.chars.next();
+			self.pos += 1;
 			self.consume_decimal_digits();
 			num_type = num_type.float();
 		}
@@ -284,28 +305,29 @@ impl<'a> Lexer<'a> {
 			&& (self.nth_char(1).is_ascii_digit()
 				|| (matches!(self.nth_char(1), '-' | '+') && self.nth_char(2).is_ascii_digit()))
 		{
-			self.current.chars.next();
+			self.pos += 1;
 			if matches!(self.nth_char(0), '-' | '+') {
-				self.current.chars.next();
+				self.pos += 1;
 			}
 			self.consume_decimal_digits();
 			num_type = num_type.float();
 		}
-		let value = self.parse_number(builder.finish(self));
+		let raw = &self.source[start..self.pos as usize];
+		let value = self.parse_number(raw);
 		match self.nth_char(0) {
 			'%' => {
-				self.current.chars.next();
-				Token::Dimension(value, atom!("%"), num_type)
+				self.pos += 1;
+				Token::Dimension(value, "%", num_type, raw)
 			}
 			c if is_ident_start_sequence(c, self.nth_char(1), self.nth_char(2)) => {
 				let unit = self.consume_ident_sequence();
-				Token::Dimension(value, unit, num_type)
+				Token::Dimension(value, unit, num_type, raw)
 			}
-			_ => Token::Number(value, num_type),
+			_ => Token::Number(value, num_type, raw),
 		}
 	}
 
-	fn consume_hash_token(&mut self) -> Token {
+	fn consume_hash_token(&mut self) -> Token<'a> {
 		let ident = self.consume_ident_sequence();
 		if ident.starts_with(is_ident_start) {
 			Token::HashId(ident)
@@ -315,17 +337,17 @@ impl<'a> Lexer<'a> {
 	}
 
 	fn consume_decimal_digits(&mut self) {
-		while self.nth_char(0).is_ascii_digit() {
-			self.current.chars.next();
+		while matches!(self.byte(self.pos as usize), Some(b'0'..=b'9')) {
+			self.pos += 1;
 		}
 	}
 
-	fn consume_ident_like_token(&mut self) -> Token {
+	fn consume_ident_like_token(&mut self) -> Token<'a> {
 		let ident = self.consume_ident_sequence();
 		if self.nth_char(0) == '(' {
-			self.current.chars.next();
-			if is_url_ident(&ident) {
-				let mut chars = self.current.chars.clone();
+			self.pos += 1;
+			if is_url_ident(ident) {
+				let mut chars = self.remaining().chars();
 				let mut char = chars.next().unwrap_or(EOF);
 				for _i in 0..=3 {
 					if is_whitespace(char) {
@@ -342,34 +364,34 @@ impl<'a> Lexer<'a> {
 		Token::Ident(ident)
 	}
 
-	fn consume_string_token(&mut self) -> Token {
-		let delimiter = self.current.chars.next().unwrap();
+	fn consume_string_token(&mut self) -> Token<'a> {
+		let delimiter = self.bump();
 		let quote = if delimiter == '"' { QuoteStyle::Double } else { QuoteStyle::Single };
-		let mut builder = AutoCow::new(self);
+		let mut builder = StringBuilder::new(self);
 		loop {
 			match self.nth_char(0) {
 				c if is_newline(c) => {
 					return Token::BadString;
 				}
 				EOF => {
-					return Token::String(Atom::from(builder.finish(self)), quote);
+					return Token::String(builder.finish(self, self.pos), quote);
 				}
 				c @ ('"' | '\'') => {
-					self.current.chars.next();
+					self.pos += 1;
 					if c == delimiter {
-						return Token::String(Atom::from(builder.finish_without_push(self)), quote);
+						return Token::String(builder.finish(self, self.pos - 1), quote);
 					}
 					builder.push_matching(c);
 				}
 				'\\' => {
-					let c = self.current.chars.next().unwrap();
-					builder.force_allocation_without_current_ascii_char(self);
+					builder.force_allocation(self, self.pos);
+					let c = self.bump();
 					match self.nth_char(0) {
 						EOF => {
-							return Token::String(Atom::from(builder.finish(self)), quote);
+							return Token::String(builder.finish(self, self.pos), quote);
 						}
 						p if is_newline(p) => {
-							self.current.chars.next();
+							self.bump();
 						}
 						p if is_escape_sequence(c, p) => {
 							builder.push_different(self.consume_escape_sequence());
@@ -380,7 +402,7 @@ impl<'a> Lexer<'a> {
 					}
 				}
 				c => {
-					self.current.chars.next();
+					self.bump();
 					builder.push_matching(c);
 				}
 			}
@@ -395,13 +417,8 @@ impl<'a> Lexer<'a> {
 	}
 
 	fn hex_digit(&mut self) -> Option<u32> {
-		let value = match self.nth_char(0) {
-			c if c.is_ascii_digit() => c as u32 - '0' as u32,
-			c @ 'a'..='f' => 10 + (c as u32 - 'a' as u32),
-			c @ 'A'..='F' => 10 + (c as u32 - 'A' as u32),
-			_ => return None,
-		};
-		self.current.chars.next();
+		let value = self.nth_char(0).to_digit(16)?;
+		self.pos += 1;
 		Some(value)
 	}
 
@@ -416,7 +433,7 @@ impl<'a> Lexer<'a> {
 			}
 		}
 		if is_whitespace(self.nth_char(0)) {
-			self.current.chars.next();
+			self.bump();
 		}
 		if value == 0 || SURROGATE_RANGE.contains(&value) {
 			return None;
@@ -424,10 +441,10 @@ impl<'a> Lexer<'a> {
 		char::from_u32(value)
 	}
 
-	fn parse_number(&mut self, s: &'a str) -> f32 {
-		match s.parse::<f32>() {
+	fn parse_number(&self, s: &str) -> f64 {
+		match s.parse::<f64>() {
 			Ok(value) => value,
-			Err(_err) => std::f32::NAN,
+			Err(_err) => std::f64::NAN,
 		}
 	}
 }
@@ -1,58 +1,47 @@
-// Copied from https://github.com/mozilla-spidermonkey/jsparagus/blob/master/crates/parser/src/lexer.rs#L2256
-
 use bumpalo::collections::String;
 
 use crate::Lexer;
 
-pub struct AutoCow<'a> {
-	pub start: &'a str,
-	pub value: Option<String<'a>>,
+/// Builds the text of a token, which borrows from the source text unless it has escapes in it. Once an escape is
+/// found the text is copied into the allocator, so the escape can be replaced with the character it stands for.
+pub struct StringBuilder<'a> {
+	start: u32,
+	value: Option<String<'a>>,
 }
 
-impl<'a> AutoCow<'a> {
+impl<'a> StringBuilder<'a> {
+	/// Starts building from the lexer's current position.
 	pub fn new(lexer: &Lexer<'a>) -> Self {
-		let start = lexer.remaining();
-		AutoCow { start, value: None }
+		Self { start: lexer.pos(), value: None }
 	}
 
-	// Push a char that matches lexer.chars().next()
+	// Push a char that matches the one just consumed from the source text.
+	#[inline]
 	pub fn push_matching(&mut self, c: char) {
 		if let Some(text) = &mut self.value {
 			text.push(c);
 		}
 	}
 
-	// Push a different character than lexer.chars().next().
-	// force_allocation_without_current_ascii_char must be called before this.
+	// Push a different character than the source text has, such as one decoded from an escape.
+	// force_allocation must be called before this.
 	pub fn push_different(&mut self, c: char) {
 		debug_assert!(self.value.is_some());
 		self.value.as_mut().unwrap().push(c);
 	}
 
-	// Force allocation of a String, excluding the current ASCII character.
-	pub fn force_allocation_without_current_ascii_char(&mut self, lexer: &'_ Lexer<'a>) {
-		if self.value.is_some() {
-			return;
-		}
-		self.value = if self.start.len() == lexer.remaining().len() {
-			Some(String::from_str_in("", lexer.allocator))
-		} else {
-			Some(String::from_str_in(&self.start[..self.start.len() - lexer.remaining().len() - 1], lexer.allocator))
-		};
-	}
-
-	pub fn finish(mut self, lexer: &Lexer<'a>) -> &'a str {
-		match self.value.take() {
-			Some(s) => s.into_bump_str(),
-			None => &self.start[..self.start.len() - lexer.remaining().len()],
+	// Copy the text so far, which runs up to `end`, into the allocator.
+	pub fn force_allocation(&mut self, lexer: &Lexer<'a>, end: u32) {
+		if self.value.is_none() {
+			self.value = Some(String::from_str_in(&lexer.source()[self.start as usize..end as usize], lexer.allocator));
 		}
 	}
 
-	// Just like finish, but without pushing current char.
-	pub fn finish_without_push(mut self, lexer: &Lexer<'a>) -> &'a str {
-		match self.value.take() {
+	// The text from the start up to `end`, unless it has been copied, in which case it's the copy.
+	pub fn finish(self, lexer: &Lexer<'a>, end: u32) -> &'a str {
+		match self.value {
 			Some(s) => s.into_bump_str(),
-			None => &self.start[..self.start.len() - lexer.remaining().len() - 1],
+			None => &lexer.source()[self.start as usize..end as usize],
 		}
 	}
 }
@@ -1,14 +1,13 @@
 use std::hash::{Hash, Hasher};
 
 use bitmask_enum::bitmask;
-use hdx_atom::Atom;
 #[cfg(feature = "serde")]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Default)]
 #[bitmask(u8)]
 #[bitmask_config(vec_debug)]
-#[cfg_attr(feature = "serde", derive(Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde())]
 pub enum NumType {
 	#[default]
 	Float = 0x01,
@@ -49,7 +48,7 @@ impl NumType {
 
 #[derive(Debug, Clone, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "kind", content = "value"))]
-pub enum Token {
+pub enum Token<'a> {
 	#[default]
 	// A token yet to be built
 	Undetermined,
@@ -58,31 +57,31 @@ pub enum Token {
 	Eof,
 
 	// <comment-token> (https://drafts.csswg.org/css-syntax/#comment-diagram)
-	Comment(Atom),
+	Comment(&'a str),
 
 	// <ident-token> (https://drafts.csswg.org/css-syntax/#ident-token-diagram)
-	Ident(Atom),
+	Ident(&'a str),
 
 	// <function-token> (https://drafts.csswg.org/css-syntax/#function-token-diagram)
-	Function(Atom),
+	Function(&'a str),
 
 	// <at-keyword> https://drafts.csswg.org/css-syntax/#at-keyword-token-diagram
-	AtKeyword(Atom),
+	AtKeyword(&'a str),
 
 	// "unrestricted" <hash-token> (https://drafts.csswg.org/css-syntax/#hash-token-diagram)
-	Hash(Atom),
+	Hash(&'a str),
 
 	// "id" <hash-token> (https://drafts.csswg.org/css-syntax/#hash-token-diagram)
-	HashId(Atom),
+	HashId(&'a str),
 
 	// <string-token> (https://drafts.csswg.org/css-syntax/#string-token-diagram)
-	String(Atom, QuoteStyle),
+	String(&'a str, QuoteStyle),
 
 	// <bad-string-token> (https://drafts.csswg.org/css-syntax/#typedef-bad-string-token)
 	BadString,
 
 	// <url-token> (https://drafts.csswg.org/css-syntax/#url-token-diagram)
-	Url(Atom, QuoteStyle),
+	Url(&'a str, QuoteStyle),
 
 	// <bad-url-token> (https://drafts.csswg.org/css-syntax/#typedef-bad-url-token)
 	BadUrl,
@@ -91,10 +90,12 @@ pub enum Token {
 	Delim(char),
 
 	// <number-token> (https://drafts.csswg.org/css-syntax/#number-token-diagram)
-	Number(f32, NumType),
+	// The last field is the number as it was written in the source text, such as `1e3` or `.50`.
+	Number(f64, NumType, &'a str),
 
 	// <dimension-token> (https://drafts.csswg.org/css-syntax/#dimension-token-diagram)
-	Dimension(f32, Atom, NumType),
+	// The last field is the number as it was written in the source text, without the unit.
+	Dimension(f64, &'a str, NumType, &'a str),
 
 	// <whitespace-token> (https://drafts.csswg.org/css-syntax/#whitespace-token-diagram)
 	Whitespace,
@@ -134,7 +135,7 @@ pub enum Token {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Default, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "kind", content = "value"))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(tag = "kind", content = "value"))]
 pub enum QuoteStyle {
 	// Some tokens/ast nodesthat would otherwise be strings (e.g. url(), named fonts) can have no quotes.
 	None,
@@ -143,7 +144,7 @@ pub enum QuoteStyle {
 	Double,
 }
 
-impl Token {
+impl<'a> Token<'a> {
 	#[inline]
 	pub fn is_trivia(&self) -> bool {
 		matches!(self, Token::Whitespace | Token::Comment(_))
@@ -168,7 +169,7 @@ impl Token {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PairWise {
 	Paren,
 	Curly,
@@ -188,7 +189,7 @@ impl PairWise {
 		}
 	}
 
-	pub fn start(&self) -> &Token {
+	pub fn start(&self) -> &Token<'static> {
 		match self {
 			Self::Paren => &Token::LeftParen,
 			Self::Curly => &Token::LeftCurly,
@@ -196,7 +197,7 @@ impl PairWise {
 		}
 	}
 
-	pub fn end(&self) -> &Token {
+	pub fn end(&self) -> &Token<'static> {
 		match self {
 			Self::Paren => &Token::RightParen,
 			Self::Curly => &Token::RightCurly,
@@ -205,13 +206,13 @@ impl PairWise {
 	}
 }
 
-impl std::fmt::Display for Token {
+impl<'a> std::fmt::Display for Token<'a> {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		write!(f, "{:?}", self)
 	}
 }
 
-impl Hash for Token {
+impl<'a> Hash for Token<'a> {
 	fn hash<H: Hasher>(&self, state: &mut H) {
 		match self {
 			Token::Undetermined => {}
@@ -260,16 +261,18 @@ impl Hash for Token {
 				11.hash(state);
 				c.hash(state);
 			}
-			Token::Number(f, n) => {
+			Token::Number(f, n, r) => {
 				12.hash(state);
 				f.to_bits().hash(state);
 				n.hash(state);
+				r.hash(state);
 			}
-			Token::Dimension(f, a, n) => {
+			Token::Dimension(f, a, n, r) => {
 				13.hash(state);
 				f.to_bits().hash(state);
 				a.hash(state);
 				n.hash(state);
+				r.hash(state);
 			}
 			Token::Whitespace => 14.hash(state),
 			Token::Cdo => 15.hash(state),
@@ -1,7 +1,6 @@
 use bumpalo::Bump;
 use console::Style;
 use glob::glob;
-use hdx_atom::atom;
 use hdx_lexer::{Include, Lexer, Token};
 use serde::{Deserialize, Serialize};
 use serde_json::{from_str, to_string_pretty};
@@ -21,7 +20,7 @@ enum Structured {
 #[derive(PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct NumberStructured {
-	value: f32,
+	value: f64,
 	#[serde(rename = "type")]
 	kind: Option<String>,
 }
@@ -29,7 +28,7 @@ struct NumberStructured {
 #[derive(PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct DimensionStructured {
-	value: f32,
+	value: f64,
 	#[serde(rename = "type")]
 	kind: String,
 	unit: String,
@@ -141,12 +140,12 @@ impl CSSTokenizerTestCase {
 fn convert_token(source_text: &str, start: usize, end: usize, token: &Token) -> CSSTokenizerTestToken {
 	let raw = source_text[start..end].to_string();
 	let structured = match &token {
-		Token::Number(value, numtype) => Some(Structured::Number(NumberStructured {
+		Token::Number(value, numtype, _) => Some(Structured::Number(NumberStructured {
 			value: *value,
 			kind: Some(String::from(if numtype.is_int() { "integer" } else { "number" })),
 		})),
-		Token::Dimension(value, unit, numtype) => {
-			if unit == &atom!("%") {
+		Token::Dimension(value, unit, numtype, _) => {
+			if *unit == "%" {
 				Some(Structured::Number(NumberStructured { value: *value, kind: None }))
 			} else {
 				Some(Structured::Dimension(DimensionStructured {
@@ -183,9 +182,9 @@ fn convert_token(source_text: &str, start: usize, end: usize, token: &Token) ->
 			Token::Url(_, _) => CSSTokenizerTestKind::Url,
 			Token::BadUrl => CSSTokenizerTestKind::BadUrl,
 			Token::Delim(_) => CSSTokenizerTestKind::Delim,
-			Token::Number(_, _) => CSSTokenizerTestKind::Number,
-			Token::Dimension(_, unit, _) => {
-				if unit == &atom!("%") {
+			Token::Number(_, _, _) => CSSTokenizerTestKind::Number,
+			Token::Dimension(_, unit, _, _) => {
+				if *unit == "%" {
 					CSSTokenizerTestKind::Percentage
 				} else {
 					CSSTokenizerTestKind::Dimension
@@ -1,10 +1,9 @@
 use bumpalo::Bump;
-use hdx_atom::{atom, Atom};
-use hdx_lexer::{Include, Lexer, NumType, Token};
+use hdx_lexer::{Include, Lexer, NumType, QuoteStyle, Token};
 
 #[test]
 fn size_test() {
-	assert_eq!(::std::mem::size_of::<Token>(), 16);
+	assert_eq!(::std::mem::size_of::<Token>(), 48);
 }
 
 #[test]
@@ -75,7 +74,7 @@ fn tokenizes_trivial_css_file() {
 	let allocator = Bump::default();
 	let mut lex = Lexer::new(&allocator, "body { color: black }/* fin */");
 	assert_eq!(lex.pos(), 0);
-	assert_eq!(lex.advance_with(Include::all()), Token::Ident(atom!("body")));
+	assert_eq!(lex.advance_with(Include::all()), Token::Ident("body"));
 	assert_eq!(lex.pos(), 4);
 	assert_eq!(lex.advance_with(Include::all()), Token::Whitespace);
 	assert_eq!(lex.pos(), 5);
@@ -83,19 +82,19 @@ fn tokenizes_trivial_css_file() {
 	assert_eq!(lex.pos(), 6);
 	assert_eq!(lex.advance_with(Include::all()), Token::Whitespace);
 	assert_eq!(lex.pos(), 7);
-	assert_eq!(lex.advance_with(Include::all()), Token::Ident(atom!("color")));
+	assert_eq!(lex.advance_with(Include::all()), Token::Ident("color"));
 	assert_eq!(lex.pos(), 12);
 	assert_eq!(lex.advance_with(Include::all()), Token::Colon);
 	assert_eq!(lex.pos(), 13);
 	assert_eq!(lex.advance_with(Include::all()), Token::Whitespace);
 	assert_eq!(lex.pos(), 14);
-	assert_eq!(lex.advance_with(Include::all()), Token::Ident(atom!("black")));
+	assert_eq!(lex.advance_with(Include::all()), Token::Ident("black"));
 	assert_eq!(lex.pos(), 19);
 	assert_eq!(lex.advance_with(Include::all()), Token::Whitespace);
 	assert_eq!(lex.pos(), 20);
 	assert_eq!(lex.advance_with(Include::all()), Token::RightCurly);
 	assert_eq!(lex.pos(), 21);
-	assert_eq!(lex.advance_with(Include::all()), Token::Comment(Atom::from(" fin ")));
+	assert_eq!(lex.advance_with(Include::all()), Token::Comment(" fin "));
 	assert_eq!(lex.pos(), 30);
 	assert_eq!(lex.advance_with(Include::all()), Token::Eof);
 	assert_eq!(lex.pos(), 30);
@@ -106,18 +105,75 @@ fn skips_whitespace_and_comments_with_next() {
 	let allocator = Bump::default();
 	let mut lex = Lexer::new(&allocator, "body { color: black }/* fin */");
 	assert_eq!(lex.pos(), 0);
-	assert_eq!(lex.advance(), Token::Ident(atom!("body")));
+	assert_eq!(lex.advance(), Token::Ident("body"));
 	assert_eq!(lex.pos(), 4);
 	assert_eq!(lex.advance(), Token::LeftCurly);
 	assert_eq!(lex.pos(), 6);
-	assert_eq!(lex.advance(), Token::Ident(atom!("color")));
+	assert_eq!(lex.advance(), Token::Ident("color"));
 	assert_eq!(lex.pos(), 12);
 	assert_eq!(lex.advance(), Token::Colon);
 	assert_eq!(lex.pos(), 13);
-	assert_eq!(lex.advance(), Token::Ident(atom!("black")));
+	assert_eq!(lex.advance(), Token::Ident("black"));
 	assert_eq!(lex.pos(), 19);
 	assert_eq!(lex.advance(), Token::RightCurly);
 	assert_eq!(lex.pos(), 21);
 	assert_eq!(lex.advance(), Token::Eof);
 	assert_eq!(lex.pos(), 30);
 }
+
+#[test]
+fn borrows_from_source_unless_escaped() {
+	let allocator = Bump::default();
+	let source = "a\\62 c 'def'";
+	let mut lex = Lexer::new(&allocator, source);
+	assert_eq!(lex.advance(), Token::Ident("abc"));
+	let lexeme = lex.advance_lexeme();
+	assert_eq!(lexeme.token, Token::String("def", QuoteStyle::Single));
+	assert_eq!((lexeme.start, lexeme.end), (7, 12));
+	if let Token::String(str, _) = lexeme.token {
+		assert_eq!(str.as_ptr(), source[8..].as_ptr());
+	}
+}
+
+#[test]
+fn rewinds_to_checkpoint() {
+	let allocator = Bump::default();
+	let mut lex = Lexer::new(&allocator, "a /* b */ c d");
+	let checkpoint = lex.checkpoint();
+	assert_eq!(lex.lookahead(2), &Token::Ident("c"));
+	assert_eq!(lex.pos(), 0);
+	assert_eq!(lex.advance(), Token::Ident("a"));
+	let lexeme = lex.advance_lexeme();
+	assert_eq!((lexeme.token, lexeme.start, lexeme.end), (Token::Ident("c"), 10, 11));
+	lex.rewind(checkpoint);
+	assert_eq!(lex.pos(), 0);
+	assert_eq!(lex.advance(), Token::Ident("a"));
+	assert_eq!(lex.advance_with(Include::Comments), Token::Comment(" b "));
+	assert_eq!(lex.advance(), Token::Ident("c"));
+	assert_eq!(lex.advance(), Token::Ident("d"));
+	assert_eq!(lex.advance(), Token::Eof);
+}
+
+#[test]
+fn rewinds_further_than_it_keeps_tokens() {
+	let allocator = Bump::default();
+	let source_text = "a ".repeat(1000);
+	let mut lex = Lexer::new(&allocator, &source_text);
+	let checkpoint = lex.checkpoint();
+	while lex.advance() != Token::Eof {}
+	lex.rewind(checkpoint);
+	for n in 0..1000 {
+		assert_eq!(lex.advance_lexeme().start, n * 2);
+	}
+	assert_eq!(lex.advance(), Token::Eof);
+}
+
+#[test]
+fn keeps_number_as_written() {
+	let allocator = Bump::default();
+	let mut lex = Lexer::new(&allocator, "1e3 1000 2147483647 .50%");
+	assert_eq!(lex.advance(), Token::Number(1000.0, NumType::Float, "1e3"));
+	assert_eq!(lex.advance(), Token::Number(1000.0, NumType::none(), "1000"));
+	assert_eq!(lex.advance(), Token::Number(2147483647.0, NumType::none(), "2147483647"));
+	assert_eq!(lex.advance(), Token::Dimension(0.5, "%", NumType::Float, ".50"));
+}
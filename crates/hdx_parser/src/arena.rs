@@ -0,0 +1,219 @@
+//! Deserializing nodes into an arena, so that a serialized AST, such as the JSON from `hdx_wasm::parse()`, can be
+//! read back in and written out as CSS.
+//!
+//! Nodes keep their children in a [Bump], which plain [Deserialize] has no way to be given. An [ArenaSeed] carries
+//! one instead, and makes it available to the fields which are allocated in it while deserializing its node. Those
+//! fields are deserialized by one of the functions in this module, which fail if they're used other than through an
+//! [ArenaSeed]. Nothing ties the lifetime they allocate for to that of the seed's arena though, so they're unsafe, and
+//! each crate with nodes names them through its own private functions, from `#[serde(deserialize_with = "...")]`.
+//!
+//! The arena is found through a thread local while the seed deserializes, which can't tell the seed's own nodes from
+//! any others the [Deserializer] it's given deserializes in the meantime, so making an [ArenaSeed] is unsafe too.
+
+use std::{cell::Cell, marker::PhantomData, ptr::NonNull};
+
+use bumpalo::Bump;
+use hdx_lexer::{NumType, QuoteStyle, Token};
+use serde::{
+	de::{DeserializeSeed, Error},
+	Deserialize, Deserializer,
+};
+
+use crate::{Box, Commented, ComplexSelector, Spanned, Vec};
+
+thread_local! {
+	// The arena of the ArenaSeed currently deserializing, if there is one.
+	static ARENA: Cell<Option<NonNull<Bump>>> = const { Cell::new(None) };
+}
+
+/// A node which can be deserialized into the arena `'a` by an [ArenaSeed].
+///
+/// # Safety
+///
+/// Everything the node allocates must live for `'a`, so `'a` has to be the lifetime of each arena allocated field
+/// within it.
+pub unsafe trait DeserializeIn<'a>: Sized {}
+
+unsafe impl<'a, T: DeserializeIn<'a>> DeserializeIn<'a> for Spanned<T> {}
+unsafe impl<'a, T: DeserializeIn<'a>> DeserializeIn<'a> for Commented<'a, T> {}
+
+/// Deserializes a `T`, allocating its children in `allocator`.
+pub struct ArenaSeed<'a, T> {
+	allocator: &'a Bump,
+	node: PhantomData<T>,
+}
+
+impl<'a, T> ArenaSeed<'a, T> {
+	/// # Safety
+	///
+	/// The [Deserializer] the seed is used with mustn't deserialize any node other than the seed's own while it runs,
+	/// as that would be allocated in `allocator` too, whatever its lifetime. Those of `serde_json` and
+	/// `serde_wasm_bindgen` only deserialize what they're asked to.
+	pub unsafe fn new(allocator: &'a Bump) -> Self {
+		Self { allocator, node: PhantomData }
+	}
+}
+
+impl<'de, 'a, T: DeserializeIn<'a> + Deserialize<'de>> DeserializeSeed<'de> for ArenaSeed<'a, T> {
+	type Value = T;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+		// Seeds can be used within the deserializing of another, so the outer arena is put back afterwards.
+		struct Restore(Option<NonNull<Bump>>);
+		impl Drop for Restore {
+			fn drop(&mut self) {
+				ARENA.with(|arena| arena.set(self.0));
+			}
+		}
+		let _restore = Restore(ARENA.with(|arena| arena.replace(Some(NonNull::from(self.allocator)))));
+		T::deserialize(deserializer)
+	}
+}
+
+// The arena of the ArenaSeed deserializing the current node. The caller picks 'a, which has to be the lifetime of
+// that node, as the seed's is.
+fn arena<'a, E: Error>() -> Result<&'a Bump, E> {
+	match ARENA.with(|arena| arena.get()) {
+		// SAFETY: the arena is only set while an ArenaSeed is deserializing, which borrows it for 'a, and its
+		// deserializer deserializes no other nodes meanwhile, as ArenaSeed::new requires.
+		Some(ptr) => Ok(unsafe { ptr.as_ref() }),
+		None => Err(E::custom("nodes with children can only be deserialized with an ArenaSeed")),
+	}
+}
+
+/// Deserializes a list into the arena.
+///
+/// # Safety
+///
+/// `'a` must be the lifetime of the arena of the [ArenaSeed] deserializing the node, which it is for a field of a node
+/// that is [DeserializeIn<'a>](DeserializeIn).
+pub unsafe fn vec<'de, 'a, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D) -> Result<Vec<'a, T>, D::Error> {
+	let items = std::vec::Vec::<T>::deserialize(deserializer)?;
+	Ok(Vec::from_iter_in(items, arena()?))
+}
+
+/// Deserializes a node into a box in the arena.
+///
+/// # Safety
+///
+/// `'a` must be the lifetime of the arena of the [ArenaSeed] deserializing the node, which it is for a field of a node
+/// that is [DeserializeIn<'a>](DeserializeIn).
+pub unsafe fn boxed<'de, 'a, D: Deserializer<'de>, T: Deserialize<'de>>(
+	deserializer: D,
+) -> Result<Box<'a, T>, D::Error> {
+	let node = T::deserialize(deserializer)?;
+	Ok(Box::new_in(node, arena()?))
+}
+
+/// Deserializes an optional node into a box in the arena.
+///
+/// # Safety
+///
+/// `'a` must be the lifetime of the arena of the [ArenaSeed] deserializing the node, which it is for a field of a node
+/// that is [DeserializeIn<'a>](DeserializeIn).
+pub unsafe fn option_boxed<'de, 'a, D: Deserializer<'de>, T: Deserialize<'de>>(
+	deserializer: D,
+) -> Result<Option<Box<'a, T>>, D::Error> {
+	match Option::<T>::deserialize(deserializer)? {
+		Some(node) => Ok(Some(Box::new_in(node, arena()?))),
+		None => Ok(None),
+	}
+}
+
+/// Deserializes a list of complex selectors, along with the compounds of each, into the arena.
+///
+/// # Safety
+///
+/// `'a` must be the lifetime of the arena of the [ArenaSeed] deserializing the node, which it is for a field of a node
+/// that is [DeserializeIn<'a>](DeserializeIn).
+pub unsafe fn complex_selectors<'de, 'a, D: Deserializer<'de>, T: Deserialize<'de>>(
+	deserializer: D,
+) -> Result<Vec<'a, ComplexSelector<'a, T>>, D::Error> {
+	// The compounds of each selector are in the arena too, which a field attribute can't reach.
+	struct Compounds<'a, T>(Vec<'a, T>);
+	impl<'de, 'a, T: Deserialize<'de>> Deserialize<'de> for Compounds<'a, T> {
+		fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+			// SAFETY: the compounds are allocated for the same 'a as the selectors they're within.
+			unsafe { vec(deserializer).map(Self) }
+		}
+	}
+	let selectors = std::vec::Vec::<Spanned<Commented<'a, Compounds<'a, T>>>>::deserialize(deserializer)?;
+	let selectors = selectors
+		.into_iter()
+		.map(|selector| selector.map(|Commented { node, trivia }| Commented { node: node.0, trivia }));
+	Ok(Vec::from_iter_in(selectors, arena()?))
+}
+
+// Tokens borrow their strings, so are read as one of these first.
+#[derive(Deserialize)]
+#[serde(tag = "kind", content = "value")]
+enum OwnedToken {
+	Undetermined,
+	Eof,
+	Comment(String),
+	Ident(String),
+	Function(String),
+	AtKeyword(String),
+	Hash(String),
+	HashId(String),
+	String(String, QuoteStyle),
+	BadString,
+	Url(String, QuoteStyle),
+	BadUrl,
+	Delim(char),
+	Number(f64, NumType, String),
+	Dimension(f64, String, NumType, String),
+	Whitespace,
+	Cdo,
+	Cdc,
+	Colon,
+	Semicolon,
+	Comma,
+	LeftSquare,
+	RightSquare,
+	LeftParen,
+	RightParen,
+	LeftCurly,
+	RightCurly,
+}
+
+/// Deserializes a token, allocating its text in the arena.
+///
+/// # Safety
+///
+/// `'a` must be the lifetime of the arena of the [ArenaSeed] deserializing the node, which it is for a field of a node
+/// that is [DeserializeIn<'a>](DeserializeIn).
+pub unsafe fn token<'de, 'a, D: Deserializer<'de>>(deserializer: D) -> Result<Token<'a>, D::Error> {
+	let token = OwnedToken::deserialize(deserializer)?;
+	let allocator = arena()?;
+	let str = |str: String| &*allocator.alloc_str(&str);
+	Ok(match token {
+		OwnedToken::Undetermined => Token::Undetermined,
+		OwnedToken::Eof => Token::Eof,
+		OwnedToken::Comment(text) => Token::Comment(str(text)),
+		OwnedToken::Ident(name) => Token::Ident(str(name)),
+		OwnedToken::Function(name) => Token::Function(str(name)),
+		OwnedToken::AtKeyword(name) => Token::AtKeyword(str(name)),
+		OwnedToken::Hash(hash) => Token::Hash(str(hash)),
+		OwnedToken::HashId(hash) => Token::HashId(str(hash)),
+		OwnedToken::String(string, quote) => Token::String(str(string), quote),
+		OwnedToken::BadString => Token::BadString,
+		OwnedToken::Url(url, quote) => Token::Url(str(url), quote),
+		OwnedToken::BadUrl => Token::BadUrl,
+		OwnedToken::Delim(ch) => Token::Delim(ch),
+		OwnedToken::Number(n, ty, text) => Token::Number(n, ty, str(text)),
+		OwnedToken::Dimension(n, unit, ty, text) => Token::Dimension(n, str(unit), ty, str(text)),
+		OwnedToken::Whitespace => Token::Whitespace,
+		OwnedToken::Cdo => Token::Cdo,
+		OwnedToken::Cdc => Token::Cdc,
+		OwnedToken::Colon => Token::Colon,
+		OwnedToken::Semicolon => Token::Semicolon,
+		OwnedToken::Comma => Token::Comma,
+		OwnedToken::LeftSquare => Token::LeftSquare,
+		OwnedToken::RightSquare => Token::RightSquare,
+		OwnedToken::LeftParen => Token::LeftParen,
+		OwnedToken::RightParen => Token::RightParen,
+		OwnedToken::LeftCurly => Token::LeftCurly,
+		OwnedToken::RightCurly => Token::RightCurly,
+	})
+}
@@ -2,7 +2,7 @@ use crate::{unexpected, Parse, Parser, Result};
 use hdx_lexer::{Include, Token};
 
 #[derive(Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type"))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
 pub enum Comparison {
 	LessThan,         // '<'
 	GreaterThan,      // '>'
@@ -13,7 +13,7 @@ pub enum Comparison {
 
 impl<'a> Parse<'a> for Comparison {
 	fn parse(parser: &mut Parser<'a>) -> Result<Comparison> {
-		Ok(match parser.next() {
+		Ok(match parser.next_token() {
 			Token::Delim('=') => Comparison::Equal,
 			Token::Delim('>') => {
 				if let Token::Delim('=') = parser.peek_with(Include::Whitespace) {
@@ -3,16 +3,17 @@ use hdx_lexer::{Include, LexerCheckpoint, Token};
 use crate::{span::Span, Parser};
 
 pub struct ParserCheckpoint<'a> {
-	lexer: LexerCheckpoint<'a>,
-	token: Token,
+	lexer: LexerCheckpoint,
+	token: Token<'a>,
 	prev_pos: u32,
-	warnings_pos: usize,
+	pub(crate) warnings_pos: usize,
 	errors_pos: usize,
+	claimed_pos: usize,
 }
 
 impl<'a> Parser<'a> {
 	#[inline]
-	pub fn cur(&self) -> &Token {
+	pub fn cur(&self) -> &Token<'a> {
 		&self.token
 	}
 
@@ -26,23 +27,38 @@ impl<'a> Parser<'a> {
 		Span::new(self.pos(), self.pos())
 	}
 
+	/// The span of the token last moved past, without any whitespace ahead of it.
+	pub fn cur_span(&self) -> Span {
+		let end = self.pos();
+		let text = &self.lexer.source()[self.prev_pos as usize..end as usize];
+		Span::new(end - text.trim_start().len() as u32, end)
+	}
+
+	/// The span of the next token, without any whitespace or comments ahead of it.
+	pub fn peek_span(&mut self) -> Span {
+		let checkpoint = self.lexer.checkpoint();
+		let lexeme = self.lexer.advance_lexeme();
+		self.lexer.rewind(checkpoint);
+		Span::new(lexeme.start, lexeme.end)
+	}
+
 	#[inline]
-	pub fn peek(&mut self) -> &Token {
+	pub fn peek(&mut self) -> &Token<'a> {
 		self.lexer.lookahead(1)
 	}
 
 	#[inline]
-	pub fn peek_with(&mut self, inc: Include) -> &Token {
+	pub fn peek_with(&mut self, inc: Include) -> &Token<'a> {
 		self.lexer.lookahead_with(1, inc)
 	}
 
 	#[inline]
-	pub fn peek_n(&mut self, n: u8) -> &Token {
+	pub fn peek_n(&mut self, n: u8) -> &Token<'a> {
 		self.lexer.lookahead(n)
 	}
 
 	#[inline]
-	pub fn peek_n_with(&mut self, n: u8, inc: Include) -> &Token {
+	pub fn peek_n_with(&mut self, n: u8, inc: Include) -> &Token<'a> {
 		self.lexer.lookahead_with(n, inc)
 	}
 
@@ -64,26 +80,27 @@ impl<'a> Parser<'a> {
 	}
 
 	#[inline]
-	pub fn next(&mut self) -> &Token {
+	pub fn next_token(&mut self) -> &Token<'a> {
 		self.prev_pos = self.lexer.pos();
 		self.token = self.lexer.advance();
 		&self.token
 	}
 
 	#[inline]
-	pub fn next_with(&mut self, inc: Include) -> &Token {
+	pub fn next_token_with(&mut self, inc: Include) -> &Token<'a> {
 		self.prev_pos = self.lexer.pos();
 		self.token = self.lexer.advance_with(inc);
 		&self.token
 	}
 
 	pub fn rewind(&mut self, checkpoint: ParserCheckpoint<'a>) {
-		let ParserCheckpoint { lexer, prev_pos, token, warnings_pos, errors_pos } = checkpoint;
+		let ParserCheckpoint { lexer, prev_pos, token, warnings_pos, errors_pos, claimed_pos } = checkpoint;
 		self.lexer.rewind(lexer);
 		self.token = token;
 		self.prev_pos = prev_pos;
 		self.warnings.truncate(warnings_pos);
 		self.errors.truncate(errors_pos);
+		self.claimed.truncate(claimed_pos);
 	}
 
 	pub fn checkpoint(&self) -> ParserCheckpoint<'a> {
@@ -93,6 +110,7 @@ impl<'a> Parser<'a> {
 			token: self.token.clone(),
 			warnings_pos: self.warnings.len(),
 			errors_pos: self.errors.len(),
+			claimed_pos: self.claimed.len(),
 		}
 	}
 }
@@ -0,0 +1,378 @@
+use std::fmt::{Display, Formatter};
+
+use miette::{Error, LabeledSpan};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{diagnostics, span::Span, ShiftSpans};
+
+/// A warning or error from parsing, with its parts laid out so they can be reported in any format, or acted on.
+/// It's also a [miette::Diagnostic], so miette can render it.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Diagnostic {
+	pub code: DiagnosticCode,
+	pub severity: Severity,
+	pub message: String,
+	pub help: Option<String>,
+	/// The first of these is the primary span; it's where the problem is.
+	pub labels: Vec<Label>,
+	/// Edits to the source text which would resolve the problem.
+	pub fixes: Vec<Fix>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	error: Error,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "lowercase"))]
+pub enum Severity {
+	#[default]
+	Error,
+	Warning,
+	Advice,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Label {
+	pub span: Span,
+	pub message: Option<String>,
+	pub primary: bool,
+}
+
+/// Replacing the text at `span` with `replacement` is safe to do without asking; it's what was meant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Fix {
+	pub span: Span,
+	pub replacement: String,
+}
+
+macro_rules! diagnostic_codes {
+	($($name: ident: $code: literal,)+) => {
+		/// Which of the [diagnostics] a [Diagnostic] is. These are stable, so they can be matched on, and used to
+		/// configure which are reported.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum DiagnosticCode {
+			$($name,)+
+			/// One which didn't come from hdx_parser.
+			Other,
+		}
+
+		impl DiagnosticCode {
+			#[cfg(test)]
+			const ALL: &'static [Self] = &[$(Self::$name,)+];
+
+			fn of(error: &Error) -> Self {
+				$(if error.is::<diagnostics::$name>() {
+					return Self::$name;
+				})+
+				Self::Other
+			}
+
+			/// The code as miette prints it, such as `hdx_parser::UnknownRule`.
+			pub fn as_str(&self) -> &'static str {
+				match self {
+					$(Self::$name => $code,)+
+					Self::Other => "hdx_parser::Other",
+				}
+			}
+		}
+	};
+}
+
+diagnostic_codes! {
+	Unimplemented: "hdx_parser::Unimplemented",
+	DisallowedAtRulePrelude: "hdx_parser::DisllowedAtRulePrelude",
+	DisallowedAtRuleBlock: "hdx_parser::DisllowedAtRuleBlock",
+	MissingAtRulePrelude: "hdx_parser::MissingAtRulePrelude",
+	MissingAtRuleBlock: "hdx_parser::MissingAtRuleBlock",
+	BadDeclaration: "hdx_parser::BadDeclaration",
+	Unexpected: "hdx_parser::Unexpected",
+	UnexpectedCharset: "hdx_parser::UnexpectedCharset",
+	UnexpectedIdent: "hdx_parser::UnexpectedIdent",
+	UnexpectedIdentSuggest: "hdx_parser::UnexpectedIdentSuggest",
+	UnexpectedDuplicateIdent: "hdx_parser::UnexpectedDuplicateIdent",
+	UnexpectedDelim: "hdx_parser::UnexpectedDelim",
+	UnexpectedPseudoClass: "hdx_parser::UnexpectedPseudo",
+	UnexpectedPseudoClassSuggest: "hdx_parser::UnexpectedPseudoClassSuggest",
+	UnexpectedPseudoClassFunction: "hdx_parser::UnexpectedPseudoClassFunction",
+	UnexpectedPseudoElement: "hdx_parser::UnexpectedPseudoElement",
+	UnexpectedPseudoElementFunction: "hdx_parser::UnexpectedPseudoElementFunction",
+	UnexpectedPseudoElementSuggest: "hdx_parser::UnexpectedPseudoElementSuggest",
+	UnexpectedTag: "hdx_parser::UnexpectedTag",
+	UnknownTagSuggest: "hdx_parser::UnknownTagSuggest",
+	UnexpectedId: "hdx_parser::UnexpectedId",
+	UnexpectedDimension: "hdx_parser::UnexpectedDimension",
+	UnexpectedAtRule: "hdx_parser::UnexpectedAtRule",
+	UnexpectedFunction: "hdx_parser::UnexpectedFunction",
+	UnknownRule: "hdx_parser::UnknownRule",
+	UnknownAtRuleSuggest: "hdx_parser::UnknownAtRuleSuggest",
+	UnknownDeclaration: "hdx_parser::UnknownDeclaration",
+	UnknownValue: "hdx_parser::UnknownValue",
+	InvalidValue: "hdx_parser::InvalidValue",
+	UnknownProperty: "hdx_parser::UnknownProperty",
+	UnknownPropertySuggest: "hdx_parser::UnknownPropertySuggest",
+	DisallowedIeHack: "hdx_parser::DisallowedIeHack",
+	DisallowedVendorPrefix: "hdx_parser::DisallowedVendorPrefix",
+	UnknownColor: "hdx_parser::UnknownColor",
+	ExpectedEnd: "hdx_parser::ExpectedEnd",
+	ExpectedToken: "hdx_parser::ExpectedToken",
+	ExpectedDimension: "hdx_parser::ExpectedDimension",
+	ExpectedIdent: "hdx_parser::ExpectedIdent",
+	ExpectedOtherIdent: "hdx_parser::ExpectedOtherIdent",
+	ExpectedIdentOf: "hdx_parser::ExpectedIdentOf",
+	ExpectedFunction: "hdx_parser::ExpectedFunction",
+	ExpectedFunctionOf: "hdx_parser::ExpectedFunctionOf",
+	ExpectedAtKeyword: "hdx_parser::ExpectedAtKeyword",
+	ExpectedAtKeywordOf: "hdx_parser::ExpectedAtKeywordOf",
+	ExpectedDelim: "hdx_parser::ExpectedDelim",
+	ExpectedDelimOf: "hdx_parser::ExpectedDelimOf",
+	WarnTrailing: "hdx_parser::WarnTrailing",
+	BadHexColor: "hdx_parser::BadHexColor",
+	NoSelector: "hdx_parser::NoSelector",
+	AdjacentSelectorCombinators: "hdx_parser::AdjacentSelectorCombinators",
+	AdjacentSelectorTypes: "hdx_parser::AdjacentSelectorTypes",
+	DisallowedValueWithoutDimension: "hdx_parser::DisallowedValueWithoutDimension",
+	DisallowedMathFunction: "hdx_parser::DisallowedMathFunction",
+	ExpectedNumber: "hdx_parser::ExpectedNumber",
+	ExpectedSign: "hdx_parser::ExpectedSign",
+	ExpectedUnsigned: "hdx_parser::ExpectedUnsigned",
+	NumberOutOfBounds: "hdx_parser::NumberOutOfBounds",
+	NumberNotNegative: "hdx_parser::NumberNotNegative",
+	NumberTooSmall: "hdx_parser::NumberTooSmall",
+	ExpectedInt: "hdx_parser::ExpectedInt",
+	ExpectedFloat: "hdx_parser::ExpectedFloat",
+	ExpectedZero: "hdx_parser::ExpectedZero",
+	DisplayHasInvalidListItemCombo: "hdx_parser::DisplayHasInvalidListItemCombo",
+}
+
+impl Display for DiagnosticCode {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for DiagnosticCode {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_str())
+	}
+}
+
+impl Diagnostic {
+	pub fn new(error: Error, severity: Severity) -> Self {
+		// Diagnostics which have been moved, such as those kept when reparsing, are errors holding a Diagnostic.
+		let error = match error.downcast::<Diagnostic>() {
+			Ok(diagnostic) => return Self { severity, ..diagnostic },
+			Err(error) => error,
+		};
+		let mut labels: Vec<Label> = error
+			.labels()
+			.into_iter()
+			.flatten()
+			.map(|label| Label {
+				span: Span::new(label.offset() as u32, (label.offset() + label.len()) as u32),
+				message: label.label().map(String::from),
+				primary: label.primary(),
+			})
+			.collect();
+		// miette only marks a label as primary when asked to, and none of the parser's are, so that falls to the first.
+		if let Some(index) = labels.iter().position(|label| label.primary) {
+			labels[..=index].rotate_right(1);
+		} else if let Some(label) = labels.first_mut() {
+			label.primary = true;
+		}
+		Self {
+			code: DiagnosticCode::of(&error),
+			severity,
+			message: error.to_string(),
+			help: error.help().map(|help| help.to_string()),
+			labels,
+			fixes: fixes(&error),
+			error,
+		}
+	}
+
+	/// Where the problem is.
+	pub fn span(&self) -> Option<Span> {
+		self.labels.first().map(|label| label.span)
+	}
+
+	/// The struct from [diagnostics] this was made from, which holds the details, such as which ident was unexpected.
+	/// Its spans are where it was first reported, even if the diagnostic has been [shifted](ShiftSpans) since.
+	pub fn downcast_ref<T: Display + std::fmt::Debug + Send + Sync + 'static>(&self) -> Option<&T> {
+		self.error.downcast_ref::<T>()
+	}
+
+	pub fn into_error(self) -> Error {
+		self.error
+	}
+}
+
+impl ShiftSpans for Diagnostic {
+	fn shift_spans(&mut self, delta: i32) {
+		for label in &mut self.labels {
+			label.span.shift_spans(delta);
+		}
+		for fix in &mut self.fixes {
+			fix.span.shift_spans(delta);
+		}
+	}
+}
+
+fn fixes(error: &Error) -> Vec<Fix> {
+	// Each of these has what was written, what was meant by it, and where it was written.
+	macro_rules! suggestions {
+		($($name: ident$(($prefix: literal))?,)+) => {
+			$(if let Some(diagnostics::$name(_, suggestion, span)) = error.downcast_ref() {
+				return vec![Fix { span: *span, replacement: format!(concat!($($prefix,)? "{}"), suggestion) }];
+			})+
+		};
+	}
+	suggestions! {
+		UnexpectedIdentSuggest,
+		UnexpectedPseudoClassSuggest,
+		UnexpectedPseudoElementSuggest,
+		UnknownTagSuggest,
+		UnknownAtRuleSuggest("@"),
+		UnknownPropertySuggest,
+	}
+	vec![]
+}
+
+/// Makes the `fixes` to `source_text`. Where fixes overlap, only the first is made.
+pub fn apply_fixes<'f>(source_text: &str, fixes: impl IntoIterator<Item = &'f Fix>) -> String {
+	let mut fixes: Vec<&Fix> = fixes.into_iter().collect();
+	fixes.sort_by_key(|fix| fix.span.start);
+	let mut output = String::with_capacity(source_text.len());
+	let mut pos = 0;
+	for fix in fixes {
+		if (fix.span.start as usize) < pos {
+			continue;
+		}
+		output.push_str(&source_text[pos..fix.span.start as usize]);
+		output.push_str(&fix.replacement);
+		pos = fix.span.end as usize;
+	}
+	output.push_str(&source_text[pos..]);
+	output
+}
+
+impl Display for Diagnostic {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.message)
+	}
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl miette::Diagnostic for Diagnostic {
+	fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+		Some(Box::new(self.code))
+	}
+
+	fn severity(&self) -> Option<miette::Severity> {
+		Some(self.severity.into())
+	}
+
+	fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+		self.help.as_ref().map(|help| Box::new(help) as Box<dyn Display>)
+	}
+
+	fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+		Some(Box::new(self.labels.iter().map(|label| {
+			let message = label.message.clone();
+			if label.primary {
+				LabeledSpan::new_primary_with_span(message, label.span)
+			} else {
+				LabeledSpan::new_with_span(message, label.span)
+			}
+		})))
+	}
+}
+
+impl From<Severity> for miette::Severity {
+	fn from(severity: Severity) -> Self {
+		match severity {
+			Severity::Error => Self::Error,
+			Severity::Warning => Self::Warning,
+			Severity::Advice => Self::Advice,
+		}
+	}
+}
+
+impl From<miette::Severity> for Severity {
+	fn from(severity: miette::Severity) -> Self {
+		match severity {
+			miette::Severity::Error => Self::Error,
+			miette::Severity::Warning => Self::Warning,
+			miette::Severity::Advice => Self::Advice,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hdx_atom::atom;
+
+	#[test]
+	fn test_diagnostic() {
+		let error: Error = diagnostics::UnknownAtRuleSuggest(atom!("meida"), atom!("media"), Span::new(0, 6)).into();
+		let diagnostic = Diagnostic::new(error, Severity::Warning);
+		assert_eq!(diagnostic.code, DiagnosticCode::UnknownAtRuleSuggest);
+		assert_eq!(diagnostic.code.as_str(), "hdx_parser::UnknownAtRuleSuggest");
+		assert_eq!(diagnostic.span(), Some(Span::new(0, 6)));
+		assert!(diagnostic.labels[0].primary);
+		assert_eq!(diagnostic.help.as_deref(), Some("Try changing this to '@media'"));
+		assert_eq!(diagnostic.fixes, vec![Fix { span: Span::new(0, 6), replacement: "@media".into() }]);
+		assert_eq!(apply_fixes("@meida print {}", &diagnostic.fixes), "@media print {}");
+		let payload = diagnostic.downcast_ref::<diagnostics::UnknownAtRuleSuggest>().unwrap();
+		assert_eq!(payload.0, atom!("meida"));
+
+		let diagnostic = Diagnostic::new(diagnostics::ExpectedEnd(Span::new(3, 5)).into(), Severity::Error);
+		assert_eq!(diagnostic.code, DiagnosticCode::ExpectedEnd);
+		assert_eq!(diagnostic.fixes, vec![]);
+		assert_eq!(diagnostic.severity, Severity::Error);
+	}
+
+	#[test]
+	fn test_codes() {
+		// Each struct in diagnostics.rs needs a code here, or it would be reported as Other.
+		let source = include_str!("diagnostics.rs");
+		let structs = source.split("#[derive(Debug, Error, Diagnostic)]").skip(1).collect::<Vec<_>>();
+		assert_eq!(structs.len(), DiagnosticCode::ALL.len());
+		for item in structs {
+			let name = item.split("pub struct ").nth(1).unwrap().split(|c: char| !c.is_alphanumeric()).next().unwrap();
+			let code = item.split("code(").nth(1).unwrap().split(')').next().unwrap();
+			let Some(variant) = DiagnosticCode::ALL.iter().find(|variant| format!("{:?}", variant) == name) else {
+				panic!("diagnostics::{} has no DiagnosticCode", name);
+			};
+			assert_eq!(variant.as_str(), code, "diagnostics::{} has a different code", name);
+		}
+	}
+
+	#[test]
+	fn test_shift() {
+		let error: Error = diagnostics::UnknownAtRuleSuggest(atom!("meida"), atom!("media"), Span::new(0, 6)).into();
+		let mut diagnostic = Diagnostic::new(error, Severity::Warning);
+		diagnostic.shift_spans(3);
+		let diagnostic = Diagnostic::new(Error::new(diagnostic), Severity::Error);
+		assert_eq!(diagnostic.code, DiagnosticCode::UnknownAtRuleSuggest);
+		assert_eq!(diagnostic.severity, Severity::Error);
+		assert_eq!(diagnostic.span(), Some(Span::new(3, 9)));
+		assert_eq!(diagnostic.fixes, vec![Fix { span: Span::new(3, 9), replacement: "@media".into() }]);
+		assert!(diagnostic.downcast_ref::<diagnostics::UnknownAtRuleSuggest>().is_some());
+	}
+
+	#[test]
+	fn test_apply_fixes() {
+		let fixes = [
+			Fix { span: Span::new(8, 13), replacement: "solid".into() },
+			Fix { span: Span::new(0, 6), replacement: "border".into() },
+			Fix { span: Span::new(9, 10), replacement: "x".into() },
+		];
+		assert_eq!(apply_fixes("bordre: sloid", &fixes), "border: solid");
+	}
+}
@@ -1,5 +1,4 @@
 use hdx_atom::Atom;
-use hdx_lexer::Token;
 use miette::{self, Diagnostic};
 use thiserror::{self, Error};
 
@@ -44,7 +43,7 @@ pub struct BadDeclaration(#[label("This is not valid syntax for a declaration.")
 #[derive(Debug, Error, Diagnostic)]
 #[error("Unexpected `{0}`")]
 #[diagnostic(help("This is not correct CSS syntax."), code(hdx_parser::Unexpected))]
-pub struct Unexpected(pub Token, #[label("This wasn't expected here")] pub Span);
+pub struct Unexpected(pub String, #[label("This wasn't expected here")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Unexpected charset '{0}'. '{0}' isn't allowed here. This must be a valid IANA language code.")]
@@ -76,6 +75,11 @@ pub struct UnexpectedDelim(pub char, #[label("This character wasn't understood")
 #[diagnostic(help("This isn't a valid psuedo selector for this rule."), code(hdx_parser::UnexpectedPseudo))]
 pub struct UnexpectedPseudoClass(pub Atom, #[label("This psuedo selector")] pub Span);
 
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unexpected pseudo selector ':{0}'. Did you mean ':{1}'?")]
+#[diagnostic(help("Try changing this to ':{1}'"), code(hdx_parser::UnexpectedPseudoClassSuggest))]
+pub struct UnexpectedPseudoClassSuggest(pub Atom, pub Atom, #[label("This psuedo selector")] pub Span);
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("Unexpected pseudo selector ':{0}'()")]
 #[diagnostic(
@@ -97,11 +101,21 @@ pub struct UnexpectedPseudoElement(pub Atom, #[label("This psuedo selector")] pu
 )]
 pub struct UnexpectedPseudoElementFunction(pub Atom, #[label("This psuedo selector")] pub Span);
 
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unexpected pseudo element '::{0}'. Did you mean '::{1}'?")]
+#[diagnostic(help("Try changing this to '::{1}'"), code(hdx_parser::UnexpectedPseudoElementSuggest))]
+pub struct UnexpectedPseudoElementSuggest(pub Atom, pub Atom, #[label("This psuedo selector")] pub Span);
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("Unexpected tag name ':{0}'")]
 #[diagnostic(help("This isn't a valid tag name."), code(hdx_parser::UnexpectedTag))]
 pub struct UnexpectedTag(pub Atom, #[label("This tag")] pub Span);
 
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unknown tag name '{0}'. Did you mean '{1}'?")]
+#[diagnostic(help("Try changing this to '{1}'"), code(hdx_parser::UnknownTagSuggest))]
+pub struct UnknownTagSuggest(pub Atom, pub Atom, #[label("This tag isn't known")] pub Span);
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("Unexpected ID selector ':{0}'")]
 #[diagnostic(help("This isn't a valid ID."), code(hdx_parser::UnexpectedId))]
@@ -135,6 +149,11 @@ pub struct UnexpectedFunction(pub Atom, #[label("??")] pub Span);
 #[diagnostic(help("This might be a mistake in the parser, please file an issue!"), code(hdx_parser::UnknownRule))]
 pub struct UnknownRule(#[label("Don't know how to interpret this")] pub Span);
 
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unknown at-rule '@{0}'. Did you mean '@{1}'?")]
+#[diagnostic(help("Try changing this to '@{1}'"), code(hdx_parser::UnknownAtRuleSuggest))]
+pub struct UnknownAtRuleSuggest(pub Atom, pub Atom, #[label("This at-rule isn't known")] pub Span);
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("Ignored property due to parse error.")]
 #[diagnostic(
@@ -148,6 +167,34 @@ pub struct UnknownDeclaration(#[label("This property was ignored.")] pub Span);
 #[diagnostic(help("This might be a mistake in the parser, please file an issue!"), code(hdx_parser::UnknownValue))]
 pub struct UnknownValue(#[label("Don't know how to interpret this")] pub Span);
 
+#[derive(Debug, Error, Diagnostic)]
+#[error("This isn't a valid value for '{0}', so it will be ignored.")]
+#[diagnostic(code(hdx_parser::InvalidValue))]
+pub struct InvalidValue(pub Atom, #[help] pub Option<String>, #[label("This doesn't match")] pub Span);
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unknown property '{0}'")]
+#[diagnostic(help("Check the spelling of this property, or remove it."), code(hdx_parser::UnknownProperty))]
+pub struct UnknownProperty(pub Atom, #[label("This isn't a known property")] pub Span);
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unknown property '{0}'. Did you mean '{1}'?")]
+#[diagnostic(help("Try changing this to '{1}'"), code(hdx_parser::UnknownPropertySuggest))]
+pub struct UnknownPropertySuggest(pub Atom, pub Atom, #[label("This isn't a known property")] pub Span);
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("'{0}' is a hack for old versions of Internet Explorer")]
+#[diagnostic(
+	help("Remove this hack, or parse with AllowIeHacks if it's still needed."),
+	code(hdx_parser::DisallowedIeHack)
+)]
+pub struct DisallowedIeHack(pub Atom, #[label("This hack")] pub Span);
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("'{0}' has a vendor prefix")]
+#[diagnostic(help("Use the unprefixed version instead."), code(hdx_parser::DisallowedVendorPrefix))]
+pub struct DisallowedVendorPrefix(pub Atom, #[label("Remove the prefix")] pub Span);
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("Unknown named color '{0}'")]
 #[diagnostic(
@@ -167,17 +214,17 @@ pub struct ExpectedEnd(#[label("All of this extra content was ignored.")] pub Sp
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected `{0}` but found `{1}` {2}")]
 #[diagnostic(help("This is not correct CSS syntax."), code(hdx_parser::ExpectedToken))]
-pub struct ExpectedToken(pub Token, pub Token, #[label("`{0}` expected")] pub Span);
+pub struct ExpectedToken(pub String, pub String, #[label("`{0}` expected")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected a dimension but found `{1}`")]
 #[diagnostic(help("This is not correct CSS syntax."), code(hdx_parser::ExpectedDimension))]
-pub struct ExpectedDimension(pub Token, #[label("dimension expected")] pub Span);
+pub struct ExpectedDimension(pub String, #[label("dimension expected")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected an identifier but found `{0}`")]
 #[diagnostic(help("This is not correct CSS syntax."), code(hdx_parser::ExpectedIdent))]
-pub struct ExpectedIdent(pub Token, #[label("This should be `{0}`")] pub Span);
+pub struct ExpectedIdent(pub String, #[label("This should be `{0}`")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected an identifier but not `{0}`")]
@@ -192,7 +239,7 @@ pub struct ExpectedIdentOf(pub Atom, pub Atom, #[label("This should be `{0}`")]
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected a function but found `{0}`")]
 #[diagnostic(help("This is not correct CSS syntax."), code(hdx_parser::ExpectedFunction))]
-pub struct ExpectedFunction(pub Token, #[label("This token")] pub Span);
+pub struct ExpectedFunction(pub String, #[label("This token")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected to see {0}() but saw {1}()")]
@@ -202,7 +249,7 @@ pub struct ExpectedFunctionOf(pub Atom, pub Atom, #[label("This function")] pub
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected an @ keyword but saw `{0}`")]
 #[diagnostic(help("This is not correct CSS syntax."), code(hdx_parser::ExpectedAtKeyword))]
-pub struct ExpectedAtKeyword(pub Token, #[label("This at-keyword")] pub Span);
+pub struct ExpectedAtKeyword(pub String, #[label("This at-keyword")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected to see @{0} but saw @{1}")]
@@ -212,7 +259,7 @@ pub struct ExpectedAtKeywordOf(pub Atom, pub Atom, #[label("This at-keyword")] p
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected a delimiter but saw `{0}`")]
 #[diagnostic(help("This is not correct CSS syntax."), code(hdx_parser::ExpectedDelim))]
-pub struct ExpectedDelim(pub Token, #[label("This at-keyword")] pub Span);
+pub struct ExpectedDelim(pub String, #[label("This at-keyword")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected to see {0} but saw {1}")]
@@ -222,7 +269,7 @@ pub struct ExpectedDelimOf(pub char, pub char, #[label("This delimiter")] pub Sp
 #[derive(Debug, Error, Diagnostic)]
 #[error("Unexpected trailing `{0}`")]
 #[diagnostic(help("Try removing the trailing {0} which will remove this warning."), code(hdx_parser::WarnTrailing))]
-pub struct WarnTrailing(pub Token, #[label("This can be removed")] pub Span);
+pub struct WarnTrailing(pub String, #[label("This can be removed")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Invalid hexidecimal value for color: '{0}'")]
@@ -269,47 +316,47 @@ pub struct DisallowedMathFunction(pub Atom, #[label("This value")] pub Span);
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected a number but saw `{0}`")]
 #[diagnostic(help("This is not correct CSS syntax."), code(hdx_parser::ExpectedNumber))]
-pub struct ExpectedNumber(pub Token, #[label("This value")] pub Span);
+pub struct ExpectedNumber(pub String, #[label("This value")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected a signed number but saw `{0}`")]
 #[diagnostic(help("This number needs a + or a -."), code(hdx_parser::ExpectedSign))]
-pub struct ExpectedSign(pub f32, #[label("Add a + here")] pub Span);
+pub struct ExpectedSign(pub f64, #[label("Add a + here")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Expected an unsigned number but saw `{0}`")]
 #[diagnostic(help("This number cannot have a + or a -."), code(hdx_parser::ExpectedUnsigned))]
-pub struct ExpectedUnsigned(pub f32, #[label("Remove the sign")] pub Span);
+pub struct ExpectedUnsigned(pub f64, #[label("Remove the sign")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("This number is out of bounds.")]
 #[diagnostic(help("This needs to be a number between {1}."), code(hdx_parser::NumberOutOfBounds))]
-pub struct NumberOutOfBounds(pub f32, pub String, #[label("This value")] pub Span);
+pub struct NumberOutOfBounds(pub f64, pub String, #[label("This value")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("This number cannot be negative.")]
 #[diagnostic(help("This needs to be greater or equal to 0"), code(hdx_parser::NumberNotNegative))]
-pub struct NumberNotNegative(pub f32, #[label("This value")] pub Span);
+pub struct NumberNotNegative(pub f64, #[label("This value")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("This number is too small.")]
 #[diagnostic(help("This needs to be larger than {0}"), code(hdx_parser::NumberTooSmall))]
-pub struct NumberTooSmall(pub f32, #[label("This value")] pub Span);
+pub struct NumberTooSmall(pub f64, #[label("This value")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("This value isn't allowed to have a fraction, it must be a whole number (integer).")]
 #[diagnostic(help("Try using {0} instead"), code(hdx_parser::ExpectedInt))]
-pub struct ExpectedInt(pub f32, #[label("This value")] pub Span);
+pub struct ExpectedInt(pub f64, #[label("This value")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("This value must have a fraction, it must be float.")]
 #[diagnostic(help("Try using {0} instead"), code(hdx_parser::ExpectedFloat))]
-pub struct ExpectedFloat(pub f32, #[label("This value")] pub Span);
+pub struct ExpectedFloat(pub f64, #[label("This value")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("This number must be 0, got {0} instead.")]
 #[diagnostic(help("Try replacing it with the literal 0 instead"), code(hdx_parser::ExpectedZero))]
-pub struct ExpectedZero(pub f32, #[label("This value")] pub Span);
+pub struct ExpectedZero(pub f64, #[label("This value")] pub Span);
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Display 'list-item' can only be combined with 'flow' or 'flow-root'")]
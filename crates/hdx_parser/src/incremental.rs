@@ -0,0 +1,181 @@
+use miette::Error;
+
+use crate::{
+	parser::Parser, span::Span, Box, Diagnostic, Features, ParserReturn, Severity, ShiftSpans, Spanned, StyleSheet,
+};
+
+/// A change to the source text: the text within `span` is replaced with `text`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextEdit<'e> {
+	pub span: Span,
+	pub text: &'e str,
+}
+
+impl<'e> TextEdit<'e> {
+	pub fn new(span: Span, text: &'e str) -> Self {
+		Self { span, text }
+	}
+
+	/// How far the edit moves the text after it.
+	#[inline]
+	pub fn delta(&self) -> i32 {
+		self.text.len() as i32 - self.span.size() as i32
+	}
+
+	/// The source text with the edit made to it.
+	pub fn apply(&self, source_text: &str) -> String {
+		let mut edited = String::with_capacity((source_text.len() as i64 + self.delta() as i64) as usize);
+		edited.push_str(&source_text[..self.span.start as usize]);
+		edited.push_str(self.text);
+		edited.push_str(&source_text[self.span.end as usize..]);
+		edited
+	}
+}
+
+impl<'a> Parser<'a> {
+	/// Parses a style sheet which has had `edit` made to it since `previous` was parsed from `old_source_text`. This
+	/// parser must be over the edited text.
+	///
+	/// Only the top-level rules which the edit touches are parsed again. Parsing stops at the first of the rules after
+	/// the edit which starts where it did before, and from there on the previous rules are kept, with their spans
+	/// shifted to where they now are. The output is the same as parsing the edited text from scratch. So are the
+	/// errors and warnings: those of the rules which were parsed again are replaced, and those of the rules after them
+	/// are shifted along with them.
+	///
+	/// With [Features::Lossless] every node needs a fingerprint, so the whole style sheet is parsed again.
+	pub fn reparse_with<T: StyleSheet<'a> + 'a>(
+		mut self,
+		previous: ParserReturn<Spanned<T>>,
+		old_source_text: &str,
+		edit: &TextEdit,
+	) -> ParserReturn<Spanned<T>> {
+		debug_assert_eq!(edit.apply(old_source_text), self.lexer.source());
+		let ParserReturn { output: Some(mut previous), errors: previous_errors, warnings: previous_warnings, .. } =
+			previous
+		else {
+			return self.parse_entirely_with();
+		};
+		if self.enabled(Features::Lossless) {
+			return self.parse_entirely_with();
+		}
+		let delta = edit.delta();
+		let (rules, trivia) = previous.node.parts_mut();
+
+		// A rule ending right where the edit starts may carry on into it, so parsing starts from the one before the
+		// first rule the edit touches.
+		let mut first = rules.iter().position(|rule| rule.span.end >= edit.span.start).unwrap_or(rules.len());
+		first = first.saturating_sub(1);
+		// Comments on the same line after a rule were claimed by it, unless it couldn't be parsed, in which case they
+		// were left for the rule after. Rather than tell which, parsing starts further back until there are none.
+		while first > 0 {
+			self.lexer.seek(rules[first - 1].span.end);
+			if !self.has_trailing_comments() {
+				break;
+			}
+			first -= 1;
+		}
+		let start = if first == 0 { 0 } else { rules[first - 1].span.end };
+		self.lexer.seek(start);
+		self.prev_pos = start;
+
+		// Once parsing is past the edit, the text is as it was before. So if a rule ends where a previous rule
+		// started, that rule and the ones after it would be parsed the same as they were before. That is, unless
+		// there are comments on the same line, which may have been taken as leading comments before but trailing ones
+		// now, or the other way around.
+		let edit_end = edit.span.start + edit.text.len() as u32;
+		let mut next = first;
+		let mut reparsed = self.new_vec();
+		let resumed = loop {
+			let Some(rule) = T::parse_next_rule(&mut self) else { break None };
+			reparsed.push(rule);
+			let pos = self.pos();
+			if pos < edit_end || self.has_trailing_comments() {
+				continue;
+			}
+			while next < rules.len() && (rules[next].span.start as i64 + delta as i64) < pos as i64 {
+				next += 1;
+			}
+			if next < rules.len()
+				&& rules[next].span.start >= edit.span.end
+				&& rules[next].span.start as i64 + delta as i64 == pos as i64
+			{
+				break Some(next);
+			}
+		};
+		let end = match resumed {
+			Some(resumed) => rules[resumed].span.start,
+			None => old_source_text.len() as u32,
+		};
+
+		// The comments which aren't part of any rule are only replaced within the text which was parsed again.
+		let mut comments = self.new_vec();
+		let mut kept = self.new_vec();
+		if let Some(trivia) = trivia.take() {
+			let trivia = Box::into_inner(trivia);
+			for mut comment in trivia.trailing {
+				if comment.span.start < start {
+					comments.push(comment);
+				} else if comment.span.start >= end {
+					comment.shift_spans(delta);
+					kept.push(comment);
+				}
+			}
+		}
+		comments.extend(self.comments_within(Span::new(start, self.pos())));
+		if resumed.is_none() {
+			comments.extend(self.leading_comments());
+		}
+		comments.extend(kept);
+		*trivia = self.new_trivia(self.new_vec(), comments);
+
+		let mut spliced = self.new_vec();
+		let old_rules = std::mem::replace(rules, self.new_vec());
+		for (i, mut rule) in old_rules.into_iter().enumerate() {
+			if i == first {
+				spliced.extend(reparsed.drain(..));
+			}
+			if i < first {
+				spliced.push(rule);
+			} else if resumed.is_some_and(|next| i >= next) {
+				rule.shift_spans(delta);
+				spliced.push(rule);
+			}
+		}
+		spliced.extend(reparsed);
+		*rules = spliced;
+
+		previous.span.end = match resumed {
+			Some(_) => previous.span.end.wrapping_add_signed(delta),
+			None => self.pos(),
+		};
+		let errors = splice_diagnostics(previous_errors, self.errors, Severity::Error, start, end, delta);
+		let warnings = splice_diagnostics(previous_warnings, self.warnings, Severity::Warning, start, end, delta);
+		ParserReturn { output: Some(previous), warnings, errors, panicked: false, fingerprints: self.fingerprints }
+	}
+}
+
+// The `previous` diagnostics from before `start` are kept as they were, and those from `end` on are shifted along with
+// the rules they came from. Those in between were of the text which was parsed again, so are replaced by `reparsed`.
+fn splice_diagnostics(
+	previous: std::vec::Vec<Error>,
+	reparsed: std::vec::Vec<Error>,
+	severity: Severity,
+	start: u32,
+	end: u32,
+	delta: i32,
+) -> std::vec::Vec<Error> {
+	let mut before = vec![];
+	let mut after = vec![];
+	for error in previous {
+		match error.labels().and_then(|mut labels| labels.next()).map(|label| label.offset() as u32) {
+			Some(offset) if offset < start => before.push(error),
+			Some(offset) if offset >= end => {
+				let mut diagnostic = Diagnostic::new(error, severity);
+				diagnostic.shift_spans(delta);
+				after.push(Error::new(diagnostic));
+			}
+			_ => {}
+		}
+	}
+	before.into_iter().chain(reparsed).chain(after).collect()
+}
@@ -1,14 +1,34 @@
+#[cfg(feature = "serde")]
+pub mod arena;
 mod comparison;
 mod cursor;
+mod diagnostic;
 pub mod diagnostics;
+mod incremental;
+mod line_index;
+mod lossless;
 mod macros;
 mod parser;
+mod recovery;
+mod shift;
 mod span;
+mod suggest;
 mod traits;
+mod trivia;
 
+#[cfg(feature = "serde")]
+pub use arena::{ArenaSeed, DeserializeIn};
 pub use bumpalo::{boxed::Box, collections::Vec};
 pub use comparison::*;
+pub use diagnostic::*;
+pub use incremental::*;
+pub use line_index::*;
+pub use lossless::*;
 pub use miette::{Error, Result};
 pub use parser::*;
+pub use recovery::*;
+pub use shift::*;
 pub use span::*;
+pub use suggest::*;
 pub use traits::*;
+pub use trivia::*;
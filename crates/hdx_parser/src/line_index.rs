@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::span::Span;
+
+/// A position in some source text as a line & column, both counting from zero.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct LineCol {
+	pub line: u32,
+	pub col: u32,
+}
+
+// A character which takes up more than one byte, at `col` bytes into its line.
+#[derive(Debug, Clone, Copy)]
+struct WideChar {
+	col: u32,
+	len: u32,
+}
+
+impl WideChar {
+	#[inline]
+	fn len_utf16(&self) -> u32 {
+		if self.len == 4 {
+			2
+		} else {
+			1
+		}
+	}
+}
+
+/// Converts between byte offsets into some source text, such as those of a [Span], and the lines & columns they fall
+/// on. Columns are either counted in bytes, or in UTF-16 code units as editors, browsers and source maps expect.
+///
+/// Lines end with `\n`, `\r\n` or `\r`. Building the index scans the whole of the source text, so it's best built
+/// once and reused for every position in it.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+	line_starts: Vec<u32>,
+	// Only lines with non-ASCII characters are in here, which are usually few.
+	wide_chars: HashMap<u32, Vec<WideChar>>,
+	len: u32,
+}
+
+impl LineIndex {
+	pub fn new(source_text: &str) -> Self {
+		let mut line_starts = vec![0];
+		let mut wide_chars: HashMap<u32, Vec<WideChar>> = HashMap::new();
+		let mut chars = source_text.char_indices().peekable();
+		while let Some((i, ch)) = chars.next() {
+			match ch {
+				'\r' if matches!(chars.peek(), Some((_, '\n'))) => {}
+				'\n' | '\r' => line_starts.push(i as u32 + 1),
+				_ if !ch.is_ascii() => {
+					let line = line_starts.len() as u32 - 1;
+					let col = i as u32 - line_starts[line as usize];
+					wide_chars.entry(line).or_default().push(WideChar { col, len: ch.len_utf8() as u32 });
+				}
+				_ => {}
+			}
+		}
+		Self { line_starts, wide_chars, len: source_text.len() as u32 }
+	}
+
+	pub fn line_count(&self) -> u32 {
+		self.line_starts.len() as u32
+	}
+
+	/// The line & byte column of `offset`. Offsets past the end of the source text are treated as the end of it.
+	pub fn line_col(&self, offset: u32) -> LineCol {
+		let offset = offset.min(self.len);
+		let line = self.line_starts.partition_point(|&start| start <= offset) as u32 - 1;
+		LineCol { line, col: offset - self.line_starts[line as usize] }
+	}
+
+	/// The line & UTF-16 column of `offset`. Offsets past the end of the source text are treated as the end of it.
+	pub fn line_col_utf16(&self, offset: u32) -> LineCol {
+		let LineCol { line, col } = self.line_col(offset);
+		let mut col_utf16 = col;
+		for wide in self.wide_chars.get(&line).into_iter().flatten() {
+			if wide.col >= col {
+				break;
+			}
+			// An offset inside of a character counts as the start of it.
+			col_utf16 -= col.min(wide.col + wide.len) - wide.col;
+			col_utf16 += if wide.col + wide.len <= col { wide.len_utf16() } else { 0 };
+		}
+		LineCol { line, col: col_utf16 }
+	}
+
+	/// The byte offset of a line & byte column, if it's within the source text.
+	pub fn offset(&self, line_col: LineCol) -> Option<u32> {
+		let offset = self.line_starts.get(line_col.line as usize)? + line_col.col;
+		(offset <= self.line_end(line_col.line)).then_some(offset)
+	}
+
+	/// The byte offset of a line & UTF-16 column, if it's within the source text.
+	pub fn offset_utf16(&self, line_col: LineCol) -> Option<u32> {
+		let mut col = line_col.col;
+		for wide in self.wide_chars.get(&line_col.line).into_iter().flatten() {
+			// Columns are in bytes from here on, so any wide characters before this one have been accounted for.
+			if wide.col >= col {
+				break;
+			}
+			col += wide.len - wide.len_utf16();
+		}
+		self.offset(LineCol { line: line_col.line, col })
+	}
+
+	/// The lines & byte columns of the start & end of `span`.
+	pub fn span(&self, span: Span) -> (LineCol, LineCol) {
+		(self.line_col(span.start), self.line_col(span.end))
+	}
+
+	/// The lines & UTF-16 columns of the start & end of `span`.
+	pub fn span_utf16(&self, span: Span) -> (LineCol, LineCol) {
+		(self.line_col_utf16(span.start), self.line_col_utf16(span.end))
+	}
+
+	// Where the line ends, ahead of its line break (or the `\n` of a `\r\n`).
+	fn line_end(&self, line: u32) -> u32 {
+		self.line_starts.get(line as usize + 1).map_or(self.len, |next| next - 1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_line_col() {
+		let index = LineIndex::new("a{\n\tb:c\r\n}\rd");
+		assert_eq!(index.line_count(), 4);
+		assert_eq!(index.line_col(0), LineCol { line: 0, col: 0 });
+		assert_eq!(index.line_col(2), LineCol { line: 0, col: 2 });
+		assert_eq!(index.line_col(4), LineCol { line: 1, col: 1 });
+		assert_eq!(index.line_col(9), LineCol { line: 2, col: 0 });
+		assert_eq!(index.line_col(11), LineCol { line: 3, col: 0 });
+		assert_eq!(index.line_col(99), LineCol { line: 3, col: 1 });
+		assert_eq!(index.offset(LineCol { line: 1, col: 1 }), Some(4));
+		assert_eq!(index.offset(LineCol { line: 3, col: 1 }), Some(12));
+		assert_eq!(index.offset(LineCol { line: 0, col: 5 }), None);
+		assert_eq!(index.offset(LineCol { line: 4, col: 0 }), None);
+	}
+
+	#[test]
+	fn test_line_col_utf16() {
+		// é is 2 bytes & 1 UTF-16 unit, 😀 is 4 bytes & 2 UTF-16 units.
+		let index = LineIndex::new("a{}\n/* é😀 */ b{}");
+		assert_eq!(index.line_col(12), LineCol { line: 1, col: 8 });
+		// Offsets within a character are treated as its start.
+		assert_eq!(index.line_col_utf16(12), LineCol { line: 1, col: 4 });
+		assert_eq!(index.line_col_utf16(13), LineCol { line: 1, col: 6 });
+		assert_eq!(index.line_col_utf16(18), LineCol { line: 1, col: 11 });
+		assert_eq!(index.offset_utf16(LineCol { line: 1, col: 6 }), Some(13));
+		assert_eq!(index.offset_utf16(LineCol { line: 1, col: 11 }), Some(18));
+		assert_eq!(index.span_utf16(Span::new(4, 18)), (LineCol { line: 1, col: 0 }, LineCol { line: 1, col: 11 }));
+	}
+}
@@ -0,0 +1,49 @@
+use std::{
+	collections::{hash_map::DefaultHasher, HashSet},
+	hash::{Hash, Hasher},
+};
+
+use crate::{parser::Parser, span::Span, Spanned};
+
+/// What each [Spanned] node looked like when it was parsed, kept when parsing with
+/// [Features::Lossless](crate::Features::Lossless).
+///
+/// Every node can be found in the source text by its span, so this is enough for a writer to tell which nodes are
+/// still as they were parsed, and copy those back out of the source text byte for byte.
+#[derive(Debug, Default)]
+pub struct Fingerprints(HashSet<(Span, u64)>);
+
+impl Fingerprints {
+	pub fn insert<T: Hash>(&mut self, span: Span, node: &T) {
+		self.0.insert((span, Self::fingerprint(node)));
+	}
+
+	/// Whether `node` is unchanged since it was parsed from `span`.
+	pub fn contains<T: Hash>(&self, span: Span, node: &T) -> bool {
+		!span.is_dummy() && self.0.contains(&(span, Self::fingerprint(node)))
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	fn fingerprint<T: Hash>(node: &T) -> u64 {
+		// DefaultHasher::new() always uses the same keys, so a node hashes the same when written as when parsed.
+		let mut hasher = DefaultHasher::new();
+		node.hash(&mut hasher);
+		hasher.finish()
+	}
+}
+
+impl<'a> Parser<'a> {
+	#[inline]
+	pub(crate) fn fingerprint<T: Hash>(&mut self, spanned: &Spanned<T>) {
+		if let Some(fingerprints) = &mut self.fingerprints {
+			fingerprints.insert(spanned.span, &spanned.node);
+		}
+	}
+}
@@ -20,7 +20,7 @@ macro_rules! todo {
 /// ```
 /// unexpected!(parser);
 ///
-/// match parser.next() {
+/// match parser.next_token() {
 ///     Token::Ident(_) => {...}
 ///     token => unexpected!(parser, token),
 /// }
@@ -28,10 +28,10 @@ macro_rules! todo {
 #[macro_export]
 macro_rules! unexpected {
 	($parser: ident, $($token: tt)+) => {
-		Err($crate::diagnostics::Unexpected($($token)+.clone(), $parser.span()))?
+		Err($crate::diagnostics::Unexpected($($token)+.to_string(), $parser.span()))?
 	};
 	($parser: ident) => {
-		Err($crate::diagnostics::Unexpected($parser.cur().clone(), $parser.span()))?
+		Err($crate::diagnostics::Unexpected($parser.cur().to_string(), $parser.span()))?
 	};
 }
 
@@ -42,14 +42,14 @@ macro_rules! unexpected {
 /// ```
 /// unexpected_ident!(parser, ident);
 ///
-/// match parser.next() {
+/// match parser.next_token() {
 ///     Token::Ident(ident) => unexpected_ident!(parser, ident),
 /// }
 /// ```
 #[macro_export]
 macro_rules! unexpected_ident {
 	($parser: ident, $($atom: tt)+) => {
-		Err($crate::diagnostics::UnexpectedIdent($($atom)+.clone(), $parser.span()))?
+		Err($crate::diagnostics::UnexpectedIdent({ use hdx_atom::ToAtom; ($($atom)+).to_atom() }, $parser.span()))?
 	};
 }
 
@@ -61,14 +61,14 @@ macro_rules! unexpected_ident {
 /// ```no_run
 /// unexpected_function!(parser, ident);
 ///
-/// match parser.next() {
+/// match parser.next_token() {
 ///     Token::Function(ident) => unexpected_function!(parser, ident),
 /// }
 /// ```
 #[macro_export]
 macro_rules! unexpected_function {
 	($parser: ident, $($atom: tt)+) => {
-		Err($crate::diagnostics::UnexpectedFunction($($atom)+.clone(), $parser.span()))?
+		Err($crate::diagnostics::UnexpectedFunction({ use hdx_atom::ToAtom; ($($atom)+).to_atom() }, $parser.span()))?
 	};
 }
 
@@ -146,18 +146,18 @@ macro_rules! discard {
 /// # Examples
 ///
 /// ```
-/// expect!(parser.next(), Token::RightCurly);
+/// expect!(parser.next_token(), Token::RightCurly);
 ///
-/// expect!(parser.next_with(Include::Whitespace), Token::Colon);
+/// expect!(parser.next_token_with(Include::Whitespace), Token::Colon);
 ///
-/// expect!(parser.next(), Token::Comma | Token::SemiColon);
+/// expect!(parser.next_token(), Token::Comma | Token::SemiColon);
 /// ```
 #[macro_export]
 macro_rules! expect {
 	($parser: ident.$method: ident($($args:tt)*), $pattern:pat $(if $guard:expr)? $(,)?) => {
 		match $parser.$method($($args)*).clone() {
 			$pattern $(if $guard)? => {},
-			token => $crate::unexpected!($parser, token.clone()),
+			token => $crate::unexpected!($parser, token),
 		}
 	};
 }
@@ -177,7 +177,7 @@ macro_rules! expect {
 /// # Examples
 ///
 /// ```
-/// expect_ignore_case!(parser.next(), Token::Ident(atom!("foo"));
+/// expect_ignore_case!(parser.next_token(), Token::Ident(atom!("foo"));
 ///
 /// let thing = expect_ignore_case!{ parser.peek(), Token::Function(_):
 ///     atom!("foo") => Thing::Foo,
@@ -203,11 +203,11 @@ macro_rules! expect_ignore_case {
         $(,)?
     ) => {
 		match $parser.$method($($args)*) {
-			hdx_lexer::Token::$tokenty(ident) => match ident.to_ascii_lowercase() {
+			hdx_lexer::Token::$tokenty(ident) => match { use hdx_atom::ToAtom; ident.to_ascii_lowercase_atom() } {
 				$($pattern $( if $guard )? => $then,)+
-				_ => $crate::unexpected_ident!($parser, ident.clone()),
+				_ => $crate::unexpected_ident!($parser, ident),
 			},
-			token => $crate::unexpected!($parser, token.clone()),
+			token => $crate::unexpected!($parser, token),
 		}
 	};
 }
@@ -226,7 +226,7 @@ macro_rules! expect_ignore_case {
 /// # Examples
 ///
 /// ```
-/// if match_ignore_case!(parser.next(), Token::Ident(atom!("foo")) {
+/// if match_ignore_case!(parser.next_token(), Token::Ident(atom!("foo")) {
 ///     // ...
 /// }
 ///
@@ -257,7 +257,7 @@ macro_rules! match_ignore_case {
         $(,)?
     ) => {
 		match $parser.$method($($args)*) {
-			hdx_lexer::Token::$tokenty(ident) => match ident.to_ascii_lowercase() {
+			hdx_lexer::Token::$tokenty(ident) => match { use hdx_atom::ToAtom; ident.to_ascii_lowercase_atom() } {
 				$($pattern $( if $guard )? => $then,)+
 				_ => false,
 			},
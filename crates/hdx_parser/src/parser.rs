@@ -1,9 +1,17 @@
 use bitmask_enum::bitmask;
 use bumpalo::Bump;
+use hdx_atom::ToAtom;
 use hdx_lexer::{Lexer, Token};
-use miette::Error;
+use miette::{Error, Result};
 
-use crate::{diagnostics, span::Spanned, traits::Parse};
+use crate::{
+	diagnostic::{Diagnostic, Severity},
+	diagnostics,
+	lossless::Fingerprints,
+	span::{Span, Spanned},
+	traits::Parse,
+	trivia::Claimed,
+};
 
 pub struct Parser<'a> {
 	pub(crate) lexer: Lexer<'a>,
@@ -14,17 +22,38 @@ pub struct Parser<'a> {
 
 	pub(crate) errors: std::vec::Vec<Error>,
 
-	pub(crate) token: Token,
+	pub(crate) token: Token<'a>,
 
 	pub(crate) state: State,
 
 	pub(crate) prev_pos: u32,
 
+	pub(crate) claimed: Claimed,
+
+	pub(crate) fingerprints: Option<Fingerprints>,
+
 	pub(crate) allocator: &'a Bump,
 }
 
 #[bitmask(u8)]
-pub enum Features {}
+pub enum Features {
+	/// Keep the [Fingerprints] of every [Spanned] node, so that untouched nodes can be written back out exactly as
+	/// they appeared in the source text, whitespace included. Comments are kept as [Trivia](crate::Trivia) either way.
+	Lossless = 0b0000_0001,
+	/// Make unknown properties, and values which don't match their property's grammar, errors rather than keeping
+	/// them as unknown values. Hacks aimed at old versions of IE are errors too, unless [Features::AllowIeHacks].
+	Strict = 0b0000_0010,
+	/// Accept the hacks aimed at old versions of IE: properties prefixed with `*` or `_`, values suffixed with `\9`,
+	/// and the `\0` media query hack.
+	AllowIeHacks = 0b0000_0100,
+	/// Parse `@custom-media` rules, and the `(--name)` media features which refer to them.
+	/// https://drafts.csswg.org/mediaqueries-5/#custom-mq
+	AllowCustomMedia = 0b0000_1000,
+	/// Parse the SCSS `$variable: value` declarations, both at the top level and within blocks.
+	Scss = 0b0001_0000,
+	/// Make vendor prefixed properties, at-rules and pseudo selectors (such as `-webkit-appearance`) errors.
+	NoVendorPrefixes = 0b0010_0000,
+}
 
 impl Default for Features {
 	fn default() -> Self {
@@ -40,6 +69,7 @@ pub enum State {
 	// Stop Tokens for some algorithms
 	StopOnSemicolon = 0b1000_0000,
 	StopOnComma = 0b1100_0000,
+	StopOnCurly = 0b0010_0000,
 }
 
 pub struct ParserReturn<T> {
@@ -47,6 +77,16 @@ pub struct ParserReturn<T> {
 	pub errors: std::vec::Vec<Error>,
 	pub warnings: std::vec::Vec<Error>,
 	pub panicked: bool,
+	/// Only kept when parsing with [Features::Lossless].
+	pub fingerprints: Option<Fingerprints>,
+}
+
+impl<T> ParserReturn<T> {
+	/// Takes the errors then the warnings, as [Diagnostics](Diagnostic).
+	pub fn take_diagnostics(&mut self) -> std::vec::Vec<Diagnostic> {
+		let errors = self.errors.drain(..).map(|error| Diagnostic::new(error, Severity::Error));
+		errors.chain(self.warnings.drain(..).map(|warning| Diagnostic::new(warning, Severity::Warning))).collect()
+	}
 }
 
 impl<'a> Parser<'a> {
@@ -60,18 +100,38 @@ impl<'a> Parser<'a> {
 			token: Token::default(),
 			state: State::none(),
 			prev_pos: 0,
+			claimed: Claimed::default(),
+			fingerprints: features.contains(Features::Lossless).then(Fingerprints::default),
 			allocator,
 		}
 	}
 
+	/// The arena the nodes are parsed into.
+	#[inline]
+	pub fn allocator(&self) -> &'a Bump {
+		self.allocator
+	}
+
 	#[inline]
 	pub fn new_vec<T>(&self) -> crate::Vec<'a, T> {
 		crate::Vec::new_in(self.allocator)
 	}
 
 	#[inline]
-	fn enabled(&self, other: Features) -> bool {
-		self.features.contains(other)
+	pub fn boxup<T>(&self, value: T) -> crate::Box<'a, T> {
+		crate::Box::new_in(value, self.allocator)
+	}
+
+	/// Whether all of the `features` were enabled when the parser was created.
+	#[inline]
+	pub fn enabled(&self, features: Features) -> bool {
+		self.features.contains(features)
+	}
+
+	/// The whole of the text being parsed.
+	#[inline]
+	pub fn source_text(&self) -> &'a str {
+		self.lexer.source()
 	}
 
 	#[inline]
@@ -79,24 +139,34 @@ impl<'a> Parser<'a> {
 		self.state.contains(state)
 	}
 
-	pub fn parse_entirely_with<T: Parse<'a>>(mut self) -> ParserReturn<Spanned<T>> {
-		let (output, panicked) = match T::parse_spanned(&mut self) {
+	pub fn parse_entirely_with<T: Parse<'a>>(self) -> ParserReturn<Spanned<T>> {
+		self.parse_entirely_by(T::parse_spanned)
+	}
+
+	/// Parses the whole of the source text with `parse`, for nodes which are parsed differently on their own than
+	/// within a style sheet, such as the declarations of a style attribute which, unlike those of a style rule, have
+	/// no curly braces around them.
+	pub fn parse_entirely_by<T>(
+		mut self,
+		parse: impl FnOnce(&mut Parser<'a>) -> Result<Spanned<T>>,
+	) -> ParserReturn<Spanned<T>> {
+		let (output, panicked) = match parse(&mut self) {
 			Ok(output) => (Some(output), false),
 			Err(error) => {
 				self.errors.push(error);
 				(None, true)
 			}
 		};
-		if !matches!(self.next(), Token::Eof) {
+		if !matches!(self.next_token(), Token::Eof) {
 			let span = self.span();
 			loop {
-				if matches!(self.next(), Token::Eof) {
+				if matches!(self.next_token(), Token::Eof) {
 					break;
 				}
 			}
 			self.errors.push(diagnostics::ExpectedEnd(span.end(self.pos())).into());
 		}
-		ParserReturn { output, warnings: self.warnings, errors: self.errors, panicked }
+		ParserReturn { output, warnings: self.warnings, errors: self.errors, panicked, fingerprints: self.fingerprints }
 	}
 
 	pub fn parse_with<T: Parse<'a>>(mut self) -> ParserReturn<Spanned<T>> {
@@ -107,10 +177,18 @@ impl<'a> Parser<'a> {
 				(None, true)
 			}
 		};
-		ParserReturn { output, warnings: self.warnings, errors: self.errors, panicked }
+		ParserReturn { output, warnings: self.warnings, errors: self.errors, panicked, fingerprints: self.fingerprints }
 	}
 
 	pub fn warn(&mut self, error: Error) {
 		self.warnings.push(error);
 	}
+
+	/// Errors if `name` has a vendor prefix, such as `-webkit-`, when [Features::NoVendorPrefixes] is enabled.
+	pub fn check_vendor_prefix(&self, name: &str, span: Span) -> Result<()> {
+		if self.enabled(Features::NoVendorPrefixes) && name.starts_with('-') && !name.starts_with("--") {
+			Err(diagnostics::DisallowedVendorPrefix(name.to_atom(), span))?
+		}
+		Ok(())
+	}
 }
@@ -0,0 +1,76 @@
+use hdx_atom::Atom;
+use hdx_lexer::Token;
+use miette::Error;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{cursor::ParserCheckpoint, parser::Parser, span::Span, Spanned};
+
+/// What's left of a rule or declaration which couldn't be parsed. The error is recorded and parsing carries on after
+/// it, keeping its source text so it can be written back out as it was.
+#[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(tag = "type", rename = "remnants"))]
+pub struct Remnants {
+	pub text: Atom,
+}
+
+impl<'a> Parser<'a> {
+	/// Goes back to `checkpoint`, the start of the rule or declaration which failed to parse, then skips over the
+	/// whole of it. Within a block this stops ahead of the `}` which closes it. Errors from the failed attempt are
+	/// dropped, so the caller should record an [error](Parser::error) for it. Its warnings are kept, as they can say
+	/// why it failed, such as a suggestion for what was misspelled.
+	// https://drafts.csswg.org/css-syntax-3/#consume-the-remnants-of-a-bad-declaration
+	pub fn recover(&mut self, checkpoint: ParserCheckpoint<'a>, nested: bool) -> Spanned<Remnants> {
+		let warnings = self.warnings.split_off(checkpoint.warnings_pos);
+		self.rewind(checkpoint);
+		self.warnings.extend(warnings);
+		let start = self.pos();
+		let mut depth = 0;
+		loop {
+			match self.peek() {
+				Token::Eof => break,
+				Token::RightCurly if nested && depth == 0 => break,
+				Token::Semicolon if depth == 0 => {
+					self.advance();
+					break;
+				}
+				Token::LeftCurly | Token::LeftParen | Token::LeftSquare | Token::Function(_) => {
+					self.advance();
+					depth += 1;
+				}
+				Token::RightCurly if depth == 1 => {
+					// A rule ends with its block.
+					self.advance();
+					break;
+				}
+				Token::RightCurly | Token::RightParen | Token::RightSquare => {
+					self.advance();
+					depth = (depth - 1).max(0);
+				}
+				_ => self.advance(),
+			}
+		}
+		let span = Span::new(start, self.pos());
+		let text = Atom::from(span.source_text(self.lexer.source()).trim());
+		Spanned { node: Remnants { text }, span }
+	}
+
+	pub fn error(&mut self, error: Error) {
+		self.errors.push(error);
+	}
+}
+
+impl Remnants {
+	/// Whether these are the remnants of a rule, rather than a declaration.
+	pub fn is_rule(&self) -> bool {
+		self.text.ends_with('}')
+	}
+
+	/// The remnants of a declaration, without the `;` which ended it, as that's written between declarations.
+	pub fn declaration(self) -> Self {
+		match self.text.strip_suffix(';') {
+			Some(text) => Self { text: Atom::from(text.trim_end()) },
+			None => self,
+		}
+	}
+}
@@ -0,0 +1,144 @@
+use hdx_atom::Atom;
+use hdx_lexer::{NumType, PairWise, QuoteStyle, Token};
+use smallvec::{Array, SmallVec};
+
+use crate::{Box, Commented, Comparison, Remnants, Span, Spanned, Trivia, Vec};
+
+/// Moves a node within the source text, by shifting every [Span] within it by `delta` bytes. This is how a node
+/// stays in place when text is added or removed ahead of it, without parsing it again.
+pub trait ShiftSpans {
+	fn shift_spans(&mut self, delta: i32);
+}
+
+impl ShiftSpans for Span {
+	fn shift_spans(&mut self, delta: i32) {
+		// Dummy spans, such as those of nodes added by a transform, aren't anywhere in the source text.
+		if !self.is_dummy() {
+			self.start = self.start.wrapping_add_signed(delta);
+			self.end = self.end.wrapping_add_signed(delta);
+		}
+	}
+}
+
+impl<T: ShiftSpans> ShiftSpans for Spanned<T> {
+	fn shift_spans(&mut self, delta: i32) {
+		self.span.shift_spans(delta);
+		self.node.shift_spans(delta);
+	}
+}
+
+impl<'a, T: ShiftSpans> ShiftSpans for Commented<'a, T> {
+	fn shift_spans(&mut self, delta: i32) {
+		self.node.shift_spans(delta);
+		self.trivia.shift_spans(delta);
+	}
+}
+
+impl<'a> ShiftSpans for Trivia<'a> {
+	fn shift_spans(&mut self, delta: i32) {
+		self.leading.shift_spans(delta);
+		self.trailing.shift_spans(delta);
+	}
+}
+
+impl<T: ShiftSpans> ShiftSpans for Option<T> {
+	fn shift_spans(&mut self, delta: i32) {
+		if let Some(node) = self {
+			node.shift_spans(delta);
+		}
+	}
+}
+
+impl<'a, T: ShiftSpans> ShiftSpans for Box<'a, T> {
+	fn shift_spans(&mut self, delta: i32) {
+		self.as_mut().shift_spans(delta);
+	}
+}
+
+impl<T: ShiftSpans> ShiftSpans for std::boxed::Box<T> {
+	fn shift_spans(&mut self, delta: i32) {
+		self.as_mut().shift_spans(delta);
+	}
+}
+
+impl<'a, T: ShiftSpans> ShiftSpans for Vec<'a, T> {
+	fn shift_spans(&mut self, delta: i32) {
+		for node in self.iter_mut() {
+			node.shift_spans(delta);
+		}
+	}
+}
+
+impl<A: Array> ShiftSpans for SmallVec<A>
+where
+	A::Item: ShiftSpans,
+{
+	fn shift_spans(&mut self, delta: i32) {
+		for node in self.iter_mut() {
+			node.shift_spans(delta);
+		}
+	}
+}
+
+impl<A: ShiftSpans, B: ShiftSpans> ShiftSpans for (A, B) {
+	fn shift_spans(&mut self, delta: i32) {
+		self.0.shift_spans(delta);
+		self.1.shift_spans(delta);
+	}
+}
+
+impl<A: ShiftSpans, B: ShiftSpans, C: ShiftSpans> ShiftSpans for (A, B, C) {
+	fn shift_spans(&mut self, delta: i32) {
+		self.0.shift_spans(delta);
+		self.1.shift_spans(delta);
+		self.2.shift_spans(delta);
+	}
+}
+
+impl<A: ShiftSpans, B: ShiftSpans, C: ShiftSpans, D: ShiftSpans> ShiftSpans for (A, B, C, D) {
+	fn shift_spans(&mut self, delta: i32) {
+		self.0.shift_spans(delta);
+		self.1.shift_spans(delta);
+		self.2.shift_spans(delta);
+		self.3.shift_spans(delta);
+	}
+}
+
+macro_rules! impl_without_spans {
+	($($ty: ty),+ $(,)?) => {
+		$(
+			impl ShiftSpans for $ty {
+				#[inline]
+				fn shift_spans(&mut self, _delta: i32) {}
+			}
+		)+
+	};
+}
+
+impl_without_spans!(
+	(),
+	bool,
+	char,
+	u8,
+	u16,
+	u32,
+	i32,
+	f32,
+	f64,
+	Atom,
+	QuoteStyle,
+	NumType,
+	PairWise,
+	Comparison,
+	Remnants
+);
+
+impl<'a> ShiftSpans for Token<'a> {
+	#[inline]
+	fn shift_spans(&mut self, _delta: i32) {}
+}
+
+impl<'a> ShiftSpans for &'a str {
+	#[inline]
+	fn shift_spans(&mut self, _delta: i32) {}
+}
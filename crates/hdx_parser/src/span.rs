@@ -3,10 +3,11 @@ use std::{fmt::Display, hash::Hash};
 use hdx_atom::{Atom, Atomizable};
 use miette::{SourceOffset, SourceSpan};
 #[cfg(feature = "serde")]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+// Nodes made outside of a parser, such as in JavaScript, can leave out their span.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Span {
 	pub start: u32,
 	pub end: u32,
@@ -54,7 +55,7 @@ impl From<Span> for SourceSpan {
 }
 
 #[derive(Debug, Clone, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize), serde())]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde())]
 pub struct Spanned<T> {
 	pub node: T,
 	#[cfg_attr(feature = "serde", serde(flatten))]
@@ -65,6 +66,10 @@ impl<T> Spanned<T> {
 	pub fn dummy(node: T) -> Self {
 		Self { node, span: Span::dummy() }
 	}
+
+	pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+		Spanned { node: f(self.node), span: self.span }
+	}
 }
 
 impl<T: Atomizable> Atomizable for Spanned<T> {
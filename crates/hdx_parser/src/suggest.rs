@@ -0,0 +1,87 @@
+use closestmatch::ClosestMatch;
+use smallvec::{smallvec, SmallVec};
+
+/// The one of `candidates` which `word` was most likely meant to be, for a "did you mean" hint. This is `None` if
+/// none of them are close enough to be a plausible typo of `word`.
+pub fn closest_match<'c>(word: &str, candidates: impl IntoIterator<Item = &'c str>) -> Option<&'c str> {
+	let word = word.to_ascii_lowercase();
+	// Only those a few edits away are plausible, which rules out words of one or two characters, as any other short
+	// word is as close.
+	let max_distance = word.len() / 3;
+	let word_letters = letters(word.as_bytes());
+	let candidates: std::vec::Vec<&'c str> = candidates
+		.into_iter()
+		.filter(|candidate| candidate.len().abs_diff(word.len()) <= max_distance)
+		// Every letter in one but not the other takes at least one edit, which is quicker to count than the edits.
+		.filter(|candidate| {
+			let other = letters(candidate.as_bytes());
+			(word_letters & !other).count_ones().max((other & !word_letters).count_ones()) as usize <= max_distance
+		})
+		.filter(|candidate| {
+			edit_distance(word.as_bytes(), candidate.to_ascii_lowercase().as_bytes(), max_distance)
+				.is_some_and(|d| d > 0)
+		})
+		.collect();
+	if candidates.len() < 2 {
+		return candidates.first().copied();
+	}
+	// Of those, the best is the one sharing the most of its letters, and runs of letters, with `word`.
+	let dictionary = candidates.iter().map(|candidate| candidate.to_string()).collect();
+	let closest = ClosestMatch::new(dictionary, vec![1, 2, 3]).get_closest(word)?;
+	// The closest match is lowercased, even if the candidate wasn't.
+	candidates.into_iter().find(|candidate| candidate.eq_ignore_ascii_case(&closest))
+}
+
+// Which letters `word` has, as bits. Some characters share a bit, which can only make words look more alike.
+fn letters(word: &[u8]) -> u64 {
+	word.iter().fold(0, |letters, char| letters | 1 << (char.to_ascii_lowercase() & 63))
+}
+
+// The number of characters which need adding, removing, replacing or swapping with the one next to them to turn `a`
+// into `b`, ignoring ASCII case, or `None` if that's more than `max`.
+// https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance#Optimal_string_alignment_distance
+fn edit_distance(a: &[u8], b: &[u8], max: usize) -> Option<usize> {
+	let (a_len, b_len) = (a.len(), b.len());
+	// Only the last two rows are needed to work out the next.
+	let mut before: SmallVec<[usize; 32]> = smallvec![0; b.len() + 1];
+	let mut previous: SmallVec<[usize; 32]> = (0..=b.len()).collect();
+	let mut current: SmallVec<[usize; 32]> = smallvec![0; b.len() + 1];
+	let (a, b) = (|i: usize| a[i].to_ascii_lowercase(), |j: usize| b[j].to_ascii_lowercase());
+	for i in 1..=a_len {
+		current[0] = i;
+		for j in 1..=b_len {
+			let cost = (a(i - 1) != b(j - 1)) as usize;
+			let mut distance = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+			if i > 1 && j > 1 && a(i - 1) == b(j - 2) && a(i - 2) == b(j - 1) {
+				distance = distance.min(before[j - 2] + 1);
+			}
+			current[j] = distance;
+		}
+		// Distances only grow from one row to the next, so once they're all too far there's no need to go on.
+		if current.iter().all(|distance| *distance > max) {
+			return None;
+		}
+		std::mem::swap(&mut before, &mut previous);
+		std::mem::swap(&mut previous, &mut current);
+	}
+	Some(previous[b_len]).filter(|distance| *distance <= max)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_closest_match() {
+		let styles = ["none", "hidden", "dotted", "dashed", "solid", "double", "groove", "ridge", "inset", "outset"];
+		assert_eq!(closest_match("sollid", styles), Some("solid"));
+		assert_eq!(closest_match("DASHD", styles), Some("dashed"));
+		assert_eq!(closest_match("red", styles), None);
+		assert_eq!(closest_match("solid", styles), None);
+		assert_eq!(closest_match("hvoer", ["hover", "focus", "active"]), Some("hover"));
+		assert_eq!(closest_match("x", ["a", "b"]), None);
+		assert_eq!(edit_distance(b"kitten", b"sitting", 3), Some(3));
+		assert_eq!(edit_distance(b"kitten", b"sitting", 2), None);
+		assert_eq!(edit_distance(b"meida", b"media", 1), Some(1));
+	}
+}
@@ -1,7 +1,9 @@
-use hdx_atom::{atom, Atom};
+use hdx_atom::{atom, Atom, ToAtom};
 use hdx_lexer::{Include, Token};
 
-use crate::{discard, expect, expect_ignore_case, match_ignore_case, parser::Parser, unexpected, Result};
+use crate::{
+	diagnostics, discard, expect, expect_ignore_case, match_ignore_case, parser::Parser, unexpected, Features, Result,
+};
 
 use super::Parse;
 
@@ -9,11 +11,44 @@ pub trait Declaration<'a>: Sized + Parse<'a> {
 	type DeclarationValue: DeclarationValue<'a>;
 
 	fn parse_name(parser: &mut Parser<'a>) -> Result<Atom> {
-		match parser.next().clone() {
+		let span = parser.span();
+		let name = match parser.next_token().clone() {
 			Token::Ident(atom) => {
-				expect!(parser.next(), Token::Colon);
-				Ok(atom.to_ascii_lowercase())
+				let name = atom.to_ascii_lowercase_atom();
+				if name.starts_with('_') && parser.enabled(Features::Strict) && !parser.enabled(Features::AllowIeHacks)
+				{
+					Err(diagnostics::DisallowedIeHack(name.clone(), span.end(parser.pos())))?
+				}
+				parser.check_vendor_prefix(&name, span.end(parser.pos()))?;
+				name
 			}
+			// `*zoom: 1` hides a declaration from everything but IE 7 and older.
+			Token::Delim('*') if matches!(parser.peek_with(Include::Whitespace), Token::Ident(_)) => {
+				if !parser.enabled(Features::AllowIeHacks) {
+					if !parser.enabled(Features::Strict) {
+						unexpected!(parser, Token::Delim('*'))
+					}
+					let name = Self::parse_prefixed_name('*', parser)?;
+					Err(diagnostics::DisallowedIeHack(name, span.end(parser.pos())))?
+				}
+				Self::parse_prefixed_name('*', parser)?.to_ascii_lowercase_atom()
+			}
+			Token::Delim('$')
+				if parser.enabled(Features::Scss)
+					&& matches!(parser.peek_with(Include::Whitespace), Token::Ident(_)) =>
+			{
+				Self::parse_prefixed_name('$', parser)?
+			}
+			token => unexpected!(parser, token),
+		};
+		expect!(parser.next_token(), Token::Colon);
+		Ok(name)
+	}
+
+	// The name of a declaration which has a delimiter ahead of it, such as an SCSS `$variable`, whose case is kept.
+	fn parse_prefixed_name(prefix: char, parser: &mut Parser<'a>) -> Result<Atom> {
+		match parser.next_token_with(Include::Whitespace).clone() {
+			Token::Ident(atom) => Ok(format!("{}{}", prefix, atom).to_atom()),
 			token => unexpected!(parser, token),
 		}
 	}
@@ -23,7 +58,7 @@ pub trait Declaration<'a>: Sized + Parse<'a> {
 			&& match_ignore_case!(parser.peek_n(2), Token::Ident(atom!("important")))
 		{
 			parser.advance();
-			expect_ignore_case!(parser.next_with(Include::all()), Token::Ident(atom!("important")));
+			expect_ignore_case!(parser.next_token_with(Include::all()), Token::Ident(atom!("important")));
 			Ok(true)
 		} else {
 			Ok(false)
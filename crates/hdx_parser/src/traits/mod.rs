@@ -1,4 +1,6 @@
-use hdx_atom::Atom;
+use std::hash::Hash;
+
+use hdx_atom::{Atom, ToAtom};
 use hdx_lexer::Token;
 
 mod declarations;
@@ -10,8 +12,8 @@ pub use rules::*;
 pub use selectors::*;
 
 use crate::{
-	expect, expect_ignore_case, parser::Parser, peek, span::Spanned, unexpected, unexpected_ident, Comparison, Result,
-	State, Vec,
+	expect, expect_ignore_case, parser::Parser, peek, span::Spanned, unexpected, unexpected_ident, Box, Comparison,
+	Remnants, Result, ShiftSpans, State, Trivia, Vec,
 };
 
 impl<'a, T: Parse<'a>> Parse<'a> for Vec<'a, T> {
@@ -33,7 +35,8 @@ impl<'a, T: Parse<'a>> Parse<'a> for Spanned<T> {
 	}
 }
 
-pub trait Parse<'a>: Sized {
+// Nodes are hashed to tell whether they've changed since they were parsed, see [Fingerprints](crate::Fingerprints).
+pub trait Parse<'a>: Sized + Hash {
 	fn parse(parser: &mut Parser<'a>) -> Result<Self>;
 
 	fn try_parse(parser: &mut Parser<'a>) -> Result<Self> {
@@ -55,13 +58,17 @@ pub trait Parse<'a>: Sized {
 	fn parse_spanned(parser: &mut Parser<'a>) -> Result<Spanned<Self>> {
 		let span = parser.span();
 		let node = Self::parse(parser)?;
-		Ok(Spanned { node, span: span.end(parser.pos()) })
+		let spanned = Spanned { node, span: span.end(parser.pos()) };
+		parser.fingerprint(&spanned);
+		Ok(spanned)
 	}
 
 	fn try_parse_spanned(parser: &mut Parser<'a>) -> Result<Spanned<Self>> {
 		let span = parser.span();
 		let node = Self::try_parse(parser)?;
-		Ok(Spanned { node, span: span.end(parser.prev_pos) })
+		let spanned = Spanned { node, span: span.end(parser.prev_pos) };
+		parser.fingerprint(&spanned);
+		Ok(spanned)
 	}
 
 	fn parse_spanned_with_state(parser: &mut Parser<'a>, state: State) -> Result<Spanned<Self>> {
@@ -73,15 +80,26 @@ pub trait Parse<'a>: Sized {
 	}
 }
 
+/// The declarations & rules within a block, in the order they each appeared in.
+pub type BlockContents<'a, D, R> = (Vec<'a, Spanned<D>>, Vec<'a, Spanned<R>>);
+
 pub trait Block<'a>: Sized + Parse<'a> {
-	type Declaration: Parse<'a>;
-	type Rule: Parse<'a>;
+	// Declarations which fail to parse are kept as declarations, unless they look like a rule, so they stay in the
+	// order they were written in.
+	type Declaration: Parse<'a> + From<Remnants>;
+	type Rule: Parse<'a> + From<Remnants>;
+
+	fn parse_block(parser: &mut Parser<'a>) -> Result<BlockContents<'a, Self::Declaration, Self::Rule>> {
+		expect!(parser.next_token(), Token::LeftCurly);
+		let contents = Self::parse_block_contents(parser)?;
+		// Either the closing curly, or the end of the file for a block which was never closed.
+		parser.advance();
+		Ok(contents)
+	}
 
 	// https://drafts.csswg.org/css-syntax-3/#consume-block-contents
-	fn parse_block(
-		parser: &mut Parser<'a>,
-	) -> Result<(Vec<'a, Spanned<Self::Declaration>>, Vec<'a, Spanned<Self::Rule>>)> {
-		expect!(parser.next(), Token::LeftCurly);
+	// This stops ahead of the closing curly, so it can also parse contents which have none, such as a style attribute.
+	fn parse_block_contents(parser: &mut Parser<'a>) -> Result<BlockContents<'a, Self::Declaration, Self::Rule>> {
 		let mut declarations = parser.new_vec();
 		let mut rules = parser.new_vec();
 		loop {
@@ -90,19 +108,39 @@ pub trait Block<'a>: Sized + Parse<'a> {
 					parser.advance();
 				}
 				Token::Eof | Token::RightCurly => {
-					parser.advance();
 					break;
 				}
 				Token::AtKeyword(_) => {
-					rules.push(Self::Rule::parse_spanned_with_state(parser, State::Nested)?);
+					let checkpoint = parser.checkpoint();
+					match Self::Rule::parse_spanned_with_state(parser, State::Nested) {
+						Ok(rule) => rules.push(rule),
+						Err(error) => {
+							let remnants = parser.recover(checkpoint, true);
+							parser.error(error);
+							rules.push(remnants.map(Self::Rule::from));
+						}
+					}
 				}
 				_ => {
 					let checkpoint = parser.checkpoint();
-					if let Ok(decl) = Self::Declaration::parse_spanned_with_state(parser, State::Nested) {
-						declarations.push(decl);
-					} else {
-						parser.rewind(checkpoint);
-						rules.push(Self::Rule::parse_spanned(parser)?);
+					match Self::Declaration::parse_spanned_with_state(parser, State::Nested) {
+						Ok(decl) => declarations.push(decl),
+						Err(decl_error) => {
+							parser.rewind(checkpoint);
+							let checkpoint = parser.checkpoint();
+							let rule = Self::Rule::parse_spanned_with_state(parser, State::Nested);
+							if let Err(rule_error) = rule.map(|rule| rules.push(rule)) {
+								// Report whichever of the two it looks like it was meant to be.
+								let remnants = parser.recover(checkpoint, true);
+								if remnants.node.is_rule() {
+									parser.error(rule_error);
+									rules.push(remnants.map(Self::Rule::from));
+								} else {
+									parser.error(decl_error);
+									declarations.push(remnants.map(|remnants| remnants.declaration().into()));
+								}
+							}
+						}
 					}
 				}
 			}
@@ -112,20 +150,40 @@ pub trait Block<'a>: Sized + Parse<'a> {
 }
 
 pub trait StyleSheet<'a>: Sized + Parse<'a> {
-	type Rule: Parse<'a>;
+	type Rule: Parse<'a> + From<Remnants> + ShiftSpans;
+
+	/// The top-level rules, along with the comments which aren't part of any of them. These are all that
+	/// [reparsing](Parser::reparse_with) needs to change when the source text is edited.
+	fn parts_mut(&mut self) -> (&mut Vec<'a, Spanned<Self::Rule>>, &mut Option<Box<'a, Trivia<'a>>>);
 
 	fn parse_stylesheet(parser: &mut Parser<'a>) -> Result<Vec<'a, Spanned<Self::Rule>>> {
 		let mut rules: Vec<'a, Spanned<Self::Rule>> = parser.new_vec();
+		while let Some(rule) = Self::parse_next_rule(parser) {
+			rules.push(rule);
+		}
+		Ok(rules)
+	}
+
+	/// Parses the next top-level rule, or returns None once the end of the file is reached.
+	fn parse_next_rule(parser: &mut Parser<'a>) -> Option<Spanned<Self::Rule>> {
 		loop {
 			match parser.peek() {
 				Token::Eof => {
-					return Ok(rules);
+					return None;
 				}
 				Token::Cdc | Token::Cdo => {
 					parser.advance();
 				}
 				_ => {
-					rules.push(Self::Rule::parse_spanned(parser)?);
+					let checkpoint = parser.checkpoint();
+					return Some(match Self::Rule::parse_spanned(parser) {
+						Ok(rule) => rule,
+						Err(error) => {
+							let remnants = parser.recover(checkpoint, false);
+							parser.error(error);
+							remnants.map(Self::Rule::from)
+						}
+					});
 				}
 			}
 		}
@@ -136,7 +194,7 @@ pub trait DiscreteMediaFeature<'a>: Sized + Default {
 	fn parse_media_feature_value(parser: &mut Parser<'a>) -> Result<Self>;
 
 	fn parse_descrete_media_feature(name: Atom, parser: &mut Parser<'a>) -> Result<Self> {
-		expect_ignore_case!(parser.next(), Token::Ident(name));
+		expect_ignore_case!(parser.next_token(), Token::Ident(name));
 		let value = match parser.peek() {
 			Token::Colon => {
 				parser.advance();
@@ -156,9 +214,9 @@ pub trait RangedMediaFeature<'a>: Sized {
 	fn parse_ranged_media_feature(name: Atom, parser: &mut Parser<'a>) -> Result<Self> {
 		let left = match parser.peek().clone() {
 			Token::Ident(atom) => {
-				parser.next();
+				parser.next_token();
 				let mut legacy = false;
-				let legacy_cmp = match atom.to_ascii_lowercase() {
+				let legacy_cmp = match atom.to_ascii_lowercase_atom() {
 					atom if atom == name => {
 						legacy = peek!(parser, Token::Colon);
 						Comparison::Equal
@@ -174,7 +232,7 @@ pub trait RangedMediaFeature<'a>: Sized {
 					_ => unexpected_ident!(parser, atom),
 				};
 				if legacy {
-					expect!(parser.next(), Token::Colon);
+					expect!(parser.next_token(), Token::Colon);
 					return Ok(Self::new((legacy_cmp, Self::Type::parse(parser)?), None, true));
 				} else {
 					let cmp = Comparison::parse(parser)?;
@@ -186,7 +244,7 @@ pub trait RangedMediaFeature<'a>: Sized {
 			}
 		};
 		let left_cmp = Comparison::parse(parser)?;
-		expect_ignore_case!(parser.next(), Token::Ident(name));
+		expect_ignore_case!(parser.next_token(), Token::Ident(name));
 		if !peek!(parser, Token::Delim(_)) {
 			return Ok(Self::new((left_cmp, left), None, false));
 		}
@@ -92,7 +92,7 @@ pub trait RuleList<'a>: Sized + Parse<'a> {
 	type Rule: Parse<'a>;
 
 	fn parse_rule_list(parser: &mut Parser<'a>) -> Result<Vec<'a, Spanned<Self::Rule>>> {
-		expect!(parser.next(), Token::LeftCurly);
+		expect!(parser.next_token(), Token::LeftCurly);
 		let mut rules = parser.new_vec();
 		loop {
 			discard!(parser, Token::Semicolon);
@@ -112,7 +112,7 @@ pub trait DeclarationRuleList<'a>: Sized + Parse<'a> {
 	fn parse_declaration_rule_list(
 		parser: &mut Parser<'a>,
 	) -> Result<(Vec<'a, Spanned<Self::Declaration>>, Vec<'a, Spanned<Self::AtRule>>)> {
-		expect!(parser.next(), Token::LeftCurly);
+		expect!(parser.next_token(), Token::LeftCurly);
 		let mut declarations = parser.new_vec();
 		let mut rules = parser.new_vec();
 		loop {
@@ -1,10 +1,13 @@
-use hdx_atom::Atom;
+use hdx_atom::{Atom, ToAtom};
 use hdx_lexer::{Include, Token};
 
-use crate::{diagnostics, discard, expect, parser::Parser, peek, span::Spanned, unexpected, Result, Vec};
+use crate::{diagnostics, discard, expect, parser::Parser, peek, span::Spanned, unexpected, Commented, Result, Vec};
 
 use super::Parse;
 
+// A selector within a selector list, such as `a > b` in `a > b, c`
+pub type ComplexSelector<'a, T> = Spanned<Commented<'a, Vec<'a, T>>>;
+
 // Parses various "Selector Lists" into their units
 // https://drafts.csswg.org/selectors-4/#typedef-selector-list
 // https://drafts.csswg.org/selectors-4/#typedef-relative-selector-list
@@ -18,9 +21,10 @@ pub trait SelectorList<'a>: Sized + Parse<'a> {
 	// https://drafts.csswg.org/selectors-4/#typedef-pseudo-element-selector
 	type SelectorComponent: Parse<'a>;
 
-	fn parse_selector_list(parser: &mut Parser<'a>) -> Result<Vec<'a, Spanned<Vec<'a, Self::SelectorComponent>>>> {
+	fn parse_selector_list(parser: &mut Parser<'a>) -> Result<Vec<'a, ComplexSelector<'a, Self::SelectorComponent>>> {
 		let mut selectors = parser.new_vec();
 		loop {
+			let leading = parser.leading_comments();
 			while discard!(parser, Include::Whitespace, Token::Whitespace) {}
 			let span = parser.span();
 			let mut selector = parser.new_vec();
@@ -33,7 +37,11 @@ pub trait SelectorList<'a>: Sized + Parse<'a> {
 					selector.push(Self::SelectorComponent::parse(parser)?);
 				}
 			}
-			selectors.push(Spanned { node: selector, span: span.end(parser.pos()) });
+			let span = span.end(parser.pos());
+			let mut trailing = parser.comments_within(span);
+			trailing.extend(parser.trailing_comments());
+			let trivia = parser.new_trivia(leading, trailing);
+			selectors.push(Spanned { node: Commented { node: selector, trivia }, span });
 			if !discard!(parser, Token::Comma) {
 				break;
 			}
@@ -52,6 +60,17 @@ pub trait SelectorComponent<'a>: Sized {
 	fn legacy_pseudo_element_from_token(atom: &Atom) -> Option<Self>;
 	fn pseudo_element_from_atom(atom: &Atom) -> Option<Self>;
 
+	// The known names which an unknown `atom` was most likely meant to be, for "did you mean" diagnostics.
+	fn suggest_type(_atom: &Atom) -> Option<&'static str> {
+		None
+	}
+	fn suggest_pseudo_class(_atom: &Atom) -> Option<&'static str> {
+		None
+	}
+	fn suggest_pseudo_element(_atom: &Atom) -> Option<&'static str> {
+		None
+	}
+
 	fn ns_type_from_token(parser: &mut Parser<'a>) -> Result<Self>;
 
 	fn parse_combinator(parser: &mut Parser<'a>) -> Result<Self>;
@@ -61,28 +80,39 @@ pub trait SelectorComponent<'a>: Sized {
 
 	fn parse_selector_component(parser: &mut Parser<'a>) -> Result<Self> {
 		match parser.peek_with(Include::Whitespace).clone() {
-			Token::Ident(ref atom) => match parser.peek_n_with(2, Include::Whitespace) {
+			Token::Ident(atom) => match parser.peek_n_with(2, Include::Whitespace) {
 				Token::Delim('|') => {
 					parser.advance_with(Include::Whitespace);
 					Self::ns_type_from_token(parser)
 				}
 				_ => {
 					parser.advance();
-					Self::type_from_atom(atom)
-						.ok_or_else(|| diagnostics::UnexpectedTag(atom.clone(), parser.span()).into())
+					let atom = atom.to_atom();
+					let Some(component) = Self::type_from_atom(&atom) else {
+						Err(diagnostics::UnexpectedTag(atom, parser.span()))?
+					};
+					if let Some(suggestion) = Self::suggest_type(&atom) {
+						parser
+							.warn(diagnostics::UnknownTagSuggest(atom, suggestion.to_atom(), parser.cur_span()).into());
+					}
+					Ok(component)
 				}
 			},
-			Token::HashId(ref atom) => {
+			Token::HashId(atom) => {
 				parser.advance();
-				Self::type_from_atom(atom).ok_or_else(|| diagnostics::UnexpectedId(atom.clone(), parser.span()).into())
+				let atom = atom.to_atom();
+				Self::type_from_atom(&atom).ok_or_else(|| diagnostics::UnexpectedId(atom, parser.span()).into())
 			}
 			Token::LeftSquare => Ok(Self::parse_attribute(parser)?),
 			Token::Delim(ch) => match ch {
 				'.' => {
 					parser.advance();
-					match parser.next_with(Include::Whitespace).clone() {
-						Token::Ident(atom) => Self::class_from_atom(&atom)
-							.ok_or_else(|| diagnostics::UnexpectedIdent(atom.clone(), parser.span()).into()),
+					match parser.next_token_with(Include::Whitespace).clone() {
+						Token::Ident(atom) => {
+							let atom = atom.to_atom();
+							Self::class_from_atom(&atom)
+								.ok_or_else(|| diagnostics::UnexpectedIdent(atom, parser.span()).into())
+						}
 						token => unexpected!(parser, token),
 					}
 				}
@@ -100,21 +130,49 @@ pub trait SelectorComponent<'a>: Sized {
 				match parser.peek_with(Include::Whitespace).clone() {
 					Token::Colon => {
 						parser.advance_with(Include::Whitespace);
-						match parser.next_with(Include::Whitespace).clone() {
-							Token::Ident(atom) => Self::pseudo_element_from_atom(&atom).ok_or_else(|| {
-								diagnostics::UnexpectedPseudoElement(atom.clone(), parser.span()).into()
-							}),
-							Token::Function(_) => Self::parse_functional_pseudo_element(parser),
+						match parser.next_token_with(Include::Whitespace).clone() {
+							Token::Ident(atom) => {
+								let atom = atom.to_atom();
+								parser.check_vendor_prefix(&atom, parser.span())?;
+								Self::pseudo_element_from_atom(&atom).ok_or_else(|| match Self::suggest_pseudo_element(
+									&atom,
+								) {
+									Some(suggestion) => diagnostics::UnexpectedPseudoElementSuggest(
+										atom,
+										suggestion.to_atom(),
+										parser.cur_span(),
+									)
+									.into(),
+									None => diagnostics::UnexpectedPseudoElement(atom, parser.span()).into(),
+								})
+							}
+							Token::Function(name) => {
+								parser.check_vendor_prefix(name, parser.span())?;
+								Self::parse_functional_pseudo_element(parser)
+							}
 							token => unexpected!(parser, token),
 						}
 					}
-					Token::Ident(ref atom) => {
+					Token::Ident(atom) => {
 						parser.advance_with(Include::Whitespace);
-						Self::legacy_pseudo_element_from_token(atom)
-							.or_else(|| Self::pseudo_class_from_atom(atom))
-							.ok_or_else(|| diagnostics::UnexpectedPseudoClass(atom.clone(), parser.span()).into())
+						let atom = atom.to_atom();
+						parser.check_vendor_prefix(&atom, parser.span())?;
+						Self::legacy_pseudo_element_from_token(&atom)
+							.or_else(|| Self::pseudo_class_from_atom(&atom))
+							.ok_or_else(|| match Self::suggest_pseudo_class(&atom) {
+								Some(suggestion) => diagnostics::UnexpectedPseudoClassSuggest(
+									atom,
+									suggestion.to_atom(),
+									parser.cur_span(),
+								)
+								.into(),
+								None => diagnostics::UnexpectedPseudoClass(atom, parser.span()).into(),
+							})
+					}
+					Token::Function(name) => {
+						parser.check_vendor_prefix(name, parser.span())?;
+						Self::parse_functional_pseudo_class(parser)
 					}
-					Token::Function(_) => Self::parse_functional_pseudo_class(parser),
 					token => unexpected!(parser, token),
 				}
 			}
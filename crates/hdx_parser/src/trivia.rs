@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, HashSet};
+
+use hdx_atom::{Atom, ToAtom};
+use hdx_lexer::{Include, Lexer, Token};
+#[cfg(feature = "serde")]
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize};
+
+use crate::{parser::Parser, span::Span, traits::Parse, Box, Remnants, Result, Spanned, Vec};
+
+/// The text of a comment, without the `/*` & `*/`.
+pub type Comment = Spanned<Atom>;
+
+/// Comments which aren't part of a node, but are kept alongside it so they can be written back out.
+#[derive(Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde())]
+pub struct Trivia<'a> {
+	/// Comments on the lines before the node.
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "comments"))]
+	pub leading: Vec<'a, Comment>,
+	/// Comments following the node on the same line, along with any from within the node that nothing inside of it
+	/// kept. For a block, these are the comments after its last child.
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "comments"))]
+	pub trailing: Vec<'a, Comment>,
+}
+
+/// A node along with the comments around it. Lists of rules, declarations, and selectors are made of these, as
+/// that's where comments usually appear.
+#[derive(Debug, PartialEq, Hash)]
+pub struct Commented<'a, T> {
+	pub node: T,
+	pub trivia: Option<Box<'a, Trivia<'a>>>,
+}
+
+impl<'a, T> Commented<'a, T> {
+	#[inline]
+	pub fn leading(&self) -> &[Comment] {
+		self.trivia.as_ref().map_or(&[], |trivia| &trivia.leading)
+	}
+
+	#[inline]
+	pub fn trailing(&self) -> &[Comment] {
+		self.trivia.as_ref().map_or(&[], |trivia| &trivia.trailing)
+	}
+}
+
+impl<'a, T: Parse<'a>> Parse<'a> for Commented<'a, T> {
+	fn parse(parser: &mut Parser<'a>) -> Result<Self> {
+		let leading = parser.leading_comments();
+		let start = parser.pos();
+		let node = T::parse(parser)?;
+		let mut trailing = parser.comments_within(Span::new(start, parser.pos()));
+		trailing.extend(parser.trailing_comments());
+		Ok(Self { node, trivia: parser.new_trivia(leading, trailing) })
+	}
+}
+
+impl<'a, T: From<Remnants>> From<Remnants> for Commented<'a, T> {
+	fn from(remnants: Remnants) -> Self {
+		Self { node: remnants.into(), trivia: None }
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'a, T: Serialize> Serialize for Commented<'a, T> {
+	// Nodes without any comments are serialized as if they weren't wrapped.
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		match &self.trivia {
+			None => self.node.serialize(serializer),
+			Some(trivia) => {
+				let mut state = serializer.serialize_struct("Commented", 2)?;
+				state.serialize_field("node", &self.node)?;
+				state.serialize_field("trivia", trivia.as_ref())?;
+				state.end()
+			}
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+fn comments<'de, 'a, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<'a, Comment>, D::Error> {
+	// SAFETY: Trivia is only deserialized alongside a Commented node, for the same 'a as it.
+	unsafe { crate::arena::vec(deserializer) }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a, T: Deserialize<'de>> Deserialize<'de> for Commented<'a, T> {
+	// Only a node with comments is wrapped, which is told apart by having just the two fields of one that is.
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+		use serde::de::Error;
+		use serde_json::Value;
+		let value = Value::deserialize(deserializer)?;
+		match value {
+			Value::Object(mut map) if map.len() == 2 && map.contains_key("node") && map.contains_key("trivia") => {
+				let node = T::deserialize(map.remove("node").unwrap()).map_err(D::Error::custom)?;
+				// SAFETY: the trivia is allocated for the same 'a as the node it's kept alongside, which is DeserializeIn<'a>.
+				let trivia = unsafe { crate::arena::option_boxed(map.remove("trivia").unwrap()) };
+				let trivia = trivia.map_err(D::Error::custom)?;
+				Ok(Self { node, trivia })
+			}
+			value => Ok(Self { node: T::deserialize(value).map_err(D::Error::custom)?, trivia: None }),
+		}
+	}
+}
+
+impl<'a> Parser<'a> {
+	/// Takes the comments between the current token and the next one.
+	pub fn leading_comments(&mut self) -> Vec<'a, Comment> {
+		self.take_comments(false)
+	}
+
+	/// Takes the comments between the current token and the end of its line.
+	pub fn trailing_comments(&mut self) -> Vec<'a, Comment> {
+		self.take_comments(true)
+	}
+
+	/// Takes any comments within `span` which haven't already been taken.
+	pub fn comments_within(&mut self, span: Span) -> Vec<'a, Comment> {
+		let mut comments = self.new_vec();
+		// Most nodes have no comments, which is quick to check without lexing them again.
+		if !span.source_text(self.lexer.source()).contains("/*") {
+			return comments;
+		}
+		// The nodes within this one have taken the comments within them already, so only the text between them is
+		// lexed.
+		let mut pos = span.start;
+		while pos < span.end {
+			let (start, end) = self.claimed.searched_after(pos, span.end).unwrap_or((span.end, span.end));
+			self.lex_comments(Span::new(pos, start), &mut comments);
+			pos = end.max(start);
+		}
+		self.claimed.searched(span);
+		comments
+	}
+
+	fn lex_comments(&mut self, span: Span, comments: &mut Vec<'a, Comment>) {
+		let text = span.source_text(self.lexer.source());
+		if !text.contains("/*") {
+			return;
+		}
+		let mut lexer = Lexer::new(self.allocator, text);
+		loop {
+			let start = span.start + lexer.pos();
+			match lexer.advance_with(Include::all()) {
+				Token::Eof => break,
+				Token::Comment(text) => {
+					let span = Span::new(start, span.start + lexer.pos());
+					if self.claimed.claim(span.start) {
+						comments.push(Spanned { node: text.to_atom(), span });
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+
+	/// Creates the [Trivia] for a node, if it has any comments.
+	pub fn new_trivia(&self, leading: Vec<'a, Comment>, trailing: Vec<'a, Comment>) -> Option<Box<'a, Trivia<'a>>> {
+		if leading.is_empty() && trailing.is_empty() {
+			None
+		} else {
+			Some(Box::new_in(Trivia { leading, trailing }, self.allocator))
+		}
+	}
+
+	// Whether there's a comment between the current token and the end of its line, whether or not it's been taken.
+	pub(crate) fn has_trailing_comments(&self) -> bool {
+		let rest = &self.lexer.source()[self.pos() as usize..];
+		let trimmed = rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+		trimmed.starts_with("/*") && !rest[..rest.len() - trimmed.len()].contains(['\n', '\r', '\x0C'])
+	}
+
+	// Comments are skipped by the lexer, so they're found by looking at the text following the current token.
+	fn take_comments(&mut self, same_line: bool) -> Vec<'a, Comment> {
+		let mut comments = self.new_vec();
+		let source = self.lexer.source();
+		let mut pos = self.pos() as usize;
+		loop {
+			let rest = &source[pos..];
+			let trimmed = rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+			if same_line && rest[..rest.len() - trimmed.len()].contains(['\n', '\r', '\x0C']) {
+				break;
+			}
+			let Some(text) = trimmed.strip_prefix("/*") else {
+				break;
+			};
+			let start = source.len() - trimmed.len();
+			let (text, len) = match text.find("*/") {
+				Some(end) => (&text[..end], end + 4),
+				None => (text, text.len() + 2),
+			};
+			pos = start + len;
+			if self.claimed.claim(start as u32) {
+				comments.push(Spanned { node: Atom::from(text), span: Span::new(start as u32, pos as u32) });
+			}
+		}
+		comments
+	}
+}
+
+/// The comments which have been attached to a node as trivia, by where each starts, along with the spans which
+/// [comments_within](Parser::comments_within) has taken them from. These are undone in the order they were done when
+/// the parser rewinds, so a checkpoint only needs to keep the [len](Claimed::len).
+#[derive(Default)]
+pub(crate) struct Claimed {
+	comments: HashSet<u32>,
+	searched: BTreeMap<u32, u32>,
+	done: std::vec::Vec<Claim>,
+}
+
+enum Claim {
+	Comment(u32),
+	Searched(u32, Option<u32>),
+}
+
+impl Claimed {
+	pub(crate) fn len(&self) -> usize {
+		self.done.len()
+	}
+
+	pub(crate) fn truncate(&mut self, len: usize) {
+		for claim in self.done.drain(len..).rev() {
+			match claim {
+				Claim::Comment(start) => {
+					self.comments.remove(&start);
+				}
+				Claim::Searched(start, None) => {
+					self.searched.remove(&start);
+				}
+				Claim::Searched(start, Some(end)) => {
+					self.searched.insert(start, end);
+				}
+			}
+		}
+	}
+
+	// Claims the comment starting at `start`, returning false if it has been claimed already.
+	fn claim(&mut self, start: u32) -> bool {
+		let claimed = self.comments.insert(start);
+		if claimed {
+			self.done.push(Claim::Comment(start));
+		}
+		claimed
+	}
+
+	fn searched(&mut self, span: Span) {
+		let previous = self.searched.get(&span.start).copied();
+		if previous.map_or(true, |end| end < span.end) {
+			self.searched.insert(span.start, span.end);
+			self.done.push(Claim::Searched(span.start, previous));
+		}
+	}
+
+	// The first span searched already which starts within `start..end`.
+	fn searched_after(&self, start: u32, end: u32) -> Option<(u32, u32)> {
+		self.searched.range(start..end).next().map(|(start, searched_end)| (*start, (*searched_end).min(end)))
+	}
+}
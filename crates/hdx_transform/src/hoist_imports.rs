@@ -0,0 +1,41 @@
+use hdx_ast::css::{stylesheet::Rule, visit::VisitMut, StyleSheet};
+
+// The order rules must be in at the top of a style sheet: `@charset`, then `@import`, then everything else.
+fn rank(rule: &Rule) -> u8 {
+	match rule {
+		Rule::Charset(_) => 0,
+		Rule::Import(_) => 1,
+		Rule::UnknownAt(rule) if rule.name.as_ref().eq_ignore_ascii_case("import") => 1,
+		_ => 2,
+	}
+}
+
+/// Moves the `@charset` and `@import` rules of a style sheet made of several others back to the top of it, keeping
+/// only the first `@charset`. Browsers ignore these rules anywhere else.
+#[derive(Default)]
+pub struct HoistImports();
+
+impl<'a> VisitMut<'a> for HoistImports {
+	fn visit_style_sheet(&mut self, stylesheet: &mut StyleSheet<'a>) {
+		stylesheet.rules.sort_by_key(|rule| rank(&rule.node.node));
+		let mut charset = false;
+		stylesheet
+			.rules
+			.retain(|rule| !matches!(rule.node.node, Rule::Charset(_)) || !std::mem::replace(&mut charset, true));
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::test_helpers::*;
+
+	#[test]
+	fn test_transform() {
+		assert_transform!(
+			HoistImports,
+			"a { color: red; }\n@charset \"utf-8\";\n@charset \"utf-8\";",
+			"@charset \"utf-8\";\na {\n\tcolor: red;\n}\n"
+		);
+	}
+}
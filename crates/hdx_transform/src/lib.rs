@@ -1,5 +1,11 @@
+mod hoist_imports;
+mod pipeline;
 mod reduce_initial;
+mod remove_empty_rules;
+pub use hoist_imports::*;
+pub use pipeline::*;
 pub use reduce_initial::*;
+pub use remove_empty_rules::*;
 
 #[cfg(test)]
 pub mod test_helpers;
@@ -0,0 +1,424 @@
+use std::{
+	collections::hash_map::DefaultHasher,
+	fmt::{Display, Formatter, Result as DisplayResult},
+	hash::{Hash, Hasher},
+	time::{Duration, Instant},
+};
+
+use bumpalo::Bump;
+use hdx_ast::css::{
+	builder::AstBuilder,
+	properties::Property,
+	rules::{Keyframe, MediaQuery},
+	selector::SelectorList,
+	visit::{Visit, VisitContext, VisitMut, Visitable, VisitableMut},
+	StyleSheet,
+};
+
+use crate::{HoistImports, ReduceInitial, RemoveEmptyRules};
+
+/// The passes which can be added to a [Pipeline] by name, with [Pipeline::add_named].
+pub const PASSES: [&str; 3] = ["reduce-initial", "remove-empty-rules", "hoist-imports"];
+
+/// A transform run over a whole [StyleSheet] by a [Pipeline].
+pub trait Pass<'a> {
+	/// The name of the pass, such as `reduce-initial`, which other passes can name to run after it.
+	fn name(&self) -> &'static str;
+
+	/// The passes which, if they're in the same pipeline, have to run before this one.
+	fn after(&self) -> &'static [&'static str] {
+		&[]
+	}
+
+	fn run(&mut self, stylesheet: &mut StyleSheet<'a>, builder: AstBuilder<'a>);
+}
+
+struct VisitorPass<V> {
+	name: &'static str,
+	after: &'static [&'static str],
+	visitor: V,
+}
+
+impl<'a, V: VisitMut<'a>> Pass<'a> for VisitorPass<V> {
+	fn name(&self) -> &'static str {
+		self.name
+	}
+
+	fn after(&self) -> &'static [&'static str] {
+		self.after
+	}
+
+	fn run(&mut self, stylesheet: &mut StyleSheet<'a>, builder: AstBuilder<'a>) {
+		stylesheet.accept_mut_with(&mut self.visitor, &mut VisitContext::new(builder));
+	}
+}
+
+struct FnPass<F> {
+	name: &'static str,
+	after: &'static [&'static str],
+	run: F,
+}
+
+impl<'a, F: FnMut(&mut StyleSheet<'a>, AstBuilder<'a>)> Pass<'a> for FnPass<F> {
+	fn name(&self) -> &'static str {
+		self.name
+	}
+
+	fn after(&self) -> &'static [&'static str] {
+		self.after
+	}
+
+	fn run(&mut self, stylesheet: &mut StyleSheet<'a>, builder: AstBuilder<'a>) {
+		(self.run)(stylesheet, builder)
+	}
+}
+
+/// The oldest version of each browser which the output of [Pipeline::downlevel] has to work in. Browsers left as
+/// `None` don't need supporting.
+///
+/// Only `ie` and `firefox` change what [Pipeline::downlevel] does for now, as the `initial` keyword is the only thing
+/// hdx can rewrite, and every version of the other browsers supports it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Targets {
+	pub chrome: Option<u32>,
+	pub edge: Option<u32>,
+	pub firefox: Option<u32>,
+	pub ie: Option<u32>,
+	pub safari: Option<u32>,
+}
+
+/// How long a pass of a [Pipeline] took, and how many changes it made, if the pipeline
+/// [tracks changes](Pipeline::track_changes).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassReport {
+	pub name: &'static str,
+	pub duration: Duration,
+	/// How many declarations, selector lists, media queries and keyframes the pass changed, added or removed, however
+	/// deeply nested.
+	pub changes: Option<usize>,
+}
+
+/// The passes of a [Pipeline] which couldn't run, as each of them has to run [after](Pass::after) another of them,
+/// such as a pass which names itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CyclicPasses(pub Vec<&'static str>);
+
+impl Display for CyclicPasses {
+	fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+		write!(f, "the passes {} each have to run after another of them", self.0.join(", "))
+	}
+}
+
+impl std::error::Error for CyclicPasses {}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PipelineReport {
+	/// In the order the passes ran.
+	pub passes: Vec<PassReport>,
+}
+
+impl PipelineReport {
+	pub fn duration(&self) -> Duration {
+		self.passes.iter().map(|pass| pass.duration).sum()
+	}
+
+	pub fn changes(&self) -> Option<usize> {
+		self.passes.iter().map(|pass| pass.changes).sum()
+	}
+}
+
+/// A set of [Passes](Pass) to run over a [StyleSheet]. Passes run in the order they were added in, other than those
+/// which name passes to run [after](Pass::after), which run once those have.
+#[derive(Default)]
+pub struct Pipeline<'a> {
+	passes: Vec<Box<dyn Pass<'a> + 'a>>,
+	track_changes: bool,
+}
+
+impl<'a> Pipeline<'a> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Removes what doesn't change how a style sheet is applied, for `hdx --minify`.
+	pub fn minify() -> Self {
+		let mut pipeline = Self::new();
+		pipeline.add_named("reduce-initial");
+		pipeline.add_named("remove-empty-rules");
+		pipeline
+	}
+
+	/// Rewrites what the `targets` don't support into what they do. This is a placeholder for now, as `initial` is the
+	/// only thing hdx can rewrite.
+	pub fn downlevel(targets: Targets) -> Self {
+		let mut pipeline = Self::new();
+		// The `initial` keyword isn't supported by IE, nor by Firefox before 19.
+		if targets.ie.is_some() || targets.firefox.is_some_and(|version| version < 19) {
+			pipeline.add_named("reduce-initial");
+		}
+		pipeline
+	}
+
+	/// Tidies up style sheets which have been joined into one. This is a placeholder for now, as hdx can't join them
+	/// yet, so only moves `@import` rules to the top.
+	pub fn bundle() -> Self {
+		let mut pipeline = Self::new();
+		pipeline.add_named("hoist-imports");
+		pipeline
+	}
+
+	pub fn add(&mut self, pass: impl Pass<'a> + 'a) -> &mut Self {
+		self.passes.push(Box::new(pass));
+		self
+	}
+
+	/// Adds a pass which visits the style sheet with `visitor`.
+	pub fn add_visitor<V: VisitMut<'a> + 'a>(
+		&mut self,
+		name: &'static str,
+		after: &'static [&'static str],
+		visitor: V,
+	) -> &mut Self {
+		self.add(VisitorPass { name, after, visitor })
+	}
+
+	/// Adds a pass which is given the whole style sheet, along with a builder for the arena it was parsed into.
+	pub fn add_fn(
+		&mut self,
+		name: &'static str,
+		after: &'static [&'static str],
+		run: impl FnMut(&mut StyleSheet<'a>, AstBuilder<'a>) + 'a,
+	) -> &mut Self {
+		self.add(FnPass { name, after, run })
+	}
+
+	/// Adds one of the [PASSES], returning false if there's no pass called `name`.
+	pub fn add_named(&mut self, name: &str) -> bool {
+		match name {
+			"reduce-initial" => self.add_visitor("reduce-initial", &[], ReduceInitial::default()),
+			"remove-empty-rules" => {
+				self.add_visitor("remove-empty-rules", &["reduce-initial"], RemoveEmptyRules::default())
+			}
+			"hoist-imports" => self.add_visitor("hoist-imports", &[], HoistImports::default()),
+			_ => return false,
+		};
+		true
+	}
+
+	/// Adds the passes of `other` which aren't already in this pipeline.
+	pub fn extend(&mut self, other: Pipeline<'a>) -> &mut Self {
+		for pass in other.passes {
+			if !self.contains(pass.name()) {
+				self.passes.push(pass);
+			}
+		}
+		self
+	}
+
+	pub fn contains(&self, name: &str) -> bool {
+		self.passes.iter().any(|pass| pass.name() == name)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.passes.is_empty()
+	}
+
+	/// Counts the [changes](PassReport::changes) each pass makes. This hashes the whole style sheet before and after
+	/// each pass, so is best left off unless the report is used.
+	pub fn track_changes(&mut self) -> &mut Self {
+		self.track_changes = true;
+		self
+	}
+
+	/// The indices of the passes, in the order they are to run in.
+	fn order(&self) -> Result<Vec<usize>, CyclicPasses> {
+		let mut order = Vec::with_capacity(self.passes.len());
+		let mut done = vec![false; self.passes.len()];
+		while order.len() < self.passes.len() {
+			// The first pass still to run with nothing left to run before it.
+			let next = (0..self.passes.len()).find(|&i| {
+				!done[i]
+					&& self.passes[i]
+						.after()
+						.iter()
+						.all(|name| self.passes.iter().enumerate().all(|(j, pass)| done[j] || pass.name() != *name))
+			});
+			let Some(next) = next else {
+				let passes = self.passes.iter().enumerate().filter(|(i, _)| !done[*i]).map(|(_, pass)| pass.name());
+				return Err(CyclicPasses(passes.collect()));
+			};
+			done[next] = true;
+			order.push(next);
+		}
+		Ok(order)
+	}
+
+	/// Runs each of the passes over `stylesheet`, in turn. None of them run if some of them have to run after each
+	/// other in a cycle.
+	pub fn run(
+		&mut self,
+		stylesheet: &mut StyleSheet<'a>,
+		allocator: &'a Bump,
+	) -> Result<PipelineReport, CyclicPasses> {
+		let mut report = PipelineReport::default();
+		for i in self.order()? {
+			let pass = &mut self.passes[i];
+			let before = self.track_changes.then(|| NodeHashes::of(stylesheet));
+			let start = Instant::now();
+			pass.run(stylesheet, AstBuilder::new(allocator));
+			let duration = start.elapsed();
+			let changes = before.map(|before| changes(&before.0, &NodeHashes::of(stylesheet).0));
+			report.passes.push(PassReport { name: pass.name(), duration, changes });
+		}
+		Ok(report)
+	}
+}
+
+// The hashes of the nodes which changes are counted in, at any depth of a style sheet. Rules are counted by their
+// selectors or media queries, rather than as a whole, so that a change within a nested rule isn't counted again for
+// each rule around it.
+#[derive(Default)]
+struct NodeHashes(Vec<u64>);
+
+impl NodeHashes {
+	// Sorted, so that they can be compared as sets.
+	fn of(stylesheet: &StyleSheet) -> Self {
+		let mut hashes = Self::default();
+		stylesheet.accept(&mut hashes);
+		hashes.0.sort_unstable();
+		hashes
+	}
+
+	fn push(&mut self, node: &impl Hash) {
+		let mut hasher = DefaultHasher::new();
+		node.hash(&mut hasher);
+		self.0.push(hasher.finish());
+	}
+}
+
+impl<'a> Visit<'a> for NodeHashes {
+	fn visit_property(&mut self, node: &Property<'a>) {
+		self.push(node);
+	}
+
+	fn visit_selector_list(&mut self, node: &SelectorList<'a>) {
+		self.push(node);
+	}
+
+	fn visit_media_query(&mut self, node: &MediaQuery) {
+		self.push(node);
+	}
+
+	fn visit_keyframe(&mut self, node: &Keyframe<'a>) {
+		self.push(node);
+	}
+}
+
+// A changed rule is one removed and one added, so the changes are whichever of those there are more of.
+fn changes(before: &[u64], after: &[u64]) -> usize {
+	let (mut removed, mut added) = (0, 0);
+	let (mut before, mut after) = (before.iter().peekable(), after.iter().peekable());
+	loop {
+		match (before.peek(), after.peek()) {
+			(Some(a), Some(b)) if a == b => {
+				before.next();
+				after.next();
+			}
+			(Some(a), Some(b)) if a < b => {
+				removed += 1;
+				before.next();
+			}
+			(Some(_), Some(_)) => {
+				added += 1;
+				after.next();
+			}
+			(Some(_), None) => {
+				removed += 1;
+				before.next();
+			}
+			(None, Some(_)) => {
+				added += 1;
+				after.next();
+			}
+			(None, None) => break,
+		}
+	}
+	removed.max(added)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use hdx_ast::css::stylesheet::Rule;
+	use hdx_parser::{Features, Parser};
+	use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
+	use std::{cell::RefCell, rc::Rc};
+
+	#[test]
+	fn test_pipeline() {
+		let allocator = Bump::default();
+		let source_text = "a {} b { width: initial } c { color: red } @media screen { e { width: initial } }";
+		let mut stylesheet =
+			Parser::new(&allocator, source_text, Features::default()).parse_with::<StyleSheet>().output.unwrap();
+		let ran = Rc::new(RefCell::new(vec![]));
+		let mut pipeline = Pipeline::minify();
+		pipeline.track_changes();
+		let (first, second) = (ran.clone(), ran.clone());
+		pipeline.add_fn("second", &["first"], move |_, _| second.borrow_mut().push("second")).add_fn(
+			"first",
+			&[],
+			move |stylesheet, builder| {
+				first.borrow_mut().push("first");
+				stylesheet.rules.push(builder.node(builder.parse_rule("d { color: blue }").unwrap()));
+			},
+		);
+		let report = pipeline.run(&mut stylesheet.node, &allocator).unwrap();
+		assert_eq!(*ran.borrow(), vec!["first", "second"]);
+		let names: Vec<_> = report.passes.iter().map(|pass| pass.name).collect();
+		assert_eq!(names, vec!["reduce-initial", "remove-empty-rules", "first", "second"]);
+		let changes: Vec<_> = report.passes.iter().map(|pass| pass.changes).collect();
+		// Adding `d` adds its selectors and its declaration.
+		assert_eq!(changes, vec![Some(2), Some(1), Some(2), Some(0)]);
+		let mut str = String::new();
+		stylesheet.write_css(&mut BaseCssWriter::new(&mut str, OutputOption::none())).unwrap();
+		assert_eq!(str, "b{width:auto}c{color:red}@media screen{e{width:auto}}d{color:blue}");
+		let report = Pipeline::minify().run(&mut stylesheet.node, &allocator).unwrap();
+		assert_eq!(report.changes(), None);
+	}
+
+	#[test]
+	fn test_changes() {
+		let allocator = Bump::default();
+		let source_text = "a { opacity: 0.5; width: 1px }";
+		let mut stylesheet =
+			Parser::new(&allocator, source_text, Features::default()).parse_with::<StyleSheet>().output.unwrap();
+		let mut pipeline = Pipeline::new();
+		pipeline.track_changes().add_fn("edit", &[], |stylesheet, builder| {
+			let Rule::Style(rule) = &mut stylesheet.rules[0].node.node else { panic!("expected a style rule") };
+			rule.style.node.declarations[0] = builder.node(builder.parse_declaration("opacity: 0.7").unwrap());
+		});
+		let report = pipeline.run(&mut stylesheet.node, &allocator).unwrap();
+		assert_eq!(report.changes(), Some(1));
+	}
+
+	#[test]
+	fn test_cycles() {
+		let allocator = Bump::default();
+		let mut stylesheet =
+			Parser::new(&allocator, "a{}", Features::default()).parse_with::<StyleSheet>().output.unwrap();
+		let mut pipeline = Pipeline::new();
+		pipeline.add_fn("itself", &["itself"], |_, _| {});
+		assert_eq!(pipeline.run(&mut stylesheet.node, &allocator), Err(CyclicPasses(vec!["itself"])));
+		let mut pipeline = Pipeline::minify();
+		pipeline.add_fn("first", &["second"], |_, _| {}).add_fn("second", &["first"], |_, _| {});
+		assert_eq!(pipeline.run(&mut stylesheet.node, &allocator), Err(CyclicPasses(vec!["first", "second"])));
+	}
+
+	#[test]
+	fn test_presets() {
+		assert!(Pipeline::downlevel(Targets::default()).is_empty());
+		assert!(Pipeline::downlevel(Targets { ie: Some(11), ..Targets::default() }).contains("reduce-initial"));
+		assert!(Pipeline::bundle().contains("hoist-imports"));
+		assert!(!Pipeline::new().add_named("reduce-final"));
+	}
+}
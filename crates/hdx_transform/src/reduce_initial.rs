@@ -2,6 +2,7 @@ use hdx_ast::css::{
 	properties::{Property, StyleValue},
 	visit::VisitMut,
 };
+use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
 
 #[derive(Default)]
 pub struct ReduceInitial();
@@ -10,7 +11,11 @@ impl<'a> VisitMut<'a> for ReduceInitial {
 	fn visit_property(&mut self, property: &mut Property<'a>) {
 		if matches!(&property.value, StyleValue::Initial) {
 			if let Some(def) = StyleValue::default_for(&property.name) {
-				property.value = def;
+				// Some values can't be written yet, and shorthands write nothing for their default.
+				let mut str = String::new();
+				if def.write_css(&mut BaseCssWriter::new(&mut str, OutputOption::none())).is_ok() && !str.is_empty() {
+					property.value = def;
+				}
 			}
 		}
 	}
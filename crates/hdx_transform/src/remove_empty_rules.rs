@@ -0,0 +1,55 @@
+use hdx_ast::css::{
+	rules::Media,
+	stylerule::StyleRule,
+	stylesheet::Rule,
+	visit::{VisitContext, VisitMut},
+	StyleSheet,
+};
+use hdx_parser::{Commented, Spanned, Vec};
+
+// Style rules without declarations, and conditional rules without rules, do nothing.
+fn is_empty(rule: &Spanned<Commented<Rule>>) -> bool {
+	match &rule.node.node {
+		Rule::Style(rule) => rule.style.node.declarations.is_empty() && rule.style.node.rules.is_empty(),
+		Rule::Media(rule) => rule.rules.node.0.is_empty(),
+		_ => false,
+	}
+}
+
+fn remove_empty(rules: &mut Vec<Spanned<Commented<Rule>>>) {
+	rules.retain(|rule| !is_empty(rule));
+}
+
+/// Removes the style rules which have no declarations, and the `@media` rules which have no rules. These are removed
+/// once their children have been visited, so rules which are only left empty by that are removed too.
+#[derive(Default)]
+pub struct RemoveEmptyRules();
+
+impl<'a> VisitMut<'a> for RemoveEmptyRules {
+	fn exit_style_sheet(&mut self, stylesheet: &mut StyleSheet<'a>, _ctx: &VisitContext) {
+		remove_empty(&mut stylesheet.rules);
+	}
+
+	fn exit_media_rule(&mut self, rule: &mut Media<'a>, _ctx: &VisitContext) {
+		remove_empty(&mut rule.rules.node.0);
+	}
+
+	fn exit_style_rule(&mut self, rule: &mut StyleRule<'a>, _ctx: &VisitContext) {
+		remove_empty(&mut rule.style.node.rules);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::test_helpers::*;
+
+	#[test]
+	fn test_transform() {
+		assert_transform!(
+			RemoveEmptyRules,
+			"a {}\n@media print { b {} }\nc { d {} }\ne { color: red; }",
+			"e {\n\tcolor: red;\n}\n"
+		);
+	}
+}
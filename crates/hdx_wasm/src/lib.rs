@@ -1,14 +1,15 @@
 use bumpalo::Bump;
-use hdx_ast::css::StyleSheet;
+use hdx_ast::css::{self, StyleSheet};
 use hdx_lexer::{Lexer, Token};
-use hdx_parser::{Features, Parser};
+use hdx_parser::{ArenaSeed, Features, LineIndex, Parser, ParserReturn, Spanned};
+use hdx_transform::Pipeline;
 use hdx_writer::{BaseCssWriter, OutputOption, WriteCss};
 #[cfg(not(feature = "fancy"))]
 use miette::JSONReportHandler;
-use miette::NamedSource;
 #[cfg(feature = "fancy")]
 use miette::{GraphicalReportHandler, GraphicalTheme};
-use serde::Serialize;
+use miette::{NamedSource, Report};
+use serde::{de::DeserializeSeed, Serialize};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(start)]
@@ -37,29 +38,42 @@ pub fn lex(source_text: String) -> Result<JsValue, serde_wasm_bindgen::Error> {
 pub fn parse(source_text: String) -> Result<SerializableParserResult, serde_wasm_bindgen::Error> {
 	let allocator = Bump::default();
 	let result = Parser::new(&allocator, source_text.as_str(), Features::default()).parse_with::<StyleSheet>();
-	let serializer = serde_wasm_bindgen::Serializer::json_compatible();
-	let diagnostics = result
-		.errors
-		.iter()
-		.chain(result.warnings.iter())
-		.flat_map(|error| {
-			let Some(labels) = error.labels() else { return vec![] };
-			labels
-				.map(|label| {
-					Diagnostic {
-						from: label.offset(),
-						to: label.offset() + label.len(),
-						code: format!("{}", error.code().unwrap_or(Box::new(""))),
-						severity: format!("{:?}", error.severity().unwrap_or_default()).to_ascii_lowercase(),
-						message: format!("{error}"),
-					}
-					.serialize(&serializer)
-					.unwrap()
-				})
-				.collect::<Vec<_>>()
-		})
-		.collect::<Vec<_>>();
-	Ok(SerializableParserResult { ast: result.output.serialize(&serializer).unwrap(), diagnostics })
+	Ok(SerializableParserResult::new(&source_text, result))
+}
+
+/// Parses the declarations of an HTML `style` attribute.
+#[wasm_bindgen]
+pub fn parse_style_attribute(source_text: String) -> Result<SerializableParserResult, serde_wasm_bindgen::Error> {
+	let allocator = Bump::default();
+	let result = css::parse_style_attribute(&allocator, source_text.as_str(), Features::default());
+	Ok(SerializableParserResult::new(&source_text, result))
+}
+
+/// Parses the value of the property `name`.
+#[wasm_bindgen]
+pub fn parse_property_value(
+	name: String,
+	source_text: String,
+) -> Result<SerializableParserResult, serde_wasm_bindgen::Error> {
+	let allocator = Bump::default();
+	let result = css::parse_property_value(&allocator, name.as_str(), source_text.as_str(), Features::default());
+	Ok(SerializableParserResult::new(&source_text, result))
+}
+
+/// Parses a list of selectors, such as one passed to `querySelector()`.
+#[wasm_bindgen]
+pub fn parse_selector_list(source_text: String) -> Result<SerializableParserResult, serde_wasm_bindgen::Error> {
+	let allocator = Bump::default();
+	let result = css::parse_selector_list(&allocator, source_text.as_str(), Features::default());
+	Ok(SerializableParserResult::new(&source_text, result))
+}
+
+/// Parses a list of media queries, such as one passed to `matchMedia()`.
+#[wasm_bindgen]
+pub fn parse_media_query_list(source_text: String) -> Result<SerializableParserResult, serde_wasm_bindgen::Error> {
+	let allocator = Bump::default();
+	let result = css::parse_media_query_list(&allocator, source_text.as_str(), Features::default());
+	Ok(SerializableParserResult::new(&source_text, result))
 }
 
 #[wasm_bindgen]
@@ -69,29 +83,49 @@ pub fn minify(source_text: String) -> Result<String, serde_wasm_bindgen::Error>
 	if !result.errors.is_empty() {
 		return Err(serde_wasm_bindgen::Error::new("Parse error"));
 	}
+	let mut stylesheet = result.output.unwrap();
+	Pipeline::minify().run(&mut stylesheet.node, &allocator).map_err(serde_wasm_bindgen::Error::new)?;
 	let mut string = String::new();
 	let mut writer = BaseCssWriter::new(&mut string, OutputOption::none());
-	result.output.unwrap().write_css(&mut writer).unwrap();
+	stylesheet.write_css(&mut writer).unwrap();
+	Ok(string)
+}
+
+/// Writes the `ast` of a style sheet from [parse()] back out as CSS, such as after it's been changed in JavaScript.
+/// Nodes added to it can leave out their `start` & `end`. It's minified first when `minify` is true.
+#[wasm_bindgen]
+pub fn print(ast: JsValue, minify: Option<bool>) -> Result<String, serde_wasm_bindgen::Error> {
+	let allocator = Bump::default();
+	let deserializer = serde_wasm_bindgen::Deserializer::from(ast);
+	// SAFETY: serde_wasm_bindgen only deserializes the style sheet.
+	let seed = unsafe { ArenaSeed::<Spanned<StyleSheet>>::new(&allocator) };
+	let mut stylesheet = seed.deserialize(deserializer)?;
+	let opts = if minify.unwrap_or(false) {
+		Pipeline::minify().run(&mut stylesheet.node, &allocator).map_err(serde_wasm_bindgen::Error::new)?;
+		OutputOption::none()
+	} else {
+		OutputOption::all()
+	};
+	let mut string = String::new();
+	let mut writer = BaseCssWriter::new(&mut string, opts);
+	if stylesheet.write_css(&mut writer).is_err() {
+		return Err(serde_wasm_bindgen::Error::new("The AST has nodes which can't yet be written"));
+	}
 	Ok(string)
 }
 
 #[wasm_bindgen]
 pub fn parse_error_report(source_text: String) -> String {
 	let allocator = Bump::default();
-	let result = Parser::new(&allocator, source_text.as_str(), Features::default()).parse_with::<StyleSheet>();
+	let mut result = Parser::new(&allocator, source_text.as_str(), Features::default()).parse_with::<StyleSheet>();
 	#[cfg(feature = "fancy")]
 	let handler = GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor());
 	#[cfg(not(feature = "fancy"))]
 	let handler = JSONReportHandler::new();
 	let mut report = String::new();
-	for err in result.errors {
-		let err = err.with_source_code(NamedSource::new("", source_text.to_string()));
-		handler.render_report(&mut report, err.as_ref()).unwrap();
-		report += "\n";
-	}
-	for warn in result.warnings {
-		let warn = warn.with_source_code(NamedSource::new("", source_text.to_string()));
-		handler.render_report(&mut report, warn.as_ref()).unwrap();
+	for diagnostic in result.take_diagnostics() {
+		let diagnostic = Report::new(diagnostic).with_source_code(NamedSource::new("", source_text.to_string()));
+		handler.render_report(&mut report, diagnostic.as_ref()).unwrap();
 		report += "\n";
 	}
 	report.to_string()
@@ -104,13 +138,88 @@ pub struct SerializableParserResult {
 	diagnostics: Vec<JsValue>,
 }
 
+/// One of the labels of a diagnostic, so there is an entry for each place in the source text it points to, the first
+/// being where the problem is. A diagnostic without any labels has a single entry, with a null `from`, `to`, `start`
+/// and `end`.
 #[derive(Default, Clone, Serialize)]
 pub struct Diagnostic {
-	pub from: usize,
-	pub to: usize,
+	pub from: Option<usize>,
+	pub to: Option<usize>,
+	pub start: Option<Position>,
+	pub end: Option<Position>,
 	pub code: String,
 	pub severity: String,
 	pub message: String,
+	/// What the label says about the text it points to, if anything.
+	pub label: Option<String>,
+	pub help: Option<String>,
+	pub fixes: Vec<Fix>,
+}
+
+/// Replacing the source text from `from` to `to` with `replacement` resolves a [Diagnostic].
+#[derive(Default, Clone, Serialize)]
+pub struct Fix {
+	pub from: usize,
+	pub to: usize,
+	pub replacement: String,
+}
+
+/// Where a [Diagnostic] starts or ends, as a zero-based line, and column in both bytes and UTF-16 code units (which
+/// is what JavaScript strings and editors count in).
+#[derive(Default, Clone, Copy, Serialize)]
+pub struct Position {
+	pub line: u32,
+	pub column: u32,
+	pub utf16_column: u32,
+}
+
+impl Position {
+	fn new(line_index: &LineIndex, offset: u32) -> Self {
+		let line_col = line_index.line_col(offset);
+		Self { line: line_col.line, column: line_col.col, utf16_column: line_index.line_col_utf16(offset).col }
+	}
+}
+
+impl SerializableParserResult {
+	fn new<T: Serialize>(source_text: &str, mut result: ParserReturn<T>) -> Self {
+		let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+		let line_index = LineIndex::new(source_text);
+		let mut diagnostics = vec![];
+		for diagnostic in result.take_diagnostics() {
+			let entry = Diagnostic {
+				code: diagnostic.code.to_string(),
+				severity: format!("{:?}", diagnostic.severity).to_ascii_lowercase(),
+				message: diagnostic.message,
+				help: diagnostic.help,
+				fixes: diagnostic
+					.fixes
+					.into_iter()
+					.map(|fix| Fix {
+						from: fix.span.start as usize,
+						to: fix.span.end as usize,
+						replacement: fix.replacement,
+					})
+					.collect(),
+				..Diagnostic::default()
+			};
+			if diagnostic.labels.is_empty() {
+				diagnostics.push(entry.serialize(&serializer).unwrap());
+			}
+			for label in diagnostic.labels {
+				let span = label.span;
+				let entry = Diagnostic {
+					from: Some(span.start as usize),
+					to: Some(span.end as usize),
+					start: Some(Position::new(&line_index, span.start)),
+					end: Some(Position::new(&line_index, span.end)),
+					label: label.message,
+					..entry.clone()
+				};
+				diagnostics.push(entry.serialize(&serializer).unwrap());
+			}
+		}
+		Self { ast: result.output.serialize(&serializer).unwrap(), diagnostics }
+	}
 }
 
 #[wasm_bindgen]
@@ -0,0 +1,74 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "kebab-case"))]
+pub enum IndentStyle {
+	#[default]
+	Tabs,
+	Spaces,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "kebab-case"))]
+pub enum QuotePreference {
+	/// Keep whichever quotes each string was written with.
+	#[default]
+	Preserve,
+	/// Use double quotes, unless the string contains a double quote (and no single quotes).
+	Double,
+	/// Use single quotes, unless the string contains a single quote (and no double quotes).
+	Single,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "kebab-case"))]
+pub enum SelectorLayout {
+	/// Keep selectors on one line, only wrapping onto the next line once `max_line_width` is reached.
+	#[default]
+	Wrapped,
+	/// Put each selector of a rule on its own line.
+	OnePerLine,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "kebab-case"))]
+pub enum HexCase {
+	/// Write hex colors in lowercase, using the short form where possible.
+	#[default]
+	Lowercase,
+	/// Write hex colors exactly as they were in the source.
+	Preserve,
+}
+
+/// Stylistic choices for how a [CssWriter](crate::CssWriter) lays out CSS. These only change the formatting of the
+/// output; what is output at all is decided by the [OutputOption](crate::OutputOption)s. The defaults match the
+/// output of a writer created without any options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+	pub indent_style: IndentStyle,
+	/// The number of spaces per level of indentation, or how many columns a tab counts as when measuring lines.
+	pub indent_width: u8,
+	/// Lines longer than this are wrapped where possible (between selectors, or the items of a comma separated
+	/// value). `None` never wraps.
+	pub max_line_width: Option<u32>,
+	pub quotes: QuotePreference,
+	/// Separate consecutive rules with an empty line.
+	pub blank_line_between_rules: bool,
+	pub selector_layout: SelectorLayout,
+	pub hex_case: HexCase,
+}
+
+impl Default for FormatOptions {
+	fn default() -> Self {
+		Self {
+			indent_style: IndentStyle::Tabs,
+			indent_width: 2,
+			max_line_width: None,
+			quotes: QuotePreference::Preserve,
+			blank_line_between_rules: false,
+			selector_layout: SelectorLayout::Wrapped,
+			hex_case: HexCase::Lowercase,
+		}
+	}
+}
@@ -0,0 +1,283 @@
+use std::{
+	fmt::{Result, Write},
+	hash::Hash,
+};
+
+use hdx_lexer::QuoteStyle;
+use hdx_parser::{Fingerprints, Span};
+
+use crate::{BaseCssWriter, CssWriter, FormatOptions, OutputOption, WriteCss};
+
+/// A [CssWriter] which reproduces the source text a tree was parsed from, byte for byte, except for the nodes which
+/// have been changed since.
+///
+/// The tree must have been parsed with [Features::Lossless](hdx_parser::Features::Lossless), and `fingerprints` be
+/// the ones the parser returned. Any [Spanned](hdx_parser::Spanned) node which is unchanged is copied from the source
+/// text, while changed or new nodes are written as usual, using `format` for their layout. Where the text between two
+/// nodes written this way is the same as in the source (ignoring whitespace and comments) the source text is kept,
+/// so an edit only changes the range of the node that was touched.
+///
+/// Call [IdentityWriter::finish] once the tree has been written, to write out the rest of the source text.
+pub struct IdentityWriter<'s, W>
+where
+	W: Write,
+{
+	sink: W,
+	source_text: &'s str,
+	fingerprints: &'s Fingerprints,
+	// Output for the nodes being written as usual, held back until it's known whether the source text can be kept
+	// in its place.
+	writer: BaseCssWriter<String>,
+	// How far into the source text the output has caught up to, if it's in step with it.
+	cursor: Option<u32>,
+	// The end of the last node copied from the source text, and its last few characters.
+	copied: Option<(u32, String)>,
+}
+
+impl<'s, W> IdentityWriter<'s, W>
+where
+	W: Write,
+{
+	pub fn new(sink: W, source_text: &'s str, fingerprints: &'s Fingerprints) -> Self {
+		Self::with_format_options(sink, source_text, fingerprints, FormatOptions::default())
+	}
+
+	pub fn with_format_options(
+		sink: W,
+		source_text: &'s str,
+		fingerprints: &'s Fingerprints,
+		format: FormatOptions,
+	) -> Self {
+		IdentityWriter {
+			sink,
+			source_text,
+			fingerprints,
+			// Semicolons after the last declaration in a block are often left out, so they're only kept if they're in
+			// the source text.
+			writer: BaseCssWriter::with_format_options(
+				String::new(),
+				OutputOption::all() & !OutputOption::Trailing,
+				format,
+			),
+			cursor: Some(0),
+			copied: None,
+		}
+	}
+
+	/// Writes out whatever is left of the source text, and returns the sink.
+	pub fn finish(mut self) -> std::result::Result<W, std::fmt::Error> {
+		let pending = std::mem::take(&mut self.writer.sink);
+		let end = self.source_text.len() as u32;
+		// Nothing being written after the outermost node means anything following it in the source text is kept too.
+		if pending.is_empty() || self.overlap(&pending, end, "").is_some() {
+			self.write_source(self.cursor.unwrap_or(end), end)?;
+		} else {
+			self.write_pending(&pending, end)?;
+		}
+		Ok(self.sink)
+	}
+
+	#[inline]
+	fn write_source(&mut self, start: u32, end: u32) -> Result {
+		self.sink.write_str(&self.source_text[start as usize..end as usize])
+	}
+
+	// Checks whether the held back output is the same as the source text from the cursor to `offset` (aside from
+	// whitespace), and if so returns how many characters of `text`, which follows `offset`, it also went on to write.
+	// Writers often put the punctuation at the edges of a node outside of it, such as the braces of a block, so
+	// this also allows for it to repeat the end of the node copied before.
+	fn overlap(&self, pending: &str, offset: u32, text: &str) -> Option<usize> {
+		let cursor = self.cursor.filter(|cursor| *cursor <= offset)?;
+		let (gap_comments, gap_text) = split_comments(&self.source_text[cursor as usize..offset as usize]);
+		let (pending_comments, pending_text) = split_comments(pending);
+		if gap_comments != pending_comments {
+			return None;
+		}
+		let text: String = text.chars().filter(|ch| !ch.is_ascii_whitespace()).take(pending_text.len()).collect();
+		let tail = self.copied.as_ref().filter(|(end, _)| *end == cursor).map_or("", |(_, tail)| tail.as_str());
+		(0..=tail.len()).filter(|i| tail.is_char_boundary(*i)).rev().find_map(|i| {
+			let extra = pending_text.strip_prefix(&tail[i..])?.strip_prefix(gap_text.as_str())?;
+			text.starts_with(extra).then(|| extra.chars().count())
+		})
+	}
+
+	// Writes out the held back output in place of the source text from the cursor to `offset`, keeping as much of the
+	// source text as possible, which is whatever the two end with.
+	fn write_pending(&mut self, pending: &str, offset: u32) -> Result {
+		if let Some(cursor) = self.cursor.filter(|cursor| *cursor <= offset) {
+			let gap = &self.source_text[cursor as usize..offset as usize];
+			let (gap_comments, gap_text) = split_comments(gap);
+			let (pending_comments, pending_text) = split_comments(pending);
+			if gap_comments.is_empty() && pending_comments.is_empty() {
+				let same = gap_text.chars().rev().zip(pending_text.chars().rev()).take_while(|(a, b)| a == b).count();
+				self.sink.write_str(&pending[..suffix_start(pending, same)])?;
+				return self.sink.write_str(&gap[suffix_start(gap, same)..]);
+			}
+		}
+		self.sink.write_str(pending)
+	}
+}
+
+// Finds the comments in some CSS text, and the rest of the text without whitespace.
+fn split_comments(text: &str) -> (Vec<&str>, String) {
+	let mut comments = vec![];
+	let mut rest = String::new();
+	let mut pos = 0;
+	while let Some(start) = text[pos..].find("/*").map(|start| pos + start) {
+		rest.extend(text[pos..start].chars().filter(|ch| !ch.is_ascii_whitespace()));
+		pos = text[start + 2..].find("*/").map_or(text.len(), |end| start + end + 4);
+		comments.push(&text[start..pos]);
+	}
+	rest.extend(text[pos..].chars().filter(|ch| !ch.is_ascii_whitespace()));
+	(comments, rest)
+}
+
+// Where the whitespace before the last `len` non-whitespace characters of `text` starts.
+fn suffix_start(text: &str, len: usize) -> usize {
+	let mut seen = 0;
+	for (i, ch) in text.char_indices().rev() {
+		if !ch.is_ascii_whitespace() {
+			if seen == len {
+				return i + ch.len_utf8();
+			}
+			seen += 1;
+		}
+	}
+	0
+}
+
+// The length of the first `len` non-whitespace characters of `text`.
+fn prefix_len(text: &str, len: usize) -> usize {
+	text.char_indices().filter(|(_, ch)| !ch.is_ascii_whitespace()).nth(len).map_or(text.len(), |(i, _)| i)
+}
+
+// The length of the whitespace and comments at the start of `text`.
+fn leading_trivia_len(text: &str) -> usize {
+	let mut rest = text.trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+	while let Some(comment) = rest.strip_prefix("/*") {
+		rest = comment.find("*/").map_or("", |end| &comment[end + 2..]);
+		rest = rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+	}
+	text.len() - rest.len()
+}
+
+impl<'s, W> CssWriter for IdentityWriter<'s, W>
+where
+	W: Write,
+{
+	fn write_original<T: Hash>(&mut self, span: Span, node: &T) -> std::result::Result<bool, std::fmt::Error> {
+		if span.is_dummy() {
+			return Ok(false);
+		}
+		let source = span.source_text(self.source_text);
+		let unchanged = self.fingerprints.contains(span, node);
+		let pending = std::mem::take(&mut self.writer.sink);
+		// Spans can start with whitespace and comments, which usually come from whatever is written before the node.
+		let leading = leading_trivia_len(source);
+		let start = span.start + leading as u32;
+		let text = &source[leading..];
+		if let Some(written) = self.overlap(&pending, start, text) {
+			self.write_source(self.cursor.unwrap(), start)?;
+			if !unchanged {
+				// The start of the node has already been written, which the source text can stand in for.
+				let len = if written > 0 { prefix_len(text, written - 1) + 1 } else { 0 };
+				self.sink.write_str(&text[..len])?;
+				self.cursor = Some(start + len as u32);
+				return Ok(false);
+			}
+		} else if unchanged && self.overlap(&pending, span.start, "") == Some(0) {
+			// The comments ahead of the node weren't written, which happens for the outermost node.
+			self.write_source(self.cursor.unwrap(), span.start)?;
+			self.sink.write_str(&source[..leading])?;
+		} else if unchanged {
+			// The writer may have started on the node anyway, which will be copied over.
+			let text_start: String = text.chars().filter(|ch| !ch.is_ascii_whitespace()).take(4).collect();
+			let (_, pending_text) = split_comments(&pending);
+			let end = (1..=text_start.len())
+				.rev()
+				.filter(|i| text_start.is_char_boundary(*i))
+				.find(|i| pending_text.ends_with(&text_start[..*i]))
+				.map_or(pending.len(), |i| suffix_start(&pending, text_start[..i].chars().count()));
+			self.write_pending(&pending[..end], start)?;
+		} else {
+			self.write_pending(&pending, start)?;
+			self.cursor = Some(start);
+			return Ok(false);
+		}
+		self.sink.write_str(text)?;
+		self.cursor = Some(span.end);
+		let tail = text.chars().rev().filter(|ch| !ch.is_ascii_whitespace()).take(4).collect::<Vec<_>>();
+		self.copied = Some((span.end, tail.into_iter().rev().collect()));
+		Ok(true)
+	}
+
+	#[inline]
+	fn can_output(&self, opt: OutputOption) -> bool {
+		self.writer.can_output(opt)
+	}
+
+	#[inline]
+	fn format_options(&self) -> &FormatOptions {
+		self.writer.format_options()
+	}
+
+	#[inline]
+	fn remaining_width(&self) -> Option<u32> {
+		self.writer.remaining_width()
+	}
+
+	#[inline]
+	fn measure<'a, T: WriteCss<'a>>(&self, node: &T) -> u32 {
+		self.writer.measure(node)
+	}
+
+	#[inline]
+	fn write_str(&mut self, str: &str) -> Result {
+		self.writer.write_str(str)
+	}
+
+	#[inline]
+	fn write_char(&mut self, ch: char) -> Result {
+		self.writer.write_char(ch)
+	}
+
+	#[inline]
+	fn write_newline(&mut self) -> Result {
+		self.writer.write_newline()
+	}
+
+	#[inline]
+	fn write_comment(&mut self, str: &str) -> Result {
+		self.writer.write_comment(str)
+	}
+
+	#[inline]
+	fn write_with_quotes(&mut self, str: &str, quote: QuoteStyle, could_be_ident: bool) -> Result {
+		self.writer.write_with_quotes(str, quote, could_be_ident)
+	}
+
+	#[inline]
+	fn write_trailing_char(&mut self, ch: char) -> Result {
+		self.writer.write_trailing_char(ch)
+	}
+
+	#[inline]
+	fn write_whitespace(&mut self) -> Result {
+		self.writer.write_whitespace()
+	}
+
+	#[inline]
+	fn write_indent(&mut self) -> Result {
+		self.writer.write_indent()
+	}
+
+	#[inline]
+	fn indent(&mut self) {
+		self.writer.indent()
+	}
+
+	#[inline]
+	fn dedent(&mut self) {
+		self.writer.dedent()
+	}
+}
@@ -2,13 +2,21 @@ use bitmask_enum::bitmask;
 use hdx_syntax::identifier::is_ident_str;
 use smallvec::SmallVec;
 
+mod format_options;
+mod identity;
 mod macros;
+mod source_map;
+
+pub use format_options::*;
+pub use identity::*;
+pub use source_map::*;
 
 pub use std::fmt::{Result, Write};
+use std::hash::Hash;
 
 use hdx_atom::Atom;
 use hdx_lexer::QuoteStyle;
-use hdx_parser::{Spanned, Vec};
+use hdx_parser::{Comment, Commented, Remnants, Span, Spanned, Vec};
 
 pub trait WriteCss<'a>: Sized {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result;
@@ -27,6 +35,7 @@ pub enum OutputOption {
 	RedundantShorthandValues,
 	RedundantDefaultValues,
 	RedundantBooleanMediaFeatures,
+	ExpandedNumbers,
 }
 
 pub trait CssWriter {
@@ -41,6 +50,25 @@ pub trait CssWriter {
 	fn indent(&mut self);
 	fn dedent(&mut self);
 	fn can_output(&self, opt: OutputOption) -> bool;
+	fn format_options(&self) -> &FormatOptions;
+
+	/// How many more columns fit on the current line before reaching the `max_line_width`, or `None` if lines can be
+	/// any length.
+	fn remaining_width(&self) -> Option<u32>;
+
+	/// How many columns `node` takes up when written out on one line.
+	fn measure<'a, T: WriteCss<'a>>(&self, node: &T) -> u32;
+
+	/// Called as each [Spanned] node starts being written, with the span it was parsed from.
+	#[inline]
+	fn add_mapping(&mut self, _span: Span) {}
+
+	/// Called before each [Spanned] node is written. A writer which can reproduce the node some other way, such as
+	/// copying it from the source text, can do so here and return `true` to skip writing it as usual.
+	#[inline]
+	fn write_original<T: Hash>(&mut self, _span: Span, _node: &T) -> std::result::Result<bool, std::fmt::Error> {
+		Ok(false)
+	}
 }
 
 pub struct BaseCssWriter<W>
@@ -51,7 +79,10 @@ where
 	col: u32,
 	line: u32,
 	indent: u8,
+	// Tabs written on the current line, which are wider than their one column when measuring the line.
+	tabs: u8,
 	opts: OutputOption,
+	format: FormatOptions,
 }
 
 impl<W> BaseCssWriter<W>
@@ -59,7 +90,11 @@ where
 	W: Write,
 {
 	pub fn new(sink: W, opts: OutputOption) -> Self {
-		BaseCssWriter { sink, col: 0, line: 0, indent: 0, opts }
+		Self::with_format_options(sink, opts, FormatOptions::default())
+	}
+
+	pub fn with_format_options(sink: W, opts: OutputOption, format: FormatOptions) -> Self {
+		BaseCssWriter { sink, col: 0, line: 0, indent: 0, tabs: 0, opts, format }
 	}
 }
 
@@ -72,18 +107,38 @@ where
 		self.opts.contains(opt)
 	}
 
+	#[inline]
+	fn format_options(&self) -> &FormatOptions {
+		&self.format
+	}
+
+	fn remaining_width(&self) -> Option<u32> {
+		let width = self.col + self.tabs as u32 * (self.format.indent_width.max(1) as u32 - 1);
+		self.format.max_line_width.map(|max| max.saturating_sub(width))
+	}
+
+	fn measure<'a, T: WriteCss<'a>>(&self, node: &T) -> u32 {
+		let mut str = String::new();
+		let format = FormatOptions { max_line_width: None, ..self.format };
+		node.write_css(&mut BaseCssWriter::with_format_options(&mut str, self.opts, format)).ok();
+		str.chars().map(|ch| ch.len_utf16() as u32).sum()
+	}
+
 	#[inline]
 	fn write_str(&mut self, str: &str) -> Result {
-		self.col += str.len() as u32;
+		// Columns are counted in UTF-16 code units, as source maps expect.
+		self.col += if str.is_ascii() { str.len() } else { str.chars().map(char::len_utf16).sum() } as u32;
 		self.sink.write_str(str)
 	}
 
 	#[inline]
 	fn write_char(&mut self, ch: char) -> Result {
 		if ch == '\n' {
-			self.line += 1
+			self.line += 1;
+			self.col = 0;
+			self.tabs = 0;
 		} else {
-			self.col += 1
+			self.col += ch.len_utf16() as u32;
 		}
 		self.sink.write_char(ch)
 	}
@@ -98,21 +153,32 @@ where
 
 	#[inline]
 	fn write_comment(&mut self, str: &str) -> Result {
-		if self.can_output(OutputOption::Comments) {
+		if keeps_comment(self, str) {
+			self.write_str("/*")?;
 			self.write_str(str)?;
+			self.write_str("*/")?;
 		}
 		Ok(())
 	}
 
 	#[inline]
 	fn write_with_quotes(&mut self, str: &str, quote: QuoteStyle, could_be_ident: bool) -> Result {
-		if could_be_ident && !self.can_output(OutputOption::QuotedIdentLikeStrings) && is_ident_str(str) {
+		if quote == QuoteStyle::None
+			|| could_be_ident && !self.can_output(OutputOption::QuotedIdentLikeStrings) && is_ident_str(str)
+		{
 			self.write_str(str)
 		} else if !self.can_output(OutputOption::InconsistentQuotes) {
 			QuoteStyle::Double.write_css(self)?;
 			self.write_str(str)?;
 			QuoteStyle::Double.write_css(self)
 		} else {
+			// Only switch to the preferred quote if it doesn't appear in the string, as it would need escaping.
+			let quote = match (self.format.quotes, quote) {
+				(QuotePreference::Preserve, _) => quote,
+				(QuotePreference::Double, _) if !str.contains('"') => QuoteStyle::Double,
+				(QuotePreference::Single, _) if !str.contains('\'') => QuoteStyle::Single,
+				_ => quote,
+			};
 			quote.write_css(self)?;
 			self.write_str(str)?;
 			quote.write_css(self)
@@ -138,10 +204,19 @@ where
 	#[inline]
 	fn write_indent(&mut self) -> Result {
 		if self.can_output(OutputOption::Whitespace) {
-			for _ in 0..(self.indent) {
-				self.write_char('\t')?;
+			match self.format.indent_style {
+				IndentStyle::Tabs => {
+					for _ in 0..(self.indent) {
+						self.write_char('\t')?;
+					}
+					self.tabs += self.indent;
+				}
+				IndentStyle::Spaces => {
+					for _ in 0..(self.indent as u32 * self.format.indent_width as u32) {
+						self.write_char(' ')?;
+					}
+				}
 			}
-			self.col += self.indent as u32;
 		}
 		Ok(())
 	}
@@ -179,42 +254,119 @@ impl<'a, T: WriteCss<'a>> WriteCss<'a> for Vec<'a, T> {
 // TODO: const N: usize?
 impl<'a, T: WriteCss<'a>> WriteCss<'a> for SmallVec<[T; 0]> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
-		let mut iter = self.iter().peekable();
-		while let Some(w) = iter.next() {
-			w.write_css(sink)?;
-			if iter.peek().is_some() {
-				sink.write_char(',')?;
-				sink.write_whitespace()?;
-			}
-		}
-		Ok(())
+		write_comma_list(sink, self)
 	}
 }
 impl<'a, T: WriteCss<'a>> WriteCss<'a> for SmallVec<[T; 1]> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
-		let mut iter = self.iter().peekable();
-		while let Some(w) = iter.next() {
-			w.write_css(sink)?;
-			if iter.peek().is_some() {
-				sink.write_char(',')?;
-				sink.write_whitespace()?;
-			}
-		}
-		Ok(())
+		write_comma_list(sink, self)
 	}
 }
 impl<'a, T: WriteCss<'a>> WriteCss<'a> for SmallVec<[T; 2]> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
-		let mut iter = self.iter().peekable();
-		while let Some(w) = iter.next() {
-			w.write_css(sink)?;
-			if iter.peek().is_some() {
-				sink.write_char(',')?;
+		write_comma_list(sink, self)
+	}
+}
+
+impl<'a, T: WriteCss<'a>> WriteCss<'a> for &[T] {
+	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
+		for item in self.iter() {
+			item.write_css(sink)?;
+		}
+		Ok(())
+	}
+}
+
+/// Writes `items` separated by commas, all on one line if they fit within the `max_line_width`, otherwise with each
+/// item after the first on its own, further indented, line.
+pub fn write_comma_list<'a, W: CssWriter, T: WriteCss<'a>>(sink: &mut W, items: &[T]) -> Result {
+	write_wrapped_list(sink, items, Some(','))
+}
+
+/// Writes `items` separated by spaces, like [write_comma_list].
+pub fn write_space_list<'a, W: CssWriter, T: WriteCss<'a>>(sink: &mut W, items: &[T]) -> Result {
+	write_wrapped_list(sink, items, None)
+}
+
+fn write_wrapped_list<'a, W: CssWriter, T: WriteCss<'a>>(sink: &mut W, items: &[T], separator: Option<char>) -> Result {
+	let wrap = items.len() > 1
+		&& sink.can_output(OutputOption::Whitespace)
+		&& sink.remaining_width().is_some_and(|remaining| {
+			let separators = (items.len() as u32 - 1) * (separator.is_some() as u32 + 1);
+			items.iter().map(|item| sink.measure(item)).sum::<u32>() + separators > remaining
+		});
+	if wrap {
+		sink.indent();
+	}
+	let mut iter = items.iter().peekable();
+	while let Some(item) = iter.next() {
+		item.write_css(sink)?;
+		if iter.peek().is_some() {
+			if let Some(separator) = separator {
+				sink.write_char(separator)?;
+			}
+			if wrap {
+				sink.write_newline()?;
+				sink.write_indent()?;
+			} else if separator.is_some() {
 				sink.write_whitespace()?;
+			} else {
+				sink.write_char(' ')?;
 			}
 		}
-		Ok(())
 	}
+	if wrap {
+		sink.dedent();
+	}
+	Ok(())
+}
+
+// Comments starting with `/*!` (such as licenses) are kept even when other comments are not.
+fn keeps_comment<W: CssWriter>(sink: &W, str: &str) -> bool {
+	sink.can_output(OutputOption::Comments) || str.starts_with('!')
+}
+
+impl<'a, T: WriteCss<'a>> WriteCss<'a> for Commented<'a, T> {
+	// Where the comments go depends on the surrounding node, which writes them with [write_comment_lines],
+	// [write_inline_comments] or [write_trailing_comments].
+	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
+		self.node.write_css(sink)
+	}
+}
+
+/// Writes each comment on its own line, for comments around nodes which are on their own lines (rules and
+/// declarations).
+pub fn write_comment_lines<W: CssWriter>(sink: &mut W, comments: &[Comment]) -> Result {
+	for comment in comments.iter() {
+		if keeps_comment(sink, &comment.node) {
+			sink.write_indent()?;
+			sink.write_comment(&comment.node)?;
+			sink.write_newline()?;
+		}
+	}
+	Ok(())
+}
+
+/// Writes comments ahead of a node on the same line.
+pub fn write_inline_comments<W: CssWriter>(sink: &mut W, comments: &[Comment]) -> Result {
+	for comment in comments.iter() {
+		if keeps_comment(sink, &comment.node) {
+			sink.write_comment(&comment.node)?;
+			sink.write_whitespace()?;
+		}
+	}
+	Ok(())
+}
+
+/// Writes comments after a node on the same line.
+pub fn write_trailing_comments<W: CssWriter>(sink: &mut W, comments: &[Comment]) -> Result {
+	for comment in comments.iter() {
+		if keeps_comment(sink, &comment.node) {
+			sink.write_whitespace()?;
+			sink.write_comment(&comment.node)?;
+		}
+	}
+	Ok(())
 }
 
 impl<'a> WriteCss<'a> for QuoteStyle {
@@ -233,12 +385,45 @@ impl<'a> WriteCss<'a> for QuoteStyle {
 	}
 }
 
-impl<'a, T: WriteCss<'a>> WriteCss<'a> for Spanned<T> {
+impl<'a, T: WriteCss<'a> + Hash> WriteCss<'a> for Spanned<T> {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
+		if sink.write_original(self.span, &self.node)? {
+			return Ok(());
+		}
+		sink.add_mapping(self.span);
 		self.node.write_css(sink)
 	}
 }
 
+impl<'a> WriteCss<'a> for Remnants {
+	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
+		if sink.can_output(OutputOption::Whitespace) {
+			return sink.write_str(&self.text);
+		}
+		// Remnants aren't understood well enough to drop whitespace from, so each run of it, outside of strings, is
+		// only shortened to a single space.
+		let mut quote = None;
+		let mut escaped = false;
+		let mut chars = self.text.chars().peekable();
+		while let Some(ch) = chars.next() {
+			match ch {
+				_ if escaped => escaped = false,
+				'\\' => escaped = true,
+				'"' | '\'' if quote.is_none() => quote = Some(ch),
+				_ if quote == Some(ch) => quote = None,
+				_ if quote.is_none() && ch.is_ascii_whitespace() => {
+					while chars.next_if(|ch| ch.is_ascii_whitespace()).is_some() {}
+					sink.write_char(' ')?;
+					continue;
+				}
+				_ => {}
+			}
+			sink.write_char(ch)?;
+		}
+		Ok(())
+	}
+}
+
 impl<'a> WriteCss<'a> for Atom {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
 		sink.write_str(self.as_ref())
@@ -247,7 +432,40 @@ impl<'a> WriteCss<'a> for Atom {
 
 impl<'a> WriteCss<'a> for f32 {
 	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
-		sink.write_str(self.to_string().as_str())
+		if sink.can_output(OutputOption::ExpandedNumbers) {
+			sink.write_str(self.to_string().as_str())
+		} else {
+			sink.write_str(shortest_number(*self).as_str())
+		}
+	}
+}
+
+impl<'a> WriteCss<'a> for f64 {
+	fn write_css<W: CssWriter>(&self, sink: &mut W) -> Result {
+		if sink.can_output(OutputOption::ExpandedNumbers) {
+			sink.write_str(self.to_string().as_str())
+		} else {
+			sink.write_str(shortest_number(*self).as_str())
+		}
+	}
+}
+
+/// The shortest way to write `n` which still reads back as exactly `n`, such as `.5` for `0.5` or `1e3` for `1000`.
+pub fn shortest_number<N: std::fmt::Display + std::fmt::LowerExp + Default + PartialEq>(n: N) -> String {
+	if n == N::default() {
+		return "0".into();
+	}
+	let mut decimal = n.to_string();
+	if let Some(rest) = decimal.strip_prefix("0.") {
+		decimal = format!(".{}", rest);
+	} else if let Some(rest) = decimal.strip_prefix("-0.") {
+		decimal = format!("-.{}", rest);
+	}
+	let exponent = format!("{:e}", n);
+	if exponent.len() < decimal.len() {
+		exponent
+	} else {
+		decimal
 	}
 }
 
@@ -0,0 +1,308 @@
+use std::fmt::{Result, Write};
+
+use hdx_lexer::QuoteStyle;
+use hdx_parser::{LineCol, LineIndex, Span};
+
+use crate::{BaseCssWriter, CssWriter, FormatOptions, OutputOption, WriteCss};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Mapping {
+	generated_line: u32,
+	generated_col: u32,
+	original_offset: u32,
+}
+
+/// A [CssWriter] which also records where each [Spanned](hdx_parser::Spanned) node was written, so that a
+/// [SourceMap] can link the output back to the source text it was parsed from.
+pub struct SourceMapWriter<W>
+where
+	W: Write,
+{
+	writer: BaseCssWriter<W>,
+	mappings: Vec<Mapping>,
+}
+
+impl<W> SourceMapWriter<W>
+where
+	W: Write,
+{
+	pub fn new(sink: W, opts: OutputOption) -> Self {
+		Self::with_format_options(sink, opts, FormatOptions::default())
+	}
+
+	pub fn with_format_options(sink: W, opts: OutputOption, format: FormatOptions) -> Self {
+		SourceMapWriter { writer: BaseCssWriter::with_format_options(sink, opts, format), mappings: Vec::new() }
+	}
+
+	/// Builds the source map for everything written so far. `source_text` must be the text the written nodes were
+	/// parsed from, it's used to turn their spans into lines & columns, and is embedded in the map as
+	/// `sourcesContent`.
+	pub fn source_map(&self, source_name: &str, source_text: &str) -> SourceMap {
+		let line_index = LineIndex::new(source_text);
+		let mut mappings = String::new();
+		let (mut line, mut prev_col, mut prev_original_line, mut prev_original_col) = (0, 0, 0, 0);
+		for (i, mapping) in self.mappings.iter().enumerate() {
+			if i > 0 && mapping.generated_line == line {
+				mappings.push(',');
+			}
+			while line < mapping.generated_line {
+				mappings.push(';');
+				line += 1;
+				prev_col = 0;
+			}
+			// Spans can include the whitespace before a node, but the mapping should point at the node itself.
+			let offset = source_text.get(mapping.original_offset as usize..).map_or(source_text.len(), |rest| {
+				source_text.len() - rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace()).len()
+			}) as u32;
+			let LineCol { line: original_line, col: original_col } = line_index.line_col_utf16(offset);
+			encode_vlq(&mut mappings, mapping.generated_col as i64 - prev_col as i64);
+			// Every mapping points into the one source.
+			encode_vlq(&mut mappings, 0);
+			encode_vlq(&mut mappings, original_line as i64 - prev_original_line as i64);
+			encode_vlq(&mut mappings, original_col as i64 - prev_original_col as i64);
+			prev_col = mapping.generated_col;
+			prev_original_line = original_line;
+			prev_original_col = original_col;
+		}
+		SourceMap {
+			file: None,
+			sources: vec![source_name.to_string()],
+			sources_content: vec![source_text.to_string()],
+			mappings,
+		}
+	}
+}
+
+impl<W> CssWriter for SourceMapWriter<W>
+where
+	W: Write,
+{
+	#[inline]
+	fn add_mapping(&mut self, span: Span) {
+		if span.is_dummy() {
+			return;
+		}
+		let mapping =
+			Mapping { generated_line: self.writer.line, generated_col: self.writer.col, original_offset: span.start };
+		// Nested nodes often start at the same place, the innermost one is the most precise.
+		match self.mappings.last_mut() {
+			Some(last)
+				if (last.generated_line, last.generated_col) == (mapping.generated_line, mapping.generated_col) =>
+			{
+				*last = mapping
+			}
+			_ => self.mappings.push(mapping),
+		}
+	}
+
+	#[inline]
+	fn can_output(&self, opt: OutputOption) -> bool {
+		self.writer.can_output(opt)
+	}
+
+	#[inline]
+	fn format_options(&self) -> &FormatOptions {
+		self.writer.format_options()
+	}
+
+	#[inline]
+	fn remaining_width(&self) -> Option<u32> {
+		self.writer.remaining_width()
+	}
+
+	#[inline]
+	fn measure<'a, T: WriteCss<'a>>(&self, node: &T) -> u32 {
+		self.writer.measure(node)
+	}
+
+	#[inline]
+	fn write_str(&mut self, str: &str) -> Result {
+		self.writer.write_str(str)
+	}
+
+	#[inline]
+	fn write_char(&mut self, ch: char) -> Result {
+		self.writer.write_char(ch)
+	}
+
+	#[inline]
+	fn write_newline(&mut self) -> Result {
+		self.writer.write_newline()
+	}
+
+	#[inline]
+	fn write_comment(&mut self, str: &str) -> Result {
+		self.writer.write_comment(str)
+	}
+
+	#[inline]
+	fn write_with_quotes(&mut self, str: &str, quote: QuoteStyle, could_be_ident: bool) -> Result {
+		self.writer.write_with_quotes(str, quote, could_be_ident)
+	}
+
+	#[inline]
+	fn write_trailing_char(&mut self, ch: char) -> Result {
+		self.writer.write_trailing_char(ch)
+	}
+
+	#[inline]
+	fn write_whitespace(&mut self) -> Result {
+		self.writer.write_whitespace()
+	}
+
+	#[inline]
+	fn write_indent(&mut self) -> Result {
+		self.writer.write_indent()
+	}
+
+	#[inline]
+	fn indent(&mut self) {
+		self.writer.indent()
+	}
+
+	#[inline]
+	fn dedent(&mut self) {
+		self.writer.dedent()
+	}
+}
+
+/// A [Source Map v3](https://sourcemaps.info/spec.html) for a single stylesheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceMap {
+	/// The name of the generated file the map belongs to.
+	pub file: Option<String>,
+	/// The source file, relative to wherever the map will be written.
+	pub sources: Vec<String>,
+	pub sources_content: Vec<String>,
+	pub mappings: String,
+}
+
+impl SourceMap {
+	pub fn to_json(&self) -> String {
+		let mut json = String::from("{\"version\":3");
+		if let Some(file) = &self.file {
+			json.push_str(",\"file\":");
+			write_json_string(&mut json, file);
+		}
+		json.push_str(",\"sources\":[");
+		for (i, source) in self.sources.iter().enumerate() {
+			if i > 0 {
+				json.push(',');
+			}
+			write_json_string(&mut json, source);
+		}
+		json.push_str("],\"sourcesContent\":[");
+		for (i, content) in self.sources_content.iter().enumerate() {
+			if i > 0 {
+				json.push(',');
+			}
+			write_json_string(&mut json, content);
+		}
+		json.push_str("],\"names\":[],\"mappings\":");
+		write_json_string(&mut json, &self.mappings);
+		json.push('}');
+		json
+	}
+
+	/// A comment to append to the generated CSS, which points devtools at the map in a separate file.
+	pub fn url_comment(url: &str) -> String {
+		format!("/*# sourceMappingURL={} */", url)
+	}
+
+	/// A comment to append to the generated CSS, which embeds the whole map as a data URL.
+	pub fn to_inline_comment(&self) -> String {
+		Self::url_comment(&format!("data:application/json;base64,{}", encode_base64(self.to_json().as_bytes())))
+	}
+}
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_vlq(out: &mut String, value: i64) {
+	// The sign is stored in the lowest bit, followed by 5 bits per digit, with the 6th bit flagging a continuation.
+	let mut vlq = if value < 0 { ((-value) << 1) | 1 } else { value << 1 };
+	loop {
+		let mut digit = vlq & 0b11111;
+		vlq >>= 5;
+		if vlq > 0 {
+			digit |= 0b100000;
+		}
+		out.push(BASE64_CHARS[digit as usize] as char);
+		if vlq == 0 {
+			break;
+		}
+	}
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+	let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+	for chunk in bytes.chunks(3) {
+		let n =
+			(chunk[0] as u32) << 16 | (*chunk.get(1).unwrap_or(&0) as u32) << 8 | *chunk.get(2).unwrap_or(&0) as u32;
+		for i in 0..4 {
+			if i <= chunk.len() {
+				out.push(BASE64_CHARS[(n >> (18 - i * 6) & 0b111111) as usize] as char);
+			} else {
+				out.push('=');
+			}
+		}
+	}
+	out
+}
+
+fn write_json_string(out: &mut String, str: &str) {
+	out.push('"');
+	for ch in str.chars() {
+		match ch {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+			ch => out.push(ch),
+		}
+	}
+	out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_encode_vlq() {
+		let mut str = String::new();
+		for value in [0, 1, -1, 15, 16, -16, 1000] {
+			encode_vlq(&mut str, value);
+			str.push(',');
+		}
+		assert_eq!(str, "A,C,D,e,gB,hB,w+B,");
+	}
+
+	#[test]
+	fn test_encode_base64() {
+		assert_eq!(encode_base64(b""), "");
+		assert_eq!(encode_base64(b"f"), "Zg==");
+		assert_eq!(encode_base64(b"fo"), "Zm8=");
+		assert_eq!(encode_base64(b"foo"), "Zm9v");
+		assert_eq!(encode_base64(b"{\"version\":3}"), "eyJ2ZXJzaW9uIjozfQ==");
+	}
+
+	#[test]
+	fn test_source_map() {
+		let source_text = "a {\n\tcolor: red;\n}\n";
+		let mut str = String::new();
+		let mut writer = SourceMapWriter::new(&mut str, OutputOption::none());
+		writer.add_mapping(Span::new(0, 18));
+		writer.write_str("a{").unwrap();
+		writer.add_mapping(Span::new(3, 15));
+		writer.write_str("color:red}").unwrap();
+		let map = writer.source_map("a.css", source_text);
+		// a{ at 0:0 → 0:0, color at 0:2 → 1:1
+		assert_eq!(map.mappings, "AAAA,EACC");
+		assert_eq!(
+			map.to_json(),
+			r#"{"version":3,"sources":["a.css"],"sourcesContent":["a {\n\tcolor: red;\n}\n"],"names":[],"mappings":"AAAA,EACC"}"#
+		);
+	}
+}
@@ -254,7 +254,9 @@ class HdxEditor extends HTMLElement {
 
   handleEvent(event) {
     if (event.type == "diagnostic") {
-      this.view.dispatch(setDiagnostics(this.view.state, event.diagnostics));
+      // Diagnostics without a label have nowhere in the code to mark.
+      const marked = event.diagnostics.filter((d) => d.from !== null);
+      this.view.dispatch(setDiagnostics(this.view.state, marked));
     } else if (event.type == "focusout") {
       codeFromUrl.set(event.target.code);
       codeFromLocal.set(event.target.code);